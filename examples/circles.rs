@@ -14,9 +14,9 @@ use text_layout::Align;
 use limn::prelude::*;
 
 use limn::input::mouse::WidgetMouseButton;
-use limn::widgets::button::{PushButtonBuilder, ToggleButtonBuilder, ToggleEvent};
+use limn::widgets::button::{PushButtonBuilder, ToggleButtonBuilder};
 use limn::widgets::slider::{SliderBuilder, SetSliderValue, SliderEvent};
-use limn::widgets::drag::DragEvent;
+use limn::widgets::drag::{DragEvent, DragState};
 use limn::draw::text::TextStyle;
 use limn::draw::rect::{RectState, RectStyle};
 use limn::draw::ellipse::{EllipseState, EllipseStyle};
@@ -96,15 +96,8 @@ impl ControlBar {
         let mut create_button = ToggleButtonBuilder::new();
         create_button
             .set_text("Create Circle", "Create Circle")
-            .on_toggle(|event, args| {
-                match *event {
-                    ToggleEvent::On => {
-                        args.ui.event(AppEvent::SetCreateMode(true));
-                    },
-                    ToggleEvent::Off => {
-                        args.ui.event(AppEvent::SetCreateMode(false));
-                    }
-                };
+            .on_toggle(|toggled, args| {
+                args.ui.event(AppEvent::SetCreateMode(toggled));
             });
         create_button.layout().add(center_vertical(&widget));
         let mut undo_widget = PushButtonBuilder::new();
@@ -176,7 +169,10 @@ impl EventHandler<CircleEvent> for CircleHandler {
                 });
             }
             CircleEvent::Drag(ref event) => {
-                args.ui.event(AppEvent::Move(self.0, event.change));
+                // a cancelled drag's change moves the circle back to where the drag started
+                if event.state == DragState::Moved || event.state == DragState::Cancel {
+                    args.ui.event(AppEvent::Move(self.0, event.change));
+                }
             }
         }
     }
@@ -83,8 +83,7 @@ impl ClockBuilder {
         let style = style!(
             EllipseStyle::BackgroundColor: WHITE,
             EllipseStyle::Border: Some((2.0, BLACK)));
-        let mut widget = WidgetBuilder::new("clock");
-        widget.set_draw_state_with_style(EllipseState::new(), style);
+        let mut widget = WidgetBuilder::with_draw_state_and_style("clock", EllipseState::new(), style);
         widget.layout().add(size(Size::new(200.0, 200.0)));
 
         let hour_angle = || rotation((Local::now().hour() % 12) as f32 / 12.0);
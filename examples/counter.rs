@@ -29,7 +29,7 @@ fn main() {
     impl EventHandler<CountEvent> for CountHandler {
         fn handle(&mut self, _: &CountEvent, mut args: EventArgs) {
             self.count += 1;
-            args.widget.update(|state: &mut TextState| state.text = format!("{}", self.count));
+            args.update_drawable(|state: &mut TextState| state.text = format!("{}", self.count));
         }
     }
 
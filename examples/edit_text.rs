@@ -9,7 +9,7 @@ use text_layout::{Align, Wrap};
 
 use limn::prelude::*;
 
-use limn::widgets::button::{ToggleButtonBuilder, ToggleEvent};
+use limn::widgets::button::ToggleButtonBuilder;
 use limn::widgets::edit_text::EditTextBuilder;
 use limn::draw::text::TextState;
 
@@ -20,7 +20,7 @@ enum EditTextSettingsEvent {
 struct EditTextSettingsHandler;
 impl EventHandler<EditTextSettingsEvent> for EditTextSettingsHandler {
     fn handle(&mut self, event: &EditTextSettingsEvent, mut args: EventArgs) {
-        args.widget.update(|draw_state: &mut TextState| {
+        args.update_drawable(|draw_state: &mut TextState| {
             match *event {
                 EditTextSettingsEvent::Align(align) => draw_state.align = align,
                 EditTextSettingsEvent::Wrap(wrap) => draw_state.wrap = wrap,
@@ -44,14 +44,11 @@ fn main() {
     let mut h_align_button = ToggleButtonBuilder::new();
     h_align_button
         .set_text("Right Align", "Left Align")
-        .on_toggle(move |event, _| {
-            match *event {
-                ToggleEvent::On => {
-                    edit_text_ref.event(EditTextSettingsEvent::Align(Align::End));
-                },
-                ToggleEvent::Off => {
-                    edit_text_ref.event(EditTextSettingsEvent::Align(Align::Start));
-                },
+        .on_toggle(move |toggled, _| {
+            if toggled {
+                edit_text_ref.event(EditTextSettingsEvent::Align(Align::End));
+            } else {
+                edit_text_ref.event(EditTextSettingsEvent::Align(Align::Start));
             }
         });
 
@@ -59,14 +56,11 @@ fn main() {
     let mut v_align_button = ToggleButtonBuilder::new();
     v_align_button
         .set_text("Wrap Word", "Wrap Char")
-        .on_toggle(move |event, _| {
-            match *event {
-                ToggleEvent::On => {
-                    edit_text_ref.event(EditTextSettingsEvent::Wrap(Wrap::Whitespace));
-                },
-                ToggleEvent::Off => {
-                    edit_text_ref.event(EditTextSettingsEvent::Wrap(Wrap::Character));
-                },
+        .on_toggle(move |toggled, _| {
+            if toggled {
+                edit_text_ref.event(EditTextSettingsEvent::Wrap(Wrap::Whitespace));
+            } else {
+                edit_text_ref.event(EditTextSettingsEvent::Wrap(Wrap::Character));
             }
         });
 
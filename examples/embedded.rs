@@ -0,0 +1,152 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+extern crate gleam;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+use std::time::Instant;
+
+use gleam::gl;
+
+use limn::prelude::*;
+
+use limn::widgets::button::PushButtonBuilder;
+use limn::draw::rect::{RectState, RectStyle};
+
+const VERTEX_SHADER: &'static str = "
+#version 150
+in vec2 position;
+uniform float angle;
+void main() {
+    float c = cos(angle);
+    float s = sin(angle);
+    gl_Position = vec4(c * position.x - s * position.y, s * position.x + c * position.y, 0.0, 1.0);
+}";
+
+const FRAGMENT_SHADER: &'static str = "
+#version 150
+out vec4 color;
+void main() {
+    color = vec4(0.9, 0.4, 0.1, 1.0);
+}";
+
+/// The app's own scene, drawn directly with GL under the UI
+struct Triangle {
+    program: gl::GLuint,
+    vertex_array: gl::GLuint,
+    vertex_buffer: gl::GLuint,
+    angle_location: gl::GLint,
+}
+impl Triangle {
+    fn new(gl: &gl::Gl) -> Self {
+        let program = gl.create_program();
+        for &(shader_type, source) in &[(gl::VERTEX_SHADER, VERTEX_SHADER), (gl::FRAGMENT_SHADER, FRAGMENT_SHADER)] {
+            let shader = gl.create_shader(shader_type);
+            gl.shader_source(shader, &[source.as_bytes()]);
+            gl.compile_shader(shader);
+            gl.attach_shader(program, shader);
+        }
+        gl.link_program(program);
+
+        let vertex_array = gl.gen_vertex_arrays(1)[0];
+        let vertex_buffer = gl.gen_buffers(1)[0];
+        gl.bind_vertex_array(vertex_array);
+        gl.bind_buffer(gl::ARRAY_BUFFER, vertex_buffer);
+        let vertices: [f32; 6] = [0.0, 0.6, -0.5, -0.4, 0.5, -0.4];
+        gl::buffer_data(gl, gl::ARRAY_BUFFER, &vertices, gl::STATIC_DRAW);
+        let position = gl.get_attrib_location(program, "position") as gl::GLuint;
+        gl.vertex_attrib_pointer(position, 2, gl::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(position);
+
+        Triangle {
+            program: program,
+            vertex_array: vertex_array,
+            vertex_buffer: vertex_buffer,
+            angle_location: gl.get_uniform_location(program, "angle"),
+        }
+    }
+    fn draw(&self, gl: &gl::Gl, angle: f32) {
+        // the UI renderer changes the GL state, so set everything the triangle needs each frame
+        gl.disable(gl::DEPTH_TEST);
+        gl.disable(gl::BLEND);
+        gl.use_program(self.program);
+        gl.bind_vertex_array(self.vertex_array);
+        gl.bind_buffer(gl::ARRAY_BUFFER, self.vertex_buffer);
+        gl.uniform_1f(self.angle_location, angle);
+        gl.draw_arrays(gl::TRIANGLES, 0, 3);
+    }
+}
+
+fn main() {
+    let mut app = util::init_default_min_size("Limn embedded demo", Size::new(600.0, 400.0));
+
+    // how fast the triangle spins, in radians per second, changed by the UI
+    let speed = Rc::new(Cell::new(1.0));
+
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(min_size(Size::new(600.0, 400.0)));
+
+    let mut panel = WidgetBuilder::new("panel");
+    panel.set_draw_state_with_style(RectState::new(), style!(
+        RectStyle::BackgroundColor: GRAY_80,
+        RectStyle::CornerRadius: Some(5.0)));
+    panel.layout().add(constraints![
+        align_top(&root).padding(20.0),
+        align_left(&root).padding(20.0),
+    ]);
+    let mut linear_layout = LinearLayoutSettings::new(Orientation::Horizontal);
+    linear_layout.padding = 10.0;
+    panel.linear_layout(linear_layout);
+
+    let mut reverse_button = PushButtonBuilder::new();
+    reverse_button.set_text("Reverse");
+    let button_speed = speed.clone();
+    reverse_button.on_click(move |_, _| {
+        button_speed.set(-button_speed.get());
+    });
+    let mut faster_button = PushButtonBuilder::new();
+    faster_button.set_text("Faster");
+    let button_speed = speed.clone();
+    faster_button.on_click(move |_, _| {
+        button_speed.set(button_speed.get() * 1.5);
+    });
+    panel
+        .add_child(reverse_button)
+        .add_child(faster_button);
+    root.add_child(panel);
+
+    app.attach(root);
+    // leave the triangle visible behind the UI
+    app.ui().set_background_color(None);
+
+    let gl = app.ui().window().gl();
+    let triangle = Triangle::new(&*gl);
+    let mut angle = 0.0;
+    let mut last_frame = Instant::now();
+    loop {
+        app.poll_events();
+        if app.ui().should_close() {
+            break;
+        }
+        let now = Instant::now();
+        let dt = now - last_frame;
+        last_frame = now;
+        angle += speed.get() * (dt.subsec_nanos() as f32 / 1_000_000_000.0);
+
+        app.ui().frame(dt);
+
+        let window_size = app.ui().window().size_u32();
+        gl.viewport(0, 0, window_size.width as i32, window_size.height as i32);
+        gl.clear_color(0.1, 0.1, 0.15, 1.0);
+        gl.clear(gl::COLOR_BUFFER_BIT);
+        triangle.draw(&*gl, angle);
+        app.ui().draw_into();
+        app.ui().window().swap_buffers();
+    }
+    app.deinit();
+}
@@ -22,9 +22,8 @@ fn main() {
 
     {
         let mut add_rect = |color| {
-            let mut rect = WidgetBuilder::new(&format!("rect_{:?}", color));
-            rect.set_draw_state_with_style(RectState::new(),
-                style!(RectStyle::BackgroundColor: color));
+            let rect = WidgetBuilder::with_draw_state_and_style(&format!("rect_{:?}", color),
+                RectState::new(), style!(RectStyle::BackgroundColor: color));
             rect_container.add_child(rect);
         };
         add_rect(GREEN);
@@ -0,0 +1,64 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+extern crate lipsum;
+extern crate rand;
+
+use lipsum::lipsum;
+use rand::Rng;
+
+mod util;
+
+use limn::prelude::*;
+
+use limn::widgets::list::{self, ListBuilder, STYLE_LIST_TEXT};
+use limn::widgets::scroll::ScrollBuilder;
+use limn::draw::rect::{RectState, RectStyle};
+use limn::draw::text::TextStyle;
+use limn::widgets::text::TextBuilder;
+
+fn section_header(title: &str) -> WidgetBuilder {
+    let mut header = WidgetBuilder::with_draw_state_and_style("section_header",
+        RectState::new(), style!(RectStyle::BackgroundColor: GRAY_10));
+    let mut text = TextBuilder::new_with_style(style!(parent: STYLE_LIST_TEXT, TextStyle::Text: title.to_owned()));
+    text.layout().add(constraints![
+        align_left(&header).padding(5.0),
+        align_top(&header).padding(5.0),
+        align_bottom(&header).padding(5.0),
+    ]);
+    header.add_child(text);
+    header
+}
+
+fn main() {
+    let app = util::init_default("Limn sections demo");
+    let mut root = WidgetBuilder::new("root");
+
+    let mut scroll_widget = ScrollBuilder::new();
+    scroll_widget.add_scrollbar();
+    scroll_widget.layout().add(constraints![
+        match_layout(&root).padding(50.0),
+        min_size(Size::new(300.0, 300.0)),
+    ]);
+
+    let mut list_widget = ListBuilder::new();
+    list_widget.layout().add(constraints![shrink(), match_width(&scroll_widget)]);
+
+    // the header of each section stays at the top while its items are scrolled past
+    for section in 0..50 {
+        let items = rand::thread_rng().gen_range(2, 8);
+        let list_data: Vec<String> = (0..items).map(|_| {
+            let words = rand::thread_rng().gen_range(1, 6);
+            lipsum(words)
+        }).collect();
+        let header = section_header(&format!("Section {}", section + 1));
+        list_widget.add_section(header, list_data.into_iter(), list::default_text_adapter);
+    }
+
+    scroll_widget.add_content(list_widget);
+    root.add_child(scroll_widget);
+
+    app.main_loop(root);
+}
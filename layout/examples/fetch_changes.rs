@@ -0,0 +1,50 @@
+//! Measures the time spent fetching changes from the solver, with 500 fixed widgets and one edit variable
+//! changing each frame, fetching changes 10 times a frame, as the ui does after each event it handles.
+//! Run with `cargo run --release --example fetch_changes` from the layout directory.
+
+#[macro_use]
+extern crate limn_layout as layout;
+
+use std::time::{Duration, Instant};
+
+use layout::{LimnSolver, Layout, Size, Point};
+use layout::constraint::*;
+
+const WIDGETS: usize = 500;
+const FRAMES: usize = 20000;
+const FETCHES_PER_FRAME: usize = 10;
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 / 1_000_000.0
+}
+
+fn main() {
+    let mut solver = LimnSolver::new();
+    for id in 0..WIDGETS {
+        let mut layout = Layout::new(id, Some(format!("widget_{}", id)));
+        layout.add(constraints![
+            top_left(Point::new(0.0, id as f32 * 10.0)),
+            size(Size::new(100.0, 10.0))
+        ]);
+        solver.update_layout(&mut layout).unwrap();
+    }
+    let mut changing = Layout::new(WIDGETS, Some("changing".to_owned()));
+    changing.add(top_left(Point::new(200.0, 0.0)));
+    changing.edit_height().set(10.0);
+    solver.update_layout(&mut changing).unwrap();
+    solver.fetch_changes();
+
+    let (mut fetching, mut changes) = (Duration::new(0, 0), 0);
+    for frame in 0..FRAMES {
+        solver.begin_frame();
+        changing.edit_width().set((frame % 100) as f32 + 1.0);
+        solver.update_layout(&mut changing).unwrap();
+        for _ in 0..FETCHES_PER_FRAME {
+            let start = Instant::now();
+            changes += solver.fetch_changes().len();
+            fetching += start.elapsed();
+        }
+    }
+    println!("{} widgets, {} frames, {} fetches per frame", WIDGETS + 1, FRAMES, FETCHES_PER_FRAME);
+    println!("{} changes fetched in {:.2}ms", changes, millis(fetching));
+}
@@ -6,12 +6,12 @@ extern crate log;
 extern crate lazy_static;
 
 use std::collections::HashSet;
-use std::ops::Drop;
+use std::ops::{Drop, BitOr, BitOrAssign};
 use std::mem;
 use std::rc::Rc;
 use std::cell::RefCell;
 
-use cassowary::{Variable, Constraint};
+use cassowary::{Variable, Constraint, Term, Expression};
 use cassowary::WeightedRelation::*;
 use cassowary::strength::*;
 
@@ -27,6 +27,41 @@ pub type Rect = euclid::Rect<f32>;
 
 pub type LayoutId = usize;
 
+/// A set of edges of a widget, combined with `|`, eg. `Edges::LEFT | Edges::RIGHT`, see `LayoutVars::pinned_to`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edges(u8);
+impl Edges {
+    pub const LEFT: Edges = Edges(1);
+    pub const TOP: Edges = Edges(1 << 1);
+    pub const RIGHT: Edges = Edges(1 << 2);
+    pub const BOTTOM: Edges = Edges(1 << 3);
+    pub const HORIZONTAL: Edges = Edges(1 | 1 << 2);
+    pub const VERTICAL: Edges = Edges(1 << 1 | 1 << 3);
+    pub const ALL: Edges = Edges(1 | 1 << 1 | 1 << 2 | 1 << 3);
+
+    pub fn empty() -> Self {
+        Edges(0)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    /// If every edge in `other` is in this set
+    pub fn contains(&self, other: Edges) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+impl BitOr for Edges {
+    type Output = Edges;
+    fn bitor(self, other: Edges) -> Edges {
+        Edges(self.0 | other.0)
+    }
+}
+impl BitOrAssign for Edges {
+    fn bitor_assign(&mut self, other: Edges) {
+        self.0 |= other.0;
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LayoutVars {
     pub left: Variable,
@@ -50,6 +85,36 @@ impl LayoutVars {
     pub fn array(&self) -> [Variable; 6] {
         [self.left, self.top, self.right, self.bottom, self.width, self.height]
     }
+    /// The variable of type `var_type`, if it's one of these vars
+    pub fn get(&self, var_type: VarType) -> Option<Variable> {
+        match var_type {
+            VarType::Left => Some(self.left),
+            VarType::Top => Some(self.top),
+            VarType::Right => Some(self.right),
+            VarType::Bottom => Some(self.bottom),
+            VarType::Width => Some(self.width),
+            VarType::Height => Some(self.height),
+            VarType::Other => None,
+        }
+    }
+    /// REQUIRED constraints aligning each of `edges` with the same edge of `other`,
+    /// eg. `Edges::HORIZONTAL` to stretch across `other`, or `Edges::ALL` to cover it
+    pub fn pinned_to(&self, other: &LayoutVars, edges: Edges) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        if edges.contains(Edges::LEFT) {
+            constraints.push(self.left | EQ(REQUIRED) | other.left);
+        }
+        if edges.contains(Edges::TOP) {
+            constraints.push(self.top | EQ(REQUIRED) | other.top);
+        }
+        if edges.contains(Edges::RIGHT) {
+            constraints.push(self.right | EQ(REQUIRED) | other.right);
+        }
+        if edges.contains(Edges::BOTTOM) {
+            constraints.push(self.bottom | EQ(REQUIRED) | other.bottom);
+        }
+        constraints
+    }
     pub fn var_type(&self, var: Variable) -> VarType {
         if var == self.left { VarType::Left }
         else if var == self.top { VarType::Top }
@@ -59,9 +124,42 @@ impl LayoutVars {
         else if var == self.height { VarType::Height }
         else { VarType::Other }
     }
+    /// The bounds of these vars, as currently solved by `solver`
+    pub fn to_rect(&self, solver: &LimnSolver) -> Rect {
+        let get_val = |var| solver.solver.get_value(var) as f32;
+        let origin = Point::new(get_val(self.left), get_val(self.top));
+        let size = Size::new(get_val(self.width), get_val(self.height));
+        Rect::new(origin, size)
+    }
+    /// The bounds of these vars as they appear inside a container scrolled by `offset`
+    pub fn apply_scroll_offset(&self, solver: &LimnSolver, offset: Point) -> Rect {
+        let rect = self.to_rect(solver);
+        rect.translate(&(Point::zero() - offset))
+    }
+    /// The bounds of these vars in the coordinate space of `other`,
+    /// where `other`'s top left corner is the origin
+    pub fn relative_to(&self, solver: &LimnSolver, other: &LayoutVars) -> Rect {
+        let rect = self.to_rect(solver);
+        let other = other.to_rect(solver);
+        rect.translate(&(Point::zero() - other.origin))
+    }
+    /// Constraints that center these vars in `container`, on both axes, with `STRONG` strength
+    pub fn center_in(&self, container: &LayoutVars) -> Vec<Constraint> {
+        let mut constraints = self.center_horizontally_in(container);
+        constraints.extend(self.center_vertically_in(container));
+        constraints
+    }
+    /// A constraint that centers these vars horizontally in `container`, with `STRONG` strength
+    pub fn center_horizontally_in(&self, container: &LayoutVars) -> Vec<Constraint> {
+        vec![ self.left + self.right | EQ(STRONG) | container.left + container.right ]
+    }
+    /// A constraint that centers these vars vertically in `container`, with `STRONG` strength
+    pub fn center_vertically_in(&self, container: &LayoutVars) -> Vec<Constraint> {
+        vec![ self.top + self.bottom | EQ(STRONG) | container.top + container.bottom ]
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VarType {
     Left,
     Top,
@@ -106,6 +204,9 @@ pub struct Layout {
     removed_children: Vec<LayoutId>,
     associated_vars: Vec<(Variable, String)>,
     pub hidden: bool,
+    /// Share of the leftover space given to this layout by containers that support it,
+    /// eg. `LinearLayout`. Must be set before the layout is added to its parent.
+    pub flex_weight: Option<f32>,
 }
 impl Layout {
     pub fn new(id: LayoutId, name: Option<String>) -> Self {
@@ -129,6 +230,7 @@ impl Layout {
             removed_children: Vec::new(),
             associated_vars: Vec::new(),
             hidden: false,
+            flex_weight: None,
         }
     }
     pub fn layout(&mut self) -> &mut Self {
@@ -183,6 +285,27 @@ impl Layout {
             }
         }
     }
+    /// The constraints added since the layout was last updated in the solver
+    pub fn added_constraints(&self) -> &HashSet<Constraint> {
+        &self.new_constraints
+    }
+    /// Move `constraints` from this layout to `other`, with the variables of this layout replaced by `other`'s,
+    /// eg. so the constraints added to a widget that's then wrapped in another constrain the wrapper instead
+    pub fn move_constraints(&mut self, constraints: Vec<Constraint>, other: &mut Layout) {
+        let (vars, other_vars) = (self.vars.array(), other.vars.array());
+        for constraint in &constraints {
+            let terms = constraint.expr().terms.iter().map(|term| {
+                let index = vars.iter().position(|&var| var == term.variable);
+                Term {
+                    variable: index.map_or(term.variable, |index| other_vars[index]),
+                    coefficient: term.coefficient,
+                }
+            }).collect();
+            let expr = Expression::new(terms, constraint.expr().constant);
+            other.new_constraints.insert(Constraint::new(expr, constraint.op(), constraint.strength()));
+        }
+        self.remove_constraints(constraints);
+    }
     pub fn has_constraint(&mut self, constraints: &Vec<Constraint>) -> bool {
         for constraint in constraints {
             if self.new_constraints.contains(constraint) || self.constraints.contains(constraint) {
@@ -215,6 +338,29 @@ impl Layout {
             container.borrow_mut().add_child(self, child);
         }
     }
+    /// Add `child` at `index` in the children, or after the last child if `index` is past the end
+    pub fn insert_child(&mut self, index: usize, child: &mut Layout) {
+        if index >= self.children.len() {
+            return self.add_child(child);
+        }
+        let before = self.children[index];
+        child.parent = Some(self.id);
+        self.children.insert(index, child.id);
+        if let Some(container) = self.container.clone() {
+            container.borrow_mut().insert_child(self, child, before);
+        }
+    }
+    /// Move `child` to `index` in the children, see `insert_child`. Unlike removing and adding it again,
+    /// the child's variables and constraints stay in the solver, only the container's constraints change.
+    pub fn move_child(&mut self, index: usize, child: &mut Layout) {
+        if let Some(container) = self.container.clone() {
+            container.borrow_mut().remove_child(self, child);
+        }
+        if let Some(pos) = self.children.iter().position(|id| child.id == *id) {
+            self.children.remove(pos);
+        }
+        self.insert_child(index, child);
+    }
     pub fn remove_child(&mut self, child: &mut Layout) {
         if let Some(container) = self.container.clone() {
             container.borrow_mut().remove_child(self, child);
@@ -248,6 +394,9 @@ impl Layout {
     pub fn show(&mut self) {
         self.hidden = false;
     }
+    pub fn set_flex_weight(&mut self, weight: f32) {
+        self.flex_weight = Some(weight);
+    }
 }
 
 pub struct VariableEditable<'a> {
@@ -318,6 +467,11 @@ macro_rules! constraints {
 
 pub trait LayoutContainer {
     fn add_child(&mut self, parent: &mut Layout, child: &mut Layout);
+    /// Add `child` just before the existing child `before`.
+    /// Containers that don't depend on the order of their children can keep the default.
+    fn insert_child(&mut self, parent: &mut Layout, child: &mut Layout, _before: LayoutId) {
+        self.add_child(parent, child);
+    }
     fn remove_child(&mut self, _: &mut Layout, _: &mut Layout) {}
 }
 
@@ -347,6 +501,7 @@ pub mod solver;
 pub mod constraint;
 pub mod linear_layout;
 pub mod grid_layout;
+pub mod strength;
 
 pub use self::solver::LimnSolver;
 
@@ -6,6 +6,7 @@ use cassowary::{Variable, Constraint};
 
 use super::{LayoutId, LayoutVars, Layout, LayoutContainer};
 use super::constraint::*;
+use super::strength::WEAK_MINUS;
 
 /// Specifies the extra space between elements along the primary axis
 #[derive(Debug, PartialEq)]
@@ -63,7 +64,7 @@ impl LinearLayoutSettings {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Orientation {
     Horizontal,
     Vertical,
@@ -75,6 +76,8 @@ struct WidgetData {
     end: Variable,
     prev: Option<LayoutId>,
     next: Option<LayoutId>,
+    // places the start after the previous widget, or at the start of the layout
+    start_constraint: Option<Constraint>,
     end_constraint: Option<Constraint>,
 }
 pub struct LinearLayout {
@@ -84,6 +87,7 @@ pub struct LinearLayout {
     end: Variable,
     space: Variable,
     size: Option<Variable>,
+    flex_unit: Option<Variable>,
 
     widgets: HashMap<LayoutId, WidgetData>,
     last_widget: Option<LayoutId>,
@@ -118,6 +122,11 @@ impl LinearLayout {
             }
         }
 
+        // the minimum size is implied by the required constraints keeping the children
+        // in order and inside the layout, prefer that size when nothing else decides it,
+        // so a layout that isn't sized by its parent fits its children
+        parent.add(shrink().strength(WEAK_MINUS));
+
         let size = if settings.fill_equal {
             let size = Variable::new();
             parent.add_associated_var(size, "linear_layout_size");
@@ -133,56 +142,39 @@ impl LinearLayout {
             end: end,
             space: space,
             size: size,
+            flex_unit: None,
             widgets: HashMap::new(),
             last_widget: None,
         }
     }
-}
-
-impl LayoutContainer for LinearLayout {
-    fn add_child(&mut self, parent: &mut Layout, child: &mut Layout) {
-
-        let child_start = beginning(self.settings.orientation, &child.vars);
-        let child_end = ending(self.settings.orientation, &child.vars);
-
-        parent.add(child_start | GE(REQUIRED) | self.start);
-        parent.add(child_end | LE(REQUIRED) | self.end);
-
-        if let Some(last_id) = self.last_widget {
-            let last_widget = self.widgets.get_mut(&last_id).unwrap();
-            parent.add(child_start | EQ(REQUIRED) | last_widget.end + self.space);
-            last_widget.next = Some(child.id);
-        } else {
-            if self.settings.spacing != Spacing::Start {
-                parent.add(child_start | EQ(REQUIRED) | self.start);
-            }
+    // the size along the primary axis given to each unit of flex weight,
+    // grows until the children fill the layout
+    fn flex_unit(&mut self, parent: &mut Layout) -> Variable {
+        if let Some(flex_unit) = self.flex_unit {
+            return flex_unit;
         }
-        let end_constraint = {
-            if self.settings.spacing != Spacing::End {
-                if let Some(last_id) = self.last_widget {
-                    let last_widget = self.widgets.get_mut(&last_id).unwrap();
-                    parent.remove_constraint(last_widget.end_constraint.take().unwrap());
-                }
-                let end_constraint = child_end | EQ(REQUIRED) | self.end;
-                parent.add(end_constraint.clone());
-                Some(end_constraint)
-            } else {
-                None
-            }
-        };
-        self.widgets.insert(child.id, WidgetData {
-            start: child_start,
-            end: child_end,
-            prev: self.last_widget,
-            next: None,
-            end_constraint: end_constraint,
-        });
-        self.last_widget = Some(child.id);
-
+        let flex_unit = Variable::new();
+        parent.add_associated_var(flex_unit, "linear_layout_flex_unit");
+        let parent_size = axis_length(self.settings.orientation, &parent.vars);
+        parent.add(vec![
+            flex_unit | GE(REQUIRED) | 0.0,
+            flex_unit | EQ(MEDIUM) | parent_size,
+        ]);
+        self.flex_unit = Some(flex_unit);
+        flex_unit
+    }
+    // constraints that don't depend on the order of the children
+    fn add_child_constraints(&mut self, parent: &mut Layout, child: &mut Layout) {
         if self.settings.fill_equal {
             let child_size = axis_length(self.settings.orientation, &child.vars);
             parent.add(child_size | EQ(REQUIRED) | self.size.unwrap());
         }
+        if let Some(weight) = child.flex_weight {
+            // STRONG so a child's own min size can override its share
+            let flex_unit = self.flex_unit(parent);
+            let child_size = axis_length(self.settings.orientation, &child.vars);
+            parent.add(child_size | EQ(STRONG) | weight * flex_unit);
+        }
         match self.settings.orientation {
             Orientation::Horizontal => {
                 match self.settings.item_align {
@@ -258,14 +250,111 @@ impl LayoutContainer for LinearLayout {
             }
         }
     }
+}
+
+impl LayoutContainer for LinearLayout {
+    fn add_child(&mut self, parent: &mut Layout, child: &mut Layout) {
+
+        let child_start = beginning(self.settings.orientation, &child.vars);
+        let child_end = ending(self.settings.orientation, &child.vars);
+
+        parent.add(child_start | GE(REQUIRED) | self.start);
+        parent.add(child_end | LE(REQUIRED) | self.end);
+
+        let start_constraint = if let Some(last_id) = self.last_widget {
+            let last_widget = self.widgets.get_mut(&last_id).unwrap();
+            last_widget.next = Some(child.id);
+            Some(child_start | EQ(REQUIRED) | last_widget.end + self.space)
+        } else if self.settings.spacing != Spacing::Start {
+            Some(child_start | EQ(REQUIRED) | self.start)
+        } else {
+            None
+        };
+        if let Some(ref start_constraint) = start_constraint {
+            parent.add(start_constraint.clone());
+        }
+        let end_constraint = {
+            if self.settings.spacing != Spacing::End {
+                if let Some(last_id) = self.last_widget {
+                    let last_widget = self.widgets.get_mut(&last_id).unwrap();
+                    parent.remove_constraint(last_widget.end_constraint.take().unwrap());
+                }
+                let end_constraint = child_end | EQ(REQUIRED) | self.end;
+                parent.add(end_constraint.clone());
+                Some(end_constraint)
+            } else {
+                None
+            }
+        };
+        self.widgets.insert(child.id, WidgetData {
+            start: child_start,
+            end: child_end,
+            prev: self.last_widget,
+            next: None,
+            start_constraint: start_constraint,
+            end_constraint: end_constraint,
+        });
+        self.last_widget = Some(child.id);
+
+        self.add_child_constraints(parent, child);
+    }
+
+    fn insert_child(&mut self, parent: &mut Layout, child: &mut Layout, before: LayoutId) {
+        let prev = match self.widgets.get(&before) {
+            Some(before) => before.prev,
+            None => return self.add_child(parent, child),
+        };
+        let child_start = beginning(self.settings.orientation, &child.vars);
+        let child_end = ending(self.settings.orientation, &child.vars);
+
+        parent.add(child_start | GE(REQUIRED) | self.start);
+        parent.add(child_end | LE(REQUIRED) | self.end);
+
+        // the child takes the place of `before`, which moves after it
+        let start_constraint = if let Some(prev_id) = prev {
+            let prev = self.widgets.get_mut(&prev_id).unwrap();
+            prev.next = Some(child.id);
+            Some(child_start | EQ(REQUIRED) | prev.end + self.space)
+        } else if self.settings.spacing != Spacing::Start {
+            Some(child_start | EQ(REQUIRED) | self.start)
+        } else {
+            None
+        };
+        if let Some(ref start_constraint) = start_constraint {
+            parent.add(start_constraint.clone());
+        }
+        {
+            let before = self.widgets.get_mut(&before).unwrap();
+            if let Some(old_start_constraint) = before.start_constraint.take() {
+                parent.remove_constraint(old_start_constraint);
+            }
+            let before_start_constraint = before.start | EQ(REQUIRED) | child_end + self.space;
+            parent.add(before_start_constraint.clone());
+            before.start_constraint = Some(before_start_constraint);
+            before.prev = Some(child.id);
+        }
+        self.widgets.insert(child.id, WidgetData {
+            start: child_start,
+            end: child_end,
+            prev: prev,
+            next: Some(before),
+            start_constraint: start_constraint,
+            end_constraint: None,
+        });
+
+        self.add_child_constraints(parent, child);
+    }
 
     fn remove_child(&mut self, parent: &mut Layout, child: &mut Layout) {
         if let Some(widget_data) = self.widgets.remove(&child.id) {
+            let mut next_start_constraint = None;
             if let Some(prev) = widget_data.prev {
                 let next_start = widget_data.next.map(|next_id| self.widgets[&next_id].start);
                 let prev = self.widgets.get_mut(&prev).unwrap();
                 if let Some(next_start) = next_start {
-                    parent.add(next_start | EQ(REQUIRED) | prev.end + self.space);
+                    let start_constraint = next_start | EQ(REQUIRED) | prev.end + self.space;
+                    parent.add(start_constraint.clone());
+                    next_start_constraint = Some(start_constraint);
                 } else {
                     if self.settings.spacing != Spacing::End {
                         let end_constraint = prev.end | EQ(REQUIRED) | self.end;
@@ -277,11 +366,15 @@ impl LayoutContainer for LinearLayout {
             } else if let Some(next) = widget_data.next {
                 if self.settings.spacing != Spacing::Start {
                     let next_start = self.widgets[&next].start;
-                    parent.add(next_start | EQ(REQUIRED) | self.start);
+                    let start_constraint = next_start | EQ(REQUIRED) | self.start;
+                    parent.add(start_constraint.clone());
+                    next_start_constraint = Some(start_constraint);
                 }
             }
             if let Some(next) = widget_data.next {
-                self.widgets.get_mut(&next).unwrap().prev = widget_data.prev;
+                let next = self.widgets.get_mut(&next).unwrap();
+                next.prev = widget_data.prev;
+                next.start_constraint = next_start_constraint;
             }
 
             if let Some(last_id) = self.last_widget {
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Write;
+use std::time::{Duration, Instant};
 
 use cassowary;
 use cassowary::strength;
@@ -7,12 +8,62 @@ use cassowary::strength::*;
 use cassowary::{Variable, Constraint, Expression};
 use cassowary::WeightedRelation::*;
 
-use super::{LayoutId, Layout, VarType, LayoutVars, EditVariable, Rect, Point, Size};
+use super::{LayoutId, Layout, VarType, LayoutVars, EditVariable};
+use super::strength::WEAK_MINUS;
+
+/// Number of times a variable can change within one frame before it is reported as thrashing
+const DEFAULT_THRASH_LIMIT: usize = 20;
+/// Number of times changes can be fetched within one frame before the rest are put off until the next frame
+const DEFAULT_MAX_SOLVES_PER_FRAME: usize = 100;
+
+/// How the solver breaks ties when the constraints don't decide where widgets go,
+/// eg. when the system is overconstrained and some constraints have to be violated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PriorityPolicy {
+    /// Only the constraints that have been added decide
+    Strict,
+    /// Prefer to keep each widget at the position it had at the start of the frame
+    KeepPosition,
+    /// Prefer to keep each widget at the size it had at the start of the frame
+    KeepSize,
+}
+
+/// Time spent in the solver since the frame began, see `LimnSolver::begin_frame`
+#[derive(Clone, Debug, Default)]
+pub struct SolverTimings {
+    pub add_constraint: Duration,
+    pub suggest_value: Duration,
+    pub fetch_changes: Duration,
+}
 
 pub struct LimnSolver {
+    /// Changes made to the solver directly are only fetched along with changes made through `LimnSolver`,
+    /// make them with `update_solver` so they're fetched by the next `fetch_changes`
     pub solver: cassowary::Solver,
     pub strict: bool,
+    /// Variables that change more than this many times in one frame are reported by `take_thrashing`
+    pub thrash_limit: usize,
+    /// After this many calls to `fetch_changes` in one frame, changes are put off until the next frame,
+    /// so a layout feedback loop can't freeze the UI. The count is reset by `begin_frame`, which should be
+    /// called at the start of every frame. Calls before the first `begin_frame` aren't counted,
+    /// eg. while a UI is built before its first frame, or for a solver that isn't used frame by frame.
+    pub max_solves_per_frame: usize,
     layouts: LayoutManager,
+    timings: SolverTimings,
+    frame_changes: HashMap<Variable, usize>,
+    frame_solves: usize,
+    // whether `begin_frame` has been called, solves are only counted and capped once it has
+    frame_started: bool,
+    thrashing: Vec<String>,
+    priority_policy: PriorityPolicy,
+    // constraints that keep variables at their last value, as chosen by the priority policy
+    stays: HashMap<Variable, Constraint>,
+    // variables whose stay constraints need updating at the start of the next frame
+    stale_stays: HashSet<Variable>,
+    // variables suggested since the last `fetch_changes`, and whether constraints were added or removed,
+    // if neither, nothing can have changed and the solver isn't asked for changes
+    dirty_vars: HashSet<Variable>,
+    constraints_changed: bool,
 }
 
 impl LimnSolver {
@@ -20,15 +71,104 @@ impl LimnSolver {
         LimnSolver {
             solver: cassowary::Solver::new(),
             strict: false,
+            thrash_limit: DEFAULT_THRASH_LIMIT,
+            max_solves_per_frame: DEFAULT_MAX_SOLVES_PER_FRAME,
             layouts: LayoutManager::new(),
+            timings: SolverTimings::default(),
+            frame_changes: HashMap::new(),
+            frame_solves: 0,
+            frame_started: false,
+            thrashing: Vec::new(),
+            priority_policy: PriorityPolicy::Strict,
+            stays: HashMap::new(),
+            stale_stays: HashSet::new(),
+            dirty_vars: HashSet::new(),
+            constraints_changed: false,
+        }
+    }
+
+    /// Reset the per frame timings and change counts.
+    /// Returns true if changes were put off from the last frame, and should be fetched again.
+    pub fn begin_frame(&mut self) -> bool {
+        let deferred = self.frame_solves > self.max_solves_per_frame;
+        self.timings = SolverTimings::default();
+        self.frame_changes.clear();
+        self.frame_solves = 0;
+        self.frame_started = true;
+        self.update_stays();
+        deferred
+    }
+
+    /// Set how ties are broken when the constraints don't decide where widgets go.
+    /// With `KeepPosition` or `KeepSize`, every widget gets a constraint weaker than `WEAK`
+    /// that keeps its position or size at the value it had at the start of the frame.
+    pub fn set_constraint_priority_policy(&mut self, policy: PriorityPolicy) {
+        self.priority_policy = policy;
+        for (_, constraint) in self.stays.drain().collect::<Vec<_>>() {
+            self.remove_constraint(&constraint);
+        }
+        self.stale_stays.clear();
+        let vars: Vec<Variable> = self.layouts.var_ids.keys().cloned().collect();
+        for var in vars {
+            self.mark_stay_stale(var);
+        }
+        self.update_stays();
+    }
+    pub fn constraint_priority_policy(&self) -> PriorityPolicy {
+        self.priority_policy
+    }
+    fn mark_stay_stale(&mut self, var: Variable) {
+        let layout_id = match self.layouts.var_ids.get(&var) {
+            Some(layout_id) => *layout_id,
+            None => return,
+        };
+        let var_type = self.layouts.layouts[&layout_id].vars.var_type(var);
+        let keep = match (self.priority_policy, var_type) {
+            (PriorityPolicy::KeepPosition, VarType::Left) |
+            (PriorityPolicy::KeepPosition, VarType::Top) |
+            (PriorityPolicy::KeepSize, VarType::Width) |
+            (PriorityPolicy::KeepSize, VarType::Height) => true,
+            _ => false,
+        };
+        if keep {
+            self.stale_stays.insert(var);
+        }
+    }
+    fn update_stays(&mut self) {
+        for var in self.stale_stays.drain().collect::<Vec<_>>() {
+            if let Some(constraint) = self.stays.remove(&var) {
+                self.remove_constraint(&constraint);
+            }
+            if self.layouts.var_ids.contains_key(&var) {
+                let value = self.solver.get_value(var);
+                let constraint = var | EQ(WEAK_MINUS) | value;
+                self.add_constraint(constraint.clone());
+                self.stays.insert(var, constraint);
+            }
         }
     }
+    pub fn timings(&self) -> &SolverTimings {
+        &self.timings
+    }
+    /// The debug names of variables that have changed more than `thrash_limit` times this frame,
+    /// usually caused by a handler that changes the layout in response to a layout change.
+    /// Each variable is only returned once per frame.
+    pub fn take_thrashing(&mut self) -> Vec<String> {
+        ::std::mem::replace(&mut self.thrashing, Vec::new())
+    }
 
-    pub fn update_layout(&mut self, layout: &mut Layout) {
+    /// Update the solver with the changes made to `layout` since the last update.
+    /// If any constraints conflict with those already in the solver, the rest of the changes are
+    /// still made, and the first conflicting constraint is returned.
+    pub fn update_layout(&mut self, layout: &mut Layout) -> Result<(), Constraint> {
+        let mut conflict = None;
 
         let registered = self.layouts.layouts.contains_key(&layout.id);
         if !registered {
             self.layouts.register_layout(layout);
+            for var in layout.vars.array().iter() {
+                self.mark_stay_stale(*var);
+            }
         }
         self.layouts.update_layout(layout);
 
@@ -41,7 +181,9 @@ impl LimnSolver {
         }
         if !registered {
             for constraint in self.layouts.dequeue_constraints(layout) {
-                self.add_constraint(constraint.clone());
+                if !self.add_constraint(constraint.clone()) {
+                    conflict = conflict.or(Some(constraint));
+                }
             }
         }
 
@@ -51,42 +193,66 @@ impl LimnSolver {
             self.unhide_layout(layout.id);
         }
         for constraint in layout.get_constraints() {
-            if self.layouts.add_constraint(&constraint) {
-                self.add_constraint(constraint.clone());
+            // constraints of hidden layouts are added to the solver when they are unhidden
+            if self.layouts.add_constraint(&constraint) && !self.layouts.layout_hidden(layout.id) {
+                if !self.add_constraint(constraint.clone()) {
+                    conflict = conflict.or(Some(constraint));
+                }
             }
         }
         for edit_var in layout.get_edit_vars() {
             self.update_edit_var(&edit_var);
             self.layouts.update_edit_var(layout.id, edit_var);
         }
+        match conflict {
+            Some(constraint) => Err(constraint),
+            None => Ok(()),
+        }
     }
     fn update_edit_var(&mut self, edit_var: &EditVariable) {
         let &EditVariable { var, val, strength } = edit_var;
+        self.dirty_vars.insert(var);
         if !self.solver.has_edit_variable(&var) {
             debug!("add edit_var {}", self.layouts.fmt_variable(var));
-            self.solver.add_edit_variable(var, strength).unwrap();
+            if self.solver.add_edit_variable(var, strength).is_err() {
+                // only fails for required strengths, edit variables can't be required
+                warn!("invalid strength for edit_var {}", self.layouts.fmt_variable(var));
+                return;
+            }
         }
         if val.is_finite() {
-            self.solver.suggest_value(var, val).unwrap();
+            let start = Instant::now();
+            if self.solver.suggest_value(var, val).is_err() {
+                warn!("failed to suggest edit_var {} {}", self.layouts.fmt_variable(var), val);
+            }
+            self.timings.suggest_value += start.elapsed();
             debug!("suggest edit_var {} {}", self.layouts.fmt_variable(var), val);
         } else {
             debug!("invalid edit_var {} {}", self.layouts.fmt_variable(var), val);
         }
     }
-    fn add_constraint(&mut self, constraint: Constraint) {
+    // returns false if the constraint conflicts with the constraints already in the solver
+    fn add_constraint(&mut self, constraint: Constraint) -> bool {
         debug!("adding constraint {}", self.layouts.fmt_constraint(&constraint));
-        if self.solver.add_constraint(constraint.clone()).is_err() {
+        self.constraints_changed = true;
+        let start = Instant::now();
+        let result = self.solver.add_constraint(constraint.clone());
+        self.timings.add_constraint += start.elapsed();
+        if result.is_err() {
             eprintln!("Failed to add constraint {}", self.layouts.fmt_constraint(&constraint));
             self.debug_associated_constraints(&constraint);
             if self.strict {
                 panic!("Solver unsatisfiable");
             }
+            return false;
         }
+        true
     }
 
     fn remove_constraint(&mut self, constraint: &Constraint) {
         debug!("removing constraint {}", self.layouts.fmt_constraint(constraint));
         if self.solver.has_constraint(constraint) {
+            self.constraints_changed = true;
             self.solver.remove_constraint(constraint).unwrap();
         }
     }
@@ -98,6 +264,10 @@ impl LimnSolver {
             }
             for var in layout.vars.array().iter() {
                 self.layouts.var_ids.remove(&var);
+                self.stale_stays.remove(var);
+                if let Some(constraint) = self.stays.remove(var) {
+                    self.remove_constraint(&constraint);
+                }
             }
         }
     }
@@ -157,6 +327,7 @@ impl LimnSolver {
     pub fn update_solver<F>(&mut self, f: F)
         where F: Fn(&mut cassowary::Solver)
     {
+        self.constraints_changed = true;
         f(&mut self.solver);
     }
 
@@ -166,16 +337,81 @@ impl LimnSolver {
     pub fn has_constraint(&self, constraint: &Constraint) -> bool {
         self.solver.has_constraint(constraint)
     }
+    /// The values last suggested for the edit variables of layout `id`, with their strengths
+    pub fn edit_values(&self, id: LayoutId) -> Vec<(VarType, f64, f64)> {
+        let mut values = Vec::new();
+        if let Some(layout) = self.layouts.layouts.get(&id) {
+            for edit_var in layout.edit_vars.values() {
+                match layout.vars.var_type(edit_var.var) {
+                    VarType::Other => (),
+                    var_type => values.push((var_type, edit_var.val, edit_var.strength)),
+                }
+            }
+        }
+        values
+    }
+    /// Stop suggesting a value for the variable `var_type` of layout `id`, so it's decided by the constraints alone
+    pub fn remove_edit_var(&mut self, id: LayoutId, var_type: VarType) {
+        let var = match self.layouts.layouts.get_mut(&id) {
+            Some(layout) => match layout.vars.get(var_type) {
+                Some(var) => {
+                    layout.edit_vars.remove(&var);
+                    var
+                }
+                None => return,
+            },
+            None => return,
+        };
+        if self.solver.has_edit_variable(&var) {
+            debug!("remove edit_var {}", self.layouts.fmt_variable(var));
+            self.solver.remove_edit_variable(var).unwrap();
+            self.dirty_vars.insert(var);
+        }
+    }
 
+    /// The variables that changed since the last call, with their new values.
+    /// Returns nothing without asking the solver, or counting towards `max_solves_per_frame`,
+    /// if no values were suggested and no constraints were added or removed since then.
+    /// Every changed variable is returned, not just those suggested, since suggesting one can move others.
+    /// Changes are put off once it's been called `max_solves_per_frame` times since the last `begin_frame`,
+    /// they aren't before `begin_frame` is first called.
     pub fn fetch_changes(&mut self) -> Vec<(LayoutId, VarType, f64)> {
+        if self.dirty_vars.is_empty() && !self.constraints_changed {
+            return Vec::new();
+        }
+        if self.frame_started {
+            self.frame_solves += 1;
+            if self.frame_solves > self.max_solves_per_frame {
+                if self.frame_solves == self.max_solves_per_frame + 1 {
+                    warn!("layout changed {} times this frame, putting off further changes until the next frame",
+                          self.max_solves_per_frame);
+                }
+                return Vec::new();
+            }
+        }
+        self.dirty_vars.clear();
+        self.constraints_changed = false;
+        let start = Instant::now();
         let mut changes = Vec::new();
+        let mut changed_vars = Vec::new();
         for &(var, val) in self.solver.fetch_changes() {
             debug!("solver {} = {}", self.layouts.fmt_variable(var), val);
             if let Some(layout_id) = self.layouts.var_ids.get(&var) {
                 let var_type = self.layouts.layouts[&layout_id].vars.var_type(var);
                 changes.push((*layout_id, var_type, val));
+
+                let change_count = self.frame_changes.entry(var).or_insert(0);
+                *change_count += 1;
+                if *change_count == self.thrash_limit + 1 {
+                    self.thrashing.push(self.layouts.fmt_variable(var));
+                }
+                changed_vars.push(var);
             }
         }
+        for var in changed_vars {
+            self.mark_stay_stale(var);
+        }
+        self.timings.fetch_changes += start.elapsed();
         changes
     }
 
@@ -251,13 +487,7 @@ impl LimnSolver {
     }
 
     pub fn debug_layout(&self, id: LayoutId) {
-        let bounds = {
-            let get_val = |var| self.solver.get_value(var) as f32;
-            let vars = &self.layouts.layouts[&id].vars;
-            let origin = Point::new(get_val(vars.left), get_val(vars.top));
-            let size = Size::new(get_val(vars.width), get_val(vars.height));
-            Rect::new(origin, size)
-        };
+        let bounds = self.layouts.layouts[&id].vars.to_rect(self);
         println!("{} {}", self.layouts.layout_name(id), bounds);
     }
 }
@@ -0,0 +1,33 @@
+//! Constraint strengths, so widget code can set strengths without depending on cassowary directly.
+
+pub use cassowary::strength::{WEAK, MEDIUM, STRONG, REQUIRED};
+
+/// Weaker than `WEAK`, but still greater than zero
+pub const WEAK_MINUS: f64 = WEAK / 2.0;
+/// Weaker than `MEDIUM`, but stronger than `WEAK`
+pub const MEDIUM_MINUS: f64 = (WEAK + MEDIUM) / 2.0;
+/// Weaker than `STRONG`, but stronger than `MEDIUM`
+pub const STRONG_MINUS: f64 = (MEDIUM + STRONG) / 2.0;
+
+/// A strength halfway between `a` and `b`
+pub fn strength_between(a: f64, b: f64) -> f64 {
+    (a + b) / 2.0
+}
+
+pub struct LayoutStrength;
+
+impl LayoutStrength {
+    /// Look up a strength by the name of its constant, eg. "strong" or "WEAK_MINUS"
+    pub fn from_name(name: &str) -> Option<f64> {
+        match name.to_lowercase().as_str() {
+            "weak_minus" => Some(WEAK_MINUS),
+            "weak" => Some(WEAK),
+            "medium_minus" => Some(MEDIUM_MINUS),
+            "medium" => Some(MEDIUM),
+            "strong_minus" => Some(STRONG_MINUS),
+            "strong" => Some(STRONG),
+            "required" => Some(REQUIRED),
+            _ => None,
+        }
+    }
+}
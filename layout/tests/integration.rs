@@ -10,8 +10,10 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 
 use cassowary::strength::*;
+use cassowary::Constraint;
+use cassowary::WeightedRelation::*;
 
-use layout::{LimnSolver, LayoutId, Layout, VarType, LayoutRef, LayoutVars};
+use layout::{LimnSolver, LayoutId, Layout, VarType, LayoutRef, LayoutVars, Edges};
 use layout::{Size, Point, Rect};
 use layout::constraint::*;
 
@@ -152,6 +154,93 @@ fn edit_var() {
     layout.update();
 }
 
+#[test]
+fn edit_values() {
+    let mut layout = TestLayout::new();
+
+    let mut panel = layout.new_widget("panel");
+    panel.add(top_left(Point::new(0.0, 0.0)));
+    panel.edit_width().set(200.0).strength(STRONG);
+    panel.edit_height().set(100.0).strength(WEAK);
+    let panel_id = panel.id;
+
+    layout.add_root(panel.clone());
+    layout.update();
+    let mut values = layout.solver.edit_values(panel_id);
+    values.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    assert_eq!(values, vec![(VarType::Height, 100.0, WEAK), (VarType::Width, 200.0, STRONG)]);
+
+    // suggesting a new value replaces the old one
+    panel.edit_width().set(150.0).strength(STRONG);
+    layout.update();
+    let mut values = layout.solver.edit_values(panel_id);
+    values.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    assert_eq!(values, vec![(VarType::Height, 100.0, WEAK), (VarType::Width, 150.0, STRONG)]);
+    assert_eq!(panel.vars.get(VarType::Width), Some(panel.vars.width));
+}
+
+#[test]
+fn conflicting_constraint_returned() {
+    let mut layout = TestLayout::new();
+    layout.solver.strict = false;
+
+    let mut widget = layout.new_widget("widget");
+    widget.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        width(100.0),
+    ]);
+    layout.add_root(widget.clone());
+    layout.update();
+
+    let conflict: Constraint = widget.vars.width | EQ(REQUIRED) | 50.0;
+    widget.add(conflict.clone());
+    widget.add(height(20.0));
+    assert!(layout.solver.update_layout(widget.deref_mut()) == Err(conflict));
+    // the rest of the changes are still made
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 20.0)),
+    }));
+}
+
+#[test]
+fn keep_position_policy() {
+    use layout::solver::PriorityPolicy;
+
+    let mut layout = TestLayout::new();
+    layout.solver.set_constraint_priority_policy(PriorityPolicy::KeepPosition);
+
+    let mut widget = layout.new_widget("widget");
+    let position = vec![
+        widget.vars.left | EQ(REQUIRED) | 50.0,
+        widget.vars.top | EQ(REQUIRED) | 50.0,
+    ];
+    widget.add(position.clone());
+    widget.add(size(Size::new(100.0, 100.0)));
+    layout.add_root(widget.clone());
+    layout.update();
+    layout.solver.begin_frame();
+
+    // without the position constraint, the widget is pulled weakly towards the origin,
+    // but stays where it was since its last position is preferred
+    widget.remove_constraints(position);
+    let pull = vec![
+        widget.vars.left | EQ(WEAK / 4.0) | 0.0,
+        widget.vars.top | EQ(WEAK / 4.0) | 0.0,
+    ];
+    widget.add(pull);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(50.0, 50.0), Size::new(100.0, 100.0)),
+    }));
+
+    layout.solver.set_constraint_priority_policy(PriorityPolicy::Strict);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 100.0)),
+    }));
+}
+
 #[test]
 fn linear_layout_fill() {
     use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation, ItemAlignment};
@@ -381,6 +470,458 @@ fn linear_layout_remove() {
     }));
 }
 
+#[test]
+fn linear_layout_flex_weight() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation, ItemAlignment};
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut item_1 = layout.new_widget("item_1");
+    let mut item_2 = layout.new_widget("item_2");
+    let mut item_3 = layout.new_widget("item_3");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 10.0))
+    ]);
+    item_1.add(width(10.0));
+    item_2.set_flex_weight(1.0);
+    item_3.set_flex_weight(2.0);
+    let mut settings = LinearLayoutSettings::new(Orientation::Horizontal);
+    settings.item_align = ItemAlignment::Fill;
+    let linear_layout = LinearLayout::new(&mut *root, settings);
+    root.set_container(linear_layout);
+
+    root.add_child(&mut *item_1);
+    root.add_child(&mut *item_2);
+    root.add_child(&mut *item_3);
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 10.0)),
+        item_1.id => Rect::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0)),
+        item_2.id => Rect::new(Point::new(10.0, 0.0), Size::new(30.0, 10.0)),
+        item_3.id => Rect::new(Point::new(40.0, 0.0), Size::new(60.0, 10.0)),
+    }));
+}
+#[test]
+fn linear_layout_insert() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation, ItemAlignment, Spacing};
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut item_1 = layout.new_widget("item_1");
+    let mut item_2 = layout.new_widget("item_2");
+    let mut item_3 = layout.new_widget("item_3");
+    let mut item_4 = layout.new_widget("item_4");
+
+    root.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 10.0))
+    ]);
+    item_1.add(width(20.0));
+    item_2.add(width(20.0));
+    item_3.add(width(20.0));
+    item_4.add(width(20.0));
+    let mut settings = LinearLayoutSettings::new(Orientation::Horizontal);
+    settings.item_align = ItemAlignment::Fill;
+    settings.spacing = Spacing::End;
+    settings.padding = 5.0;
+    let linear_layout = LinearLayout::new(&mut *root, settings);
+    root.set_container(linear_layout);
+
+    root.add_child(&mut *item_1);
+    root.add_child(&mut *item_3);
+
+    layout.add_root(root.clone());
+    layout.update();
+
+    root.insert_child(1, &mut *item_2);
+    root.insert_child(0, &mut *item_4);
+    layout.update();
+    assert_eq!(root.get_children(), &vec![item_4.id, item_1.id, item_2.id, item_3.id]);
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 10.0)),
+        item_4.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 10.0)),
+        item_1.id => Rect::new(Point::new(25.0, 0.0), Size::new(20.0, 10.0)),
+        item_2.id => Rect::new(Point::new(50.0, 0.0), Size::new(20.0, 10.0)),
+        item_3.id => Rect::new(Point::new(75.0, 0.0), Size::new(20.0, 10.0)),
+    }));
+
+    // removing an inserted child closes the gap it leaves
+    root.remove_child(&mut *item_4);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 10.0)),
+        item_1.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 10.0)),
+        item_2.id => Rect::new(Point::new(25.0, 0.0), Size::new(20.0, 10.0)),
+        item_3.id => Rect::new(Point::new(50.0, 0.0), Size::new(20.0, 10.0)),
+    }));
+}
+
+#[test]
+fn layout_vars_rects() {
+    let mut layout = TestLayout::new();
+
+    let mut parent = layout.new_widget("parent");
+    let mut child = layout.new_widget("child");
+    parent.add(constraints![
+        top_left(Point::new(10.0, 20.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    child.add(constraints![
+        top_left(Point::new(30.0, 50.0)),
+        size(Size::new(20.0, 10.0))
+    ]);
+
+    layout.add_root(parent.clone());
+    layout.add_root(child.clone());
+    layout.update();
+    let child_vars = child.layout_ref();
+    let parent_vars = parent.layout_ref();
+    assert_eq!(child_vars.to_rect(&layout.solver),
+        Rect::new(Point::new(30.0, 50.0), Size::new(20.0, 10.0)));
+    assert_eq!(child_vars.relative_to(&layout.solver, &parent_vars),
+        Rect::new(Point::new(20.0, 30.0), Size::new(20.0, 10.0)));
+    assert_eq!(child_vars.apply_scroll_offset(&layout.solver, Point::new(5.0, 15.0)),
+        Rect::new(Point::new(25.0, 35.0), Size::new(20.0, 10.0)));
+}
+#[test]
+fn layout_vars_center_in() {
+    let mut layout = TestLayout::new();
+
+    let mut parent = layout.new_widget("parent");
+    let mut centered = layout.new_widget("centered");
+    let mut horizontal = layout.new_widget("horizontal");
+    parent.add(constraints![
+        top_left(Point::new(10.0, 20.0)),
+        size(Size::new(100.0, 100.0))
+    ]);
+    let parent_vars = parent.layout_ref();
+    centered.add(size(Size::new(20.0, 10.0)));
+    let center_constraints = centered.vars.center_in(&parent_vars);
+    centered.add(center_constraints);
+    horizontal.add(constraints![
+        size(Size::new(40.0, 10.0)),
+        align_top(&parent_vars),
+    ]);
+    let center_constraints = horizontal.vars.center_horizontally_in(&parent_vars);
+    horizontal.add(center_constraints);
+
+    layout.add_root(parent.clone());
+    layout.add_root(centered.clone());
+    layout.add_root(horizontal.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        parent.id => Rect::new(Point::new(10.0, 20.0), Size::new(100.0, 100.0)),
+        centered.id => Rect::new(Point::new(50.0, 65.0), Size::new(20.0, 10.0)),
+        horizontal.id => Rect::new(Point::new(40.0, 20.0), Size::new(40.0, 10.0)),
+    }));
+}
+#[test]
+fn strength_presets() {
+    use layout::strength::{self, LayoutStrength};
+
+    assert!(strength::WEAK_MINUS < strength::WEAK);
+    assert!(strength::WEAK < strength::MEDIUM_MINUS && strength::MEDIUM_MINUS < strength::MEDIUM);
+    assert!(strength::MEDIUM < strength::STRONG_MINUS && strength::STRONG_MINUS < strength::STRONG);
+    assert_eq!(strength::strength_between(strength::WEAK, strength::MEDIUM), strength::MEDIUM_MINUS);
+    assert_eq!(LayoutStrength::from_name("strong"), Some(strength::STRONG));
+    assert_eq!(LayoutStrength::from_name("WEAK_MINUS"), Some(strength::WEAK_MINUS));
+    assert_eq!(LayoutStrength::from_name("very_strong"), None);
+}
+#[test]
+fn remeasure_moves_sibling() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation};
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut label = layout.new_widget("label");
+    let mut sibling = layout.new_widget("sibling");
+
+    root.add(top_left(Point::new(0.0, 0.0)));
+    let linear_layout = LinearLayout::new(&mut *root, LinearLayoutSettings::new(Orientation::Horizontal));
+    root.set_container(linear_layout);
+
+    // the measured size of "9"
+    let label_size = label.create_constraint(size(Size::new(10.0, 10.0)));
+    label.add(label_size.clone());
+    sibling.add(size(Size::new(10.0, 10.0)));
+    root.add_child(&mut *label);
+    root.add_child(&mut *sibling);
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        label.id => Rect::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0)),
+        sibling.id => Rect::new(Point::new(10.0, 0.0), Size::new(10.0, 10.0)),
+    }));
+
+    // the label text changes to "1000" and is measured again
+    label.remove_constraints(label_size);
+    label.add(size(Size::new(40.0, 10.0)));
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        label.id => Rect::new(Point::new(0.0, 0.0), Size::new(40.0, 10.0)),
+        sibling.id => Rect::new(Point::new(40.0, 0.0), Size::new(10.0, 10.0)),
+    }));
+}
+#[test]
+fn hidden_before_first_update() {
+    let mut layout = TestLayout::new();
+
+    let mut parent = layout.new_widget("parent");
+    let mut child = layout.new_widget("child");
+    parent.add(constraints![
+        top_left(Point::new(0.0, 0.0)),
+        size(Size::new(100.0, 100.0)),
+    ]);
+    child.add(constraints![
+        align_top(&parent).padding(10.0),
+        align_left(&parent),
+        match_width(&parent),
+        height(20.0),
+    ]);
+    child.hide();
+    parent.add_child(&mut *child);
+
+    layout.add_root(parent.clone());
+    layout.update();
+    // hidden layouts stay at zero, so no changes are reported for them
+    assert!(layout.layout_rects.get(&child.id).map_or(true, |rect| *rect == Rect::zero()));
+
+    child.show();
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        child.id => Rect::new(Point::new(0.0, 10.0), Size::new(100.0, 20.0)),
+    }));
+}
+
+#[test]
+fn linear_layout_fits_children() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation, ItemAlignment};
+
+    let mut layout = TestLayout::new();
+
+    // a form, with a row of a label and a field for each entry
+    let mut root = layout.new_widget("form");
+    root.add(top_left(Point::new(0.0, 0.0)));
+    let mut settings = LinearLayoutSettings::new(Orientation::Vertical);
+    settings.item_align = ItemAlignment::Left;
+    settings.padding = 5.0;
+    let linear_layout = LinearLayout::new(&mut *root, settings);
+    root.set_container(linear_layout);
+
+    let mut rows = Vec::new();
+    for index in 0..3 {
+        let mut row = layout.new_widget(&format!("row_{}", index));
+        let mut settings = LinearLayoutSettings::new(Orientation::Horizontal);
+        settings.item_align = ItemAlignment::Top;
+        settings.padding = 10.0;
+        let linear_layout = LinearLayout::new(&mut *row, settings);
+        row.set_container(linear_layout);
+
+        let mut label = layout.new_widget(&format!("label_{}", index));
+        label.add(size(Size::new(50.0, 20.0)));
+        // the last field is wider than the others
+        let field_width = if index == 2 { 200.0 } else { 100.0 };
+        let mut field = layout.new_widget(&format!("field_{}", index));
+        field.add(size(Size::new(field_width, 30.0)));
+        row.add_child(label.deref_mut());
+        row.add_child(field.deref_mut());
+        root.add_child(row.deref_mut());
+        rows.push(row);
+    }
+
+    layout.add_root(root.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(260.0, 100.0)),
+        rows[0].id => Rect::new(Point::new(0.0, 0.0), Size::new(160.0, 30.0)),
+        rows[1].id => Rect::new(Point::new(0.0, 35.0), Size::new(160.0, 30.0)),
+        rows[2].id => Rect::new(Point::new(0.0, 70.0), Size::new(260.0, 30.0)),
+    }));
+
+    // the form shrinks to fit the remaining rows, in both directions
+    root.remove_child(rows[2].deref_mut());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        root.id => Rect::new(Point::new(0.0, 0.0), Size::new(160.0, 65.0)),
+        rows[0].id => Rect::new(Point::new(0.0, 0.0), Size::new(160.0, 30.0)),
+        rows[1].id => Rect::new(Point::new(0.0, 35.0), Size::new(160.0, 30.0)),
+    }));
+}
+
+#[test]
+fn thrashing_variable_reported() {
+    let mut layout = TestLayout::new();
+    layout.solver.thrash_limit = 2;
+
+    let mut widget = layout.new_widget("widget");
+    widget.add(top_left(Point::new(0.0, 0.0)));
+    layout.add_root(widget.clone());
+
+    // a handler resizing the widget every time the layout changes
+    for width in 1..4 {
+        widget.edit_width().set(width as f32 * 10.0);
+        widget.edit_height().set(10.0);
+        layout.update();
+    }
+    let mut thrashing = layout.solver.take_thrashing();
+    thrashing.sort();
+    assert_eq!(thrashing, vec!["widget.right".to_owned(), "widget.width".to_owned()]);
+    // only reported once per frame
+    widget.edit_width().set(50.0);
+    layout.update();
+    assert!(layout.solver.take_thrashing().is_empty());
+
+    layout.solver.begin_frame();
+    widget.edit_width().set(60.0);
+    layout.update();
+    assert!(layout.solver.take_thrashing().is_empty());
+}
+
+#[test]
+fn solves_per_frame_capped() {
+    let mut layout = TestLayout::new();
+    layout.solver.max_solves_per_frame = 1;
+    layout.solver.begin_frame();
+
+    let mut widget = layout.new_widget("widget");
+    widget.add(top_left(Point::new(0.0, 0.0)));
+    widget.edit_width().set(10.0);
+    widget.edit_height().set(10.0);
+    layout.add_root(widget.clone());
+    layout.update();
+
+    // changes past the cap are put off until the next frame
+    widget.edit_width().set(20.0);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(10.0, 10.0)),
+    }));
+    assert!(layout.solver.begin_frame());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 10.0)),
+    }));
+    assert!(!layout.solver.begin_frame());
+}
+
+#[test]
+fn solves_not_capped_before_first_frame() {
+    let mut layout = TestLayout::new();
+    layout.solver.max_solves_per_frame = 1;
+
+    let mut widget = layout.new_widget("widget");
+    widget.add(top_left(Point::new(0.0, 0.0)));
+    widget.edit_width().set(10.0);
+    widget.edit_height().set(10.0);
+    layout.add_root(widget.clone());
+    layout.update();
+    widget.edit_width().set(20.0);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 10.0)),
+    }));
+    assert!(!layout.solver.begin_frame());
+}
+
+#[test]
+fn unchanged_layout_not_solved() {
+    let mut layout = TestLayout::new();
+    layout.solver.max_solves_per_frame = 2;
+
+    let mut widget = layout.new_widget("widget");
+    widget.add(top_left(Point::new(0.0, 0.0)));
+    widget.edit_width().set(10.0);
+    widget.edit_height().set(10.0);
+    layout.add_root(widget.clone());
+    layout.update();
+
+    // nothing has changed, so this doesn't use up one of the frame's solves
+    assert!(layout.solver.fetch_changes().is_empty());
+    widget.edit_width().set(20.0);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        widget.id => Rect::new(Point::new(0.0, 0.0), Size::new(20.0, 10.0)),
+    }));
+}
+
+#[test]
+fn suggested_value_moves_sibling() {
+    use layout::linear_layout::{LinearLayout, LinearLayoutSettings, Orientation};
+
+    let mut layout = TestLayout::new();
+
+    let mut root = layout.new_widget("root");
+    let mut label = layout.new_widget("label");
+    let mut sibling = layout.new_widget("sibling");
+
+    root.add(top_left(Point::new(0.0, 0.0)));
+    let linear_layout = LinearLayout::new(&mut *root, LinearLayoutSettings::new(Orientation::Horizontal));
+    root.set_container(linear_layout);
+    label.add(height(10.0));
+    label.edit_width().set(10.0).strength(STRONG);
+    sibling.add(size(Size::new(10.0, 10.0)));
+    root.add_child(&mut *label);
+    root.add_child(&mut *sibling);
+
+    layout.add_root(root.clone());
+    layout.update();
+
+    // only the label's width is suggested, the sibling's change is still fetched
+    label.edit_width().set(40.0).strength(STRONG);
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        label.id => Rect::new(Point::new(0.0, 0.0), Size::new(40.0, 10.0)),
+        sibling.id => Rect::new(Point::new(40.0, 0.0), Size::new(10.0, 10.0)),
+    }));
+}
+
+#[test]
+fn pinned_edges() {
+    let mut layout = TestLayout::new();
+
+    let mut parent = layout.new_widget("parent");
+    let mut cover = layout.new_widget("cover");
+    let mut bar = layout.new_widget("bar");
+    parent.add(constraints![
+        top_left(Point::new(10.0, 20.0)),
+        size(Size::new(200.0, 100.0)),
+    ]);
+    let cover_constraints = cover.vars.pinned_to(&parent.vars, Edges::ALL);
+    assert_eq!(cover_constraints.len(), 4);
+    cover.add(cover_constraints);
+    let bar_constraints = bar.vars.pinned_to(&parent.vars, Edges::LEFT | Edges::RIGHT | Edges::TOP);
+    assert_eq!(bar_constraints.len(), 3);
+    bar.add(bar_constraints);
+    bar.add(height(30.0));
+
+    layout.add_root(parent.clone());
+    layout.add_root(cover.clone());
+    layout.add_root(bar.clone());
+    layout.update();
+    assert!(layout.match_layouts(hashmap!{
+        parent.id => Rect::new(Point::new(10.0, 20.0), Size::new(200.0, 100.0)),
+        cover.id => Rect::new(Point::new(10.0, 20.0), Size::new(200.0, 100.0)),
+        bar.id => Rect::new(Point::new(10.0, 20.0), Size::new(200.0, 30.0)),
+    }));
+}
+
+#[test]
+fn edge_sets() {
+    assert_eq!(Edges::LEFT | Edges::RIGHT, Edges::HORIZONTAL);
+    assert_eq!(Edges::HORIZONTAL | Edges::VERTICAL, Edges::ALL);
+    assert!(Edges::ALL.contains(Edges::TOP));
+    assert!(!Edges::VERTICAL.contains(Edges::LEFT));
+    assert!(Edges::empty().is_empty());
+}
+
 #[derive(Clone)]
 struct SharedLayout(Rc<RefCell<Layout>>);
 impl SharedLayout {
@@ -438,7 +979,7 @@ impl TestLayout {
         self.roots.push(layout);
     }
     fn update_layout(&mut self, mut layout: SharedLayout) {
-        self.solver.update_layout(layout.deref_mut());
+        self.solver.update_layout(layout.deref_mut()).unwrap();
         for child in layout.get_children() {
             let layout = self.layouts[child].clone();
             self.update_layout(layout);
@@ -1,6 +1,8 @@
 use std::time::{Instant, Duration};
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::thread;
+use std::sync::mpsc;
 
 use glutin;
 
@@ -22,7 +24,12 @@ use geometry::Size;
 pub struct App {
     ui: Ui,
     next_frame_time: Instant,
-    events_loop: Rc<RefCell<glutin::EventsLoop>>,
+    // time the last `AnimationFrame` was sent, while any animation is active
+    last_animation_frame: Option<Instant>,
+    // `None` for a headless app
+    events_loop: Option<Rc<RefCell<glutin::EventsLoop>>>,
+    // wakes the events loop when the next timer is due, while the main loop is blocked waiting for events
+    timer_waker: Option<mpsc::Sender<Instant>>,
 }
 
 impl App {
@@ -32,12 +39,32 @@ impl App {
         let mut app = App {
             ui: ui,
             next_frame_time: Instant::now(),
-            events_loop: Rc::new(RefCell::new(events_loop)),
+            last_animation_frame: None,
+            timer_waker: Some(timer_waker(events_loop.create_proxy())),
+            events_loop: Some(Rc::new(RefCell::new(events_loop))),
         };
         app.initialize_handlers();
         app
     }
 
+    /// An app without a window, that handles events and solves the layout of a UI of `size` without drawing it,
+    /// eg. to test widgets. Add widgets with `attach`, send input with `RecordedInput::dispatch`,
+    /// and handle the events that follows with `Ui::frame`. Each thread can run its own headless app.
+    pub fn headless(size: Size) -> Self {
+        event::queue_claim_thread();
+        let mut app = App {
+            ui: Ui::headless(size),
+            next_frame_time: Instant::now(),
+            last_animation_frame: None,
+            events_loop: None,
+            timer_waker: None,
+        };
+        app.initialize_handlers();
+        app.ui.window_resized(size);
+        app.handle_events();
+        app
+    }
+
     /// Initialize the handlers that are used in a typical desktop app.
     /// The handlers that make up the event flow in an application are configurable
     fn initialize_handlers(&mut self) {
@@ -50,20 +77,54 @@ impl App {
         self.add_drag_handlers();
     }
 
+    pub fn ui(&mut self) -> &mut Ui {
+        &mut self.ui
+    }
+
+    /// Add the root widget and handle the events that sets off.
+    /// Used instead of `main_loop` by apps that run their own main loop,
+    /// calling `poll_events`, `Ui::frame` and `Ui::draw_into` every frame.
+    pub fn attach(&mut self, root: WidgetBuilder) {
+        self.ui.root.add_child(root);
+        self.handle_events();
+    }
+
+    /// Handle any window events received since the last call, without waiting for more
+    pub fn poll_events(&mut self) {
+        if let Some(events_loop) = self.events_loop.clone() {
+            events_loop.borrow_mut().poll_events(|event| {
+                self.handle_window_event(event);
+            });
+        }
+    }
+
+    /// Release the renderer, for apps that run their own main loop, once they're done with the UI
+    pub fn deinit(self) {
+        if let Some(render) = self.ui.render {
+            render.deinit();
+        }
+    }
+
     fn handle_window_event(&mut self, event: glutin::Event) {
         debug!("handle window event {:?}", event);
-        if let glutin::Event::WindowEvent { event, .. } = event {
-            if let glutin::WindowEvent::Resized(width, height) = event {
-                self.ui.window_resized(Size::new(width as f32, height as f32));
-            } else {
-                self.ui.event(InputEvent(event));
+        match event {
+            glutin::Event::WindowEvent { event, .. } => {
+                if let glutin::WindowEvent::Resized(width, height) = event {
+                    self.ui.window_resized(Size::new(width as f32, height as f32));
+                } else {
+                    self.ui.event(InputEvent(event));
+                }
+            }
+            glutin::Event::DeviceEvent { event: glutin::DeviceEvent::MouseMotion { delta }, .. } => {
+                self.ui.relative_motion(delta.0 as f32, delta.1 as f32);
             }
+            _ => (),
         }
     }
     /// Application main loop
     pub fn main_loop(mut self, root: WidgetBuilder) {
         self.ui.root.add_child(root);
-        let events_loop = self.events_loop.clone();
+        let events_loop = self.events_loop.clone().expect("A headless app has no main loop, use Ui::frame");
         let mut events_loop = events_loop.borrow_mut();
 
         // Handle set up events to allow layout to 'settle' and initialize the window size to the initial layout size
@@ -74,7 +135,9 @@ impl App {
                 self.handle_window_event(event);
             });
             if self.ui.should_close() {
-                self.ui.render.deinit();
+                if let Some(render) = self.ui.render.take() {
+                    render.deinit();
+                }
                 return;
             }
             self.handle_events();
@@ -86,11 +149,27 @@ impl App {
                 } else {
                     self.next_frame_time += frame_length;
                 }
+                self.ui.begin_frame();
+                self.ui.fire_timers();
+                self.animation_frame(now);
+                self.handle_events();
                 self.ui.draw_if_needed();
             }
             self.ui.update();
 
-            if !self.ui.needs_redraw() && !self.ui.render.frame_ready() {
+            if self.ui.render.as_mut().map_or(false, |render| render.frame_ready()) {
+                continue;
+            }
+            if self.ui.needs_frame() {
+                // sleep until the next frame rather than spinning, events that arrive meanwhile are handled then
+                let now = Instant::now();
+                if self.next_frame_time > now {
+                    thread::sleep(self.next_frame_time - now);
+                }
+            } else {
+                if let (Some(deadline), Some(waker)) = (self.ui.next_timer(), self.timer_waker.as_ref()) {
+                    waker.send(deadline).ok();
+                }
                 let mut events = Vec::new();
                 events_loop.run_forever(|window_event| {
                     events.push(window_event);
@@ -103,11 +182,20 @@ impl App {
         }
     }
 
+    /// Advance any active animations, the events they send are handled before the frame is drawn
+    fn animation_frame(&mut self, now: Instant) {
+        if self.ui.animations_active() {
+            let elapsed = self.last_animation_frame.map(|last| now - last).unwrap_or(Duration::new(0, 0));
+            self.last_animation_frame = Some(now);
+            self.ui.animation_frame(elapsed);
+        } else {
+            self.last_animation_frame = None;
+        }
+    }
+
     /// Handle all the pending events in the event queue
     fn handle_events(&mut self) {
-        while let Some((event_address, type_id, data)) = event::queue_next() {
-            self.ui.handle_event(event_address, type_id, data.as_ref());
-        }
+        self.ui.handle_events();
     }
 
     /// Add a new stateful global event handler
@@ -121,3 +209,33 @@ impl App {
         self
     }
 }
+
+// a thread that wakes the events loop at the last deadline it was sent
+fn timer_waker(events_loop_proxy: glutin::EventsLoopProxy) -> mpsc::Sender<Instant> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut deadline: Option<Instant> = None;
+        loop {
+            let received = match deadline {
+                Some(wake_at) => {
+                    let now = Instant::now();
+                    if wake_at <= now {
+                        deadline = None;
+                        if events_loop_proxy.wakeup().is_err() {
+                            return;
+                        }
+                        continue;
+                    }
+                    receiver.recv_timeout(wake_at - now)
+                }
+                None => receiver.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(wake_at) => deadline = Some(wake_at),
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+    sender
+}
@@ -16,6 +16,140 @@ impl ::std::fmt::Debug for Color {
     }
 }
 
+/// Error returned by `Color::from_hex` for a string that isn't a hex color
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseColorError {
+    /// The string doesn't start with `#`
+    MissingHash,
+    /// The number of digits isn't 3, 4, 6 or 8
+    InvalidLength(usize),
+    InvalidDigit(char),
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParseColorError::MissingHash => write!(f, "hex color must start with '#'"),
+            ParseColorError::InvalidLength(len) => write!(f, "hex color must have 3, 4, 6 or 8 digits, found {}", len),
+            ParseColorError::InvalidDigit(digit) => write!(f, "invalid hex digit '{}'", digit),
+        }
+    }
+}
+
+impl ::std::error::Error for ParseColorError {
+    fn description(&self) -> &str {
+        "invalid hex color"
+    }
+}
+
+impl Color {
+    /// Parse a web style hex color: `#RRGGBB`, `#RRGGBBAA`, or the shorthands `#RGB` and `#RGBA`.
+    /// Colors without an alpha component are opaque.
+    pub fn from_hex(hex: &str) -> Result<Color, ParseColorError> {
+        if !hex.starts_with('#') {
+            return Err(ParseColorError::MissingHash);
+        }
+        let mut digits = Vec::new();
+        for digit in hex[1..].chars() {
+            match digit.to_digit(16) {
+                Some(value) => digits.push(value),
+                None => return Err(ParseColorError::InvalidDigit(digit)),
+            }
+        }
+        // shorthand digits are doubled, so #F80 is #FF8800
+        let digits = match digits.len() {
+            3 | 4 => digits.iter().flat_map(|digit| vec![*digit, *digit]).collect(),
+            6 | 8 => digits,
+            len => return Err(ParseColorError::InvalidLength(len)),
+        };
+        let mut color = digits.iter().fold(0, |color, digit| color << 4 | digit);
+        if digits.len() == 6 {
+            color = color << 8 | 0xFF;
+        }
+        Ok(Color(color))
+    }
+    /// Format as `#RRGGBB`, ignoring alpha
+    pub fn to_hex(&self) -> String {
+        format!("#{:06X}", self.0 >> 8)
+    }
+    /// Color from its red, green, blue and alpha channels
+    pub fn from_rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color((red as u32) << 24 | (green as u32) << 16 | (blue as u32) << 8 | alpha as u32)
+    }
+    /// The red, green, blue and alpha channels
+    pub fn to_rgba(&self) -> (u8, u8, u8, u8) {
+        ((self.0 >> 24) as u8, (self.0 >> 16) as u8, (self.0 >> 8) as u8, self.0 as u8)
+    }
+    /// Opaque color from hue in degrees, and saturation and lightness between 0 and 1
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        let saturation = saturation.max(0.0).min(1.0);
+        let lightness = lightness.max(0.0).min(1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        from_hue_chroma(hue, chroma, lightness - chroma / 2.0)
+    }
+    /// Hue in degrees, and saturation and lightness between 0 and 1, ignoring alpha
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let ColorF { r, g, b, .. } = rgba(self.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return (0.0, 0.0, lightness);
+        }
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+        (hue(r, g, b, max, chroma), saturation, lightness)
+    }
+    /// Opaque color from hue in degrees, and saturation and value between 0 and 1
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Color {
+        let saturation = saturation.max(0.0).min(1.0);
+        let value = value.max(0.0).min(1.0);
+        let chroma = value * saturation;
+        from_hue_chroma(hue, chroma, value - chroma)
+    }
+    /// Hue in degrees, and saturation and value between 0 and 1, ignoring alpha.
+    /// The hue of grays is 0, and the saturation of black is 0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let ColorF { r, g, b, .. } = rgba(self.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        if chroma == 0.0 {
+            return (0.0, 0.0, max);
+        }
+        (hue(r, g, b, max, chroma), chroma / max, max)
+    }
+}
+
+// opaque color with the given hue and chroma, with `m` added to each channel
+fn from_hue_chroma(hue: f32, chroma: f32, m: f32) -> Color {
+    let hue = ((hue % 360.0) + 360.0) % 360.0 / 60.0;
+    let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |value: f32| ((value + m) * 255.0).round() as u32;
+    Color(channel(r) << 24 | channel(g) << 16 | channel(b) << 8 | 0xFF)
+}
+
+// hue in degrees of a color with a non zero chroma
+fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    let hue = if max == r {
+        ((g - b) / chroma) % 6.0
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    let hue = hue * 60.0;
+    if hue < 0.0 { hue + 360.0 } else { hue }
+}
+
 impl Into<ColorF> for Color {
     fn into(self) -> ColorF {
         rgba(self.0)
@@ -45,3 +179,7 @@ pub const FUSCHIA: Color = Color(0xFF00FFFF);
 pub const CYAN: Color = Color(0x00FFFFFF);
 
 pub const BLUE_HIGHLIGHT: Color = Color(0x6060D0FF);
+/// Drawn over a widget whose event handler panicked
+pub const ERROR_OVERLAY: Color = Color(0xFF000040);
+/// Drawn under raised surfaces, like cards
+pub const SHADOW: Color = Color(0x00000040);
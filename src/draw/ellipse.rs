@@ -26,7 +26,8 @@ impl EllipseState {
     }
 }
 
-fn clip_ellipse(rect: Rect) -> LocalClip {
+/// A clip to the ellipse inscribed in `rect`, to crop other primitives to a circle or ellipse
+pub fn clip_ellipse(rect: Rect) -> LocalClip {
     let rect = rect.typed();
     let clip_region = ComplexClipRegion::new(rect, BorderRadius::uniform_size(rect.size / 2.0));
     LocalClip::RoundedRect(rect, clip_region)
@@ -38,6 +39,12 @@ fn push_ellipse(renderer: &mut RenderBuilder, rect: Rect, clip_rect: Rect, color
     renderer.builder.push_rect(&info, color.into());
 }
 
+/// Draw a filled circle, for widgets that draw circles as part of their own draw state
+pub fn draw_circle(renderer: &mut RenderBuilder, center: Point, radius: f32, color: Color) {
+    let rect = Rect::new(Point::new(center.x - radius, center.y - radius), Size::new(radius * 2.0, radius * 2.0));
+    push_ellipse(renderer, rect, rect, color);
+}
+
 impl Draw for EllipseState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
         // rounding is a hack to prevent bug in webrender that produces artifacts around the corners
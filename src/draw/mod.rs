@@ -1,4 +1,5 @@
 pub mod rect;
 pub mod ellipse;
 pub mod text;
-pub mod image;
\ No newline at end of file
+pub mod image;
+pub mod wedge;
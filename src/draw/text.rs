@@ -1,10 +1,13 @@
-use webrender_api::{LayoutPoint, GlyphInstance, PrimitiveInfo, FontInstanceKey};
+use std::cmp;
+
+use webrender_api::{LayoutPoint, LayoutVector2D, GlyphInstance, PrimitiveInfo, FontInstanceKey};
 use rusttype::{Scale, GlyphId, VMetrics};
 
 use render::RenderBuilder;
 use text_layout::{self, Wrap, Align};
-use resources::resources;
-use geometry::{Size, Rect, RectExt, Vector};
+use text_layout::runs::{TextRun, RunsLayout};
+use resources::{resources, DEFAULT_FONT};
+use geometry::{Size, Rect, RectExt, Point, Vector};
 use render;
 use widget::draw::Draw;
 use widget::property::PropSet;
@@ -21,28 +24,206 @@ pub struct TextState {
     pub background_color: Color,
     pub wrap: Wrap,
     pub align: Align,
+    /// If set, the smallest font size the text is drawn at when it's too wide for its bounds at `font_size`,
+    /// see `effective_font_size`. The widget is still measured at `font_size`.
+    pub shrink_to_fit: Option<f32>,
+    /// If set, an outline drawn around the glyphs, behind them, so the text is readable over any background
+    pub outline: Option<TextOutline>,
+    /// Draw a line under each line of text, in the text color
+    pub underline: bool,
+    /// Draw a line through the middle of each line of text, in the text color
+    pub strikethrough: bool,
+    // if set, the text is drawn from these runs instead of from `text`, see `set_runs`
+    runs: Option<Vec<StyledRun>>,
+    // the font size last chosen by `fit_to_width`, and what it was chosen for
+    fitted: Option<FittedFontSize>,
+}
+/// An outline around text, see `TextState::outline`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextOutline {
+    pub color: Color,
+    /// How far the outline extends past the glyphs. It isn't included in the measured size of the text,
+    /// so it can be cropped at the edges of the widget, unless the widget is padded.
+    pub width: f32,
+}
+
+impl TextOutline {
+    pub fn new(color: Color, width: f32) -> Self {
+        TextOutline {
+            color: color,
+            width: width,
+        }
+    }
+}
+
+/// The offsets the glyphs are drawn at in the outline color, behind the text, to outline text `width` wide.
+/// An approximation of a stroke, around the glyphs in eight directions.
+pub fn outline_offsets(width: f32) -> Vec<Vector> {
+    let diagonal = width * ::std::f32::consts::FRAC_1_SQRT_2;
+    vec![
+        Vector::new(-width, 0.0),
+        Vector::new(width, 0.0),
+        Vector::new(0.0, -width),
+        Vector::new(0.0, width),
+        Vector::new(-diagonal, -diagonal),
+        Vector::new(diagonal, -diagonal),
+        Vector::new(-diagonal, diagonal),
+        Vector::new(diagonal, diagonal),
+    ]
+}
+
+/// A part of the text of a `TextState` with its own color, font or font size, see `TextState::set_runs`.
+/// Whatever isn't set is taken from the `TextState`, so a bold run only needs a bold font, eg. `NotoSans/NotoSans-Bold`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyledRun {
+    pub text: String,
+    pub color: Option<Color>,
+    pub font: Option<String>,
+    pub font_size: Option<f32>,
+}
+
+impl StyledRun {
+    pub fn new(text: &str) -> Self {
+        StyledRun {
+            text: text.to_owned(),
+            color: None,
+            font: None,
+            font_size: None,
+        }
+    }
+    pub fn colored(text: &str, color: Color) -> Self {
+        StyledRun {
+            color: Some(color),
+            ..StyledRun::new(text)
+        }
+    }
+}
+
+/// The text of `runs`, one after another
+pub fn runs_text(runs: &[StyledRun]) -> String {
+    runs.iter().map(|run| run.text.as_str()).collect()
 }
+
+// a line drawn across text, see `TextState::underline` and `TextState::strikethrough`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TextDecoration {
+    Underline,
+    Strikethrough,
+}
+
+// how far below the baseline the center of a decoration line is drawn, negative for above the baseline,
+// for a font with `ascent` above the baseline and `descent` below it, negative like rusttype's
+fn decoration_offset(decoration: TextDecoration, ascent: f32, descent: f32) -> f32 {
+    match decoration {
+        TextDecoration::Underline => -descent / 3.0,
+        // roughly half the height of lowercase letters
+        TextDecoration::Strikethrough => -ascent * 0.3,
+    }
+}
+
+// thickness of the decoration lines drawn across text at `font_size`
+fn decoration_thickness(font_size: f32) -> f32 {
+    (font_size / 16.0).max(1.0)
+}
+
+/// Font size of text widgets at the default density of the theme
+pub const DEFAULT_FONT_SIZE: f32 = 24.0;
+/// How much the font size of text that doesn't fit is reduced by at a time, see `shrink_to_fit`
+const FIT_STEP: f32 = 1.0;
+
+struct FittedFontSize {
+    text: String,
+    font: String,
+    font_size: f32,
+    min_font_size: f32,
+    width: f32,
+    fitted: f32,
+}
+
+/// The largest font size, stepping down from `font_size` to no smaller than `min_font_size`,
+/// that `text_width` says is no wider than `width`, or `min_font_size` if none of them fit
+pub fn shrink_to_fit<F>(font_size: f32, min_font_size: f32, width: f32, text_width: F) -> f32
+    where F: Fn(f32) -> f32
+{
+    let mut size = font_size;
+    while size > min_font_size && text_width(size) > width {
+        size = (size - FIT_STEP).max(min_font_size);
+    }
+    size
+}
+
 impl Default for TextState {
     fn default() -> Self {
         TextState {
             text: "".to_owned(),
-            font: "NotoSans/NotoSans-Regular".to_owned(),
-            font_size: 24.0,
+            font: DEFAULT_FONT.to_owned(),
+            font_size: DEFAULT_FONT_SIZE,
             text_color: BLACK,
             background_color: TRANSPARENT,
             wrap: Wrap::Whitespace,
             align: Align::Start,
+            shrink_to_fit: None,
+            outline: None,
+            underline: false,
+            strikethrough: false,
+            runs: None,
+            fitted: None,
         }
     }
 }
 
+// what the size of the text, and the constraints added for it, depend on, see `TextState::measure_inputs`
+#[derive(PartialEq)]
+pub(crate) struct MeasureInputs {
+    text: String,
+    font: String,
+    font_size: f32,
+    wrap: Wrap,
+    shrink_to_fit: bool,
+    runs: Option<Vec<StyledRun>>,
+}
+
 impl TextState {
     pub fn new(text: &str) -> Self {
         let mut draw_state = TextState::default();
         draw_state.text = text.to_owned();
         draw_state
     }
+    /// Text made of `runs`, see `TextState::set_runs`
+    pub fn with_runs(runs: Vec<StyledRun>) -> Self {
+        let mut draw_state = TextState::default();
+        draw_state.set_runs(runs);
+        draw_state
+    }
+    /// Draw the text from `runs`, one after another, and set `text` to their text, so it can still be read as plain text.
+    /// Once `text` is set to anything else, the runs are dropped and `text` is drawn. `shrink_to_fit` only applies to `text`.
+    pub fn set_runs(&mut self, runs: Vec<StyledRun>) {
+        self.text = runs_text(&runs);
+        self.runs = Some(runs);
+    }
+    /// The runs the text is drawn from, see `set_runs`
+    pub fn runs(&self) -> Option<&[StyledRun]> {
+        match self.runs {
+            Some(ref runs) if runs_text(runs) == self.text => Some(runs),
+            _ => None,
+        }
+    }
+    /// What `measure` depends on, the widget is only measured again when these change
+    pub(crate) fn measure_inputs(&self) -> MeasureInputs {
+        MeasureInputs {
+            text: self.text.clone(),
+            font: self.font.clone(),
+            font_size: self.font_size,
+            wrap: self.wrap,
+            shrink_to_fit: self.shrink_to_fit.is_some(),
+            runs: self.runs().map(|runs| runs.to_vec()),
+        }
+    }
     pub fn measure(&self) -> Size {
+        if let Some(runs) = self.runs() {
+            let wrap = self.wrap;
+            return self.with_text_runs(runs, |text_runs| text_layout::runs::get_runs_size(text_runs, wrap));
+        }
         let line_height = self.line_height();
         let mut resources = resources();
         let font = resources.get_font(&self.font);
@@ -57,7 +238,48 @@ impl TextState {
         self.line_height()
     }
     pub fn line_height(&self) -> f32 {
-        self.font_size + self.v_metrics().line_gap
+        self.line_height_at(self.font_size)
+    }
+    /// The font size the text is drawn at, smaller than `font_size` if it's shrunk to fit,
+    /// as of the last time it was drawn or `fit_to_width` was called
+    pub fn effective_font_size(&self) -> f32 {
+        match (self.shrink_to_fit, &self.fitted) {
+            (Some(_), &Some(ref fitted)) => fitted.fitted,
+            _ => self.font_size,
+        }
+    }
+    /// Choose the font size to shrink the text to so it fits in `width`, see `shrink_to_fit`.
+    /// Called when the text is drawn, the search only runs again if the width, text or font changed.
+    pub fn fit_to_width(&mut self, width: f32) {
+        let min_font_size = match self.shrink_to_fit {
+            Some(min_font_size) => min_font_size,
+            None => {
+                self.fitted = None;
+                return;
+            }
+        };
+        if let Some(ref fitted) = self.fitted {
+            if fitted.width == width && fitted.font_size == self.font_size && fitted.min_font_size == min_font_size
+                && fitted.text == self.text && fitted.font == self.font {
+                return;
+            }
+        }
+        let fitted = {
+            let mut resources = resources();
+            let font = resources.get_font(&self.font);
+            // the line height doesn't affect the width
+            shrink_to_fit(self.font_size, min_font_size, width, |font_size| {
+                text_layout::get_text_size(&self.text, &font.info, font_size, font_size, self.wrap).width
+            })
+        };
+        self.fitted = Some(FittedFontSize {
+            text: self.text.clone(),
+            font: self.font.clone(),
+            font_size: self.font_size,
+            min_font_size: min_font_size,
+            width: width,
+            fitted: fitted,
+        });
     }
     pub fn text_fits(&self, text: &str, bounds: Rect) -> bool {
         let line_height = self.line_height();
@@ -73,28 +295,30 @@ impl TextState {
         height <= bounds.height()
     }
     fn get_line_rects(&self, bounds: Rect) -> Vec<Rect> {
-        let line_height = self.line_height();
+        let font_size = self.effective_font_size();
+        let line_height = self.line_height_at(font_size);
         let mut resources = resources();
         let font = resources.get_font(&self.font);
         text_layout::get_line_rects(
             &self.text,
             bounds,
             &font.info,
-            self.font_size,
+            font_size,
             line_height,
             self.wrap,
             self.align)
     }
     fn position_glyphs(&self, bounds: Rect) -> Vec<GlyphInstance> {
-        let line_height = self.line_height();
-        let descent = self.v_metrics().descent;
+        let font_size = self.effective_font_size();
+        let line_height = self.line_height_at(font_size);
+        let descent = self.v_metrics_at(font_size).descent;
         let mut resources = resources();
         let font = resources.get_font(&self.font);
         let positions = text_layout::get_positioned_glyphs(
             &self.text,
             bounds,
             &font.info,
-            self.font_size,
+            font_size,
             line_height,
             self.wrap,
             self.align).iter().map(|glyph| {
@@ -106,22 +330,188 @@ impl TextState {
             }).collect();
         positions
     }
-    fn font_instance_key(&self) -> FontInstanceKey {
-        *resources().get_font_instance(&self.font, self.font_size)
-    }
-    fn v_metrics(&self) -> VMetrics {
+    /// Left edge of each glyph relative to `bounds`, followed by the right edge of the last glyph
+    pub fn glyph_positions(&self, bounds: Rect) -> Vec<f32> {
+        if let Some(runs) = self.runs() {
+            let layout = self.layout_runs(runs, bounds);
+            let mut positions: Vec<f32> = layout.glyphs.iter().map(|glyph| glyph.glyph.position().x - bounds.left()).collect();
+            if let Some(glyph) = layout.glyphs.last() {
+                let advance_width = glyph.glyph.unpositioned().h_metrics().advance_width;
+                positions.push(glyph.glyph.position().x + advance_width - bounds.left());
+            }
+            return positions;
+        }
+        let line_height = self.line_height();
         let mut resources = resources();
         let font = resources.get_font(&self.font);
-        font.info.v_metrics(Scale::uniform(self.font_size))
+        let glyphs = text_layout::get_positioned_glyphs(
+            &self.text,
+            bounds,
+            &font.info,
+            self.font_size,
+            line_height,
+            self.wrap,
+            self.align);
+        let mut positions: Vec<f32> = glyphs.iter().map(|glyph| glyph.position().x - bounds.left()).collect();
+        if let Some(glyph) = glyphs.last() {
+            let advance_width = glyph.unpositioned().h_metrics().advance_width;
+            positions.push(glyph.position().x + advance_width - bounds.left());
+        }
+        positions
+    }
+    // the font and size of each run, and the color it's drawn in
+    fn run_styles(&self, runs: &[StyledRun]) -> Vec<(String, f32, Color)> {
+        runs.iter().map(|run| {
+            let font = run.font.clone().unwrap_or_else(|| self.font.clone());
+            (font, run.font_size.unwrap_or(self.font_size), run.color.unwrap_or(self.text_color))
+        }).collect()
     }
+    fn with_text_runs<F, T>(&self, runs: &[StyledRun], f: F) -> T
+        where F: FnOnce(&[TextRun]) -> T
+    {
+        let styles = self.run_styles(runs);
+        let mut resources = resources();
+        // load the fonts first, each run borrows its font
+        for &(ref font, _, _) in &styles {
+            resources.get_font(font);
+        }
+        let text_runs: Vec<TextRun> = runs.iter().zip(&styles).map(|(run, &(ref font, font_size, _))| TextRun {
+            text: &run.text,
+            font: &resources.fonts[font].info,
+            font_size: font_size,
+        }).collect();
+        f(&text_runs)
+    }
+    fn layout_runs(&self, runs: &[StyledRun], bounds: Rect) -> RunsLayout {
+        let (wrap, align) = (self.wrap, self.align);
+        self.with_text_runs(runs, |text_runs| text_layout::runs::layout_runs(text_runs, bounds, wrap, align))
+    }
+    // each line's bounds and baseline
+    fn line_baselines(&self, bounds: Rect) -> Vec<(Rect, f32)> {
+        let font_size = self.effective_font_size();
+        let descent = self.v_metrics_at(font_size).descent;
+        // where the glyphs are placed, see `position_glyphs`
+        self.get_line_rects(bounds).into_iter().map(|line| (line, line.top() + font_size + descent)).collect()
+    }
+    /// The lines drawn across the text for its underline and strikethrough, aligned like the text, and their colors.
+    /// Text drawn from runs has a line across each run, placed and sized for the run's font, in the run's color.
+    pub fn decoration_rects(&self, bounds: Rect) -> Vec<(Rect, Color)> {
+        let mut decorations = Vec::new();
+        if self.underline {
+            decorations.push(TextDecoration::Underline);
+        }
+        if self.strikethrough {
+            decorations.push(TextDecoration::Strikethrough);
+        }
+        if decorations.is_empty() {
+            return Vec::new();
+        }
+        // each span of text decorated the same way: its left and right edges, baseline, font, font size and color
+        let mut spans: Vec<(f32, f32, f32, String, f32, Color)> = Vec::new();
+        if let Some(runs) = self.runs() {
+            let layout = self.layout_runs(runs, bounds);
+            let styles = self.run_styles(runs);
+            let mut last_run = None;
+            for glyph in &layout.glyphs {
+                let position = glyph.glyph.position();
+                let right = position.x + glyph.glyph.unpositioned().h_metrics().advance_width;
+                // glyphs are positioned on their line's baseline
+                if last_run == Some((glyph.run, position.y)) {
+                    spans.last_mut().unwrap().1 = right;
+                } else {
+                    let (ref font, font_size, color) = styles[glyph.run];
+                    spans.push((position.x, right, position.y, font.clone(), font_size, color));
+                    last_run = Some((glyph.run, position.y));
+                }
+            }
+        } else {
+            let font_size = self.effective_font_size();
+            for (line, baseline) in self.line_baselines(bounds) {
+                spans.push((line.left(), line.right(), baseline, self.font.clone(), font_size, self.text_color));
+            }
+        }
+        let mut rects = Vec::new();
+        for (left, right, baseline, font, font_size, color) in spans {
+            if right <= left {
+                continue;
+            }
+            let v_metrics = v_metrics(&font, font_size);
+            let thickness = decoration_thickness(font_size);
+            for decoration in &decorations {
+                let center = baseline + decoration_offset(*decoration, v_metrics.ascent, v_metrics.descent);
+                let rect = Rect::new(Point::new(left, center - thickness / 2.0), Size::new(right - left, thickness));
+                rects.push((rect, color));
+            }
+        }
+        rects
+    }
+    // each run in its own font and color, see `set_runs`
+    fn draw_runs(&self, runs: &[StyledRun], bounds: Rect, renderer: &mut RenderBuilder) {
+        let layout = self.layout_runs(runs, bounds);
+        let styles = self.run_styles(runs);
+        let info = PrimitiveInfo::new(bounds.typed());
+        let run_glyphs: Vec<Vec<GlyphInstance>> = (0..runs.len()).map(|run| {
+            layout.glyphs.iter().filter(|glyph| glyph.run == run).map(|glyph| {
+                let position = glyph.glyph.position();
+                GlyphInstance {
+                    index: glyph.glyph.id().0,
+                    point: LayoutPoint::new(position.x, position.y),
+                }
+            }).collect()
+        }).collect();
+        let keys: Vec<FontInstanceKey> = styles.iter().map(|&(ref font, font_size, _)| {
+            *resources().get_font_instance(font, font_size)
+        }).collect();
+        // all of the outlines go behind all of the runs, a run's outline can overlap the runs next to it
+        if let Some(outline) = self.outline {
+            if outline.width > 0.0 {
+                for offset in outline_offsets(outline.width) {
+                    let offset = LayoutVector2D::from_untyped(&offset);
+                    for (glyphs, key) in run_glyphs.iter().zip(&keys) {
+                        let outline_glyphs: Vec<GlyphInstance> = glyphs.iter().map(|glyph| GlyphInstance {
+                            index: glyph.index,
+                            point: glyph.point + offset,
+                        }).collect();
+                        renderer.builder.push_text(&info, &outline_glyphs, *key, outline.color.into(), None);
+                    }
+                }
+            }
+        }
+        for ((glyphs, key), &(_, _, color)) in run_glyphs.iter().zip(&keys).zip(&styles) {
+            renderer.builder.push_text(&info, glyphs, *key, color.into(), None);
+        }
+        for (rect, color) in self.decoration_rects(bounds) {
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect.typed()), color.into());
+        }
+    }
+    fn font_instance_key(&self) -> FontInstanceKey {
+        *resources().get_font_instance(&self.font, self.effective_font_size())
+    }
+    fn line_height_at(&self, font_size: f32) -> f32 {
+        font_size + self.v_metrics_at(font_size).line_gap
+    }
+    fn v_metrics_at(&self, font_size: f32) -> VMetrics {
+        v_metrics(&self.font, font_size)
+    }
+}
+
+fn v_metrics(font: &str, font_size: f32) -> VMetrics {
+    let mut resources = resources();
+    let font = resources.get_font(font);
+    font.info.v_metrics(Scale::uniform(font_size))
 }
 
 impl Draw for TextState {
     fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        if let Some(runs) = self.runs() {
+            self.draw_runs(runs, bounds, renderer);
+            return;
+        }
+        self.fit_to_width(bounds.width());
         let glyphs = self.position_glyphs(bounds);
         if DEBUG_LINE_BOUNDS {
             let line_rects = self.get_line_rects(bounds);
-            let v_metrics = self.v_metrics();
+            let v_metrics = self.v_metrics_at(self.effective_font_size());
             let mut resources = resources();
             let font = resources.get_font(&self.font);
             for mut rect in line_rects {
@@ -130,7 +520,7 @@ impl Draw for TextState {
                 rect.size.height = 1.0;
                 render::draw_rect_outline(rect, RED, renderer);
             }
-            let scale = Scale::uniform(self.font_size);
+            let scale = Scale::uniform(self.effective_font_size());
             for glyph in &glyphs {
                 let scaled_glyph = font.info.glyph(GlyphId(glyph.index)).unwrap().scaled(scale);
                 if let Some(rect) = scaled_glyph.exact_bounding_box() {
@@ -142,6 +532,18 @@ impl Draw for TextState {
         }
         let key = self.font_instance_key();
         let info = PrimitiveInfo::new(bounds.typed());
+        if let Some(outline) = self.outline {
+            if outline.width > 0.0 {
+                for offset in outline_offsets(outline.width) {
+                    let offset = LayoutVector2D::from_untyped(&offset);
+                    let outline_glyphs: Vec<GlyphInstance> = glyphs.iter().map(|glyph| GlyphInstance {
+                        index: glyph.index,
+                        point: glyph.point + offset,
+                    }).collect();
+                    renderer.builder.push_text(&info, &outline_glyphs, key, outline.color.into(), None);
+                }
+            }
+        }
         renderer.builder.push_text(
             &info,
             &glyphs,
@@ -149,6 +551,9 @@ impl Draw for TextState {
             self.text_color.into(),
             None,
         );
+        for (rect, color) in self.decoration_rects(bounds) {
+            renderer.builder.push_rect(&PrimitiveInfo::new(rect.typed()), color.into());
+        }
     }
 }
 
@@ -161,6 +566,10 @@ pub enum TextStyle {
     BackgroundColor(Value<Color>),
     Wrap(Value<Wrap>),
     Align(Value<Align>),
+    ShrinkToFit(Value<Option<f32>>),
+    Outline(Value<Option<TextOutline>>),
+    Underline(Value<bool>),
+    Strikethrough(Value<bool>),
 }
 
 impl Style<TextState> for TextStyle {
@@ -173,6 +582,201 @@ impl Style<TextState> for TextStyle {
             TextStyle::BackgroundColor(ref val) => style::update(&mut state.background_color, val.get(props)),
             TextStyle::Wrap(ref val) => style::update(&mut state.wrap, val.get(props)),
             TextStyle::Align(ref val) => style::update(&mut state.align, val.get(props)),
+            TextStyle::ShrinkToFit(ref val) => style::update(&mut state.shrink_to_fit, val.get(props)),
+            TextStyle::Outline(ref val) => style::update(&mut state.outline, val.get(props)),
+            TextStyle::Underline(ref val) => style::update(&mut state.underline, val.get(props)),
+            TextStyle::Strikethrough(ref val) => style::update(&mut state.strikethrough, val.get(props)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CharClass {
+    Whitespace,
+    Punctuation,
+    Word,
+}
+fn char_class(char: char) -> CharClass {
+    if char.is_whitespace() {
+        CharClass::Whitespace
+    } else if char.is_alphanumeric() || char == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// The character index where the word before `index` starts, skipping any whitespace before `index` first.
+/// A word is a run of letters, digits and underscores, in any script, or a run of other non-whitespace characters,
+/// so punctuation is skipped separately from the words around it.
+pub fn previous_word_start(text: &str, index: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = cmp::min(index, chars.len());
+    while index > 0 && char_class(chars[index - 1]) == CharClass::Whitespace {
+        index -= 1;
+    }
+    if index > 0 {
+        let class = char_class(chars[index - 1]);
+        while index > 0 && char_class(chars[index - 1]) == class {
+            index -= 1;
+        }
+    }
+    index
+}
+/// The character index where the word after `index` ends, skipping any whitespace after `index` first,
+/// see `previous_word_start`
+pub fn next_word_end(text: &str, index: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut index = cmp::min(index, chars.len());
+    while index < chars.len() && char_class(chars[index]) == CharClass::Whitespace {
+        index += 1;
+    }
+    if index < chars.len() {
+        let class = char_class(chars[index]);
+        while index < chars.len() && char_class(chars[index]) == class {
+            index += 1;
+        }
+    }
+    index
+}
+/// The character range, start inclusive, end exclusive, of the word containing the character at `index`,
+/// or the last character if `index` is past the end. A run of whitespace counts as a word.
+pub fn word_at(text: &str, index: usize) -> (usize, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return (0, 0);
+    }
+    let index = cmp::min(index, chars.len() - 1);
+    let class = char_class(chars[index]);
+    let mut start = index;
+    while start > 0 && char_class(chars[start - 1]) == class {
+        start -= 1;
+    }
+    let mut end = index + 1;
+    while end < chars.len() && char_class(chars[end]) == class {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// The character boundary nearest `x`, given the edges of a line of glyphs, as returned by `glyph_positions`:
+/// the left edge of each glyph, then the right edge of the last one. There's one glyph per character,
+/// so the result is a character index, not a byte index, for text with multibyte characters.
+/// Halfway between two edges, the earlier one is used.
+pub fn index_at_x(glyph_positions: &[f32], x: f32) -> usize {
+    match glyph_positions.iter().position(|&edge| edge > x) {
+        None => glyph_positions.len().saturating_sub(1),
+        Some(0) => 0,
+        Some(after) => {
+            if x - glyph_positions[after - 1] <= glyph_positions[after] - x {
+                after - 1
+            } else {
+                after
+            }
+        }
+    }
+}
+
+/// Highlights a range of glyphs in a block of text, one rectangle per line.
+/// Meant to be drawn behind a widget using `TextState`, with matching bounds.
+pub struct TextSelectionOverlay {
+    /// Selected glyph indices, start inclusive, end exclusive
+    pub selection: Option<(usize, usize)>,
+    pub line_height: f32,
+    /// Left edge of each glyph relative to the text bounds, followed by the right edge of the last glyph,
+    /// as returned by `TextState::glyph_positions`. A position less than the previous one starts a new line.
+    pub glyph_positions: Vec<f32>,
+    pub color: Color,
+    /// Glyph index the caret is drawn before, if any
+    pub caret: Option<usize>,
+    pub caret_color: Color,
+    /// False to hide the caret without losing its position, eg. while it blinks
+    pub caret_visible: bool,
+}
+impl Default for TextSelectionOverlay {
+    fn default() -> Self {
+        TextSelectionOverlay {
+            selection: None,
+            line_height: 0.0,
+            glyph_positions: Vec::new(),
+            color: BLUE_HIGHLIGHT,
+            caret: None,
+            caret_color: BLACK,
+            caret_visible: true,
+        }
+    }
+}
+impl TextSelectionOverlay {
+    pub fn new() -> Self {
+        TextSelectionOverlay::default()
+    }
+    pub fn selection_rects(&self, bounds: Rect) -> Vec<Rect> {
+        let mut rects: Vec<Rect> = Vec::new();
+        let (start, end) = match self.selection {
+            Some((start, end)) => (cmp::min(start, end), cmp::max(start, end)),
+            None => return rects,
+        };
+        let num_glyphs = self.glyph_positions.len().saturating_sub(1);
+        let end = cmp::min(end, num_glyphs);
+        let mut line = 0;
+        let mut rect_line = None;
+        for index in 0..end {
+            let left = self.glyph_positions[index];
+            if index > 0 && left < self.glyph_positions[index - 1] {
+                line += 1;
+            }
+            if index < start {
+                continue;
+            }
+            // a selection that continues onto the next line is highlighted to the end of the line
+            let next = self.glyph_positions[index + 1];
+            let right = if next >= left { next } else { bounds.width() };
+            if rect_line == Some(line) {
+                let rect = rects.last_mut().unwrap();
+                rect.size.width = right - rect.origin.x;
+            } else {
+                let top = line as f32 * self.line_height;
+                rects.push(Rect::new(Point::new(left, top), Size::new(right - left, self.line_height)));
+                rect_line = Some(line);
+            }
         }
+        let origin = bounds.origin.to_vector();
+        rects.iter().map(|rect| rect.translate(&origin)).collect()
+    }
+    pub fn caret_rect(&self, bounds: Rect) -> Option<Rect> {
+        let caret = match self.caret {
+            Some(caret) => caret,
+            None => return None,
+        };
+        let caret = cmp::min(caret, self.glyph_positions.len().saturating_sub(1));
+        let mut line = 0;
+        for index in 1..(caret + 1) {
+            if self.glyph_positions[index] < self.glyph_positions[index - 1] {
+                line += 1;
+            }
+        }
+        let left = self.glyph_positions.get(caret).cloned().unwrap_or(0.0);
+        let top = line as f32 * self.line_height;
+        let rect = Rect::new(Point::new(left, top), Size::new(1.0, self.line_height));
+        Some(rect.translate(&bounds.origin.to_vector()))
+    }
+}
+
+impl Draw for TextSelectionOverlay {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        for rect in self.selection_rects(bounds) {
+            let info = PrimitiveInfo::new(rect.typed());
+            renderer.builder.push_rect(&info, self.color.into());
+        }
+        if !self.caret_visible {
+            return;
+        }
+        if let Some(rect) = self.caret_rect(bounds) {
+            let info = PrimitiveInfo::new(rect.typed());
+            renderer.builder.push_rect(&info, self.caret_color.into());
+        }
+    }
+    fn is_under_cursor(&self, _: Rect, _: Point) -> bool {
+        false
     }
 }
@@ -0,0 +1,178 @@
+use std::f32;
+use std::f32::consts::PI;
+
+use webrender_api::PrimitiveInfo;
+
+use render::RenderBuilder;
+use widget::draw::Draw;
+use widget::property::PropSet;
+use widget::style::{self, Style, Value};
+use geometry::{Rect, RectExt, Point, Size, Vector};
+use color::*;
+
+/// A slice of a ring, between two angles, centered in the widget's bounds with the outer radius fitting the bounds.
+/// Angles are in radians, clockwise from straight up.
+pub struct WedgeState {
+    pub start_angle: f32,
+    pub end_angle: f32,
+    /// Radius of the hole in the middle, as a fraction of the outer radius
+    pub inner_radius: f32,
+    pub background_color: Color,
+}
+impl Default for WedgeState {
+    fn default() -> Self {
+        WedgeState {
+            start_angle: 0.0,
+            end_angle: 2.0 * PI,
+            inner_radius: 0.0,
+            background_color: WHITE,
+        }
+    }
+}
+
+impl WedgeState {
+    pub fn new(start_angle: f32, end_angle: f32) -> Self {
+        WedgeState {
+            start_angle: start_angle,
+            end_angle: end_angle,
+            ..WedgeState::default()
+        }
+    }
+    fn radii(&self, bounds: Rect) -> (f32, f32) {
+        let outer = f32::min(bounds.width(), bounds.height()) / 2.0;
+        (outer * self.inner_radius, outer)
+    }
+    /// The one pixel high rects the wedge is filled with when drawn in `bounds`, from the top.
+    /// There's no path primitive, so the wedge is drawn one row of pixels at a time.
+    pub fn spans(&self, bounds: Rect) -> Vec<Rect> {
+        let (inner_radius, outer_radius) = self.radii(bounds);
+        let center = bounds.center();
+        let sweep = self.end_angle - self.start_angle;
+        let sectors = if sweep >= 2.0 * PI {
+            None
+        } else if sweep > PI {
+            let middle = self.start_angle + sweep / 2.0;
+            Some(vec![(self.start_angle, middle), (middle, self.end_angle)])
+        } else {
+            Some(vec![(self.start_angle, self.end_angle)])
+        };
+        let mut rects = Vec::new();
+        let mut row = -outer_radius.ceil();
+        while row < outer_radius {
+            let y = row + 0.5;
+            for ring_span in ring_spans(y, inner_radius, outer_radius) {
+                let spans = match sectors {
+                    None => vec![ring_span],
+                    Some(ref sectors) => sectors.iter()
+                        .filter_map(|&(start, end)| sector_span(y, start, end))
+                        .filter_map(|sector_span| intersect(ring_span, sector_span))
+                        .collect(),
+                };
+                for (left, right) in spans {
+                    rects.push(Rect::new(Point::new(center.x + left, center.y + row), Size::new(right - left, 1.0)));
+                }
+            }
+            row += 1.0;
+        }
+        rects
+    }
+}
+
+/// Angle of `offset` in radians, clockwise from straight up, between 0 and 2 PI
+pub fn clockwise_angle(offset: Vector) -> f32 {
+    let angle = offset.x.atan2(-offset.y);
+    if angle < 0.0 { angle + 2.0 * PI } else { angle }
+}
+
+/// True if `offset` from the center of a ring is inside the slice of the ring between `start_angle` and `end_angle`
+pub fn point_inside_wedge(offset: Vector, inner_radius: f32, outer_radius: f32, start_angle: f32, end_angle: f32) -> bool {
+    let distance = offset.length();
+    if distance < inner_radius || distance > outer_radius {
+        return false;
+    }
+    if end_angle - start_angle >= 2.0 * PI {
+        return true;
+    }
+    let start = start_angle % (2.0 * PI);
+    let start = if start < 0.0 { start + 2.0 * PI } else { start };
+    let angle = clockwise_angle(offset);
+    let from_start = if angle < start { angle + 2.0 * PI - start } else { angle - start };
+    from_start <= end_angle - start_angle
+}
+
+// horizontal spans of the ring at height `y` from its center
+fn ring_spans(y: f32, inner_radius: f32, outer_radius: f32) -> Vec<(f32, f32)> {
+    if y.abs() > outer_radius {
+        return Vec::new();
+    }
+    let outer = (outer_radius.powi(2) - y.powi(2)).sqrt();
+    if y.abs() < inner_radius {
+        let inner = (inner_radius.powi(2) - y.powi(2)).sqrt();
+        vec![(-outer, -inner), (inner, outer)]
+    } else {
+        vec![(-outer, outer)]
+    }
+}
+
+// span of x values where `a * x + b >= 0`
+fn half_line(a: f32, b: f32) -> Option<(f32, f32)> {
+    if a.abs() < f32::EPSILON {
+        if b >= 0.0 { Some((f32::NEG_INFINITY, f32::INFINITY)) } else { None }
+    } else if a > 0.0 {
+        Some((-b / a, f32::INFINITY))
+    } else {
+        Some((f32::NEG_INFINITY, -b / a))
+    }
+}
+
+fn intersect(a: (f32, f32), b: (f32, f32)) -> Option<(f32, f32)> {
+    let span = (f32::max(a.0, b.0), f32::min(a.1, b.1));
+    if span.0 < span.1 { Some(span) } else { None }
+}
+
+// span of a sector no wider than PI at height `y` from its center, the intersection of two half planes
+fn sector_span(y: f32, start_angle: f32, end_angle: f32) -> Option<(f32, f32)> {
+    let start = Vector::new(start_angle.sin(), -start_angle.cos());
+    let end = Vector::new(end_angle.sin(), -end_angle.cos());
+    // clockwise of the start direction, and counter clockwise of the end direction
+    let after_start = half_line(-start.y, start.x * y);
+    let before_end = half_line(end.y, -end.x * y);
+    match (after_start, before_end) {
+        (Some(after_start), Some(before_end)) => intersect(after_start, before_end),
+        _ => None,
+    }
+}
+
+fn push_span(renderer: &mut RenderBuilder, rect: Rect, color: Color) {
+    let info = PrimitiveInfo::new(rect.typed());
+    renderer.builder.push_rect(&info, color.into());
+}
+
+impl Draw for WedgeState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        for rect in self.spans(bounds) {
+            push_span(renderer, rect, self.background_color);
+        }
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        let (inner_radius, outer_radius) = self.radii(bounds);
+        point_inside_wedge(cursor - bounds.center(), inner_radius, outer_radius, self.start_angle, self.end_angle)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum WedgeStyle {
+    BackgroundColor(Value<Color>),
+    InnerRadius(Value<f32>),
+}
+
+impl Style<WedgeState> for WedgeStyle {
+    fn apply(&self, state: &mut WedgeState, props: &PropSet) -> bool {
+        match *self {
+            WedgeStyle::BackgroundColor(ref val) => {
+                style::update(&mut state.background_color, val.get(props))
+            }
+            WedgeStyle::InnerRadius(ref val) => style::update(&mut state.inner_radius, val.get(props)),
+        }
+    }
+}
@@ -1,12 +1,25 @@
 use std::any::{Any, TypeId};
-use std::cell::{Cell, RefCell};
+use std::cell::{Cell, RefCell, RefMut};
 use std::sync::Mutex;
 use std::collections::VecDeque;
+use std::rc::Rc;
+use std::cmp;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::thread;
 
 use glutin::{EventsLoop, EventsLoopProxy};
+use serde_json;
 
 use ui::Ui;
-use widget::WidgetRef;
+use widget::{WidgetRef, WidgetError};
+use resources::WidgetId;
+use widget::draw::Draw;
+use geometry::Point;
+use task::{TaskHandle, TaskCompleteEvent};
 
 /// Defines the different targets that events can be delivered to.
 /// An event will be sent to all handlers that match both the Target,
@@ -24,9 +37,26 @@ pub enum Target {
     Root,
 }
 
+/// The type of a queued event, and when it was sent, if the queue is instrumented, see `Ui::set_event_hook`
+#[derive(Clone, Copy, Debug)]
+pub(super) struct EventOrigin {
+    pub type_name: &'static str,
+    pub sent: Option<Instant>,
+}
+
+impl EventOrigin {
+    fn new<T>(instrumented: bool) -> Self {
+        EventOrigin {
+            type_name: ::type_name::<T>(),
+            sent: if instrumented { Some(Instant::now()) } else { None },
+        }
+    }
+}
+
 struct Queue {
-    queue: VecDeque<(Target, TypeId, Box<Any>)>,
+    queue: VecDeque<(Target, TypeId, Box<Any>, EventOrigin)>,
     events_loop_proxy: Option<EventsLoopProxy>,
+    instrumented: bool,
 }
 
 impl Queue {
@@ -34,6 +64,7 @@ impl Queue {
         Queue {
             queue: VecDeque::new(),
             events_loop_proxy: None,
+            instrumented: false,
         }
     }
     fn set_events_loop(&mut self, events_loop: EventsLoopProxy) {
@@ -42,16 +73,17 @@ impl Queue {
     /// Push a new event on the queue and wake the window up if it is asleep
     fn push<T: 'static>(&mut self, address: Target, data: T) {
         let type_id = TypeId::of::<T>();
-        self.queue.push_back((address, type_id, Box::new(data)));
+        let origin = EventOrigin::new::<T>(self.instrumented);
+        self.queue.push_back((address, type_id, Box::new(data), origin));
         if let Some(ref events_loop_proxy) = self.events_loop_proxy {
             events_loop_proxy.wakeup().unwrap();
         }
     }
 }
 impl Iterator for Queue {
-    type Item = (Target, TypeId, Box<Any>);
+    type Item = (Target, TypeId, Box<Any>, EventOrigin);
     /// Take the next event off the Queue, should only be called by App
-    fn next(&mut self) -> Option<(Target, TypeId, Box<Any>)> {
+    fn next(&mut self) -> Option<(Target, TypeId, Box<Any>, EventOrigin)> {
         self.queue.pop_front()
     }
 }
@@ -63,6 +95,40 @@ pub struct EventArgs<'a> {
     pub ui: &'a mut Ui,
     pub handled: &'a mut bool,
 }
+impl<'a> EventArgs<'a> {
+    /// Convert a point in window coordinates to be relative to the top left of the receiving widget,
+    /// where it's drawn, see `WidgetRef::local_point`
+    pub fn to_local(&self, point: Point) -> Point {
+        self.widget.local_point(point)
+    }
+    /// Convert a point relative to the top left of the receiving widget to window coordinates
+    pub fn to_global(&self, point: Point) -> Point {
+        self.widget.global_point(point)
+    }
+    /// The receiving widget's draw state, if it is a `T`, see `WidgetRef::drawable`
+    pub fn drawable<T: Draw + 'static>(&self) -> Option<RefMut<T>> {
+        self.widget.drawable()
+    }
+    /// Modify the receiving widget's draw state, if it is a `T`, see `WidgetRef::update_drawable`
+    pub fn update_drawable<F, T: Draw + 'static>(&mut self, f: F) -> bool
+        where F: FnOnce(&mut T)
+    {
+        self.widget.update_drawable(f)
+    }
+    /// Run `task` on a background thread, and send its result back to the receiving widget as a `TaskCompleteEvent`.
+    /// The result is dropped if the widget is removed first, or the task is cancelled with the handle returned.
+    /// At most `Ui::set_max_concurrent_tasks` tasks run at a time, the rest wait for one to finish.
+    pub fn spawn<F, T>(&mut self, task: F) -> TaskHandle
+        where F: FnOnce() -> T + Send + 'static,
+              T: Send + 'static
+    {
+        let widget_id = self.widget.id();
+        let sender = self.ui.event_sender();
+        let handle = self.ui.tasks.spawn(task, move |result| sender.send_to(widget_id, TaskCompleteEvent(result)));
+        self.widget.add_task(handle.clone());
+        handle
+    }
+}
 
 /// Used to create a stateful event handler for widgets.
 pub trait EventHandler<T> {
@@ -72,7 +138,9 @@ pub trait EventHandler<T> {
 /// Non-generic `EventHandler` or Widget callback wrapper.
 pub(super) struct EventHandlerWrapper {
     handler: Box<Any>,
-    handle_fn: Box<Fn(&mut Any, &Any, EventArgs)>,
+    handle_fn: Box<Fn(&mut Any, &Any, EventArgs) -> Result<(), WidgetError>>,
+    /// Name of the event type handled, for reporting panics
+    pub event_type_name: &'static str,
 }
 
 impl EventHandlerWrapper {
@@ -81,13 +149,15 @@ impl EventHandlerWrapper {
               E: 'static
     {
         let handle_fn = |handler: &mut Any, event: &Any, args: EventArgs| {
-            let event: &E = event.downcast_ref().unwrap();
-            let handler: &mut H = handler.downcast_mut().unwrap();
+            let event: &E = event.downcast_ref().ok_or(WidgetError::UnknownEventType)?;
+            let handler: &mut H = handler.downcast_mut().ok_or(WidgetError::DowncastFailed)?;
             handler.handle(event, args);
+            Ok(())
         };
         EventHandlerWrapper {
             handler: Box::new(handler),
             handle_fn: Box::new(handle_fn),
+            event_type_name: ::type_name::<E>(),
         }
     }
     pub fn new_from_fn<H, E>(handler: H) -> Self
@@ -95,18 +165,20 @@ impl EventHandlerWrapper {
               E: 'static
     {
         let handle_fn = |handler: &mut Any, event: &Any, args: EventArgs| {
-            let event: &E = event.downcast_ref().unwrap();
+            let event: &E = event.downcast_ref().ok_or(WidgetError::UnknownEventType)?;
             debug!("widget handle {}", ::type_name::<E>());
-            let handler: &mut H = handler.downcast_mut().unwrap();
+            let handler: &mut H = handler.downcast_mut().ok_or(WidgetError::DowncastFailed)?;
             handler(event, args);
+            Ok(())
         };
         EventHandlerWrapper {
             handler: Box::new(handler),
             handle_fn: Box::new(handle_fn),
+            event_type_name: ::type_name::<E>(),
         }
     }
-    pub fn handle(&mut self, event: &Any, args: EventArgs) {
-        (self.handle_fn)(self.handler.as_mut(), event, args);
+    pub fn handle(&mut self, event: &Any, args: EventArgs) -> Result<(), WidgetError> {
+        (self.handle_fn)(self.handler.as_mut(), event, args)
     }
 }
 
@@ -116,52 +188,125 @@ lazy_static! {
 }
 
 thread_local! {
-    static LOCAL_QUEUE: Option<RefCell<Queue>> = {
+    // the queue for events sent on the UI thread, `None` on other threads
+    static LOCAL_QUEUE: RefCell<Option<Queue>> = {
         let first = FIRST_THREAD.lock().unwrap();
         if first.get() {
             first.set(false);
-            Some(RefCell::new(Queue::new()))
+            RefCell::new(Some(Queue::new()))
         } else {
-            None
+            RefCell::new(None)
         }
     }
 }
 
-pub(super) fn queue_next() -> Option<(Target, TypeId, Box<Any>)> {
-    if let Some(next) = GLOBAL_QUEUE.lock().unwrap().next() {
-        Some((Target::Root, next.0, next.1))
-    } else {
-        let mut next = None;
-        LOCAL_QUEUE.with(|queue| next = Some(queue.as_ref().unwrap().borrow_mut().next()));
-        next.unwrap()
+/// Give the current thread its own queue for events sent on it, if it doesn't have one yet,
+/// so a headless app can run on any thread, eg. one for each test, see `App::headless`
+pub(super) fn queue_claim_thread() {
+    LOCAL_QUEUE.with(|queue| {
+        let mut queue = queue.borrow_mut();
+        if queue.is_none() {
+            *queue = Some(Queue::new());
+        }
+    });
+}
+
+/// Take the next event, events sent from other threads first.
+/// `get_widget` finds the widgets events from other threads are sent to,
+/// events for widgets that no longer exist are dropped.
+pub(super) fn queue_next<F>(get_widget: F) -> Option<(Target, TypeId, Box<Any>, EventOrigin)>
+    where F: Fn(WidgetId) -> Option<WidgetRef>
+{
+    loop {
+        let next = GLOBAL_QUEUE.lock().unwrap().next();
+        match next {
+            Some((None, type_id, data, origin)) => return Some((Target::Root, type_id, data, origin)),
+            Some((Some(widget_id), type_id, data, origin)) => {
+                if let Some(widget_ref) = get_widget(widget_id) {
+                    return Some((Target::Widget(widget_ref), type_id, data, origin));
+                }
+                debug!("dropped event sent to removed widget {:?}", widget_id);
+            }
+            None => break,
+        }
     }
+    LOCAL_QUEUE.with(|queue| queue.borrow_mut().as_mut().unwrap().next())
 }
 
 pub(super) fn queue_set_events_loop(events_loop: &EventsLoop) {
     GLOBAL_QUEUE.lock().unwrap().set_events_loop(events_loop.create_proxy());
-    LOCAL_QUEUE.with(|queue| queue.as_ref().unwrap().borrow_mut().set_events_loop(events_loop.create_proxy()));
+    LOCAL_QUEUE.with(|queue| queue.borrow_mut().as_mut().unwrap().set_events_loop(events_loop.create_proxy()));
+}
+
+/// Record when each event is sent, to measure how long it waits in the queue, see `Ui::set_event_hook`
+pub(super) fn queue_set_instrumented(instrumented: bool) {
+    GLOBAL_QUEUE.lock().unwrap().instrumented = instrumented;
+    LOCAL_QUEUE.with(|queue| {
+        if let Some(ref mut queue) = *queue.borrow_mut() {
+            queue.instrumented = instrumented;
+        }
+    });
 }
 
 /// Send message to target address, must be sent from main UI thread.
 pub fn event<T: 'static>(address: Target, data: T) {
     LOCAL_QUEUE.with(|queue| {
-        if let Some(queue) = queue.as_ref() {
+        if let Some(ref mut queue) = *queue.borrow_mut() {
             debug!("push event {}", ::type_name::<T>());
-            queue.borrow_mut().push(address, data);
+            queue.push(address, data);
         } else {
             eprintln!("Tried to send event off the main thread, use event_global");
         }
     });
 }
 
+/// Call `f`, catching a panic and returning its message instead of unwinding further.
+/// Used to keep a panicking event handler from closing the app, see `Ui::set_catch_handler_panics`.
+pub fn catch_handler_panic<F: FnOnce()>(f: F) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown panic".to_owned()
+        }
+    })
+}
+
 /// Send message to UI from any thread.
 pub fn event_global<T: 'static + Send>(data: T) {
-    GLOBAL_QUEUE.lock().unwrap().push(data);
+    GLOBAL_QUEUE.lock().unwrap().push(None, data);
+}
+
+/// Sends events to the UI from other threads, eg. when work done in the background finishes.
+/// Events are handled on the UI thread in the order they're sent, before events sent from the UI thread,
+/// waking the main loop up if it's waiting for input.
+/// It can be cloned and moved to any thread, see `Ui::event_sender`.
+#[derive(Clone, Debug, Default)]
+pub struct EventSender {
+    _private: (),
+}
+
+impl EventSender {
+    pub fn new() -> Self {
+        EventSender::default()
+    }
+    /// Send an event to the root widget, like `event_global`
+    pub fn send<T: 'static + Send>(&self, data: T) {
+        GLOBAL_QUEUE.lock().unwrap().push(None, data);
+    }
+    /// Send an event to a widget, the event is dropped if the widget has been removed by the time it's handled
+    pub fn send_to<T: 'static + Send>(&self, widget_id: WidgetId, data: T) {
+        GLOBAL_QUEUE.lock().unwrap().push(Some(widget_id), data);
+    }
 }
 
 struct GlobalQueue {
-    queue: VecDeque<(TypeId, Box<Any + Send>)>,
+    // the widget each event is sent to, or `None` for the root
+    queue: VecDeque<(Option<WidgetId>, TypeId, Box<Any + Send>, EventOrigin)>,
     events_loop_proxy: Option<EventsLoopProxy>,
+    instrumented: bool,
 }
 
 impl GlobalQueue {
@@ -169,23 +314,254 @@ impl GlobalQueue {
         GlobalQueue {
             queue: VecDeque::new(),
             events_loop_proxy: None,
+            instrumented: false,
         }
     }
     pub fn set_events_loop(&mut self, events_loop: EventsLoopProxy) {
         self.events_loop_proxy = Some(events_loop);
     }
     /// Push a new event on the queue and wake the window up if it is asleep
-    pub fn push<T: 'static + Send>(&mut self, data: T) {
+    pub fn push<T: 'static + Send>(&mut self, widget_id: Option<WidgetId>, data: T) {
         let type_id = TypeId::of::<T>();
-        self.queue.push_back((type_id, Box::new(data)));
+        let origin = EventOrigin::new::<T>(self.instrumented);
+        self.queue.push_back((widget_id, type_id, Box::new(data), origin));
         if let Some(ref events_loop_proxy) = self.events_loop_proxy {
-            events_loop_proxy.wakeup().unwrap();
+            // fails once the events loop is gone, when the app is closing, the event is never handled then anyway
+            events_loop_proxy.wakeup().ok();
         }
     }
 }
 impl Iterator for GlobalQueue {
-    type Item = (TypeId, Box<Any + Send>);
-    fn next(&mut self) -> Option<(TypeId, Box<Any + Send>)> {
+    type Item = (Option<WidgetId>, TypeId, Box<Any + Send>, EventOrigin);
+    fn next(&mut self) -> Option<(Option<WidgetId>, TypeId, Box<Any + Send>, EventOrigin)> {
         self.queue.pop_front()
     }
 }
+
+/// A mouse button, in a form that can be saved, see `RecordedInput`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedButton {
+    Left,
+    Right,
+    Middle,
+    Other(u8),
+}
+
+/// Window input, in a form that can be saved and replayed, see `EventRecorder`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInput {
+    MouseMoved(f32, f32),
+    MouseButton { pressed: bool, button: RecordedButton },
+    /// Scrolled by `x` and `y` lines, or pixels if `pixels` is true
+    MouseWheel { x: f32, y: f32, pixels: bool },
+    /// `key` is the name of the virtual key code, if there is one, eg. "Return"
+    Key { pressed: bool, scancode: u32, key: Option<String> },
+    Character(char),
+}
+
+/// Input received while recording, `time` is in seconds since the recording started
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub time: f64,
+    pub input: RecordedInput,
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1_000_000_000.0
+}
+
+/// Records the window input the UI receives, with the time it was received, to replay it with an `EventReplayer`,
+/// eg. to reproduce a bug, or as a test. Only input from the window is recorded, every other event follows from it,
+/// and is sent again by the handlers when the input is replayed.
+/// See `Ui::start_recording` and `Ui::stop_recording`.
+pub struct EventRecorder {
+    start: Instant,
+    events: Vec<RecordedEvent>,
+}
+
+impl EventRecorder {
+    pub fn new() -> Self {
+        EventRecorder {
+            start: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+    pub fn record(&mut self, input: RecordedInput) {
+        let time = seconds(self.start.elapsed());
+        self.events.push(RecordedEvent { time: time, input: input });
+    }
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+    /// Write the recorded events to a file, one JSON object per line.
+    /// Only the window input is saved, not the other events pushed on the queue, those are sent again when it's replayed.
+    pub fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        for event in &self.events {
+            writeln!(file, "{}", serde_json::to_string(event)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sends recorded input to the UI again, with the same timing it was recorded with
+pub struct EventReplayer {
+    events: Vec<RecordedEvent>,
+    next: usize,
+    // when the first step was taken, the events are timed from then
+    start: Option<Instant>,
+}
+
+impl EventReplayer {
+    pub fn new(events: Vec<RecordedEvent>) -> Self {
+        EventReplayer {
+            events: events,
+            next: 0,
+            start: None,
+        }
+    }
+    /// Read events saved by `EventRecorder::save_to_file`
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        let mut events = Vec::new();
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let event: RecordedEvent = serde_json::from_str(line).map_err(|error| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("Invalid recorded event: {}, {}", line, error))
+            })?;
+            events.push(event);
+        }
+        Ok(EventReplayer::new(events))
+    }
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+    pub fn is_finished(&self) -> bool {
+        self.next == self.events.len()
+    }
+    /// Send the events that are due since the first step and handle them, returns false once every event has been sent
+    pub fn step(&mut self, ui: &mut Ui) -> bool {
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let elapsed = seconds(start.elapsed());
+        while let Some(event) = self.events.get(self.next) {
+            if event.time > elapsed {
+                break;
+            }
+            event.input.dispatch(ui);
+            self.next += 1;
+        }
+        ui.handle_events();
+        !self.is_finished()
+    }
+    /// Replay the remaining events, drawing and waiting between them, returns once the last event is handled
+    pub fn run_to_end(&mut self, ui: &mut Ui) {
+        while self.step(ui) {
+            ui.draw_if_needed();
+            let elapsed = seconds(self.start.unwrap().elapsed());
+            let wait = self.events[self.next].time - elapsed;
+            if wait > 0.0 {
+                thread::sleep(Duration::new(wait.trunc() as u64, (wait.fract() * 1_000_000_000.0) as u32));
+            }
+        }
+        ui.draw_if_needed();
+    }
+}
+
+/// What happened when an event was handled, passed to the hook set with `Ui::set_event_hook`
+pub struct EventDispatch<'a> {
+    pub type_id: TypeId,
+    /// The event's type, only available with the `nightly` feature
+    pub type_name: &'static str,
+    pub target: &'a Target,
+    /// How long the event waited in the queue, from when it was sent until it started being handled
+    pub latency: Duration,
+    /// How long handling the event took, including every handler it was sent to
+    pub handler_time: Duration,
+    /// The widgets with handlers for the event it was sent to, in the order they were called
+    pub handled_by: &'a [WidgetId],
+}
+
+/// Called after each event is handled, see `Ui::set_event_hook`
+pub type EventHook = Box<FnMut(&EventDispatch)>;
+
+/// Totals for one event type, collected by `EventStats`
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventTotals {
+    pub type_id: TypeId,
+    /// The event's type, for display only, see `EventDispatch::type_name`
+    pub type_name: &'static str,
+    pub count: u32,
+    pub latency: Duration,
+    pub handler_time: Duration,
+    pub max_handler_time: Duration,
+}
+
+/// An event hook that adds up the count, latency and handler time of each event type, for quick diagnostics.
+/// Clones share the same totals, so one can be installed with `hook` while another prints them:
+/// `ui.set_event_hook(Some(stats.hook()))`, then later `stats.print()`.
+#[derive(Clone, Default)]
+pub struct EventStats {
+    totals: Rc<RefCell<Vec<EventTotals>>>,
+}
+
+impl EventStats {
+    pub fn new() -> Self {
+        EventStats::default()
+    }
+    /// A hook that records every event handled into these stats
+    pub fn hook(&self) -> EventHook {
+        let stats = self.clone();
+        Box::new(move |dispatch| stats.record(dispatch))
+    }
+    pub fn record(&self, dispatch: &EventDispatch) {
+        let mut totals = self.totals.borrow_mut();
+        // keyed by type id, since without the `nightly` feature every type has the same name
+        let index = match totals.iter().position(|totals| totals.type_id == dispatch.type_id) {
+            Some(index) => index,
+            None => {
+                totals.push(EventTotals {
+                    type_id: dispatch.type_id,
+                    type_name: dispatch.type_name,
+                    count: 0,
+                    latency: Duration::default(),
+                    handler_time: Duration::default(),
+                    max_handler_time: Duration::default(),
+                });
+                totals.len() - 1
+            }
+        };
+        let totals = &mut totals[index];
+        totals.count += 1;
+        totals.latency += dispatch.latency;
+        totals.handler_time += dispatch.handler_time;
+        totals.max_handler_time = cmp::max(totals.max_handler_time, dispatch.handler_time);
+    }
+    /// The totals for each event type, the ones that took longest to handle first
+    pub fn totals(&self) -> Vec<EventTotals> {
+        let mut totals = self.totals.borrow().clone();
+        totals.sort_by(|a, b| b.handler_time.cmp(&a.handler_time));
+        totals
+    }
+    pub fn clear(&self) {
+        self.totals.borrow_mut().clear();
+    }
+    /// The totals as a table, one row per event type, with times in milliseconds
+    pub fn table(&self) -> String {
+        let mut table = format!("{:<40} {:>8} {:>12} {:>12} {:>12} {:>12}\n",
+            "event", "count", "handler ms", "mean ms", "max ms", "latency ms");
+        for totals in self.totals() {
+            let mean = millis(totals.handler_time) / totals.count as f64;
+            table.push_str(&format!("{:<40} {:>8} {:>12.3} {:>12.3} {:>12.3} {:>12.3}\n",
+                totals.type_name, totals.count, millis(totals.handler_time), mean,
+                millis(totals.max_handler_time), millis(totals.latency)));
+        }
+        table
+    }
+    pub fn print(&self) {
+        print!("{}", self.table());
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    seconds(duration) * 1000.0
+}
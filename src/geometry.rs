@@ -19,6 +19,7 @@ pub trait RectExt<T> {
     fn width(&self) -> T;
     fn height(&self) -> T;
     fn center(&self) -> Point;
+    fn distance_to(&self, point: Point) -> T;
     fn shrink_bounds(&self, size: T) -> Self;
     fn typed(&self) -> LayoutRect;
 }
@@ -52,6 +53,12 @@ impl RectExt<f32> for Rect {
     fn center(&self) -> Point {
         Point::new(self.left() + self.width() / 2.0, self.top() + self.height() / 2.0)
     }
+    /// Distance from `point` to the nearest edge of the rect, zero if the point is inside
+    fn distance_to(&self, point: Point) -> f32 {
+        let dx = f32::max(f32::max(self.left() - point.x, point.x - self.right()), 0.0);
+        let dy = f32::max(f32::max(self.top() - point.y, point.y - self.bottom()), 0.0);
+        (dx * dx + dy * dy).sqrt()
+    }
     fn shrink_bounds(&self, size: f32) -> Self {
         Rect::new(
             Point::new(self.origin.x + size / 2.0, self.origin.y + size / 2.0),
@@ -89,3 +96,95 @@ impl SizeExt<f32> for Size {
         LayoutSize::from_untyped(self)
     }
 }
+
+/// A uniform scale followed by an offset, mapping where a widget's children are laid out
+/// to where they're drawn, both relative to the widget's top left corner,
+/// see `WidgetRef::set_view_transform`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewTransform {
+    pub offset: Vector,
+    pub scale: f32,
+}
+impl ViewTransform {
+    pub fn new(offset: Vector, scale: f32) -> Self {
+        ViewTransform {
+            offset: offset,
+            scale: scale,
+        }
+    }
+    pub fn identity() -> Self {
+        ViewTransform::new(Vector::zero(), 1.0)
+    }
+    /// Where the point `point` of the content is drawn
+    pub fn transform_point(&self, point: Point) -> Point {
+        (point.to_vector() * self.scale + self.offset).to_point()
+    }
+    /// The point of the content drawn at `point`
+    pub fn inverse_transform_point(&self, point: Point) -> Point {
+        ((point.to_vector() - self.offset) / self.scale).to_point()
+    }
+    pub fn transform_rect(&self, rect: Rect) -> Rect {
+        Rect::new(self.transform_point(rect.origin), rect.size * self.scale)
+    }
+    pub fn inverse_transform_rect(&self, rect: Rect) -> Rect {
+        Rect::new(self.inverse_transform_point(rect.origin), rect.size / self.scale)
+    }
+    /// The same view at `scale`, with the point of the content drawn at `anchor` kept in place
+    pub(crate) fn zoom_around(&self, anchor: Point, scale: f32) -> Self {
+        let content_anchor = self.inverse_transform_point(anchor);
+        ViewTransform::new(anchor.to_vector() - content_anchor.to_vector() * scale, scale)
+    }
+    /// The view that shows all of `content` centered in a view of `size`, as large as it fits,
+    /// with the scale limited to between `min_scale` and `max_scale`
+    pub(crate) fn fit(content: Rect, size: Size, min_scale: f32, max_scale: f32) -> Self {
+        let scale = if content.width() > 0.0 && content.height() > 0.0 {
+            f32::min(size.width / content.width(), size.height / content.height())
+        } else {
+            1.0
+        };
+        let scale = scale.max(min_scale).min(max_scale);
+        let view_center = Point::new(size.width / 2.0, size.height / 2.0);
+        ViewTransform::new(view_center.to_vector() - content.center().to_vector() * scale, scale)
+    }
+}
+
+/// A shape a widget clips itself and its children to, inside its bounds, see `WidgetRef::set_clip_shape`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClipShape {
+    /// The bounds with rounded corners of this radius, limited to half the width and height
+    RoundedRect(f32),
+    /// The ellipse inscribed in the bounds, a circle if they're square
+    Ellipse,
+}
+impl ClipShape {
+    /// If `point` is inside the shape, when clipping `bounds`
+    pub(crate) fn contains(&self, bounds: Rect, point: Point) -> bool {
+        if !bounds.contains(&point) {
+            return false;
+        }
+        let radii = self.radii(bounds);
+        // distance from the center of the nearest corner's curve, relative to its radius,
+        // zero along the straight edges
+        let distance = |point: f32, min: f32, max: f32, radius: f32| {
+            if radius > 0.0 {
+                (point - point.max(min + radius).min(max - radius)) / radius
+            } else {
+                0.0
+            }
+        };
+        let dx = distance(point.x, bounds.left(), bounds.right(), radii.width);
+        let dy = distance(point.y, bounds.top(), bounds.bottom(), radii.height);
+        dx * dx + dy * dy <= 1.0
+    }
+    pub(crate) fn clip_region(&self, bounds: Rect) -> ComplexClipRegion {
+        ComplexClipRegion::new(bounds.typed(), BorderRadius::uniform_size(self.radii(bounds).typed()))
+    }
+    // the horizontal and vertical radius of each corner
+    fn radii(&self, bounds: Rect) -> Size {
+        let max = Size::new(bounds.width() / 2.0, bounds.height() / 2.0);
+        match *self {
+            ClipShape::RoundedRect(radius) => Size::new(radius.max(0.0).min(max.width), radius.max(0.0).min(max.height)),
+            ClipShape::Ellipse => max,
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
+use std::hash::Hash;
 
 use stable_bst::map::TreeMap;
-use stable_bst::Bound::{Excluded, Unbounded};
+use stable_bst::Bound::{Included, Excluded, Unbounded};
 
 use widget::{WidgetRef, WidgetBuilder};
 use widget::property::Property;
 use input::mouse::ClickEvent;
+use input::keymap::KeyMapHandler;
+use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
+use layout::LayoutSettled;
 use event::{EventHandler, EventArgs};
 use app::App;
 
@@ -19,39 +23,119 @@ pub struct KeyboardInput(pub glutin::ElementState, pub glutin::ScanCode, pub Opt
 pub struct WidgetKeyboardInput(pub glutin::ElementState, pub glutin::ScanCode, pub Option<glutin::VirtualKeyCode>);
 #[derive(Debug)]
 pub struct WidgetReceivedCharacter(pub char);
+/// Sent to a widget when it becomes the focused widget
+#[derive(Debug)]
+pub struct FocusGained;
+/// Sent to the focused widget when focus moves to another widget, or it's removed
+#[derive(Debug)]
+pub struct FocusLost;
+
+// the order tab moves focus through the focusable widgets, by tab index, then by the
+// order they were added, which is the tree order for widgets added as they're attached.
+// items with the same tab index keep the order they were added in among themselves,
+// unless `insert_ordered` adds them before others
+struct TabOrder<T> {
+    keys: HashMap<T, (i32, usize)>,
+    // can replace TreeMap with std BTreeMap once the range API or similar is stable
+    order: TreeMap<(i32, usize), T>,
+    next_added: usize,
+}
+impl<T: Clone + Eq + Hash> TabOrder<T> {
+    fn new() -> Self {
+        TabOrder {
+            keys: HashMap::new(),
+            order: TreeMap::new(),
+            next_added: 0,
+        }
+    }
+    fn contains(&self, item: &T) -> bool {
+        self.keys.contains_key(item)
+    }
+    // add `item` after the items with a lower tab index, and before the first item with the same tab index
+    // that `precedes` returns true for, eg. for a widget inserted before its siblings.
+    // does nothing if it's already added
+    fn insert_ordered<F>(&mut self, item: T, tab_index: i32, precedes: F)
+        where F: Fn(&T) -> bool
+    {
+        if self.contains(&item) {
+            return;
+        }
+        let before = self.order.range(Included(&(tab_index, 0)), Included(&(tab_index, ::std::usize::MAX)))
+            .find(|&(_, other)| precedes(other))
+            .map(|(key, _)| *key);
+        let key = match before {
+            Some(key) => {
+                // make room by moving the items from `key` on one place later
+                let later: Vec<((i32, usize), T)> = self.order.range(Included(&key), Included(&(tab_index, ::std::usize::MAX)))
+                    .map(|(key, item)| (*key, item.clone()))
+                    .collect();
+                for &(ref later_key, _) in &later {
+                    self.order.remove(later_key);
+                }
+                for (later_key, later_item) in later {
+                    let moved_key = (later_key.0, later_key.1 + 1);
+                    self.order.insert(moved_key, later_item.clone());
+                    self.keys.insert(later_item, moved_key);
+                }
+                key
+            }
+            None => (tab_index, self.next_added),
+        };
+        self.next_added += 1;
+        self.order.insert(key, item.clone());
+        self.keys.insert(item, key);
+    }
+    fn remove(&mut self, item: &T) {
+        if let Some(key) = self.keys.remove(item) {
+            self.order.remove(&key);
+        }
+    }
+    // the item after `current`, or the first item if there's none after it or no `current`
+    fn next(&self, current: Option<&T>) -> Option<&T> {
+        current.and_then(|current| self.keys.get(current))
+            .and_then(|key| self.order.range(Excluded(key), Unbounded).next())
+            .or_else(|| self.order.iter().next())
+            .map(|(_, item)| item)
+    }
+}
 
-/**
-Note on focus:
-The tab key iterates through the widgets that have registered as focusable.
-Currently the order of this iteration is just based on the order the widgets
-are registered as focusable.
-Later on maybe it should be based on the relative positioning of widgets (could get
-ugly updating the treemap as widgets change position), or some user defined ordering.
-*/
+// the index of the widget and each of its ancestors among their siblings, from the root, to compare widgets in tree order
+fn tree_path(widget: &WidgetRef) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut widget = widget.clone();
+    while let Some(parent) = widget.parent() {
+        path.push(parent.children().iter().position(|child| *child == widget).unwrap_or(0));
+        widget = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Moves focus between the focusable widgets, in the order of their tab index, see `WidgetBuilder::tab_index`
 pub struct FocusHandler {
-    focusable_map: HashMap<WidgetRef, usize>,
-     // can replace TreeMap with std BTreeMap once the range API or similar is stable
-    focusable: TreeMap<usize, WidgetRef>,
+    tab_order: TabOrder<WidgetRef>,
     focused: Option<WidgetRef>,
-    focus_index_max: usize,
+    // widget to focus once the layout has settled after it's attached, see `WidgetBuilder::set_initial_focus`
+    initial_focus: Option<WidgetRef>,
 }
 impl FocusHandler {
     pub fn new() -> Self {
         FocusHandler {
-            focusable_map: HashMap::new(),
-            focusable: TreeMap::new(),
+            tab_order: TabOrder::new(),
             focused: None,
-            focus_index_max: 0,
+            initial_focus: None,
         }
     }
     fn set_focus(&mut self, new_focus: Option<WidgetRef>) {
         if new_focus != self.focused {
             if let Some(ref mut focused) = self.focused {
                 focused.remove_prop(Property::Focused);
+                focused.event(FocusLost);
             }
             self.focused = new_focus;
             if let Some(ref mut focused) = self.focused {
                 focused.add_prop(Property::Focused);
+                focused.event(FocusGained);
             }
         }
     }
@@ -60,9 +144,12 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
     fn handle(&mut self, event: &KeyboardInputEvent, _: EventArgs) {
         match *event {
             KeyboardInputEvent::AddFocusable(ref widget_id) => {
-                self.focusable.insert(self.focus_index_max, widget_id.clone());
-                self.focusable_map.insert(widget_id.clone(), self.focus_index_max);
-                self.focus_index_max += 1;
+                if self.tab_order.contains(widget_id) {
+                    return;
+                }
+                // in tree order, so a widget inserted before its siblings is also before them in the tab order
+                let path = tree_path(widget_id);
+                self.tab_order.insert_ordered(widget_id.clone(), widget_id.tab_index(), |other| path < tree_path(other));
                 if self.focused.is_none() {
                     self.set_focus(Some(widget_id.clone()));
                 }
@@ -73,12 +160,22 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
                         self.set_focus(None);
                     }
                 }
-                let index = self.focusable_map.remove(widget_id).unwrap();
-                self.focusable.remove(&index);
+                if self.initial_focus.as_ref() == Some(widget_id) {
+                    self.initial_focus = None;
+                }
+                self.tab_order.remove(widget_id);
             }
             KeyboardInputEvent::FocusChange(ref new_focus) => {
                 self.set_focus(new_focus.clone());
             }
+            KeyboardInputEvent::InitialFocus(ref widget_ref) => {
+                self.initial_focus = Some(widget_ref.clone());
+            }
+            KeyboardInputEvent::LayoutSettled => {
+                if let Some(widget_ref) = self.initial_focus.take() {
+                    self.set_focus(Some(widget_ref));
+                }
+            }
             KeyboardInputEvent::KeyboardInput(ref key_input) => {
                 if let Some(ref focused) = self.focused {
                     let &KeyboardInput(state, scan_code, maybe_keycode) = key_input;
@@ -89,14 +186,7 @@ impl EventHandler<KeyboardInputEvent> for FocusHandler {
             KeyboardInputEvent::ReceivedCharacter(ref received_char) => {
                 let &ReceivedCharacter(char) = received_char;
                 if char == '\t' {
-                    let mut new_focus = self.focused.clone().and_then(|focused| {
-                        let index = &self.focusable_map[&focused];
-                        self.focusable.range(Excluded(index), Unbounded).next().map(|(_, v)| v.clone())
-                    });
-                    if new_focus.is_none() {
-                        // focus on first, if any
-                        new_focus = self.focusable.iter().next().map(|(_, v)| v.clone());
-                    }
+                    let new_focus = self.tab_order.next(self.focused.as_ref()).cloned();
                     self.set_focus(new_focus);
                 } else if let Some(ref focused) = self.focused {
                     let event = WidgetReceivedCharacter(char);
@@ -113,6 +203,8 @@ pub enum KeyboardInputEvent {
     FocusChange(Option<WidgetRef>),
     KeyboardInput(KeyboardInput),
     ReceivedCharacter(ReceivedCharacter),
+    InitialFocus(WidgetRef),
+    LayoutSettled,
 }
 
 impl WidgetBuilder {
@@ -121,6 +213,35 @@ impl WidgetBuilder {
             args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(args.widget)));
         })
     }
+    /// Make the widget focusable, and part of the tab order while it's attached
+    pub fn make_tab_focusable(&mut self) -> &mut Self {
+        self.add_handler_fn(|_: &WidgetAttachedEvent, args| {
+                args.ui.event(KeyboardInputEvent::AddFocusable(args.widget));
+            })
+            .add_handler_fn(|_: &WidgetDetachedEvent, args| {
+                args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
+            })
+            .make_focusable()
+    }
+    /// Focus this widget when it's attached, once the layout of the new widgets has been solved,
+    /// eg. the first field of a dialog. The widget doesn't need to be in the tab order.
+    pub fn set_initial_focus(&mut self, initial_focus: bool) -> &mut Self {
+        if initial_focus {
+            self.add_handler_fn(|_: &WidgetAttachedEvent, args| {
+                args.ui.event(KeyboardInputEvent::InitialFocus(args.widget));
+            });
+        }
+        self
+    }
+}
+
+/// Whether a key press should activate a focused widget, like a click would
+pub fn is_activation_key(event: &WidgetKeyboardInput) -> bool {
+    match *event {
+        WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::Space)) |
+        WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::Return)) => true,
+        _ => false,
+    }
 }
 
 impl App {
@@ -131,6 +252,11 @@ impl App {
         self.add_handler_fn(|event: &ReceivedCharacter, args| {
             args.widget.event(KeyboardInputEvent::ReceivedCharacter(event.clone()));
         });
+        self.add_handler_fn(|_: &LayoutSettled, args| {
+            args.widget.event(KeyboardInputEvent::LayoutSettled);
+        });
         self.add_handler(FocusHandler::new());
+        // the raw `KeyboardInput` is still delivered, in addition to any `ActionEvent`
+        self.add_handler(KeyMapHandler::default());
     }
 }
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use glutin::{ElementState, VirtualKeyCode};
+
+use event::{EventHandler, EventArgs};
+use input::keyboard::KeyboardInput;
+
+/// Identifies an action that can be bound to a `KeyCombo`, eg. "save" or "undo"
+pub type ActionId = &'static str;
+
+/// A key, plus the modifier keys that must be held down with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    pub key: VirtualKeyCode,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub logo: bool,
+}
+impl KeyCombo {
+    pub fn new(key: VirtualKeyCode) -> Self {
+        KeyCombo {
+            key: key,
+            shift: false,
+            ctrl: false,
+            alt: false,
+            logo: false,
+        }
+    }
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+    pub fn logo(mut self) -> Self {
+        self.logo = true;
+        self
+    }
+}
+
+/// Sent to the root widget when a key combo bound to an action is pressed
+#[derive(Clone, Debug)]
+pub struct ActionEvent(pub ActionId);
+
+/// Maps key combos to actions, owned by the `Ui`.
+/// Each action is bound to at most one combo, and each combo to at most one action.
+#[derive(Default)]
+pub struct KeyMap {
+    defaults: HashMap<ActionId, KeyCombo>,
+    bindings: HashMap<KeyCombo, ActionId>,
+}
+impl KeyMap {
+    pub fn new() -> Self {
+        KeyMap::default()
+    }
+    /// Add an action, initially bound to `default_combo`
+    pub fn register_action(&mut self, action: ActionId, default_combo: KeyCombo) {
+        self.defaults.insert(action, default_combo);
+        self.rebind(action, default_combo);
+    }
+    /// Bind `action` to `combo`, replacing its current binding.
+    /// Any other action bound to `combo` is left unbound.
+    pub fn rebind(&mut self, action: ActionId, combo: KeyCombo) {
+        if !self.defaults.contains_key(action) {
+            warn!("rebinding unregistered action {}", action);
+        }
+        self.unbind(action);
+        self.bindings.insert(combo, action);
+    }
+    /// Remove the binding for `action`, if any
+    pub fn unbind(&mut self, action: ActionId) {
+        self.bindings.retain(|_, bound_action| *bound_action != action);
+    }
+    /// Rebind every registered action to its default combo
+    pub fn reset_to_defaults(&mut self) {
+        self.bindings.clear();
+        for (action, combo) in &self.defaults {
+            self.bindings.insert(*combo, *action);
+        }
+    }
+    pub fn action_for(&self, combo: KeyCombo) -> Option<ActionId> {
+        self.bindings.get(&combo).cloned()
+    }
+    pub fn combo_for(&self, action: ActionId) -> Option<KeyCombo> {
+        self.bindings.iter()
+            .find(|&(_, bound_action)| *bound_action == action)
+            .map(|(combo, _)| *combo)
+    }
+}
+
+/// Sends an `ActionEvent` when a key combo bound in the `Ui`'s `KeyMap` is pressed,
+/// tracking the modifier keys to find the combo for each key press
+#[derive(Default)]
+pub(super) struct KeyMapHandler {
+    shift: bool,
+    ctrl: bool,
+    alt: bool,
+    logo: bool,
+}
+impl EventHandler<KeyboardInput> for KeyMapHandler {
+    fn handle(&mut self, event: &KeyboardInput, args: EventArgs) {
+        let &KeyboardInput(state, _, maybe_keycode) = event;
+        let pressed = state == ElementState::Pressed;
+        match maybe_keycode {
+            Some(VirtualKeyCode::LShift) | Some(VirtualKeyCode::RShift) => self.shift = pressed,
+            Some(VirtualKeyCode::LControl) | Some(VirtualKeyCode::RControl) => self.ctrl = pressed,
+            Some(VirtualKeyCode::LAlt) | Some(VirtualKeyCode::RAlt) => self.alt = pressed,
+            Some(VirtualKeyCode::LWin) | Some(VirtualKeyCode::RWin) => self.logo = pressed,
+            Some(key) if pressed => {
+                let combo = KeyCombo {
+                    key: key,
+                    shift: self.shift,
+                    ctrl: self.ctrl,
+                    alt: self.alt,
+                    logo: self.logo,
+                };
+                if let Some(action) = args.ui.key_map().action_for(combo) {
+                    args.widget.event(ActionEvent(action));
+                }
+            }
+            _ => (),
+        }
+    }
+}
@@ -1,15 +1,17 @@
 pub mod mouse;
 pub mod keyboard;
+pub mod keymap;
 
 use glutin;
 use glutin::ElementState;
 use webrender;
 
-use event::{EventHandler, EventArgs};
+use event::{EventHandler, EventArgs, RecordedInput, RecordedButton};
 use input::mouse::{MouseMoved, MouseButton, MouseWheel};
 use input::keyboard::{KeyboardInput, ReceivedCharacter};
 use geometry::Point;
 use app::App;
+use ui::Ui;
 
 #[derive(Clone)]
 pub struct InputEvent(pub glutin::WindowEvent);
@@ -17,31 +19,163 @@ pub struct InputEvent(pub glutin::WindowEvent);
 impl App {
     pub fn add_input_handlers(&mut self) {
         self.add_handler_fn(|event: &InputEvent, args| {
-            let InputEvent(event) = event.clone();
-            match event {
-                glutin::WindowEvent::Closed => {
-                    args.ui.close();
-                }
-                glutin::WindowEvent::MouseWheel { delta, .. } => {
-                    args.widget.event(MouseWheel(delta));
-                }
-                glutin::WindowEvent::MouseInput { state, button, .. } => {
-                    args.widget.event(MouseButton(state, button));
-                }
-                glutin::WindowEvent::MouseMoved { position, .. } => {
-                    let point = Point::new(position.0 as f32, position.1 as f32);
-                    args.widget.event(MouseMoved(point));
-                }
-                glutin::WindowEvent::KeyboardInput { input, .. } => {
-                    let key_input = KeyboardInput(input.state, input.scancode, input.virtual_keycode);
-                    args.widget.event(key_input);
+            let InputEvent(ref event) = *event;
+            if let glutin::WindowEvent::Closed = *event {
+                args.ui.close();
+            } else if let Some(input) = RecordedInput::from_window_event(event) {
+                args.ui.record_input(&input);
+                input.dispatch(args.ui);
+            }
+        });
+    }
+}
+
+// every key, by the name it's recorded with, its `Debug` name
+macro_rules! key_names {
+    ($($key:ident),*) => {
+        /// Every virtual key code that can be recorded and replayed, see `key_name`
+        pub const NAMED_KEYS: &'static [glutin::VirtualKeyCode] = &[$(glutin::VirtualKeyCode::$key),*];
+
+        /// The name a key is recorded with, the name of its variant.
+        /// Keys that aren't named are recorded without a virtual key code.
+        pub fn key_name(key: glutin::VirtualKeyCode) -> Option<&'static str> {
+            #[allow(unreachable_patterns)]
+            match key {
+                $(glutin::VirtualKeyCode::$key => Some(stringify!($key)),)*
+                _ => None,
+            }
+        }
+        /// The key recorded as `name`, see `key_name`
+        pub fn key_from_name(name: &str) -> Option<glutin::VirtualKeyCode> {
+            match name {
+                $(stringify!($key) => Some(glutin::VirtualKeyCode::$key),)*
+                _ => None,
+            }
+        }
+    };
+}
+key_names!(
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Escape,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15,
+    Snapshot, Scroll, Pause,
+    Insert, Home, Delete, End, PageDown, PageUp,
+    Left, Up, Right, Down,
+    Back, Return, Space, Compose,
+    Numlock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    AbntC1, AbntC2, Add, Apostrophe, Apps, At, Ax, Backslash, Calculator, Capital, Colon, Comma, Convert,
+    Decimal, Divide, Equals, Grave, Kana, Kanji,
+    LAlt, LBracket, LControl, LMenu, LShift, LWin,
+    Mail, MediaSelect, MediaStop, Minus, Multiply, Mute, MyComputer,
+    NavigateForward, NavigateBackward, NextTrack, NoConvert, NumpadComma, NumpadEnter, NumpadEquals,
+    OEM102, Period, PlayPause, Power, PrevTrack,
+    RAlt, RBracket, RControl, RMenu, RShift, RWin,
+    Semicolon, Slash, Sleep, Stop, Subtract, Sysrq, Tab, Underline, Unlabeled,
+    VolumeDown, VolumeUp, Wake,
+    WebBack, WebFavorites, WebForward, WebHome, WebRefresh, WebSearch, WebStop,
+    Yen
+);
+
+impl From<glutin::MouseButton> for RecordedButton {
+    fn from(button: glutin::MouseButton) -> Self {
+        match button {
+            glutin::MouseButton::Left => RecordedButton::Left,
+            glutin::MouseButton::Right => RecordedButton::Right,
+            glutin::MouseButton::Middle => RecordedButton::Middle,
+            glutin::MouseButton::Other(button) => RecordedButton::Other(button),
+        }
+    }
+}
+impl From<RecordedButton> for glutin::MouseButton {
+    fn from(button: RecordedButton) -> Self {
+        match button {
+            RecordedButton::Left => glutin::MouseButton::Left,
+            RecordedButton::Right => glutin::MouseButton::Right,
+            RecordedButton::Middle => glutin::MouseButton::Middle,
+            RecordedButton::Other(button) => glutin::MouseButton::Other(button),
+        }
+    }
+}
+
+fn element_state(pressed: bool) -> ElementState {
+    if pressed { ElementState::Pressed } else { ElementState::Released }
+}
+
+impl RecordedInput {
+    /// The input a window event is handled as, if it's handled as input
+    pub fn from_window_event(event: &glutin::WindowEvent) -> Option<Self> {
+        let input = match *event {
+            glutin::WindowEvent::MouseWheel { delta, .. } => match delta {
+                glutin::MouseScrollDelta::LineDelta(x, y) => RecordedInput::MouseWheel { x: x, y: y, pixels: false },
+                glutin::MouseScrollDelta::PixelDelta(x, y) => RecordedInput::MouseWheel { x: x, y: y, pixels: true },
+            },
+            glutin::WindowEvent::MouseInput { state, button, .. } => {
+                RecordedInput::MouseButton { pressed: state == ElementState::Pressed, button: button.into() }
+            }
+            glutin::WindowEvent::MouseMoved { position, .. } => {
+                RecordedInput::MouseMoved(position.0 as f32, position.1 as f32)
+            }
+            glutin::WindowEvent::KeyboardInput { input, .. } => RecordedInput::Key {
+                pressed: input.state == ElementState::Pressed,
+                scancode: input.scancode,
+                key: input.virtual_keycode.and_then(key_name).map(|name| name.to_owned()),
+            },
+            glutin::WindowEvent::ReceivedCharacter(char) => RecordedInput::Character(char),
+            _ => return None,
+        };
+        Some(input)
+    }
+    // handles input while the pointer is locked, returns true if it shouldn't be handled as usual:
+    // the cursor doesn't move, and escape unlocks the pointer
+    fn pointer_lock_input(&self, ui: &mut Ui) -> bool {
+        match *self {
+            RecordedInput::MouseMoved(..) => true,
+            RecordedInput::Key { pressed, key: Some(ref key), .. } if key == "Escape" => {
+                if pressed {
+                    ui.unlock_pointer();
                 }
-                glutin::WindowEvent::ReceivedCharacter(char) => {
-                    args.widget.event(ReceivedCharacter(char));
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Update the input state of the UI and send the input to the root widget, as if it was just received.
+    /// Keys without a virtual key code, or whose names aren't known, are sent without one, see `key_name`.
+    pub fn dispatch(&self, ui: &mut Ui) {
+        if ui.pointer_lock().is_some() && self.pointer_lock_input(ui) {
+            return;
+        }
+        match *self {
+            RecordedInput::MouseWheel { x, y, pixels } => {
+                let delta = if pixels {
+                    glutin::MouseScrollDelta::PixelDelta(x, y)
+                } else {
+                    glutin::MouseScrollDelta::LineDelta(x, y)
+                };
+                ui.event(MouseWheel(delta));
+            }
+            RecordedInput::MouseButton { pressed, button } => {
+                let (state, button) = (element_state(pressed), button.into());
+                // updated first, so handlers of this event see the new state
+                ui.set_mouse_button_state(state, button);
+                ui.event(MouseButton(state, button));
+            }
+            RecordedInput::MouseMoved(x, y) => {
+                let point = Point::new(x, y);
+                ui.set_cursor_position(point);
+                ui.event(MouseMoved(point));
+            }
+            RecordedInput::Key { pressed, scancode, ref key } => {
+                let state = element_state(pressed);
+                let key = key.as_ref().and_then(|key| key_from_name(key));
+                if let Some(key) = key {
+                    ui.set_key_state(state, key);
                 }
-                _ => (),
+                ui.event(KeyboardInput(state, scancode, key));
             }
-        });
+            RecordedInput::Character(char) => ui.event(ReceivedCharacter(char)),
+        }
     }
 }
 
@@ -80,7 +214,9 @@ impl EventHandler<KeyboardInput> for DebugSettingsHandler {
             ui.solver.debug_variables();
         }
         if let KeyboardInput(ElementState::Released, _, Some(glutin::VirtualKeyCode::P)) = *event {
-            ui.render.toggle_flags(webrender::PROFILER_DBG);
+            if let Some(ref mut render) = ui.render {
+                render.toggle_flags(webrender::PROFILER_DBG);
+            }
         }
     }
 }
@@ -24,9 +24,34 @@ pub enum MouseInputEvent {
 
 #[derive(Clone, Copy, Debug)]
 pub struct ClickEvent {
+    /// mouse position in window coordinates
     pub position: Point,
+    /// mouse position relative to the top left of the clicked widget
+    pub local_position: Point,
 }
 
+/// Sent to the widget under the cursor when the right mouse button is pressed, bubbling up
+/// until a widget handles it, usually by opening a context menu, see `WidgetBuilder::set_context_menu`
+#[derive(Clone, Copy, Debug)]
+pub struct ContextMenuEvent {
+    /// mouse position in window coordinates
+    pub position: Point,
+    /// mouse position relative to the top left of the widget under the cursor
+    pub local_position: Point,
+}
+
+/// Sent to the widget the pointer is locked to each time the mouse moves, with how far it moved,
+/// in the units the device reports, see `Ui::lock_pointer`
+#[derive(Clone, Copy, Debug)]
+pub struct RelativeMotionEvent {
+    pub dx: f32,
+    pub dy: f32,
+}
+
+/// Sent to the widget the pointer was locked to once it's unlocked, by `Ui::unlock_pointer` or pressing escape
+#[derive(Clone, Copy, Debug)]
+pub struct PointerUnlocked;
+
 struct MouseController {
     pub mouse: Point,
     pub widget_under_mouse: Option<WidgetRef>,
@@ -66,7 +91,19 @@ impl EventHandler<MouseInputEvent> for MouseController {
                 if let Some(ref widget_under) = self.widget_under_mouse {
                     widget_under.event_bubble_up(WidgetMouseButton(state, button));
                     if (state == glutin::ElementState::Released) && (button == glutin::MouseButton::Left) {
-                        let event = ClickEvent { position: self.mouse };
+                        let local_position = widget_under.local_point(self.mouse);
+                        let event = ClickEvent {
+                            position: self.mouse,
+                            local_position: local_position,
+                        };
+                        widget_under.event_bubble_up(event);
+                    }
+                    if (state == glutin::ElementState::Pressed) && (button == glutin::MouseButton::Right) {
+                        let local_position = widget_under.local_point(self.mouse);
+                        let event = ContextMenuEvent {
+                            position: self.mouse,
+                            local_position: local_position,
+                        };
                         widget_under.event_bubble_up(event);
                     }
                 }
@@ -1,13 +1,15 @@
 use std::ops::DerefMut;
+use std::collections::HashSet;
 
 use limn_layout::linear_layout::{LinearLayout, LinearLayoutSettings};
 use limn_layout::grid_layout::GridLayout;
 
 use resources::WidgetId;
+use geometry::Size;
 
 use app::App;
 
-use widget::{WidgetRef, WidgetBuilder};
+use widget::{WidgetRef, WidgetBuilder, WidgetError};
 
 pub use self::solver::LimnSolver;
 pub use limn_layout::*;
@@ -30,6 +32,60 @@ pub struct UpdateLayout(pub WidgetRef);
 pub struct ResizeWindow;
 pub struct LayoutChanged(pub Vec<(usize, VarType, f64)>);
 pub struct LayoutUpdated;
+/// Sent to a widget built with `WidgetBuilder::respond_to_resize` when its width or height changes,
+/// once per layout change, after its bounds are updated. `old` and `new` aren't rounded, even for
+/// a widget that only responds to whole pixel changes, `old` is the size sent with the last event then.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetResizedEvent {
+    pub widget: WidgetId,
+    pub old: Size,
+    pub new: Size,
+}
+// if a change in size from `old` to `new` changes the size rounded to whole pixels
+fn pixel_size_changed(old: Size, new: Size) -> bool {
+    old.width.round() != new.width.round() || old.height.round() != new.height.round()
+}
+/// Sent to the root widget once the layout has stopped changing, after
+/// all the pending layout updates have been solved.
+/// Sent again each time the layout changes and settles.
+pub struct LayoutSettled;
+/// Sent to the root widget when layout variables change too many times in one frame,
+/// with the debug names of the variables, usually caused by a handler that changes
+/// the layout in response to a layout change. See `LimnSolver::thrash_limit`.
+pub struct LayoutThrashingEvent(pub Vec<String>);
+
+/// The values suggested for the edit variables of a set of widgets, that can be saved and
+/// suggested again later, eg. to switch between arrangements of resizable panels.
+/// See `Ui::capture_layout_preset` and `Ui::apply_layout_preset`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPreset {
+    pub values: Vec<PresetValue>,
+}
+
+/// The solved bounds of widgets, as `[left, top, width, height]`, see `Ui::layout_snapshot` and `Ui::restore_layout`
+pub type LayoutSnapshot = Vec<(WidgetId, [f64; 4])>;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresetValue {
+    /// Name of the widget the value is suggested for
+    pub key: String,
+    #[serde(with = "VarTypeDef")]
+    pub var: VarType,
+    pub value: f64,
+    pub strength: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "VarType")]
+enum VarTypeDef {
+    Left,
+    Top,
+    Right,
+    Bottom,
+    Width,
+    Height,
+    Other,
+}
 
 impl App {
     pub fn add_layout_handlers(&mut self) {
@@ -41,16 +97,25 @@ impl App {
             let UpdateLayout(widget_ref) = event;
             let mut widget_mut = widget_ref.widget_mut();
             let layout = &mut widget_mut.layout;
-            args.ui.solver.update_layout(layout);
+            if let Err(constraint) = args.ui.solver.update_layout(layout) {
+                args.ui.report_error(WidgetError::ConstraintConflict(constraint));
+            }
             args.ui.check_layout_changes();
         });
         self.add_handler_fn(|event: &LayoutChanged, args| {
             let changes = &event.0;
+            // widgets tracking resizes, with their size before the change
+            let mut resized: Vec<(WidgetId, Size)> = Vec::new();
+            let mut resized_ids = HashSet::new();
             for &(widget_id, var, value) in changes {
                 let widget_id = WidgetId(widget_id);
-                if let Some(widget) = args.ui.get_widget(widget_id) {
+                if let Some(widget_ref) = args.ui.get_widget(widget_id) {
                     {
-                        let widget = &mut *widget.widget_mut();
+                        let widget = &mut *widget_ref.widget_mut();
+                        if widget.tracks_resize && (var == VarType::Width || var == VarType::Height)
+                            && resized_ids.insert(widget_id) {
+                            resized.push((widget_id, widget.bounds.size));
+                        }
                         let value = value as f32;
                         debug!("{:?}: {:?} = {}", widget.name(), var, value);
                         match var {
@@ -61,7 +126,31 @@ impl App {
                             _ => (),
                         }
                     }
-                    widget.event(LayoutUpdated);
+                    widget_ref.event(LayoutUpdated);
+                }
+            }
+            for (widget_id, old) in resized {
+                if let Some(widget_ref) = args.ui.get_widget(widget_id) {
+                    let new = widget_ref.bounds().size;
+                    let old = {
+                        let mut widget = widget_ref.widget_mut();
+                        if widget.resize_whole_pixels {
+                            // compared with the size last sent, so the changes between events add up
+                            if !pixel_size_changed(widget.last_resize, new) {
+                                continue;
+                            }
+                            ::std::mem::replace(&mut widget.last_resize, new)
+                        } else if new != old {
+                            old
+                        } else {
+                            continue;
+                        }
+                    };
+                    widget_ref.event(WidgetResizedEvent {
+                        widget: widget_id,
+                        old: old,
+                        new: new,
+                    });
                 }
             }
             // redraw everything when layout changes, for now
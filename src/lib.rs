@@ -24,6 +24,12 @@ extern crate webrender_api;
 extern crate gleam;
 extern crate app_units;
 extern crate image;
+extern crate clipboard;
+extern crate regex;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 
 #[macro_use]
 pub mod event;
@@ -42,6 +48,9 @@ pub mod input;
 pub mod prelude;
 pub mod render;
 pub mod window;
+pub mod theme;
+pub mod util;
+pub mod task;
 
 #[cfg(not(feature="nightly"))]
 fn type_name<T>() -> &'static str {
@@ -1,16 +1,16 @@
-pub use cassowary::strength::*;
+pub use layout::strength::*;
 pub use cassowary::WeightedRelation::*;
 
 pub use geometry::{Point, PointExt, Rect, RectExt, Size, SizeExt, Vector};
 pub use event::{Target, EventHandler, EventArgs};
-pub use event::{event, event_global};
+pub use event::{event, event_global, EventSender};
 pub use widget::{WidgetRef, WidgetBuilder};
 pub use widget::draw::{Draw, DrawEventHandler};
 pub use widget::property::Property;
 pub use widget::property::states::*;
 pub use render::RenderBuilder;
 pub use resources::WidgetId;
-pub use ui::Ui;
+pub use ui::{Ui, CommandSender};
 pub use color::*;
 pub use layout::constraint::*;
 pub use layout::LAYOUT;
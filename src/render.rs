@@ -19,7 +19,8 @@ pub(super) struct WebRenderContext {
     pub pipeline_id: PipelineId,
     pub document_id: DocumentId,
     pub device_pixel_ratio: f32,
-    pub root_background_color: ColorF,
+    // drawn behind the UI, if `None` whatever was in the framebuffer is left behind it
+    pub root_background_color: Option<ColorF>,
     // store frame ready event in case it is received after
     // update but before the event queue is waiting, otherwise
     // the event queue can go idle while there is a frame ready
@@ -55,7 +56,7 @@ impl WebRenderContext {
         renderer.set_render_notifier(notifier);
 
         let epoch = Epoch(0);
-        let root_background_color = ColorF::new(0.8, 0.8, 0.8, 1.0);
+        let root_background_color = Some(ColorF::new(0.8, 0.8, 0.8, 1.0));
 
         let pipeline_id = PipelineId(0, 0);
         api.set_root_pipeline(document_id, pipeline_id);
@@ -84,7 +85,7 @@ impl WebRenderContext {
         self.render_api.set_display_list(
             self.document_id,
             self.epoch,
-            Some(self.root_background_color),
+            self.root_background_color,
             window_size,
             builder.finalize(),
             true,
@@ -3,11 +3,15 @@ pub mod id;
 
 use std::sync::{Mutex, MutexGuard};
 use std::collections::HashMap;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io;
 
 use webrender::api::*;
 use image;
 use rusttype;
 use app_units;
+use clipboard::{ClipboardProvider, ClipboardContext};
 
 use text_layout;
 
@@ -15,25 +19,117 @@ use self::id::{Id, IdGen};
 
 pub type Font = rusttype::Font<'static>;
 
+/// The font text is drawn in unless another is chosen, see `TextState::font`
+pub const DEFAULT_FONT: &str = "NotoSans/NotoSans-Regular";
+// built in, so text can be drawn without any font files, see `Resources::get_font`
+static DEFAULT_FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/NotoSans/NotoSans-Regular.ttf");
+
 lazy_static! {
     static ref RES: Mutex<Resources> = Mutex::new(Resources::new());
+    // the last widget registered with each name, for `lookup_widget_by_name`
+    static ref DEBUG_WIDGET_IDS: Mutex<HashMap<String, WidgetId>> = Mutex::new(HashMap::new());
+}
+thread_local! {
+    // kept open, on some platforms copied text is only available while it is
+    static SYSTEM_CLIPBOARD: RefCell<Option<ClipboardContext>> = RefCell::new(ClipboardProvider::new().ok());
+    // false once `use_app_clipboard` is called
+    static USE_SYSTEM_CLIPBOARD: Cell<bool> = Cell::new(true);
+    // used when the system clipboard isn't, on the thread the UI runs on
+    static CLIPBOARD: RefCell<Option<String>> = RefCell::new(None);
 }
 
 pub fn init_resources(render_api: RenderApi) {
-    RES.try_lock().unwrap().render = Some(render_api);
+    resources().render = Some(render_api);
 }
-// Allow global access to Resources
+// Allow global access to Resources. It's locked rather than borrowed, since widgets can be built on any thread,
+// eg. by several headless apps at once in tests, so it mustn't be held while calling code that might use it again.
 pub fn resources() -> MutexGuard<'static, Resources> {
-    RES.try_lock().unwrap()
+    RES.lock().unwrap()
 }
 
 named_id!(WidgetId);
 
+impl WidgetId {
+    /// The id of the widget named `name`, see `lookup_widget_by_name`
+    pub fn from_debug_name(name: &str) -> Option<WidgetId> {
+        lookup_widget_by_name(name)
+    }
+}
+
+/// The id of the widget named `name`, or if there are several with that name,
+/// the one registered or renamed most recently. Useful for tests and debugging,
+/// to refer to widgets without keeping their ids around.
+pub fn lookup_widget_by_name(name: &str) -> Option<WidgetId> {
+    DEBUG_WIDGET_IDS.lock().unwrap().get(name).cloned()
+}
+/// Make `name` refer to the widget `id` in `lookup_widget_by_name`
+pub(crate) fn register_widget_name(name: &str, id: WidgetId) {
+    DEBUG_WIDGET_IDS.lock().unwrap().insert(name.to_owned(), id);
+}
+/// Stop `name` referring to the widget `id`, if it hasn't since been taken by another widget
+pub(crate) fn unregister_widget_name(name: &str, id: WidgetId) {
+    let mut widget_ids = DEBUG_WIDGET_IDS.lock().unwrap();
+    if widget_ids.get(name) == Some(&id) {
+        widget_ids.remove(name);
+    }
+}
+
+/// Keep text that's cut or copied in the app, on the calling thread, instead of using the system clipboard,
+/// eg. so tests don't change the user's clipboard, or depend on what's on it
+pub fn use_app_clipboard() {
+    USE_SYSTEM_CLIPBOARD.with(|use_system| use_system.set(false));
+}
+
+/// Text on the system clipboard, if it has any. If the system clipboard can't be read,
+/// or isn't used, see `use_app_clipboard`, the text most recently cut or copied in this app.
+pub fn clipboard_text() -> Option<String> {
+    let system_text = if USE_SYSTEM_CLIPBOARD.with(|use_system| use_system.get()) {
+        SYSTEM_CLIPBOARD.with(|clipboard| {
+            clipboard.borrow_mut().as_mut().and_then(|clipboard| clipboard.get_contents().ok())
+        })
+    } else {
+        None
+    };
+    match system_text {
+        Some(text) => if text.is_empty() { None } else { Some(text) },
+        None => CLIPBOARD.with(|clipboard| clipboard.borrow().clone()),
+    }
+}
+pub fn set_clipboard_text(text: &str) {
+    CLIPBOARD.with(|clipboard| *clipboard.borrow_mut() = Some(text.to_owned()));
+    if !USE_SYSTEM_CLIPBOARD.with(|use_system| use_system.get()) {
+        return;
+    }
+    SYSTEM_CLIPBOARD.with(|clipboard| {
+        if let Some(ref mut clipboard) = *clipboard.borrow_mut() {
+            if let Err(error) = clipboard.set_contents(text.to_owned()) {
+                warn!("couldn't copy to the system clipboard: {}", error);
+            }
+        }
+    });
+}
+
 pub struct FontInfo {
     pub key: FontKey,
     pub info: Font,
 }
 
+/// Error returned by `Resources::add_font` for data that isn't a TrueType font, with the name of the font
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidFontError(pub String);
+
+impl fmt::Display for InvalidFontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "the data added for font {} isn't a TrueType font", self.0)
+    }
+}
+
+impl ::std::error::Error for InvalidFontError {
+    fn description(&self) -> &str {
+        "invalid font data"
+    }
+}
+
 pub struct ImageInfo {
     pub key: ImageKey,
     pub info: ImageDescriptor,
@@ -70,6 +166,10 @@ pub struct Resources {
     pub font_instances: HashMap<(String, app_units::Au), FontInstanceKey>,
     pub images: HashMap<String, ImageInfo>,
     pub widget_id: IdGen<WidgetId>,
+    // fonts added with `add_font`, used instead of loading the font from a file
+    font_data: HashMap<String, Vec<u8>>,
+    // width, height and RGBA pixels of the images added with `add_image`, used instead of loading the image from a file
+    image_data: HashMap<String, (u32, u32, Vec<u8>)>,
 }
 impl Resources {
     pub fn new() -> Self {
@@ -79,6 +179,8 @@ impl Resources {
             font_instances: HashMap::new(),
             images: HashMap::new(),
             widget_id: IdGen::new(),
+            font_data: HashMap::new(),
+            image_data: HashMap::new(),
         }
     }
     pub fn widget_id(&mut self) -> WidgetId {
@@ -87,26 +189,74 @@ impl Resources {
 
     pub fn get_image(&mut self, name: &str) -> &ImageInfo {
         if !self.images.contains_key(name) {
-            let (data, descriptor) = load_image(name).unwrap();
-            let key = self.render.as_ref().unwrap().generate_image_key();
-            let mut resources = ResourceUpdates::new();
-            resources.add_image(key, descriptor, data, None);
-            self.render.as_ref().unwrap().update_resources(resources);
+            let (data, descriptor) = match self.image_data.get(name) {
+                Some(&(width, height, ref pixels)) => rgba_image(width, height, pixels.clone()),
+                None => load_image(name).unwrap(),
+            };
+            let key = match self.render {
+                Some(ref render) => {
+                    let key = render.generate_image_key();
+                    let mut resources = ResourceUpdates::new();
+                    resources.add_image(key, descriptor, data, None);
+                    render.update_resources(resources);
+                    key
+                }
+                // never drawn without a renderer
+                None => ImageKey::new(IdNamespace(0), 0),
+            };
             let image_info = ImageInfo { key: key, info: descriptor };
             self.images.insert(name.to_owned(), image_info);
         }
         &self.images[name]
     }
 
+    /// Use the RGBA `pixels` of an image `width` by `height`, row by row from the top,
+    /// for the image `name`, instead of loading it from `assets/images/<name>`, eg. for images generated by the app.
+    pub fn add_image(&mut self, name: &str, width: u32, height: u32, pixels: Vec<u8>) {
+        assert_eq!(pixels.len(), (width * height * 4) as usize, "Image {} isn't {}x{} RGBA pixels", name, width, height);
+        self.images.remove(name);
+        self.image_data.insert(name.to_owned(), (width, height, pixels));
+    }
+
+    /// Use the TrueType font `data` for the font `name`, instead of loading it from `assets/fonts/<name>.ttf`,
+    /// eg. to bundle fonts with the app. Adding a font named `DEFAULT_FONT` replaces the built in default font.
+    /// If `data` isn't a font, an error is returned, and the font `name` is left as it was.
+    pub fn add_font(&mut self, name: &str, data: Vec<u8>) -> Result<(), InvalidFontError> {
+        if font_from_data(data.clone()).is_none() {
+            return Err(InvalidFontError(name.to_owned()));
+        }
+        self.fonts.remove(name);
+        self.font_instances.retain(|&(ref font, _), _| font != name);
+        self.font_data.insert(name.to_owned(), data);
+        Ok(())
+    }
+
+    /// The font `name`, loaded from the data added with `add_font`, or from `assets/fonts/<name>.ttf`.
+    /// If neither exist, or the file isn't a font, the default font is used, so text is still drawn.
     pub fn get_font(&mut self, name: &str) -> &FontInfo {
         if !self.fonts.contains_key(name) {
-            let data = load_font_data(name).unwrap();
-            let key = self.render.as_ref().unwrap().generate_font_key();
-            let mut resources = ResourceUpdates::new();
-            resources.add_raw_font(key, data, 0);
-
-            let font = load_font(name).unwrap();
-            self.render.as_ref().unwrap().update_resources(resources);
+            let data = match self.font_data.get(name) {
+                Some(data) => data.clone(),
+                None => load_font_data(name).unwrap_or_else(|err| {
+                    // the default font is built in, it's only loaded from a file to replace it
+                    if name != DEFAULT_FONT || err.kind() != io::ErrorKind::NotFound {
+                        warn!("Can't load font {}, using the default font: {}", name, err);
+                    }
+                    self.font_data.get(DEFAULT_FONT).cloned().unwrap_or_else(|| DEFAULT_FONT_DATA.to_vec())
+                }),
+            };
+            let key = match self.render {
+                Some(ref render) => {
+                    let key = render.generate_font_key();
+                    let mut resources = ResourceUpdates::new();
+                    resources.add_raw_font(key, data.clone(), 0);
+                    render.update_resources(resources);
+                    key
+                }
+                // a headless app only measures text, it never draws it
+                None => FontKey::new(IdNamespace(0), 0),
+            };
+            let font = font_from_data(data).expect("Invalid font data");
             let font_info = FontInfo { key: key, info: font };
             self.fonts.insert(name.to_owned(), font_info);
         }
@@ -117,10 +267,16 @@ impl Resources {
         let font_key = self.get_font(name).key;
         let size = app_units::Au::from_f32_px(text_layout::px_to_pt(font_size));
         if !self.font_instances.contains_key(&(name.to_owned(), size)) {
-            let instance_key = self.render.as_ref().unwrap().generate_font_instance_key();
-            let mut resources = ResourceUpdates::new();
-            resources.add_font_instance(instance_key, font_key, size, None, None, Vec::new());
-            self.render.as_ref().unwrap().update_resources(resources);
+            let instance_key = match self.render {
+                Some(ref render) => {
+                    let instance_key = render.generate_font_instance_key();
+                    let mut resources = ResourceUpdates::new();
+                    resources.add_font_instance(instance_key, font_key, size, None, None, Vec::new());
+                    render.update_resources(resources);
+                    instance_key
+                }
+                None => FontInstanceKey::new(IdNamespace(0), 0),
+            };
             self.font_instances.insert((name.to_owned(), size), instance_key);
         }
         &self.font_instances[&(name.to_owned(), size)]
@@ -138,15 +294,23 @@ fn load_image(file: &str) -> Result<(ImageData, ImageDescriptor), image::ImageEr
             return Err(image::ImageError::UnsupportedError(format!("ImageLumaA8 unsupported")));
         }
     };
-    let mut bytes = image.raw_pixels();
+    let bytes = image.raw_pixels();
     if format == ImageFormat::BGRA8 {
-        premultiply(bytes.as_mut_slice());
+        return Ok(rgba_image(image_dims.0, image_dims.1, bytes));
     }
     let opaque = is_image_opaque(format, &bytes[..]);
     let descriptor = ImageDescriptor::new(image_dims.0, image_dims.1, format, opaque);
     let data = ImageData::new(bytes);
     Ok((data, descriptor))
 }
+// the image data of RGBA pixels, in the premultiplied BGRA format webrender draws
+fn rgba_image(width: u32, height: u32, mut bytes: Vec<u8>) -> (ImageData, ImageDescriptor) {
+    rgba_to_bgra(bytes.as_mut_slice());
+    premultiply(bytes.as_mut_slice());
+    let opaque = is_image_opaque(ImageFormat::BGRA8, &bytes[..]);
+    let descriptor = ImageDescriptor::new(width, height, ImageFormat::BGRA8, opaque);
+    (ImageData::new(bytes), descriptor)
+}
 fn is_image_opaque(format: ImageFormat, bytes: &[u8]) -> bool {
     match format {
         ImageFormat::BGRA8 => {
@@ -166,6 +330,15 @@ fn is_image_opaque(format: ImageFormat, bytes: &[u8]) -> bool {
     }
 }
 
+// images are decoded as RGBA, but webrender takes BGRA, it has no RGBA8 format
+fn rgba_to_bgra(data: &mut [u8]) {
+    for pixel in data.chunks_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+// WebRender blends with premultiplied alpha, in sRGB space, so image data
+// has to be premultiplied the same way as the colors it draws.
 // From webrender/wrench
 // These are slow. Gecko's gfx/2d/Swizzle.cpp has better versions
 pub fn premultiply(data: &mut [u8]) {
@@ -182,17 +355,58 @@ pub fn premultiply(data: &mut [u8]) {
     }
 }
 
-fn load_font_data(name: &str) -> Result<Vec<u8>, ::std::io::Error> {
+// the data of `assets/fonts/<name>.ttf`, an `InvalidData` error if the file is there but isn't a font,
+// eg. if it's truncated, so it falls back to the default font like a missing file
+fn load_font_data(name: &str) -> Result<Vec<u8>, io::Error> {
     use std::fs::File;
     use std::io::Read;
-    let mut file = File::open(format!("assets/fonts/{}.ttf", name)).expect("Font missing");
+    let mut file = try!(File::open(format!("assets/fonts/{}.ttf", name)));
     let mut data = Vec::new();
     try!(file.read_to_end(&mut data));
+    if font_from_data(data.clone()).is_none() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a TrueType font"));
+    }
     Ok(data)
 }
 
-pub fn load_font(name: &str) -> Result<Font, ::std::io::Error> {
-    let data = try!(load_font_data(name));
+// `None` if `data` isn't a font
+fn font_from_data(data: Vec<u8>) -> Option<Font> {
+    // shorter than the tag the font format is read from
+    if data.len() < 4 {
+        return None;
+    }
     let collection = rusttype::FontCollection::from_bytes(data);
-    Ok(collection.into_font().unwrap())
+    collection.into_font()
+}
+
+/// Load the font `assets/fonts/<name>.ttf`, an error if the file can't be read.
+/// If the file isn't a font, eg. it's corrupt or truncated, the built in default font is returned instead.
+pub fn load_font(name: &str) -> Result<Font, io::Error> {
+    let data = match load_font_data(name) {
+        Err(ref err) if err.kind() == io::ErrorKind::InvalidData => {
+            warn!("Can't load font {}, using the default font: {}", name, err);
+            DEFAULT_FONT_DATA.to_vec()
+        }
+        result => try!(result),
+    };
+    Ok(font_from_data(data).expect("Invalid font data"))
+}
+
+#[cfg(test)]
+mod tests {
+    use webrender::api::{ImageData, ImageFormat};
+    use super::rgba_image;
+
+    #[test]
+    fn added_pixels_are_premultiplied_bgra() {
+        let (red, blue, half_green) = ([255, 0, 0, 255], [0, 0, 255, 255], [0, 255, 0, 128]);
+        let pixels = red.iter().chain(blue.iter()).chain(half_green.iter()).cloned().collect();
+        let (data, descriptor) = rgba_image(3, 1, pixels);
+        assert_eq!(descriptor.format, ImageFormat::BGRA8);
+        assert!(!descriptor.is_opaque);
+        match data {
+            ImageData::Raw(ref bytes) => assert_eq!(&bytes[..], &[0, 0, 255, 255, 255, 0, 0, 255, 0, 128, 0, 128][..]),
+            _ => panic!("image data isn't raw pixels"),
+        }
+    }
 }
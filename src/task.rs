@@ -0,0 +1,131 @@
+//! Running work in the background for widgets, see `EventArgs::spawn`.
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender, Receiver};
+use std::thread;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Number of tasks that run at the same time, unless set with `Ui::set_max_concurrent_tasks`
+pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 4;
+
+/// Sent to a widget with the result of a task it started with `EventArgs::spawn`
+#[derive(Clone, Debug)]
+pub struct TaskCompleteEvent<T>(pub T);
+
+#[derive(Debug, Default)]
+struct TaskState {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+}
+
+/// Cancels a task started on a `TaskPool`. A task that hasn't started yet is never run,
+/// and the result of a task that's already running is dropped instead of being delivered.
+#[derive(Clone, Debug)]
+pub struct TaskHandle(Arc<TaskState>);
+
+impl TaskHandle {
+    fn new() -> Self {
+        TaskHandle(Arc::new(TaskState::default()))
+    }
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+    /// If the task has run, or been skipped because it was cancelled
+    pub fn is_finished(&self) -> bool {
+        self.0.finished.load(Ordering::SeqCst)
+    }
+}
+
+// the task, wrapped so it can be called from a box
+type Job = Box<FnMut() + Send>;
+
+/// Runs tasks on background threads, at most `max_threads` at a time, the rest wait in the order they're spawned.
+/// Threads are started as they're needed, and kept waiting for more tasks until the pool is dropped.
+pub struct TaskPool {
+    max_threads: usize,
+    threads: usize,
+    sender: Sender<Job>,
+    receiver: Arc<Mutex<Receiver<Job>>>,
+    // threads waiting for a task, and tasks waiting for a thread
+    idle: Arc<AtomicUsize>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl TaskPool {
+    pub fn new(max_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        TaskPool {
+            max_threads: max_threads.max(1),
+            threads: 0,
+            sender: sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            idle: Arc::new(AtomicUsize::new(0)),
+            queued: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+    /// Limit the number of tasks run at the same time, threads already started aren't stopped
+    /// if the limit is lowered, so it should be set before any tasks are spawned
+    pub fn set_max_threads(&mut self, max_threads: usize) {
+        self.max_threads = max_threads.max(1);
+    }
+    /// Run `task` on one of the pool's threads, then call `deliver` with its result on the same thread,
+    /// unless the task is cancelled with the handle returned first
+    pub fn spawn<F, T, D>(&mut self, task: F, deliver: D) -> TaskHandle
+        where F: FnOnce() -> T + Send + 'static,
+              D: FnOnce(T) + Send + 'static,
+              T: Send + 'static
+    {
+        let handle = TaskHandle::new();
+        let job_handle = handle.clone();
+        let mut job = Some((task, deliver));
+        let job: Job = Box::new(move || {
+            if let Some((task, deliver)) = job.take() {
+                // a task that panics is finished without a result, and the thread is kept for the next task
+                let run = panic::catch_unwind(AssertUnwindSafe(|| {
+                    if !job_handle.is_cancelled() {
+                        let result = task();
+                        if !job_handle.is_cancelled() {
+                            deliver(result);
+                        }
+                    }
+                }));
+                if run.is_err() {
+                    error!("background task panicked");
+                }
+                job_handle.0.finished.store(true, Ordering::SeqCst);
+            }
+        });
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        if queued > self.idle.load(Ordering::SeqCst) && self.threads < self.max_threads {
+            self.start_thread();
+        }
+        self.sender.send(job).unwrap();
+        handle
+    }
+    fn start_thread(&mut self) {
+        self.threads += 1;
+        let receiver = self.receiver.clone();
+        let idle = self.idle.clone();
+        let queued = self.queued.clone();
+        idle.fetch_add(1, Ordering::SeqCst);
+        thread::spawn(move || {
+            loop {
+                let job = receiver.lock().unwrap().recv();
+                idle.fetch_sub(1, Ordering::SeqCst);
+                match job {
+                    Ok(mut job) => {
+                        queued.fetch_sub(1, Ordering::SeqCst);
+                        job();
+                    }
+                    // the pool was dropped
+                    Err(_) => break,
+                }
+                idle.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+    }
+}
@@ -0,0 +1,71 @@
+//! Spacing values shared by widgets, so they can be changed in one place,
+//! eg. to switch between a compact and a spacious layout.
+
+/// How tightly the built-in widgets are laid out, scales the theme's paddings, and the
+/// paddings, default sizes and font sizes of the built-in widgets.
+/// Sizes given explicitly in constraints or draw states aren't scaled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Density {
+    /// The default sizes, suited to mouse and touch input
+    Comfortable,
+    /// Three quarters of the default sizes, for data dense tools
+    Compact,
+    /// The default sizes multiplied by a factor
+    Custom(f32),
+}
+
+impl Density {
+    pub fn factor(&self) -> f32 {
+        match *self {
+            Density::Comfortable => 1.0,
+            Density::Compact => 0.75,
+            Density::Custom(factor) => factor,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    /// Space between a widget and the edges of its parent
+    pub widget_padding: f32,
+    /// Space between a button's edges and its content
+    pub button_padding: f32,
+    /// Space between the edges of a text input and its text
+    pub input_padding: f32,
+    pub density: Density,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            widget_padding: 10.0,
+            button_padding: 10.0,
+            input_padding: 5.0,
+            density: Density::Comfortable,
+        }
+    }
+}
+
+impl Theme {
+    /// One of the padding values, scaled by the density
+    pub fn padding(&self, padding: ThemePadding) -> f32 {
+        let padding = match padding {
+            ThemePadding::Widget => self.widget_padding,
+            ThemePadding::Button => self.button_padding,
+            ThemePadding::Input => self.input_padding,
+        };
+        self.scale(padding)
+    }
+    /// A size for the default density scaled by the density, rounded to whole pixels
+    pub fn scale(&self, size: f32) -> f32 {
+        (size * self.density.factor()).round()
+    }
+}
+
+/// Selects one of the padding values of a `Theme`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThemePadding {
+    Widget,
+    Button,
+    Input,
+}
@@ -1,50 +1,157 @@
 use std::collections::{HashSet, HashMap, VecDeque};
 use std::any::{Any, TypeId};
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
+use std::time::{Duration, Instant};
+use std::cmp;
 
 use cassowary::Constraint;
 use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
 
 use glutin;
 
 use window::Window;
 use app::App;
-use widget::{WidgetRef, WidgetBuilder};
-use layout::{LimnSolver, LayoutChanged, LayoutVars, ExactFrame};
+use widget::{Widget, WidgetRef, WidgetBuilder, WidgetError, ThemeConstraints};
+use widget::reconcile::{WidgetSpec, reconcile};
+use widgets::notification::{NotificationManager, NotificationBannerWidget, NotificationKind};
+use util::easing::lerp;
+use layout::{LimnSolver, LayoutChanged, LayoutSettled, LayoutThrashingEvent, LayoutVars, ExactFrame};
+use layout::{LayoutPreset, LayoutSnapshot, PresetValue, VarType, VariableEditable};
 use layout::constraint::*;
 use geometry::{Point, Rect, Size};
-use resources::WidgetId;
-use event::Target;
+use resources::{self, WidgetId};
+use event::{self, Target, EventSender, EventRecorder, RecordedInput, EventOrigin, EventDispatch, EventHook};
 use render::WebRenderContext;
+use theme::{Theme, Density};
+use input::keymap::KeyMap;
+use input::mouse::{RelativeMotionEvent, PointerUnlocked};
+use task::{TaskPool, DEFAULT_MAX_CONCURRENT_TASKS};
+use color::Color;
+use draw::text::TextState;
 
 /// If true, the constraint that matches the root layout size to the window size
 /// is required. This can be useful for debugging but can result in panics from resizing the window.
 const WINDOW_CONSTRAINT_REQUIRED: bool = false;
 
+/// Time taken to move between layout presets, when applying a preset is animated
+const PRESET_ANIMATION_MILLIS: u64 = 250;
+/// Number of images in the window's swap chain, unless set with `Ui::set_buffer_count`
+const DEFAULT_BUFFER_COUNT: u32 = 2;
+
+/// When the main loop draws a new frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RedrawMode {
+    /// Only draw when something has changed or an animation is active,
+    /// otherwise block until the next window or queued event
+    Lazy,
+    /// Draw every frame, at up to 60 frames per second, whether or not anything changed
+    Continuous,
+}
+
+/// Sent to each widget with an active animation before every frame, with the time since the previous frame
+#[derive(Clone, Debug)]
+pub struct AnimationFrame(pub Duration);
+
+/// Sent to a widget once the delay it was given to `Ui::start_timer` has passed
+#[derive(Clone, Debug)]
+pub struct TimerEvent;
+
+/// How the root widget is fit to the window when the window is resized
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResizePolicy {
+    /// Suggest the window size to the root, the root's constraints decide how much of it is used
+    Stretch,
+    /// Resize the window back to the size of the root, if the root can't take the new size
+    SnapBack,
+    /// Keep the root at the size allowed by its constraints, centered in the window
+    Letterbox,
+}
+
 pub struct Ui {
     pub(crate) root: WidgetRef,
     widget_map: HashMap<WidgetId, WidgetRef>,
     pub(crate) solver: LimnSolver,
-    pub(crate) render: WebRenderContext,
+    // `None` for a headless UI, see `App::headless`
+    pub(crate) render: Option<WebRenderContext>,
     needs_redraw: bool,
     should_close: bool,
     debug_draw_bounds: bool,
-    window: Rc<RefCell<Window>>,
+    window: Option<Rc<RefCell<Window>>>,
+    // the size of a headless UI, which has no window to ask
+    headless_size: Size,
     window_constraints: Vec<Constraint>,
+    theme: Theme,
+    key_map: KeyMap,
+    resize_policy: ResizePolicy,
+    // keeps the root in the top left corner, unless letterboxing
+    root_position: Vec<Constraint>,
+    letterbox_constraints: Vec<Constraint>,
+    // window size to snap back from, once the layout for it has been solved
+    snap_window_from: Option<Size>,
+    // set when the layout changes, cleared once `LayoutSettled` is sent
+    layout_changed: bool,
+    redraw_mode: RedrawMode,
+    // widgets that get an `AnimationFrame` every frame
+    animating: HashSet<WidgetId>,
+    // when each widget waiting for a `TimerEvent` gets it, see `start_timer`
+    timers: HashMap<WidgetId, Instant>,
+    // widgets that have handlers for each event type, used to skip the rest when sending to a subtree
+    handler_index: HashMap<TypeId, HashSet<WidgetId>>,
+    preset_animation: Option<PresetAnimation>,
+    error_handler: Box<Fn(WidgetError)>,
+    buffer_count: u32,
+    // frames to present before the main loop can block, so every buffer in the swap chain is up to date
+    frames_to_present: u32,
+    // mouse buttons currently held down, in the order they were pressed
+    pressed_mouse_buttons: Vec<glutin::MouseButton>,
+    // keys currently held down, in the order they were pressed
+    pressed_keys: Vec<glutin::VirtualKeyCode>,
+    cursor_position: Point,
+    // the widget the pointer is locked to, and where the cursor was when it was locked, see `lock_pointer`
+    pointer_lock: Option<(WidgetId, Point)>,
+    // see `start_recording`
+    recorder: Option<EventRecorder>,
+    // see `set_catch_handler_panics`
+    pub(crate) catch_handler_panics: bool,
+    // see `set_event_hook`
+    event_hook: Option<EventHook>,
+    // the widgets with handlers for the event being handled, while there's an event hook
+    handled_by: Option<Vec<WidgetId>>,
+    // see `show_notification`
+    notifications: NotificationManager,
+    // runs the tasks started with `EventArgs::spawn`
+    pub(crate) tasks: TaskPool,
+}
+
+// a layout preset being applied gradually, one step each frame
+struct PresetAnimation {
+    // the widget, variable, value to move from, value to move to, and strength
+    targets: Vec<(WidgetId, VarType, f64, f64, f64)>,
+    elapsed: Duration,
 }
 
 impl Ui {
     pub(super) fn new(mut window: Window, events_loop: &glutin::EventsLoop) -> Self {
+        let render = WebRenderContext::new(&mut window, events_loop);
+        let size = window.size_f32();
+        Ui::with_backend(Some(window), Some(render), Size::new(size.width, size.height))
+    }
+    /// A UI without a window or renderer, it handles events and solves the layout but doesn't draw
+    pub(super) fn headless(size: Size) -> Self {
+        Ui::with_backend(None, None, size)
+    }
+    fn with_backend(window: Option<Window>, render: Option<WebRenderContext>, size: Size) -> Self {
         let mut root = WidgetBuilder::new("window");
         root.layout().set_container(ExactFrame);
-        root.layout().add(top_left(Point::zero()));
+        let root_position = root.layout().create_constraint(top_left(Point::zero()));
+        root.layout().add(root_position.clone());
         if !WINDOW_CONSTRAINT_REQUIRED {
             let mut root_layout = root.layout();
             root_layout.edit_right().strength(REQUIRED - 1.0);
             root_layout.edit_bottom().strength(REQUIRED - 1.0);
         }
-        let render = WebRenderContext::new(&mut window, events_loop);
         Ui {
             widget_map: HashMap::new(),
             root: root.into(),
@@ -53,8 +160,34 @@ impl Ui {
             needs_redraw: true,
             should_close: false,
             debug_draw_bounds: false,
-            window: Rc::new(RefCell::new(window)),
+            window: window.map(|window| Rc::new(RefCell::new(window))),
+            headless_size: size,
             window_constraints: Vec::new(),
+            theme: Theme::default(),
+            key_map: KeyMap::new(),
+            resize_policy: ResizePolicy::Stretch,
+            root_position: root_position,
+            letterbox_constraints: Vec::new(),
+            snap_window_from: None,
+            layout_changed: false,
+            redraw_mode: RedrawMode::Lazy,
+            animating: HashSet::new(),
+            timers: HashMap::new(),
+            handler_index: HashMap::new(),
+            preset_animation: None,
+            error_handler: Box::new(|error| error!("{}", error)),
+            buffer_count: DEFAULT_BUFFER_COUNT,
+            frames_to_present: DEFAULT_BUFFER_COUNT,
+            pressed_mouse_buttons: Vec::new(),
+            pressed_keys: Vec::new(),
+            cursor_position: Point::zero(),
+            pointer_lock: None,
+            recorder: None,
+            catch_handler_panics: false,
+            event_hook: None,
+            handled_by: None,
+            notifications: NotificationManager::new(),
+            tasks: TaskPool::new(DEFAULT_MAX_CONCURRENT_TASKS),
         }
     }
 
@@ -66,21 +199,179 @@ impl Ui {
         self.root.clone()
     }
 
+    /// The bounds of the widget `widget_id` from its last layout, `None` if there's no such widget
+    pub fn get_widget_bounds(&self, widget_id: WidgetId) -> Option<Rect> {
+        self.get_widget(widget_id).map(|widget| widget.bounds())
+    }
+
+    /// Remove the widget `widget_id` and its children from its parent, if it's still in the UI
+    pub fn remove_widget(&mut self, widget_id: WidgetId) {
+        if let Some(mut widget) = self.get_widget(widget_id) {
+            widget.remove_widget();
+        }
+    }
+
+    /// Add `widget` as a child of `parent`, at `index` in its children.
+    /// An `index` past the end adds the widget after the last child.
+    pub fn add_widget_at<U: Into<WidgetRef>>(&mut self, widget: U, parent: WidgetId, index: usize) -> Result<(), WidgetError> {
+        let mut parent = self.get_widget(parent).ok_or(WidgetError::WidgetNotFound(parent))?;
+        parent.insert_child(index, widget);
+        Ok(())
+    }
+
+    /// Update the widget `widget_id` and its descendants to match `spec`, see `reconcile`
+    pub fn reconcile(&mut self, widget_id: WidgetId, spec: &WidgetSpec) -> Result<(), WidgetError> {
+        let mut widget = self.get_widget(widget_id).ok_or(WidgetError::WidgetNotFound(widget_id))?;
+        reconcile(&mut widget, spec);
+        Ok(())
+    }
+
+    /// Show `message` in a banner below the top edge of the window, under any other notifications still shown.
+    /// With a `timeout`, the banner fades out and is removed once it's passed, otherwise it stays until it's removed,
+    /// eg. with `WidgetRef::remove_widget` on the banner returned.
+    pub fn show_notification(&mut self, message: &str, kind: NotificationKind, timeout: Option<Duration>) -> WidgetRef {
+        let mut root = self.get_root();
+        self.notifications.show(&mut root, NotificationBannerWidget::new(message, kind, timeout))
+    }
+    /// The banners shown with `show_notification` that haven't been removed yet, from the top
+    pub fn notifications(&self) -> Vec<WidgetRef> {
+        self.notifications.banners()
+    }
+
+    /// Limit the number of tasks started with `EventArgs::spawn` that run at the same time.
+    /// Threads already started aren't stopped when the limit is lowered, so set it before spawning any tasks.
+    pub fn set_max_concurrent_tasks(&mut self, max_tasks: usize) {
+        self.tasks.set_max_threads(max_tasks);
+    }
+
+    /// Replace the function that's called with errors that happen while handling events.
+    /// By default errors are logged.
+    pub fn set_error_handler(&mut self, error_handler: Box<Fn(WidgetError)>) {
+        self.error_handler = error_handler;
+    }
+    /// Catch panics in the event handlers of widgets other than the root, instead of closing the app.
+    /// A widget with a panicking handler is poisoned: it's drawn with an error overlay, none of its handlers
+    /// are called again, and a `HandlerPanickedEvent` is sent to the root. It stays in the layout, and can be removed.
+    /// A `WidgetErrorEvent` is also sent up from the widget, so an `ErrorBoundaryBuilder` around it can replace it.
+    pub fn set_catch_handler_panics(&mut self, catch_handler_panics: bool) {
+        self.catch_handler_panics = catch_handler_panics;
+    }
+    pub fn report_error(&self, error: WidgetError) {
+        (self.error_handler)(error);
+    }
+
+    /// The widgets that have at least one handler for the event type `type_id`
+    pub fn widgets_with_handler(&self, type_id: TypeId) -> Vec<WidgetId> {
+        self.handler_index.get(&type_id)
+            .map(|widgets| widgets.iter().cloned().collect())
+            .unwrap_or_else(Vec::new)
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+    /// Set the theme, the paddings, sizes and font sizes derived from the old theme are replaced
+    /// with ones derived from the new theme, and the affected widgets are laid out again
+    pub fn set_theme(&mut self, theme: Theme) {
+        let previous = ::std::mem::replace(&mut self.theme, theme);
+        for widget_ref in self.widget_map.values() {
+            widget_ref.apply_theme(&self.theme, Some(&previous));
+        }
+    }
+    /// Switch the density of the theme, eg. to `Density::Compact` for a data dense view, see `set_theme`
+    pub fn set_density(&mut self, density: Density) {
+        let mut theme = self.theme.clone();
+        theme.density = density;
+        self.set_theme(theme);
+    }
+
+    /// Key combos bound to actions, see `input::keymap`
+    pub fn key_map(&mut self) -> &mut KeyMap {
+        &mut self.key_map
+    }
+
     pub fn event<T: 'static>(&self, data: T) {
         self.get_root().event(data);
     }
+    /// A handle to send events to the UI from other threads, see `EventSender`
+    pub fn event_sender(&self) -> EventSender {
+        EventSender::new()
+    }
+    /// A handle to change the UI from other threads, see `CommandSender`
+    pub fn command_sender(&self) -> CommandSender {
+        CommandSender::new()
+    }
 
     pub fn close(&mut self) {
         self.should_close = true;
     }
 
-    pub(super) fn should_close(&self) -> bool {
+    pub fn should_close(&self) -> bool {
         self.should_close
     }
 
+    /// The window the UI is drawn in, panics if the UI is headless
+    pub fn window(&self) -> Ref<Window> {
+        self.window.as_ref().expect("A headless Ui has no window").borrow()
+    }
+    pub fn is_headless(&self) -> bool {
+        self.window.is_none()
+    }
+    // the size of the window, or of a headless UI
+    fn window_size(&self) -> Size {
+        match self.window {
+            Some(ref window) => {
+                let size = window.borrow().size_f32();
+                Size::new(size.width, size.height)
+            }
+            None => self.headless_size,
+        }
+    }
+    /// Resize a headless UI, as if its window was resized, see `App::headless`.
+    /// A UI with a window is only resized with the window.
+    pub fn resize_headless(&mut self, size: Size) {
+        if self.is_headless() {
+            self.window_resized(size);
+        }
+    }
+
+    /// Set the color drawn behind the UI. With `None` the UI is drawn over whatever is
+    /// already in the framebuffer, see `draw_into`.
+    pub fn set_background_color(&mut self, color: Option<Color>) {
+        if let Some(ref mut render) = self.render {
+            render.root_background_color = color.map(|color| color.into());
+        }
+        self.needs_redraw = true;
+    }
+
     pub(super) fn resize_window_to_fit(&mut self) {
         let window_dims = self.get_root_dims();
-        self.window.borrow_mut().resize(window_dims.width as u32, window_dims.height as u32);
+        if let Some(ref window) = self.window {
+            window.borrow_mut().resize(window_dims.width as u32, window_dims.height as u32);
+        }
+    }
+
+    pub fn resize_policy(&self) -> ResizePolicy {
+        self.resize_policy
+    }
+    pub fn set_resize_policy(&mut self, resize_policy: ResizePolicy) {
+        let was_letterbox = self.resize_policy == ResizePolicy::Letterbox;
+        let letterbox = resize_policy == ResizePolicy::Letterbox;
+        self.resize_policy = resize_policy;
+        if letterbox != was_letterbox {
+            let root_position = self.root_position.clone();
+            let letterbox_constraints: Vec<Constraint> = self.letterbox_constraints.drain(..).collect();
+            self.get_root().update_layout(|layout| {
+                if letterbox {
+                    layout.remove_constraints(root_position);
+                } else {
+                    layout.remove_constraints(letterbox_constraints);
+                    layout.add(root_position);
+                }
+            });
+        }
+        let window_size = self.window_size();
+        self.window_resized(window_size);
     }
 
     pub fn get_root_dims(&self) -> Size {
@@ -93,8 +384,12 @@ impl Ui {
     }
 
     pub(super) fn window_resized(&mut self, window_dims: Size) {
-        let window_size = self.window.borrow_mut().size_u32();
-        self.render.window_resized(window_size);
+        if let (Some(window), Some(render)) = (self.window.as_ref(), self.render.as_mut()) {
+            render.window_resized(window.borrow().size_u32());
+        } else {
+            self.headless_size = window_dims;
+        }
+        self.frames_to_present = self.buffer_count;
         let mut root = self.get_root();
 
         if WINDOW_CONSTRAINT_REQUIRED {
@@ -115,74 +410,550 @@ impl Ui {
                 layout.edit_bottom().set(window_dims.height);
             });
         }
+        match self.resize_policy {
+            ResizePolicy::Stretch => (),
+            ResizePolicy::SnapBack => {
+                self.snap_window_from = Some(window_dims);
+            }
+            ResizePolicy::Letterbox => {
+                // center the root, if it can't take the window size the edit variables above give way
+                let vars = root.layout_vars();
+                let letterbox_constraints = vec![
+                    vars.left + vars.right | EQ(REQUIRED) | window_dims.width as f64,
+                    vars.top + vars.bottom | EQ(REQUIRED) | window_dims.height as f64,
+                ];
+                {
+                    let letterbox_constraints = letterbox_constraints.clone();
+                    root.update_layout(|layout| {
+                        for constraint in self.letterbox_constraints.drain(..) {
+                            layout.remove_constraint(constraint);
+                        }
+                        layout.add(letterbox_constraints);
+                    });
+                }
+                self.letterbox_constraints = letterbox_constraints;
+            }
+        }
         self.needs_redraw = true;
     }
 
     pub fn check_layout_changes(&mut self) {
-
         let changes = self.solver.fetch_changes();
         debug!("layout has {} changes", changes.len());
         if !changes.is_empty() {
+            self.layout_changed = true;
             self.event(LayoutChanged(changes));
         }
+        let thrashing = self.solver.take_thrashing();
+        if !thrashing.is_empty() {
+            warn!("layout variables changed repeatedly in one frame: {}", thrashing.join(", "));
+            self.event(LayoutThrashingEvent(thrashing));
+        }
+        if let Some(window_dims) = self.snap_window_from.take() {
+            let root_size = self.get_root().layout_vars().to_rect(&self.solver).size;
+            if root_size != window_dims {
+                let width = f32::max(100.0, root_size.width);
+                let height = f32::max(100.0, root_size.height);
+                if let Some(ref window) = self.window {
+                    window.borrow_mut().resize(width as u32, height as u32);
+                }
+            }
+        }
+    }
+
+    /// Called once the event queue is empty, so no layout updates are pending.
+    /// Returns true if the layout has changed since the last time it settled.
+    pub(super) fn check_layout_settled(&mut self) -> bool {
+        let settled = self.layout_changed;
+        self.layout_changed = false;
+        settled
     }
 
     pub fn redraw(&mut self) {
         self.needs_redraw = true;
     }
 
+    /// True if something has changed since the last frame was drawn, and the UI should be redrawn.
+    /// Doesn't draw anything, so a custom main loop can use it to decide whether to present a frame or sleep.
     pub fn needs_redraw(&self) -> bool {
         self.needs_redraw
     }
 
+    pub fn redraw_mode(&self) -> RedrawMode {
+        self.redraw_mode
+    }
+    pub fn set_redraw_mode(&mut self, redraw_mode: RedrawMode) {
+        self.redraw_mode = redraw_mode;
+        self.needs_redraw = true;
+    }
+
+    /// Send an `AnimationFrame` to `widget_id` before every frame, until `stop_animation` is called.
+    /// While any animation is active the main loop keeps drawing frames instead of waiting for events.
+    pub fn start_animation(&mut self, widget_id: WidgetId) {
+        self.animating.insert(widget_id);
+    }
+    pub fn stop_animation(&mut self, widget_id: WidgetId) {
+        self.animating.remove(&widget_id);
+    }
+    pub fn animations_active(&self) -> bool {
+        !self.animating.is_empty() || self.preset_animation.is_some()
+    }
+
+    /// Send a `TimerEvent` to `widget_id` once `delay` has passed, replacing any timer it's already waiting for.
+    /// Unlike an animation, the main loop can block until then, for changes that only happen now and then.
+    pub fn start_timer(&mut self, widget_id: WidgetId, delay: Duration) {
+        self.timers.insert(widget_id, Instant::now() + delay);
+    }
+    pub fn cancel_timer(&mut self, widget_id: WidgetId) {
+        self.timers.remove(&widget_id);
+    }
+    /// When the next timer is due, if any are waiting, so a main loop that blocks can wake up in time
+    pub fn next_timer(&self) -> Option<Instant> {
+        self.timers.values().min().cloned()
+    }
+    fn timer_due(&self) -> bool {
+        self.next_timer().map_or(false, |deadline| deadline <= Instant::now())
+    }
+    pub(super) fn fire_timers(&mut self) {
+        let now = Instant::now();
+        let due: Vec<WidgetId> = self.timers.iter()
+            .filter(|&(_, deadline)| *deadline <= now)
+            .map(|(widget_id, _)| *widget_id)
+            .collect();
+        for widget_id in due {
+            self.timers.remove(&widget_id);
+            if let Some(widget_ref) = self.widget_map.get(&widget_id) {
+                widget_ref.event(TimerEvent);
+            }
+        }
+    }
+
+    /// True if the main loop should keep drawing frames, false if it can block until the next event or timer
+    pub fn needs_frame(&self) -> bool {
+        self.needs_redraw || self.animations_active() || self.timer_due() || self.frames_to_present > 0 ||
+            self.redraw_mode == RedrawMode::Continuous
+    }
+
+    /// Set the number of images in the window's swap chain, eg. 3 for triple buffering.
+    /// After the UI changes or the window is resized, that many frames are presented before
+    /// the main loop waits for events again, so no buffer is left showing an old frame.
+    /// Glutin can't report the swap chain size, so it's assumed to be 2 unless set here.
+    pub fn set_buffer_count(&mut self, buffer_count: u32) {
+        self.buffer_count = cmp::max(1, buffer_count);
+    }
+    pub fn buffer_count(&self) -> u32 {
+        self.buffer_count
+    }
+
+    /// Whether `button` is currently held down, eg. to tell a left drag from a right drag while handling mouse moves
+    pub fn mouse_button_pressed(&self, button: glutin::MouseButton) -> bool {
+        self.pressed_mouse_buttons.contains(&button)
+    }
+    /// The mouse buttons currently held down, in the order they were pressed
+    pub fn pressed_mouse_buttons(&self) -> &[glutin::MouseButton] {
+        &self.pressed_mouse_buttons
+    }
+    pub(super) fn set_mouse_button_state(&mut self, state: glutin::ElementState, button: glutin::MouseButton) {
+        self.pressed_mouse_buttons.retain(|pressed| *pressed != button);
+        if state == glutin::ElementState::Pressed {
+            self.pressed_mouse_buttons.push(button);
+        }
+    }
+    /// Whether `key` is currently held down, eg. to check for modifier keys while handling mouse input
+    pub fn key_pressed(&self, key: glutin::VirtualKeyCode) -> bool {
+        self.pressed_keys.contains(&key)
+    }
+    /// Whether either control key is held down
+    pub fn ctrl_pressed(&self) -> bool {
+        self.key_pressed(glutin::VirtualKeyCode::LControl) || self.key_pressed(glutin::VirtualKeyCode::RControl)
+    }
+    /// Whether either shift key is held down
+    pub fn shift_pressed(&self) -> bool {
+        self.key_pressed(glutin::VirtualKeyCode::LShift) || self.key_pressed(glutin::VirtualKeyCode::RShift)
+    }
+    pub(super) fn set_key_state(&mut self, state: glutin::ElementState, key: glutin::VirtualKeyCode) {
+        self.pressed_keys.retain(|pressed| *pressed != key);
+        if state == glutin::ElementState::Pressed {
+            self.pressed_keys.push(key);
+        }
+    }
+    /// The last position of the mouse in window coordinates
+    pub fn cursor_position(&self) -> Point {
+        self.cursor_position
+    }
+    pub(super) fn set_cursor_position(&mut self, position: Point) {
+        self.cursor_position = position;
+    }
+    /// Hide the cursor and send the mouse's movement to the widget `widget_id` as `RelativeMotionEvent`s,
+    /// without the cursor moving, eg. to adjust a value by dragging as far as needed, past the edges of the screen.
+    /// Other widgets don't see the mouse move until `unlock_pointer` is called, or escape is pressed,
+    /// then the cursor is shown where it was when it was locked.
+    pub fn lock_pointer(&mut self, widget_id: WidgetId) {
+        if self.get_widget(widget_id).is_none() {
+            return;
+        }
+        if let Some((locked_widget, _)) = self.pointer_lock {
+            if locked_widget != widget_id {
+                self.unlock_pointer();
+            }
+        }
+        let cursor = self.pointer_lock.map_or(self.cursor_position, |(_, cursor)| cursor);
+        self.pointer_lock = Some((widget_id, cursor));
+        if let Some(ref window) = self.window {
+            window.borrow().set_cursor_grabbed(true);
+        }
+    }
+    /// Show the cursor again, where it was when the pointer was locked, and send `PointerUnlocked`
+    /// to the widget it was locked to, if it's locked
+    pub fn unlock_pointer(&mut self) {
+        if let Some((widget_id, cursor)) = self.pointer_lock.take() {
+            if let Some(ref window) = self.window {
+                let window = window.borrow();
+                window.set_cursor_grabbed(false);
+                window.set_cursor_position(cursor.x, cursor.y);
+            }
+            if let Some(widget) = self.get_widget(widget_id) {
+                widget.event(PointerUnlocked);
+            }
+        }
+    }
+    /// The widget the pointer is locked to, if it's locked, see `lock_pointer`
+    pub fn pointer_lock(&self) -> Option<WidgetId> {
+        self.pointer_lock.map(|(widget_id, _)| widget_id)
+    }
+    /// Handle the mouse moving by `dx`, `dy`, as reported by the device rather than the window.
+    /// It's only sent on, as a `RelativeMotionEvent`, while the pointer is locked.
+    pub fn relative_motion(&mut self, dx: f32, dy: f32) {
+        if let Some((widget_id, _)) = self.pointer_lock {
+            match self.get_widget(widget_id) {
+                Some(widget) => widget.event(RelativeMotionEvent { dx: dx, dy: dy }),
+                // the widget was removed while the pointer was locked to it
+                None => self.unlock_pointer(),
+            }
+        }
+    }
+
+    /// Start recording the window input, replacing any recording in progress, see `EventRecorder`.
+    /// Other events pushed on the queue aren't recorded, they follow from the input when it's replayed.
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(EventRecorder::new());
+    }
+    /// Stop recording, returns the recording, if one was in progress
+    pub fn stop_recording(&mut self) -> Option<EventRecorder> {
+        self.recorder.take()
+    }
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+    pub(super) fn record_input(&mut self, input: &RecordedInput) {
+        if let Some(ref mut recorder) = self.recorder {
+            recorder.record(input.clone());
+        }
+    }
+
+    /// Reset the solver's per frame diagnostics, and fetch any layout changes put off from the last frame
+    pub(super) fn begin_frame(&mut self) {
+        if self.solver.begin_frame() {
+            self.check_layout_changes();
+        }
+    }
+
+    pub(super) fn animation_frame(&mut self, elapsed: Duration) {
+        for widget_id in &self.animating {
+            if let Some(widget_ref) = self.widget_map.get(widget_id) {
+                widget_ref.event(AnimationFrame(elapsed));
+            }
+        }
+        if let Some(mut animation) = self.preset_animation.take() {
+            animation.elapsed += elapsed;
+            let elapsed = animation.elapsed;
+            let millis = elapsed.as_secs() * 1000 + (elapsed.subsec_nanos() / 1_000_000) as u64;
+            let progress = f64::min(1.0, millis as f64 / PRESET_ANIMATION_MILLIS as f64);
+            for &(widget_id, var, from, to, strength) in &animation.targets {
+                self.suggest_value(widget_id, var, lerp(from, to, progress), strength);
+            }
+            if progress < 1.0 {
+                self.preset_animation = Some(animation);
+            }
+        }
+    }
+
+    /// Record the values currently suggested for the edit variables of the widgets named in `keys`.
+    /// Widgets that can't be found are left out.
+    pub fn capture_layout_preset(&self, keys: &[&str]) -> LayoutPreset {
+        let mut preset = LayoutPreset::default();
+        for key in keys {
+            if let Some(widget_ref) = self.find_widget(key) {
+                for (var, value, strength) in self.solver.edit_values(widget_ref.id().0) {
+                    preset.values.push(PresetValue {
+                        key: key.to_string(),
+                        var: var,
+                        value: value,
+                        strength: strength,
+                    });
+                }
+            } else {
+                debug!("capture_layout_preset: no widget named {}", key);
+            }
+        }
+        preset
+    }
+
+    /// Suggest the values in `preset` again, either immediately, or moving towards them over
+    /// a few frames if `animated` is true. Values for widgets that can't be found are skipped.
+    pub fn apply_layout_preset(&mut self, preset: &LayoutPreset, animated: bool) {
+        let mut targets = Vec::new();
+        for preset_value in &preset.values {
+            if let Some(widget_ref) = self.find_widget(&preset_value.key) {
+                let bounds = widget_ref.bounds();
+                let current = match preset_value.var {
+                    VarType::Left => bounds.origin.x,
+                    VarType::Top => bounds.origin.y,
+                    VarType::Right => bounds.origin.x + bounds.size.width,
+                    VarType::Bottom => bounds.origin.y + bounds.size.height,
+                    VarType::Width => bounds.size.width,
+                    VarType::Height => bounds.size.height,
+                    VarType::Other => continue,
+                };
+                targets.push((widget_ref.id(), preset_value.var, current as f64, preset_value.value, preset_value.strength));
+            } else {
+                debug!("apply_layout_preset: no widget named {}", preset_value.key);
+            }
+        }
+        if animated {
+            self.preset_animation = Some(PresetAnimation {
+                targets: targets,
+                elapsed: Duration::new(0, 0),
+            });
+        } else {
+            self.preset_animation = None;
+            for (widget_id, var, _, to, strength) in targets {
+                self.suggest_value(widget_id, var, to, strength);
+            }
+        }
+    }
+
+    /// The solved bounds of every widget, to return to with `restore_layout`, eg. to undo changes made by a layout editor
+    pub fn layout_snapshot(&self) -> LayoutSnapshot {
+        self.widgets_bfs().map(|widget_ref| {
+            let bounds = widget_ref.bounds();
+            let values = [bounds.origin.x as f64, bounds.origin.y as f64, bounds.size.width as f64, bounds.size.height as f64];
+            (widget_ref.id(), values)
+        }).collect()
+    }
+
+    /// Suggest the bounds in `snapshot` for the widgets in it, widgets that have since been removed are skipped.
+    /// Only solved values are restored, not constraints, so a widget whose constraints have changed since
+    /// might not return to its old bounds. The values are only suggested until they've been solved,
+    /// after that the widgets are laid out as usual, eg. when the window is resized,
+    /// except for values that were already being suggested, like those of a preset, which keep the restored value.
+    pub fn restore_layout(&mut self, snapshot: LayoutSnapshot) {
+        self.preset_animation = None;
+        let mut suggested = Vec::new();
+        let root_id = self.root.id();
+        // the root is sized by the window
+        for (widget_id, values) in snapshot.into_iter().filter(|&(widget_id, _)| widget_id != root_id) {
+            let edit_values = self.solver.edit_values(widget_id.0);
+            let vars = [VarType::Left, VarType::Top, VarType::Width, VarType::Height];
+            for (&var, &value) in vars.iter().zip(values.iter()) {
+                self.suggest_value(widget_id, var, value, STRONG);
+                if !edit_values.iter().any(|&(edited, _, _)| edited == var) {
+                    suggested.push((widget_id, var));
+                }
+            }
+        }
+        // handled after the suggestions have been solved
+        self.event(RestoredLayout(suggested));
+    }
+
+    // the first widget named `name`, searching breadth first from the root
+    fn find_widget(&self, name: &str) -> Option<WidgetRef> {
+        self.widgets_bfs().find(|widget_ref| widget_ref.name() == name)
+    }
+
+    fn suggest_value(&self, widget_id: WidgetId, var_type: VarType, value: f64, strength: f64) {
+        if let Some(widget_ref) = self.widget_map.get(&widget_id) {
+            widget_ref.update_layout(|layout| {
+                if let Some(var) = layout.vars.get(var_type) {
+                    VariableEditable::new(layout, var).set(value as f32).strength(strength);
+                }
+            });
+        }
+    }
+
+    /// Advance the UI by one frame, for apps that run their own main loop instead of `App::main_loop`.
+    /// Sends a `TimerEvent` to widgets whose timers are due and an `AnimationFrame` with `dt` to animating widgets,
+    /// then handles all the queued events.
+    pub fn frame(&mut self, dt: Duration) {
+        self.begin_frame();
+        self.fire_timers();
+        self.animation_frame(dt);
+        self.handle_events();
+    }
+
+    /// Draw the UI into the current framebuffer, for apps that run their own main loop and draw
+    /// their own scene under the UI. The framebuffer isn't cleared first, unless there is a
+    /// background color, and the buffers aren't swapped, that's left to the caller.
+    /// The display list is only rebuilt if something changed, unless the redraw mode is `Continuous`.
+    pub fn draw_into(&mut self) {
+        self.draw_if_needed();
+        if let (Some(window), Some(render)) = (self.window.as_ref(), self.render.as_mut()) {
+            render.update(window.borrow().size_u32());
+        }
+    }
+
+    /// Handle all the pending events in the event queue
+    pub(super) fn handle_events(&mut self) {
+        loop {
+            loop {
+                let next = event::queue_next(|widget_id| self.get_widget(widget_id));
+                match next {
+                    Some((event_address, type_id, data, origin)) => {
+                        if self.event_hook.is_some() {
+                            self.handle_event_with_hook(event_address, type_id, data.as_ref(), origin);
+                        } else {
+                            self.handle_event(event_address, type_id, data.as_ref());
+                        }
+                    }
+                    None => break,
+                }
+            }
+            if !self.check_layout_settled() {
+                break;
+            }
+            self.event(LayoutSettled);
+        }
+    }
+
     pub(super) fn draw_if_needed(&mut self) {
-        if self.needs_redraw {
+        if self.needs_redraw || self.redraw_mode == RedrawMode::Continuous {
             self.draw();
             self.needs_redraw = false;
         }
     }
 
     fn draw(&mut self) {
-        let window_size = self.window.borrow_mut().size_f32();
+        let (window, render) = match (self.window.as_ref(), self.render.as_mut()) {
+            (Some(window), Some(render)) => (window, render),
+            // nothing to draw to
+            _ => return,
+        };
+        let window_size = window.borrow().size_f32();
         let (builder, resources) = {
-            let mut renderer = self.render.render_builder(window_size);
+            let mut renderer = render.render_builder(window_size);
             let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
             self.root.widget_mut().draw(crop_to, &mut renderer);
             if self.debug_draw_bounds {
-                self.root.widget_mut().draw_debug(&mut renderer);
+                self.root.widget_mut().draw_debug(crop_to, 0, &mut renderer);
             }
             (renderer.builder, renderer.resources)
         };
-        self.render.set_display_list(builder, resources, window_size);
-        self.render.generate_frame();
+        render.set_display_list(builder, resources, window_size);
+        render.generate_frame();
+        self.frames_to_present = self.buffer_count;
     }
 
     // Call after drawing
     pub(super) fn update(&mut self) {
-        self.render.update(self.window.borrow_mut().size_u32());
-        let window = self.window.borrow_mut();
-        window.swap_buffers();
+        if let (Some(window), Some(render)) = (self.window.as_ref(), self.render.as_mut()) {
+            // a new frame from the renderer needs presenting to every buffer, not just the next one
+            if render.frame_ready() {
+                self.frames_to_present = self.buffer_count;
+            }
+            render.update(window.borrow().size_u32());
+            window.borrow().swap_buffers();
+        }
+        self.frames_to_present = self.frames_to_present.saturating_sub(1);
     }
 
     pub fn widgets_bfs(&self) -> WidgetsBfs {
         WidgetsBfs::new(self.get_root())
     }
 
+    /// Call `f` with each widget in the subtree starting at `root`, depth first, parents before their children.
+    /// Each widget is only borrowed while `f` is called with it, so `f` can't reach its children through it.
+    pub fn walk_mut<F: FnMut(&mut Widget)>(&mut self, root: WidgetId, mut f: F) {
+        let mut stack: Vec<WidgetRef> = self.get_widget(root).into_iter().collect();
+        while let Some(widget_ref) = stack.pop() {
+            f(&mut widget_ref.widget_mut());
+            stack.extend(widget_ref.children().into_iter().rev());
+        }
+    }
+
     pub fn widgets_under_cursor(&mut self, point: Point) -> WidgetsUnderCursor {
         WidgetsUnderCursor::new(point, self.get_root())
     }
 
-    /// Find the first widget under the cursor, ie. the last to be drawn that is under the cursor
+    /// Find the widget that should receive input at the cursor, usually the last
+    /// to be drawn that is under the cursor, see `closest_hit`
     pub fn widget_under_cursor(&mut self, point: Point) -> Option<WidgetRef> {
-        self.widgets_under_cursor(point).next()
+        let hits = WidgetsDfsPostReverse::new(self.get_root()).filter_map(|widget_ref| {
+            let distance = widget_ref.content_point(point).and_then(|point| widget_ref.widget().hit_distance(point));
+            distance.map(|distance| (widget_ref, distance))
+        });
+        closest_hit(hits)
+    }
+
+    /// Find the widgets whose bounds overlap `rect`, in draw order, eg. for selecting widgets by dragging a box over them.
+    /// Widgets are clipped to their parents the same way they are when drawn, so only the visible part
+    /// of a widget is tested, and invisible widgets aren't included.
+    pub fn widgets_in_rect(&self, rect: Rect) -> Vec<WidgetId> {
+        let mut widgets = Vec::new();
+        let crop_to = Rect::new(Point::zero(), Size::new(::std::f32::MAX, ::std::f32::MAX));
+        add_widgets_in_rect(&self.get_root(), rect, crop_to, &mut widgets);
+        widgets
+    }
+
+    /// Call `hook` after each event is handled, with the event's type, target, how long it waited in the queue,
+    /// how long it took to handle and which widgets handled it, eg. to send to a profiler.
+    /// Events are only timestamped while there's a hook, so there's no cost when it's `None`.
+    /// See `EventStats` for a hook that adds up the times for each event type.
+    pub fn set_event_hook(&mut self, hook: Option<EventHook>) {
+        event::queue_set_instrumented(hook.is_some());
+        self.event_hook = hook;
+    }
+
+    fn handle_event_with_hook(&mut self, address: Target, type_id: TypeId, data: &Any, origin: EventOrigin) {
+        let start = Instant::now();
+        let latency = origin.sent.map(|sent| start - sent).unwrap_or_default();
+        self.handled_by = Some(Vec::new());
+        self.handle_event(address.clone(), type_id, data);
+        let handler_time = start.elapsed();
+        let handled_by = self.handled_by.take().unwrap_or_default();
+        if let Some(ref mut hook) = self.event_hook {
+            hook(&EventDispatch {
+                type_id: type_id,
+                type_name: origin.type_name,
+                target: &address,
+                latency: latency,
+                handler_time: handler_time,
+                handled_by: &handled_by,
+            });
+        }
     }
 
     fn handle_widget_event(&mut self, widget_ref: WidgetRef, type_id: TypeId, data: &Any) -> bool {
-        let handled = widget_ref.trigger_event(self, type_id, data);
+        if let Some(ref mut handled_by) = self.handled_by {
+            let widget_id = widget_ref.id();
+            if self.handler_index.get(&type_id).map_or(false, |widgets| widgets.contains(&widget_id)) {
+                handled_by.push(widget_id);
+            }
+        }
+        let handled = match widget_ref.trigger_event(self, type_id, data) {
+            Ok(handled) => handled,
+            Err(error) => {
+                self.report_error(error);
+                false
+            }
+        };
         if widget_ref.has_updated() {
             self.needs_redraw = true;
             widget_ref.set_updated(false);
         }
+        if widget_ref.needs_measure() {
+            widget_ref.set_needs_measure(false);
+            widget_ref.event(MeasureEvent);
+        }
         handled
     }
 
@@ -211,13 +982,25 @@ impl Ui {
     }
 
     fn handle_event_subtree(&mut self, widget_ref: WidgetRef, type_id: TypeId, data: &Any) {
-        self.handle_widget_event(widget_ref.clone(), type_id, data);
-        let children = &widget_ref.children();
-        for child in children {
-            self.handle_event_subtree(child.clone(), type_id, data);
+        // start from the widgets with a handler for the event, rather than walking the whole subtree,
+        // and keep those in the subtree, in tree order
+        let mut handlers: Vec<(Vec<usize>, WidgetRef)> = {
+            let widget_map = &self.widget_map;
+            match self.handler_index.get(&type_id) {
+                Some(widgets) => widgets.iter()
+                    .filter_map(|widget_id| widget_map.get(widget_id))
+                    .filter_map(|handler| subtree_path(&widget_ref, handler).map(|path| (path, handler.clone())))
+                    .collect(),
+                None => return,
+            }
+        };
+        handlers.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, handler) in handlers {
+            self.handle_widget_event(handler, type_id, data);
         }
     }
 
+    /// Outline every widget, labelled with its name, see `Widget::draw_debug`
     pub fn set_debug_draw_bounds(&mut self, debug_draw_bounds: bool) {
         self.debug_draw_bounds = debug_draw_bounds;
         self.redraw();
@@ -233,10 +1016,79 @@ impl Ui {
     }
 }
 
+// the index of `widget` and each of its ancestors among their siblings, from `root`, to sort widgets in tree order,
+// `None` if `widget` isn't `root` or one of its descendants
+fn subtree_path(root: &WidgetRef, widget: &WidgetRef) -> Option<Vec<usize>> {
+    let mut path = Vec::new();
+    let mut widget = widget.clone();
+    while widget != *root {
+        let parent = match widget.parent() {
+            Some(parent) => parent,
+            None => return None,
+        };
+        path.push(parent.children().iter().position(|child| *child == widget).unwrap_or(0));
+        widget = parent;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// A change to the UI requested from another thread, see `CommandSender`
+pub enum UiCommand {
+    /// Add the widget `build` makes as the last child of `parent`
+    AddWidget { parent: WidgetId, build: Box<Fn() -> WidgetBuilder + Send> },
+    RemoveWidget(WidgetId),
+    /// Set the text of a widget drawn with a `TextState`
+    SetText(WidgetId, String),
+    /// Call a function with the `Ui`, for changes there's no command for
+    Run(Box<Fn(&mut Ui) + Send>),
+}
+
+/// Changes the UI from other threads. Widgets and the `Ui` can't be sent between threads,
+/// so worker threads send commands instead.
+/// Commands are applied on the UI thread in the order they're sent, along with the events sent with an `EventSender`.
+/// Commands for widgets that have been removed by then are ignored.
+/// It can be cloned and moved to any thread, see `Ui::command_sender`.
+#[derive(Clone, Debug, Default)]
+pub struct CommandSender {
+    events: EventSender,
+}
+
+impl CommandSender {
+    pub fn new() -> Self {
+        CommandSender::default()
+    }
+    pub fn send(&self, command: UiCommand) {
+        self.events.send(command);
+    }
+    /// Add the widget `build` makes on the UI thread as the last child of `parent`
+    pub fn add_widget<F>(&self, parent: WidgetId, build: F)
+        where F: Fn() -> WidgetBuilder + Send + 'static
+    {
+        self.send(UiCommand::AddWidget { parent: parent, build: Box::new(build) });
+    }
+    pub fn remove_widget(&self, widget_id: WidgetId) {
+        self.send(UiCommand::RemoveWidget(widget_id));
+    }
+    /// Set the text of a widget drawn with a `TextState`, like those made by `TextBuilder`
+    pub fn set_text(&self, widget_id: WidgetId, text: &str) {
+        self.send(UiCommand::SetText(widget_id, text.to_owned()));
+    }
+    pub fn run<F>(&self, f: F)
+        where F: Fn(&mut Ui) + Send + 'static
+    {
+        self.send(UiCommand::Run(Box::new(f)));
+    }
+}
+
 #[derive(Clone)]
 pub struct RegisterWidget(pub WidgetRef);
 #[derive(Clone)]
 pub struct RemoveWidget(pub WidgetRef);
+// the values suggested by `Ui::restore_layout` that weren't suggested before, to stop suggesting once they're solved
+struct RestoredLayout(Vec<(WidgetId, VarType)>);
+/// Sent when a widget gets its first handler for an event type
+pub(crate) struct HandlerAdded(pub WidgetRef, pub TypeId);
 
 impl App {
     pub fn add_ui_handlers(&mut self) {
@@ -244,26 +1096,153 @@ impl App {
             let event = event.clone();
             let RegisterWidget(widget_ref) = event;
             args.ui.widget_map.insert(widget_ref.id(), widget_ref.clone());
+            resources::register_widget_name(&widget_ref.name(), widget_ref.id());
+            for type_id in widget_ref.handler_types() {
+                args.ui.handler_index.entry(type_id).or_insert_with(HashSet::new).insert(widget_ref.id());
+            }
+            let theme_padding = widget_ref.widget().theme_padding;
+            if let (Some(theme_padding), Some(parent)) = (theme_padding, widget_ref.parent()) {
+                let parent = parent.layout_vars();
+                let padding_constraints = ThemeConstraints::new(move |theme| {
+                    constraints![bound_by(&parent).padding(theme.padding(theme_padding))]
+                });
+                // replaces the padding inside the parent it was registered with before, if any
+                let previous = ::std::mem::replace(&mut widget_ref.widget_mut().theme_padding_constraints, Some(padding_constraints));
+                if let Some(previous) = previous {
+                    widget_ref.update_layout(|layout| layout.remove_constraints(previous.added));
+                }
+            }
+            widget_ref.apply_theme(&args.ui.theme, None);
         });
         self.add_handler_fn(|event: &RemoveWidget, args| {
             let event = event.clone();
             let RemoveWidget(widget_ref) = event;
-            args.ui.solver.remove_layout(widget_ref.id().0);
+            // the widget is removed with all of its descendants
+            let mut removed = vec![widget_ref.clone()];
+            while let Some(widget_ref) = removed.pop() {
+                removed.extend(widget_ref.children());
+                args.ui.solver.remove_layout(widget_ref.id().0);
+                args.ui.widget_map.remove(&widget_ref.id());
+                resources::unregister_widget_name(&widget_ref.name(), widget_ref.id());
+                for widgets in args.ui.handler_index.values_mut() {
+                    widgets.remove(&widget_ref.id());
+                }
+                args.ui.animating.remove(&widget_ref.id());
+                args.ui.timers.remove(&widget_ref.id());
+            }
+            // the pointer can't stay locked to a widget that's gone
+            if args.ui.pointer_lock().map_or(false, |widget_id| args.ui.get_widget(widget_id).is_none()) {
+                args.ui.unlock_pointer();
+            }
             args.ui.check_layout_changes();
-            args.ui.widget_map.remove(&widget_ref.id());
+            widget_ref.cancel_tasks();
+        });
+        self.add_handler_fn(|event: &RestoredLayout, args| {
+            for &(widget_id, var) in &event.0 {
+                args.ui.solver.remove_edit_var(widget_id.0, var);
+            }
+            args.ui.check_layout_changes();
+        });
+        self.add_handler_fn(|command: &UiCommand, args| {
+            let widget_id = match *command {
+                UiCommand::AddWidget { parent: widget_id, .. } |
+                UiCommand::RemoveWidget(widget_id) |
+                UiCommand::SetText(widget_id, _) => widget_id,
+                UiCommand::Run(ref f) => {
+                    f(args.ui);
+                    return;
+                }
+            };
+            let mut widget_ref = match args.ui.get_widget(widget_id) {
+                Some(widget_ref) => widget_ref,
+                None => {
+                    debug!("dropped command for removed widget {:?}", widget_id);
+                    return;
+                }
+            };
+            match *command {
+                UiCommand::AddWidget { ref build, .. } => {
+                    widget_ref.add_child(build());
+                }
+                UiCommand::RemoveWidget(_) => widget_ref.remove_widget(),
+                UiCommand::SetText(_, ref text) => {
+                    if !widget_ref.update_drawable(|state: &mut TextState| state.text = text.clone()) {
+                        error!("Can't set the text of {}, it isn't drawn with a TextState", widget_ref.name());
+                    }
+                }
+                UiCommand::Run(_) => (),
+            }
+        });
+        self.add_handler_fn(|event: &HandlerAdded, args| {
+            let &HandlerAdded(ref widget_ref, type_id) = event;
+            // handlers added before the widget is registered are indexed by RegisterWidget
+            if args.ui.widget_map.contains_key(&widget_ref.id()) {
+                args.ui.handler_index.entry(type_id).or_insert_with(HashSet::new).insert(widget_ref.id());
+            }
         });
     }
 }
 pub struct WidgetAttachedEvent;
+/// Sent to the root when an event handler of `widget` panics, if `Ui::set_catch_handler_panics` is enabled
+#[derive(Clone, Debug)]
+pub struct HandlerPanickedEvent {
+    pub widget: WidgetRef,
+    /// The type of event being handled, only available with the `nightly` feature
+    pub type_name: &'static str,
+    pub message: String,
+}
 pub struct WidgetDetachedEvent;
 pub struct ChildAttachedEvent(pub WidgetId, pub LayoutVars);
+/// Sent to a text widget after a change to its draw state that can change its size, eg. its text or font,
+/// so its size constraints can be updated to fit the new content
+pub struct MeasureEvent;
 
 pub enum ChildrenUpdatedEvent {
     Added(WidgetRef),
     Removed(WidgetRef),
+    /// The child was moved to another position in the draw order, or in the children with `move_child`
+    Reordered(WidgetRef),
 }
 
 
+/// Resolves which of the widgets under the cursor should receive input.
+// `hits` are in reverse draw order, paired with the distance from the cursor to
+// their visual bounds, which is only non zero if the cursor is within their hit padding.
+// The first widget hit within its visual bounds is chosen, unless widgets drawn above it
+// were hit within their padding, then the one with the closest visual bounds is chosen,
+// or the topmost of those if equally close.
+fn closest_hit<T, I>(hits: I) -> Option<T>
+    where I: Iterator<Item = (T, f32)>
+{
+    let mut closest: Option<(T, f32)> = None;
+    for (item, distance) in hits {
+        if distance <= 0.0 {
+            return Some(closest.map_or(item, |(closest, _)| closest));
+        }
+        if closest.as_ref().map_or(true, |&(_, closest_distance)| distance < closest_distance) {
+            closest = Some((item, distance));
+        }
+    }
+    closest.map(|(item, _)| item)
+}
+
+fn add_widgets_in_rect(widget_ref: &WidgetRef, rect: Rect, crop_to: Rect, widgets: &mut Vec<WidgetId>) {
+    if !widget_ref.is_visible() {
+        return;
+    }
+    // in the coordinates the widget is laid out in, if it's drawn moved
+    let offset = widget_ref.draw_offset();
+    let (rect, crop_to) = (rect.translate(&-offset), crop_to.translate(&-offset));
+    if let Some(visible_bounds) = crop_to.intersection(&widget_ref.bounds()) {
+        if visible_bounds.intersects(&rect) {
+            widgets.push(widget_ref.id());
+        }
+        for child in widget_ref.children() {
+            add_widgets_in_rect(&child, rect, visible_bounds, widgets);
+        }
+    }
+}
+
 pub struct WidgetsUnderCursor {
     point: Point,
     dfs: WidgetsDfsPostReverse,
@@ -281,9 +1260,10 @@ impl Iterator for WidgetsUnderCursor {
     type Item = WidgetRef;
     fn next(&mut self) -> Option<WidgetRef> {
         for widget_ref in self.dfs.by_ref() {
-            let widget = &widget_ref.widget();
-            if widget.is_under_cursor(self.point) {
-                return Some(widget_ref.clone());
+            if let Some(point) = widget_ref.content_point(self.point) {
+                if widget_ref.widget().is_under_cursor(point) {
+                    return Some(widget_ref.clone());
+                }
             }
         }
         None
@@ -291,7 +1271,8 @@ impl Iterator for WidgetsUnderCursor {
 }
 
 // Iterates in reverse of draw order, that is, depth first post order,
-// with siblings in reverse of insertion order
+// with siblings in reverse of insertion order.
+// Invisible widgets aren't drawn, so they and their children are skipped
 struct WidgetsDfsPostReverse {
     stack: Vec<WidgetRef>,
     discovered: HashSet<WidgetRef>,
@@ -312,7 +1293,9 @@ impl Iterator for WidgetsDfsPostReverse {
     type Item = WidgetRef;
     fn next(&mut self) -> Option<WidgetRef> {
         while let Some(widget_ref) = self.stack.last().cloned() {
-            if self.discovered.insert(widget_ref.clone()) {
+            if !widget_ref.is_visible() {
+                self.stack.pop();
+            } else if self.discovered.insert(widget_ref.clone()) {
                 for child in &widget_ref.children() {
                     self.stack.push(child.clone());
                 }
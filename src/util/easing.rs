@@ -0,0 +1,4 @@
+/// The value a fraction `t` of the way from `from` to `to`, `from` when `t` is 0 and `to` when it's 1
+pub fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
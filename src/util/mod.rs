@@ -0,0 +1,27 @@
+//! Helpers for animating between two states, eg. to compute the values suggested for a widget's layout
+//! variables, or its colors, on each frame of an animation.
+
+pub mod easing;
+
+use geometry::{Rect, RectExt, Point, Size};
+use color::Color;
+
+use self::easing::lerp;
+
+/// The rect a fraction `t` of the way from `from` to `to`, each edge moving in a straight line
+pub fn interpolate_rects(from: Rect, to: Rect, t: f64) -> Rect {
+    let edge = |from: f32, to: f32| lerp(from as f64, to as f64, t) as f32;
+    let left = edge(from.left(), to.left());
+    let top = edge(from.top(), to.top());
+    let right = edge(from.right(), to.right());
+    let bottom = edge(from.bottom(), to.bottom());
+    Rect::new(Point::new(left, top), Size::new(right - left, bottom - top))
+}
+
+/// The color a fraction `t` of the way from `from` to `to`, interpolating each channel, including alpha
+pub fn interpolate_colors(from: Color, to: Color, t: f64) -> Color {
+    let (from_r, from_g, from_b, from_a) = from.to_rgba();
+    let (to_r, to_g, to_b, to_a) = to.to_rgba();
+    let channel = |from: u8, to: u8| lerp(from as f64, to as f64, t).round().max(0.0).min(255.0) as u8;
+    Color::from_rgba(channel(from_r, to_r), channel(from_g, to_g), channel(from_b, to_b), channel(from_a, to_a))
+}
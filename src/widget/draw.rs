@@ -28,6 +28,8 @@ pub(super) struct DrawStyle {
 pub(super) struct DrawWrapper {
     pub state: Box<Draw>,
     pub style: Option<DrawStyle>,
+    /// Name of the draw state's type, for reporting mismatched downcasts
+    pub type_name: &'static str,
 }
 impl DrawWrapper {
     pub fn new<T: Draw + 'static>(draw_state: T) -> Self
@@ -35,6 +37,7 @@ impl DrawWrapper {
         DrawWrapper {
             state: Box::new(draw_state),
             style: None,
+            type_name: ::type_name::<T>(),
         }
     }
     pub fn new_with_style<T: Draw + 'static, S: Style<T> + 'static>(draw_state: T, style: S) -> Self
@@ -51,6 +54,7 @@ impl DrawWrapper {
         DrawWrapper {
             state: Box::new(draw_state),
             style: style,
+            type_name: ::type_name::<T>(),
         }
     }
     pub fn apply_style(&mut self, props: &PropSet) -> bool {
@@ -81,7 +85,7 @@ impl<T: 'static, E> DrawEventHandler<T, E> {
 
 impl<T: Draw + 'static, E> EventHandler<E> for DrawEventHandler<T, E> {
     fn handle(&mut self, _: &E, mut args: EventArgs) {
-        args.widget.update(|state: &mut T| {
+        args.update_drawable(|state: &mut T| {
             (self.draw_callback)(state);
         });
     }
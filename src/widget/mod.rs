@@ -2,6 +2,7 @@
 pub mod style;
 pub mod property;
 pub mod draw;
+pub mod reconcile;
 
 use std::any::{TypeId, Any};
 use std::collections::HashMap;
@@ -10,25 +11,94 @@ use std::cell::{RefCell, Ref, RefMut};
 use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use std::fmt;
+use std::cmp;
+
+use webrender_api::{PrimitiveInfo, LayoutTransform, ScrollPolicy, PropertyBinding, TransformStyle, MixBlendMode};
 
 use render::RenderBuilder;
 use event::{self, EventHandler, EventArgs, EventHandlerWrapper};
 use layout::{Layout, LayoutVars, LayoutRef};
+use cassowary::Constraint;
 use ui::Ui;
 use resources::{resources, WidgetId};
-use geometry::{Point, Rect, RectExt};
+use geometry::{Point, Rect, RectExt, Size, Vector, ViewTransform, ClipShape};
 use render;
 use color::Color;
 use event::Target;
 use layout::UpdateLayout;
+use layout::constraint::ConstraintBuilder;
+use theme::{Theme, ThemePadding};
+use draw::text::{TextState, MeasureInputs};
+use task::TaskHandle;
 
 use self::property::{PropSet, Property};
 use self::draw::{Draw, DrawWrapper};
 use self::style::Style;
 
+/// Font size of the widget names drawn by `Widget::draw_debug`
+const DEBUG_LABEL_FONT_SIZE: f32 = 10.0;
+/// Outline colors used by `Widget::draw_debug` for each level of nesting, repeating for deeper levels
+const DEBUG_DEPTH_COLORS: [Color; 6] = [::color::GREEN, ::color::CYAN, ::color::YELLOW, ::color::FUSCHIA, ::color::RED, ::color::BLUE];
+
+// the color debug bounds are outlined with for widgets `depth` levels below the root,
+// unless they have a color set with `set_debug_color`
+fn debug_color_for_depth(depth: usize) -> Color {
+    DEBUG_DEPTH_COLORS[depth % DEBUG_DEPTH_COLORS.len()]
+}
+
 #[derive(Clone)]
 pub struct WidgetRef(pub Rc<RefCell<Widget>>);
 
+/// Errors reported to the `Ui`'s error handler, see `Ui::set_error_handler`
+#[derive(Clone, Debug)]
+pub enum WidgetError {
+    /// An event handler was called with an event of a different type than it handles
+    UnknownEventType,
+    /// A constraint couldn't be added because it conflicts with the existing constraints
+    ConstraintConflict(Constraint),
+    WidgetNotFound(WidgetId),
+    /// An event handler wasn't of the type it was registered as
+    DowncastFailed,
+}
+
+impl fmt::Display for WidgetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            WidgetError::UnknownEventType => write!(f, "event handler called with the wrong event type"),
+            WidgetError::ConstraintConflict(_) => write!(f, "constraint conflicts with existing constraints"),
+            WidgetError::WidgetNotFound(id) => write!(f, "no widget with id {}", id.0),
+            WidgetError::DowncastFailed => write!(f, "event handler has the wrong type"),
+        }
+    }
+}
+
+impl ::std::error::Error for WidgetError {
+    fn description(&self) -> &str {
+        "widget error"
+    }
+}
+
+/// A widget that's its own ancestor, which would make drawing and sending events to it loop forever,
+/// see `WidgetRef::assert_no_cycles`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CycleError {
+    /// The names of the widgets in the cycle, from a widget to its parent, ending with the first widget again.
+    /// Widgets without a name are identified by their id.
+    pub widgets: Vec<String>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "widget is its own ancestor: {}", self.widgets.join(" -> "))
+    }
+}
+
+impl ::std::error::Error for CycleError {
+    fn description(&self) -> &str {
+        "widget cycle"
+    }
+}
+
 impl WidgetRef {
     fn new(widget: Widget) -> Self {
         let widget_ref = WidgetRef(Rc::new(RefCell::new(widget)));
@@ -47,11 +117,61 @@ impl WidgetRef {
     pub fn add_handler_fn<E: 'static, T: Fn(&E, EventArgs) + 'static>(&mut self, handler: T) -> &mut Self {
         self.add_handler_wrapper(TypeId::of::<E>(), EventHandlerWrapper::new_from_fn(handler))
     }
+    /// Add a handler that's only called if none of the widget's other handlers for the event mark it as handled,
+    /// however they're ordered. Used by widgets for default behaviour, like keyboard activation, that apps can override.
+    pub fn add_default_handler<E: 'static, T: EventHandler<E> + 'static>(&mut self, handler: T) -> &mut Self {
+        self.add_default_handler_wrapper(TypeId::of::<E>(), EventHandlerWrapper::new(handler))
+    }
+    pub fn add_default_handler_fn<E: 'static, T: Fn(&E, EventArgs) + 'static>(&mut self, handler: T) -> &mut Self {
+        self.add_default_handler_wrapper(TypeId::of::<E>(), EventHandlerWrapper::new_from_fn(handler))
+    }
     fn add_handler_wrapper(&mut self, type_id: TypeId, handler: EventHandlerWrapper) -> &mut Self {
+        let first_for_type = !self.has_handler_for(type_id);
         self.widget_mut().handlers.entry(type_id).or_insert_with(Vec::new)
             .push(Rc::new(RefCell::new(handler)));
+        if first_for_type {
+            event::event(Target::Root, ::ui::HandlerAdded(self.clone(), type_id));
+        }
         self
     }
+    fn add_default_handler_wrapper(&mut self, type_id: TypeId, handler: EventHandlerWrapper) -> &mut Self {
+        let first_for_type = !self.has_handler_for(type_id);
+        self.widget_mut().default_handlers.entry(type_id).or_insert_with(Vec::new)
+            .push(Rc::new(RefCell::new(handler)));
+        if first_for_type {
+            event::event(Target::Root, ::ui::HandlerAdded(self.clone(), type_id));
+        }
+        self
+    }
+    fn has_handler_for(&self, type_id: TypeId) -> bool {
+        let widget = self.widget();
+        widget.handlers.contains_key(&type_id) || widget.default_handlers.contains_key(&type_id)
+    }
+    /// Whether one of the widget's handlers panicked, see `Ui::set_catch_handler_panics`
+    pub fn is_poisoned(&self) -> bool {
+        self.widget().poisoned
+    }
+    /// Position of the widget in the tab order, see `WidgetBuilder::tab_index`
+    pub fn tab_index(&self) -> i32 {
+        self.widget().tab_index
+    }
+    /// Rebuild the widget's constraints and font size derived from the theme, see `Ui::set_theme`
+    pub(crate) fn apply_theme(&self, theme: &Theme, previous: Option<&Theme>) {
+        let (layout_changed, font_changed) = self.widget_mut().apply_theme(theme, previous);
+        if layout_changed {
+            event::event(Target::Root, UpdateLayout(self.clone()));
+        }
+        if font_changed {
+            self.event(self::style::StyleUpdated);
+        }
+    }
+    /// The event types this widget has handlers for
+    pub fn handler_types(&self) -> Vec<TypeId> {
+        let widget = self.widget();
+        let mut types: Vec<TypeId> = widget.handlers.keys().cloned().collect();
+        types.extend(widget.default_handlers.keys().filter(|type_id| !widget.handlers.contains_key(type_id)).cloned());
+        types
+    }
     pub fn layout(&mut self) -> LayoutGuard {
         LayoutGuard { guard: self.0.borrow() }
     }
@@ -85,8 +205,16 @@ impl WidgetRef {
         self.0.borrow().id
     }
     pub fn set_name(&mut self, name: &str) -> &mut Self {
-        self.widget_mut().name = name.to_owned();
-        self.widget_mut().layout.name = Some(name.to_owned());
+        let id = self.id();
+        ::resources::unregister_widget_name(&self.name(), id);
+        ::resources::register_widget_name(name, id);
+        {
+            let mut widget = self.widget_mut();
+            widget.name = name.to_owned();
+            if widget.layout_name.is_none() {
+                widget.layout.name = Some(name.to_owned());
+            }
+        }
         event::event(Target::Root, UpdateLayout(self.clone()));
         self
     }
@@ -100,15 +228,153 @@ impl WidgetRef {
     pub fn debug_color(&self) -> Option<Color> {
         self.0.borrow().debug_color
     }
+    /// The color the widget's bounds are outlined with while debug drawing is on, the color set with
+    /// `set_debug_color`, or else the color for how many levels the widget is below the root
+    pub fn debug_outline_color(&self) -> Color {
+        let mut depth = 0;
+        let mut parent = self.parent();
+        while let Some(widget) = parent {
+            depth += 1;
+            parent = widget.parent();
+        }
+        self.debug_color().unwrap_or_else(|| debug_color_for_depth(depth))
+    }
     pub fn has_updated(&self) -> bool {
         self.0.borrow().has_updated
     }
     pub fn set_updated(&self, has_updated: bool) {
         self.0.borrow_mut().has_updated = has_updated;
     }
+    pub(crate) fn needs_measure(&self) -> bool {
+        self.0.borrow().needs_measure
+    }
+    pub(crate) fn set_needs_measure(&self, needs_measure: bool) {
+        self.0.borrow_mut().needs_measure = needs_measure;
+    }
     pub fn bounds(&self) -> Rect {
         self.0.borrow().bounds
     }
+    /// Show or hide the widget and its children. Unlike hiding the layout,
+    /// an invisible widget still takes up space, it just isn't drawn and can't be hit.
+    pub fn set_visible(&mut self, visible: bool) {
+        if self.is_visible() != visible {
+            {
+                let mut widget = self.widget_mut();
+                widget.visible = visible;
+                widget.has_updated = true;
+            }
+            self.event(VisibilityChanged(visible));
+        }
+    }
+    pub fn is_visible(&self) -> bool {
+        self.0.borrow().visible
+    }
+    /// Let clicks and hovering pass through the widget to whatever is drawn beneath it, eg. for a decorative overlay.
+    /// The widget is still drawn, and its children can still be hit unless they're also pointer transparent.
+    pub fn set_pointer_transparent(&mut self, pointer_transparent: bool) {
+        self.widget_mut().pointer_transparent = pointer_transparent;
+    }
+    pub fn is_pointer_transparent(&self) -> bool {
+        self.0.borrow().pointer_transparent
+    }
+    /// Draw the widget's children moved and scaled by `transform`, eg. to pan and zoom them,
+    /// without changing their layout. Hit testing goes through the inverse transform,
+    /// and the children can only be hit inside the widget's bounds, where they're drawn.
+    pub fn set_view_transform(&mut self, transform: Option<ViewTransform>) {
+        let mut widget = self.widget_mut();
+        if widget.view_transform != transform {
+            widget.view_transform = transform;
+            widget.has_updated = true;
+        }
+    }
+    pub fn view_transform(&self) -> Option<ViewTransform> {
+        self.0.borrow().view_transform
+    }
+    /// Draw the widget and its children moved by `offset`, without changing their layout, eg. to keep a header
+    /// in view while the rest of a list scrolls. They're still cropped to the widget's parent where they're drawn,
+    /// and hit testing uses where they're drawn, not where they're laid out.
+    pub fn set_draw_offset(&mut self, offset: Vector) {
+        let mut widget = self.widget_mut();
+        if widget.draw_offset != offset {
+            widget.draw_offset = offset;
+            widget.has_updated = true;
+        }
+    }
+    pub fn draw_offset(&self) -> Vector {
+        self.0.borrow().draw_offset
+    }
+    /// Clip the widget's children to `shape` inside the widget's bounds, eg. for a circular avatar
+    /// or a card with rounded corners whose image reaches its edges. The widget itself isn't clipped.
+    /// Hit testing still uses the children's bounds, unless it's enabled with `set_clip_hit_testing`.
+    pub fn set_clip_shape(&mut self, shape: Option<ClipShape>) {
+        let mut widget = self.widget_mut();
+        if widget.clip_shape != shape {
+            widget.clip_shape = shape;
+            widget.has_updated = true;
+        }
+    }
+    pub fn clip_shape(&self) -> Option<ClipShape> {
+        self.0.borrow().clip_shape
+    }
+    /// Only hit the widget's children inside its clip shape, so the clipped off parts can't be clicked
+    pub fn set_clip_hit_testing(&mut self, clip_hit_testing: bool) {
+        self.widget_mut().clip_hit_testing = clip_hit_testing;
+    }
+    /// The point in the coordinates the widget is laid out in, that is drawn at `point` in the window,
+    /// undoing the view transforms of its ancestors, and the draw offsets of the widget and its ancestors.
+    /// `None` if the point is outside of an ancestor with a view transform, where the widget is cropped,
+    /// or outside of an ancestor's clip shape, if its hit testing is enabled.
+    pub fn content_point(&self, point: Point) -> Option<Point> {
+        let mut ancestors = Vec::new();
+        let mut parent = self.parent();
+        while let Some(ancestor) = parent {
+            parent = ancestor.parent();
+            ancestors.push(ancestor);
+        }
+        let mut point = point;
+        for ancestor in ancestors.iter().rev() {
+            point = point - ancestor.draw_offset();
+            if let Some(shape) = ancestor.hit_clip_shape() {
+                if !shape.contains(ancestor.bounds(), point) {
+                    return None;
+                }
+            }
+            if let Some(transform) = ancestor.view_transform() {
+                let bounds = ancestor.bounds();
+                if !bounds.contains(&point) {
+                    return None;
+                }
+                let local = (point - bounds.origin).to_point();
+                point = bounds.origin + transform.inverse_transform_point(local).to_vector();
+            }
+        }
+        Some(point - self.draw_offset())
+    }
+    fn hit_clip_shape(&self) -> Option<ClipShape> {
+        let widget = self.0.borrow();
+        if widget.clip_hit_testing { widget.clip_shape } else { None }
+    }
+    /// `point` in window coordinates, relative to the widget's top left corner, see `content_point`
+    pub fn local_point(&self, point: Point) -> Point {
+        let point = self.content_point(point).unwrap_or(point);
+        (point - self.bounds().origin).to_point()
+    }
+    /// The point in window coordinates where `point`, relative to the widget's top left corner, is drawn,
+    /// the inverse of `local_point`
+    pub fn global_point(&self, point: Point) -> Point {
+        let mut point = self.bounds().origin + point.to_vector() + self.draw_offset();
+        let mut parent = self.parent();
+        while let Some(ancestor) = parent {
+            if let Some(transform) = ancestor.view_transform() {
+                let bounds = ancestor.bounds();
+                let local = (point - bounds.origin).to_point();
+                point = bounds.origin + transform.transform_point(local).to_vector();
+            }
+            point = point + ancestor.draw_offset();
+            parent = ancestor.parent();
+        }
+        point
+    }
 
     pub fn update<F, T: Draw + 'static>(&mut self, f: F)
         where F: FnOnce(&mut T)
@@ -116,6 +382,51 @@ impl WidgetRef {
         self.0.borrow_mut().update(f);
         self.event(self::style::StyleUpdated);
     }
+    /// The widget's draw state, if it is a `T`. Logs an error and returns `None` if it isn't.
+    pub fn drawable<T: Draw + 'static>(&self) -> Option<RefMut<T>> {
+        if !self.widget().draw_state_is::<T>() {
+            return None;
+        }
+        Some(RefMut::map(self.0.borrow_mut(), |widget| widget.draw_state_mut::<T>().unwrap()))
+    }
+    /// Attach application data to the widget, eg. the index of the model item it shows,
+    /// replacing any data attached before, to get back in handlers with `user_data`
+    pub fn set_user_data<T: 'static>(&mut self, data: T) {
+        self.widget_mut().set_user_data(data);
+    }
+    /// The data attached with `set_user_data`, if there is some and it is a `T`
+    pub fn user_data<T: 'static>(&self) -> Option<Ref<T>> {
+        if self.widget().user_data::<T>().is_none() {
+            return None;
+        }
+        Some(Ref::map(self.0.borrow(), |widget| widget.user_data::<T>().unwrap()))
+    }
+    // keep the handle of a task the widget started, forgetting tasks that have finished
+    pub(crate) fn add_task(&self, task: TaskHandle) {
+        let mut widget = self.widget_mut();
+        widget.tasks.retain(|task| !task.is_finished());
+        widget.tasks.push(task);
+    }
+    // cancel the tasks started by the widget and its descendants
+    pub(crate) fn cancel_tasks(&self) {
+        for task in self.widget_mut().tasks.drain(..) {
+            task.cancel();
+        }
+        for child in self.children() {
+            child.cancel_tasks();
+        }
+    }
+    /// Like `update`, but logs an error instead of panicking if the draw state isn't a `T`.
+    /// Returns true if `f` was called.
+    pub fn update_drawable<F, T: Draw + 'static>(&mut self, f: F) -> bool
+        where F: FnOnce(&mut T)
+    {
+        if !self.widget().draw_state_is::<T>() {
+            return false;
+        }
+        self.update(f);
+        true
+    }
     pub fn update_layout<F>(&self, f: F)
         where F: FnOnce(&mut Layout)
     {
@@ -130,22 +441,92 @@ impl WidgetRef {
         }
     }
 
+    /// Add a child after this widget's other children, but before any kept on top of them,
+    /// see `WidgetBuilder::set_keep_on_top`
     pub fn add_child<U: Into<WidgetRef>>(&mut self, child: U) -> &mut Self {
         let mut child = child.into();
-        event::event(Target::Root, ::layout::UpdateLayout(child.clone()));
-        child.widget_mut().parent = Some(self.downgrade());
-        child.widget_mut().props.extend(self.props().iter().cloned());
-        child.apply_style();
+        if let Some(index) = self.first_kept_on_top(&child) {
+            return self.insert_child(index, child);
+        }
+        self.attach_child(&mut child);
         self.widget_mut().children.push(child.clone());
         self.update_layout(|layout| {
             child.update_layout(|child_layout| {
                 layout.add_child(child_layout);
             });
         });
-        self.event(::ui::WidgetAttachedEvent);
+        self.child_attached(child);
+        self
+    }
+
+    /// Add a child at `index` in this widget's children, before the child currently at `index`.
+    /// The order of the children is the order they are drawn in, and their order in ordered layouts
+    /// like `LinearLayout`. An `index` past the end adds the child after the last child.
+    pub fn insert_child<U: Into<WidgetRef>>(&mut self, index: usize, child: U) -> &mut Self {
+        let mut child = child.into();
+        self.attach_child(&mut child);
+        let index = {
+            let mut widget = self.widget_mut();
+            let index = cmp::min(index, widget.children.len());
+            widget.children.insert(index, child.clone());
+            index
+        };
+        self.update_layout(|layout| {
+            child.update_layout(|child_layout| {
+                layout.insert_child(index, child_layout);
+            });
+        });
+        self.child_attached(child);
+        self
+    }
+
+    /// Move `child` to `index` in this widget's children, see `insert_child`.
+    /// Unlike removing it and inserting it again, the child keeps its layout and isn't detached.
+    pub fn move_child(&mut self, child: &WidgetRef, index: usize) {
+        let index = {
+            let mut widget = self.widget_mut();
+            let from = match widget.children.iter().position(|sibling| sibling == child) {
+                Some(from) => from,
+                None => return,
+            };
+            let child = widget.children.remove(from);
+            let index = cmp::min(index, widget.children.len());
+            widget.children.insert(index, child);
+            widget.has_updated = true;
+            index
+        };
+        self.update_layout(|layout| {
+            child.update_layout(|child_layout| {
+                layout.move_child(index, child_layout);
+            });
+        });
+        self.event(::ui::ChildrenUpdatedEvent::Reordered(child.clone()));
+    }
+
+    // the index of the first child kept on top of its siblings, unless `child` is kept on top too
+    fn first_kept_on_top(&self, child: &WidgetRef) -> Option<usize> {
+        if child.widget().keep_on_top {
+            return None;
+        }
+        self.widget().children.iter().position(|sibling| sibling.widget().keep_on_top)
+    }
+
+    fn attach_child(&mut self, child: &mut WidgetRef) {
+        event::event(Target::Root, ::layout::UpdateLayout(child.clone()));
+        child.widget_mut().parent = Some(self.downgrade());
+        if cfg!(debug_assertions) {
+            if let Err(error) = child.assert_no_cycles() {
+                panic!("{}", error);
+            }
+        }
+        child.widget_mut().props.extend(self.props().iter().cloned());
+        child.apply_style();
+    }
+
+    fn child_attached(&mut self, child: WidgetRef) {
+        child.event(::ui::WidgetAttachedEvent);
         self.event(::ui::ChildAttachedEvent(self.id(), child.layout().vars.clone()));
         self.event(::ui::ChildrenUpdatedEvent::Added(child));
-        self
     }
 
     pub fn remove_child(&mut self, child_ref: WidgetRef) {
@@ -160,7 +541,8 @@ impl WidgetRef {
             widget.children.remove(index);
         }
         self.event(::ui::ChildrenUpdatedEvent::Removed(child_ref.clone()));
-        child_ref.event(::ui::WidgetDetachedEvent);
+        // the child's descendants are detached from the UI along with it
+        child_ref.event_subtree(::ui::WidgetDetachedEvent);
         event::event(Target::Root, ::ui::RemoveWidget(child_ref.clone()));
     }
 
@@ -170,13 +552,94 @@ impl WidgetRef {
         }
     }
 
+    /// Check that this widget and its ancestors aren't their own ancestors.
+    /// Checked whenever a child is added in debug builds, which panic if adding it made a cycle.
+    pub fn assert_no_cycles(&self) -> Result<(), CycleError> {
+        let mut ancestors: Vec<WidgetRef> = vec![self.clone()];
+        let mut parent = self.parent();
+        while let Some(ancestor) = parent {
+            if let Some(start) = ancestors.iter().position(|other| *other == ancestor) {
+                let mut widgets: Vec<String> = ancestors[start..].iter().map(|widget| widget.debug_name()).collect();
+                widgets.push(ancestor.debug_name());
+                return Err(CycleError { widgets: widgets });
+            }
+            parent = ancestor.parent();
+            ancestors.push(ancestor);
+        }
+        Ok(())
+    }
+    fn debug_name(&self) -> String {
+        let widget = self.widget();
+        if widget.name.is_empty() { format!("{}", widget.id.0) } else { widget.name.clone() }
+    }
+
     pub fn parent(&self) -> Option<WidgetRef> {
         self.widget().parent.as_ref().and_then(|parent| parent.upgrade())
     }
 
+    /// The widget's children, in the order they are drawn, which is the order they were added
+    /// unless they've been reordered with `bring_to_front` or `send_to_back`
     pub fn children(&self) -> Vec<WidgetRef> {
         self.widget().children.clone()
     }
+    /// Move this widget after its siblings, so it's drawn over them and receives input first,
+    /// unless they're kept on top. Only the draw order changes, the position in the parent's layout stays the same.
+    pub fn bring_to_front(&mut self) {
+        self.move_in_parent(true);
+    }
+    /// Move this widget before its siblings, so it's drawn under them.
+    /// Only the draw order changes, the position in the parent's layout stays the same.
+    pub fn send_to_back(&mut self) {
+        self.move_in_parent(false);
+    }
+    fn move_in_parent(&self, to_front: bool) {
+        if let Some(parent) = self.parent() {
+            {
+                let mut parent_widget = parent.widget_mut();
+                let index = match parent_widget.children.iter().position(|sibling| sibling == self) {
+                    Some(index) => index,
+                    None => return,
+                };
+                let child = parent_widget.children.remove(index);
+                if to_front {
+                    let index = if child.widget().keep_on_top {
+                        None
+                    } else {
+                        parent_widget.children.iter().position(|sibling| sibling.widget().keep_on_top)
+                    };
+                    let index = index.unwrap_or(parent_widget.children.len());
+                    parent_widget.children.insert(index, child);
+                } else {
+                    parent_widget.children.insert(0, child);
+                }
+                parent_widget.has_updated = true;
+            }
+            parent.event(::ui::ChildrenUpdatedEvent::Reordered(self.clone()));
+        }
+    }
+    /// The sibling added to the parent just before this widget, in the order children are iterated
+    pub fn sibling_before(&self) -> Option<WidgetRef> {
+        self.sibling_at_offset(-1)
+    }
+    /// The sibling added to the parent just after this widget, in the order children are iterated
+    pub fn sibling_after(&self) -> Option<WidgetRef> {
+        self.sibling_at_offset(1)
+    }
+    fn sibling_at_offset(&self, offset: isize) -> Option<WidgetRef> {
+        let parent = match self.parent() {
+            Some(parent) => parent,
+            None => return None,
+        };
+        let siblings = parent.children();
+        siblings.iter().position(|sibling| sibling == self).and_then(|index| {
+            let index = index as isize + offset;
+            if index >= 0 {
+                siblings.get(index as usize).cloned()
+            } else {
+                None
+            }
+        })
+    }
 
     pub fn event<T: 'static>(&self, data: T) {
         event::event(Target::Widget(self.clone()), data);
@@ -187,20 +650,32 @@ impl WidgetRef {
     pub fn event_bubble_up<T: 'static>(&self, data: T) {
         event::event(Target::BubbleUp(self.clone()), data);
     }
-    pub fn trigger_event(&self, ui: &mut Ui, type_id: TypeId, event: &Any) -> bool {
-        let handlers = {
-            let mut widget = self.0.borrow_mut();
-            let mut handlers: Vec<Rc<RefCell<EventHandlerWrapper>>> = Vec::new();
-            if let Some(event_handlers) = widget.handlers.get_mut(&type_id) {
-                for handler in event_handlers {
-                    handlers.push(handler.clone());
-                }
-            }
-            handlers
+    /// Call this widget's handlers for the event type `type_id`, returns true if one of them
+    /// marked the event as handled. If a handler can't be called with `event`,
+    /// the other handlers are still called and the first error is returned.
+    pub fn trigger_event(&self, ui: &mut Ui, type_id: TypeId, event: &Any) -> Result<bool, WidgetError> {
+        let (handlers, default_handlers) = {
+            let widget = self.0.borrow();
+            let handlers_for = |handlers: &HashMap<TypeId, Vec<Rc<RefCell<EventHandlerWrapper>>>>| {
+                handlers.get(&type_id).cloned().unwrap_or_default()
+            };
+            (handlers_for(&widget.handlers), handlers_for(&widget.default_handlers))
         };
 
+        if self.is_poisoned() {
+            return Ok(false);
+        }
+        // the root is never poisoned, its handlers run the ui
+        let catch_panics = ui.catch_handler_panics && *self != ui.get_root();
         let mut handled = false;
-        for event_handler in handlers {
+        let mut error = None;
+        let mut panicked = None;
+        for (event_handler, is_default) in handlers.into_iter().map(|handler| (handler, false))
+            .chain(default_handlers.into_iter().map(|handler| (handler, true)))
+        {
+            if is_default && handled {
+                break;
+            }
             // will panic in the case of circular handler calls
             let mut handler = event_handler.borrow_mut();
             let event_args = EventArgs {
@@ -208,9 +683,38 @@ impl WidgetRef {
                 ui: ui,
                 handled: &mut handled,
             };
-            handler.handle(event, event_args);
+            let result = if catch_panics {
+                let mut result = Ok(());
+                if let Err(message) = event::catch_handler_panic(|| result = handler.handle(event, event_args)) {
+                    panicked = Some((handler.event_type_name, message));
+                    break;
+                }
+                result
+            } else {
+                handler.handle(event, event_args)
+            };
+            if let Err(err) = result {
+                error = error.or(Some(err));
+            }
+        }
+        if let Some((type_name, message)) = panicked {
+            {
+                let mut widget = self.0.borrow_mut();
+                widget.poisoned = true;
+                widget.has_updated = true;
+            }
+            event::event(Target::Root, ::ui::HandlerPanickedEvent {
+                widget: self.clone(),
+                type_name: type_name,
+                message: message.clone(),
+            });
+            // lets the nearest error boundary replace the widget
+            self.event_bubble_up(::widgets::error_boundary::WidgetErrorEvent(message));
+        }
+        match error {
+            Some(err) => Err(err),
+            None => Ok(handled),
         }
-        handled
     }
 }
 
@@ -306,19 +810,86 @@ impl WidgetWeak {
     }
 }
 
+/// Sent to a widget when it is shown or hidden with `WidgetRef::set_visible`
+#[derive(Clone, Debug)]
+pub struct VisibilityChanged(pub bool);
+
+/// Constraints built from the Ui's theme, rebuilt when the theme changes, see `WidgetBuilder::add_theme_constraints`
+pub(super) struct ThemeConstraints {
+    build: Box<Fn(&Theme) -> Vec<Box<ConstraintBuilder>>>,
+    pub(super) added: Vec<Constraint>,
+}
+impl ThemeConstraints {
+    pub(super) fn new<F>(build: F) -> Self
+        where F: Fn(&Theme) -> Vec<Box<ConstraintBuilder>> + 'static
+    {
+        ThemeConstraints {
+            build: Box::new(build),
+            added: Vec::new(),
+        }
+    }
+}
+
 /// Internal Widget representation, usually handled through a WidgetRef
 pub struct Widget {
     id: WidgetId,
     draw_state: Option<DrawWrapper>,
     props: PropSet,
     has_updated: bool,
+    visible: bool,
+    /// Set when a text draw state is set, or its text, font, font size or wrapping changes,
+    /// the Ui then sends the widget a `MeasureEvent`
+    needs_measure: bool,
     pub(super) layout: Layout,
     pub(super) bounds: Rect,
+    // send a `WidgetResizedEvent` when the width or height changes, see `WidgetBuilder::respond_to_resize`
+    pub(super) tracks_resize: bool,
+    // only send `WidgetResizedEvent` when the size rounded to whole pixels changes
+    pub(super) resize_whole_pixels: bool,
+    // the size sent with the last `WidgetResizedEvent`, if only whole pixel changes are sent
+    pub(super) last_resize: Size,
+    hit_padding: f32,
+    // skipped by hit testing, so the cursor hits the widgets under it, see `WidgetRef::set_pointer_transparent`
+    pointer_transparent: bool,
+    // stays after the siblings added after it, see `WidgetBuilder::set_keep_on_top`
+    keep_on_top: bool,
+    pub(super) theme_padding: Option<ThemePadding>,
+    // the padding from the theme inside the parent the widget was registered with
+    pub(super) theme_padding_constraints: Option<ThemeConstraints>,
+    theme_constraints: Vec<ThemeConstraints>,
+    // font size of the text draw state at the default density, see `WidgetBuilder::font_size_from_theme`
+    theme_font_size: Option<f32>,
     name: String,
+    // overrides the name used for the layout in solver debug output, see `WidgetBuilder::set_layout_debug_name`
+    layout_name: Option<String>,
     debug_color: Option<Color>,
     children: Vec<WidgetRef>,
     parent: Option<WidgetWeak>,
     handlers: HashMap<TypeId, Vec<Rc<RefCell<EventHandlerWrapper>>>>,
+    // called after `handlers`, unless one of them handled the event
+    default_handlers: HashMap<TypeId, Vec<Rc<RefCell<EventHandlerWrapper>>>>,
+    // one of the handlers panicked, see `Ui::set_catch_handler_panics`
+    poisoned: bool,
+    tab_index: i32,
+    // applied to the children when they're drawn and hit tested, see `WidgetRef::set_view_transform`
+    view_transform: Option<ViewTransform>,
+    // applied to the widget and its children when they're drawn and hit tested, see `WidgetRef::set_draw_offset`
+    draw_offset: Vector,
+    // the children are clipped to this shape, see `WidgetRef::set_clip_shape`
+    clip_shape: Option<ClipShape>,
+    // children are only hit inside the clip shape
+    clip_hit_testing: bool,
+    // the kind and key of the spec the widget was built from, see `reconcile`
+    spec_id: Option<reconcile::SpecId>,
+    // application data attached to the widget, see `WidgetRef::set_user_data`
+    user_data: Option<Box<Any>>,
+    // background tasks started by the widget's handlers, cancelled when it's removed, see `EventArgs::spawn`
+    tasks: Vec<TaskHandle>,
+}
+
+// what measuring the widget depends on, if it draws text, so only text changes send it a `MeasureEvent`
+fn measure_inputs(draw_state: &DrawWrapper) -> Option<MeasureInputs> {
+    draw_state.state.as_ref().downcast_ref::<TextState>().map(TextState::measure_inputs)
 }
 
 impl Widget {
@@ -330,21 +901,55 @@ impl Widget {
             props: PropSet::new(),
             layout: Layout::new(id.0, Some(name.clone())),
             has_updated: false,
+            visible: true,
+            needs_measure: false,
             bounds: Rect::zero(),
+            tracks_resize: false,
+            resize_whole_pixels: false,
+            last_resize: Size::zero(),
+            hit_padding: 0.0,
+            pointer_transparent: false,
+            keep_on_top: false,
+            theme_padding: None,
+            theme_padding_constraints: None,
+            theme_constraints: Vec::new(),
+            theme_font_size: None,
             name: name,
+            layout_name: None,
             debug_color: None,
             children: Vec::new(),
             parent: None,
             handlers: HashMap::new(),
+            default_handlers: HashMap::new(),
+            poisoned: false,
+            tab_index: 0,
+            view_transform: None,
+            draw_offset: Vector::zero(),
+            clip_shape: None,
+            clip_hit_testing: false,
+            spec_id: None,
+            user_data: None,
+            tasks: Vec::new(),
         }
     }
     pub fn name(&self) -> &str {
         &self.name
     }
+    pub fn set_user_data<T: 'static>(&mut self, data: T) {
+        self.user_data = Some(Box::new(data));
+    }
+    /// The data attached with `set_user_data`, if there is some and it is a `T`
+    pub fn user_data<T: 'static>(&self) -> Option<&T> {
+        self.user_data.as_ref().and_then(|data| data.downcast_ref::<T>())
+    }
     pub fn layout(&mut self) -> &mut Layout {
         &mut self.layout
     }
     pub fn draw(&mut self, crop_to: Rect, renderer: &mut RenderBuilder) {
+        if !self.visible {
+            return;
+        }
+        let crop_to = self.push_draw_offset(crop_to, renderer);
         let bounds = self.bounds;
         let clip_id = renderer.builder.define_clip(None, bounds.typed(), vec![], None);
         renderer.builder.push_clip_id(clip_id);
@@ -352,41 +957,189 @@ impl Widget {
             draw_state.state.draw(bounds, crop_to, renderer);
         }
         if let Some(crop_to) = crop_to.intersection(&bounds) {
+            self.push_clip_shape(renderer);
+            let crop_to = self.push_view_transform(crop_to, renderer);
             for child in &self.children {
                 let mut child = child.widget_mut();
                 child.draw(crop_to, renderer);
             }
+            self.pop_view_transform(renderer);
+            self.pop_clip_shape(renderer);
+        }
+        if self.poisoned {
+            render::draw_rect_outline(bounds, ::color::RED, renderer);
+            let info = PrimitiveInfo::new(bounds.typed());
+            renderer.builder.push_rect(&info, ::color::ERROR_OVERLAY.into());
+        }
+        renderer.builder.pop_clip_id();
+        self.pop_draw_offset(renderer);
+    }
+    /// Outline the bounds of this widget and its children, clipped the same way as `draw`,
+    /// so widgets scrolled out of view aren't outlined.
+    /// Each outline is labelled with the widget's name, or its id if it has no name.
+    /// `depth` is the number of levels this widget is below the root, used to pick the outline color.
+    pub fn draw_debug(&mut self, crop_to: Rect, depth: usize, renderer: &mut RenderBuilder) {
+        if !self.visible {
+            return;
+        }
+        let crop_to = self.push_draw_offset(crop_to, renderer);
+        let bounds = self.bounds;
+        let clip_id = renderer.builder.define_clip(None, bounds.typed(), vec![], None);
+        renderer.builder.push_clip_id(clip_id);
+        let color = self.debug_color.unwrap_or_else(|| debug_color_for_depth(depth));
+        render::draw_rect_outline(bounds, color, renderer);
+        let label = if self.name.is_empty() { format!("{}", self.id.0) } else { self.name.clone() };
+        let mut label = TextState::new(&label);
+        label.font_size = DEBUG_LABEL_FONT_SIZE;
+        label.text_color = color;
+        let label_bounds = Rect::new(bounds.origin, label.measure());
+        label.draw(label_bounds, crop_to, renderer);
+        if let Some(crop_to) = crop_to.intersection(&bounds) {
+            self.push_clip_shape(renderer);
+            let crop_to = self.push_view_transform(crop_to, renderer);
+            for child in &self.children {
+                child.widget_mut().draw_debug(crop_to, depth + 1, renderer);
+            }
+            self.pop_view_transform(renderer);
+            self.pop_clip_shape(renderer);
         }
         renderer.builder.pop_clip_id();
+        self.pop_draw_offset(renderer);
+    }
+
+    // clip the children to the clip shape, if there is one
+    fn push_clip_shape(&self, renderer: &mut RenderBuilder) {
+        if let Some(shape) = self.clip_shape {
+            let clip_id = renderer.builder.define_clip(None, self.bounds.typed(), vec![shape.clip_region(self.bounds)], None);
+            renderer.builder.push_clip_id(clip_id);
+        }
     }
-    pub fn draw_debug(&mut self, renderer: &mut RenderBuilder) {
-        let color = self.debug_color.unwrap_or(::color::GREEN);
-        render::draw_rect_outline(self.bounds, color, renderer);
-        for child in &self.children {
-            child.widget_mut().draw_debug(renderer);
+    fn pop_clip_shape(&self, renderer: &mut RenderBuilder) {
+        if self.clip_shape.is_some() {
+            renderer.builder.pop_clip_id();
+        }
+    }
+    // start drawing the widget moved by its draw offset, if it has one,
+    // returns `crop_to` in the coordinates the widget is laid out in
+    fn push_draw_offset(&self, crop_to: Rect, renderer: &mut RenderBuilder) -> Rect {
+        if self.draw_offset != Vector::zero() {
+            let offset = self.draw_offset;
+            renderer.builder.push_stacking_context(
+                &PrimitiveInfo::new(Rect::zero().typed()),
+                ScrollPolicy::Fixed,
+                Some(PropertyBinding::Value(LayoutTransform::create_translation(offset.x, offset.y, 0.0))),
+                TransformStyle::Flat,
+                None,
+                MixBlendMode::Normal,
+                Vec::new(),
+            );
+            crop_to.translate(&-offset)
+        } else {
+            crop_to
+        }
+    }
+    fn pop_draw_offset(&self, renderer: &mut RenderBuilder) {
+        if self.draw_offset != Vector::zero() {
+            renderer.builder.pop_stacking_context();
+        }
+    }
+    // start drawing the children with the view transform if there is one,
+    // returns `crop_to` in the coordinates the children are laid out in
+    fn push_view_transform(&self, crop_to: Rect, renderer: &mut RenderBuilder) -> Rect {
+        if let Some(transform) = self.view_transform {
+            // scale around the widget's top left corner, then offset
+            let origin = self.bounds.origin;
+            let layout_transform = LayoutTransform::create_translation(origin.x + transform.offset.x, origin.y + transform.offset.y, 0.0)
+                .pre_mul(&LayoutTransform::create_scale(transform.scale, transform.scale, 1.0))
+                .pre_mul(&LayoutTransform::create_translation(-origin.x, -origin.y, 0.0));
+            renderer.builder.push_stacking_context(
+                &PrimitiveInfo::new(Rect::zero().typed()),
+                ScrollPolicy::Fixed,
+                Some(PropertyBinding::Value(layout_transform)),
+                TransformStyle::Flat,
+                None,
+                MixBlendMode::Normal,
+                Vec::new(),
+            );
+            let local = crop_to.translate(&-origin.to_vector());
+            transform.inverse_transform_rect(local).translate(&origin.to_vector())
+        } else {
+            crop_to
+        }
+    }
+    fn pop_view_transform(&self, renderer: &mut RenderBuilder) {
+        if self.view_transform.is_some() {
+            renderer.builder.pop_stacking_context();
         }
     }
 
     pub fn is_under_cursor(&self, cursor: Point) -> bool {
+        self.hit_distance(cursor).is_some()
+    }
+    /// If the cursor is over this widget or its hit padding, the distance from
+    /// the cursor to the widget's visual bounds, zero if it's over the drawn shape.
+    /// Inside the bounds but outside the shape, eg. the corners of an ellipse, isn't a hit,
+    /// the padding only extends the bounds outwards.
+    pub fn hit_distance(&self, cursor: Point) -> Option<f32> {
+        if self.pointer_transparent {
+            return None;
+        }
         if let Some(ref draw_state) = self.draw_state {
-            draw_state.is_under_cursor(self.bounds, cursor)
-        } else {
-            false
+            if draw_state.is_under_cursor(self.bounds, cursor) {
+                return Some(0.0);
+            }
+            let distance = self.bounds.distance_to(cursor);
+            if distance > 0.0 && distance <= self.hit_padding {
+                return Some(distance);
+            }
         }
+        None
     }
     pub fn update<F, T: Draw + 'static>(&mut self, f: F)
         where F: FnOnce(&mut T)
     {
         if let Some(ref mut draw_state) = self.draw_state {
             self.has_updated = true;
-            let state = draw_state.state.as_mut().downcast_mut::<T>().expect("Called update on widget with wrong draw_state type");
-            f(state);
+            let before = measure_inputs(draw_state);
+            {
+                let state = draw_state.state.as_mut().downcast_mut::<T>().expect("Called update on widget with wrong draw_state type");
+                f(state);
+            }
+            if measure_inputs(draw_state) != before {
+                self.needs_measure = true;
+            }
+        }
+    }
+    // rebuild the constraints and font size derived from the theme, returns whether the layout and the draw state changed.
+    // the font size isn't changed if it was changed from the one derived from the `previous` theme
+    fn apply_theme(&mut self, theme: &Theme, previous: Option<&Theme>) -> (bool, bool) {
+        let mut layout_changed = false;
+        for theme_constraints in self.theme_padding_constraints.iter_mut().chain(self.theme_constraints.iter_mut()) {
+            let constraints = self.layout.create_constraint((theme_constraints.build)(theme));
+            self.layout.remove_constraints(::std::mem::replace(&mut theme_constraints.added, constraints.clone()));
+            self.layout.add(constraints);
+            layout_changed = true;
+        }
+        let mut font_changed = false;
+        if let Some(font_size) = self.theme_font_size {
+            let derived = previous.map_or(font_size, |previous| previous.scale(font_size));
+            let scaled = theme.scale(font_size);
+            let current = self.draw_state::<TextState>().map(|state| state.font_size);
+            if current == Some(derived) && derived != scaled {
+                self.update(|state: &mut TextState| state.font_size = scaled);
+                font_changed = true;
+            }
         }
+        (layout_changed, font_changed)
     }
     fn apply_style(&mut self) -> bool {
         if let Some(ref mut draw_state) = self.draw_state {
+            let before = measure_inputs(draw_state);
             if draw_state.apply_style(&self.props) {
                 self.has_updated = true;
+                if measure_inputs(draw_state) != before {
+                    self.needs_measure = true;
+                }
                 return true;
             }
         }
@@ -399,6 +1152,24 @@ impl Widget {
             None
         }
     }
+    pub fn draw_state_mut<T: Draw>(&mut self) -> Option<&mut T> {
+        if let Some(ref mut draw_state) = self.draw_state {
+            draw_state.state.as_mut().downcast_mut::<T>()
+        } else {
+            None
+        }
+    }
+    fn draw_state_is<T: Draw>(&self) -> bool {
+        let (matches, actual) = match self.draw_state {
+            Some(ref draw_state) => (draw_state.state.as_ref().is::<T>(), draw_state.type_name),
+            None => (false, "no draw state"),
+        };
+        if !matches {
+            error!("widget {} has draw state {}, expected {}", self.name, actual, ::type_name::<T>());
+            debug_assert!(matches, "widget {} has draw state {}, expected {}", self.name, actual, ::type_name::<T>());
+        }
+        matches
+    }
 }
 
 /// Used to initialize and modify a Widget before it's been added to a parent Widget
@@ -412,6 +1183,18 @@ impl WidgetBuilder {
             widget: WidgetRef::new(Widget::new(name.to_owned())),
         }
     }
+    /// Create a widget that draws `draw_state`, same as `new` followed by `set_draw_state`
+    pub fn with_draw_state<T: Draw + 'static>(name: &str, draw_state: T) -> Self {
+        let mut widget = WidgetBuilder::new(name);
+        widget.set_draw_state(draw_state);
+        widget
+    }
+    /// Create a widget that draws `draw_state` with `style`, same as `new` followed by `set_draw_state_with_style`
+    pub fn with_draw_state_and_style<T: Draw + 'static, S: Style<T> + 'static>(name: &str, draw_state: T, style: S) -> Self {
+        let mut widget = WidgetBuilder::new(name);
+        widget.set_draw_state_with_style(draw_state, style);
+        widget
+    }
     pub fn widget_ref(&self) -> WidgetRef {
         self.widget.clone()
     }
@@ -420,12 +1203,14 @@ impl WidgetBuilder {
     }
     pub fn set_draw_state<T: Draw + 'static>(&mut self, draw_state: T) -> &mut Self {
         self.widget.widget_mut().draw_state = Some(DrawWrapper::new(draw_state));
+        self.widget.widget_mut().needs_measure = TypeId::of::<T>() == TypeId::of::<TextState>();
         self.widget.widget_mut().apply_style();
         self.widget.event(self::style::StyleUpdated);
         self
     }
     pub fn set_draw_state_with_style<T: Draw + 'static, S: Style<T> + 'static>(&mut self, draw_state: T, style: S) -> &mut Self {
         self.widget.widget_mut().draw_state = Some(DrawWrapper::new_with_style(draw_state, style));
+        self.widget.widget_mut().needs_measure = TypeId::of::<T>() == TypeId::of::<TextState>();
         self.widget.widget_mut().apply_style();
         self.widget.event(self::style::StyleUpdated);
         self
@@ -438,6 +1223,15 @@ impl WidgetBuilder {
         self.widget.add_handler_fn(handler);
         self
     }
+    /// See `WidgetRef::add_default_handler`
+    pub fn add_default_handler<E: 'static, T: EventHandler<E> + 'static>(&mut self, handler: T) -> &mut Self {
+        self.widget.add_default_handler(handler);
+        self
+    }
+    pub fn add_default_handler_fn<E: 'static, T: Fn(&E, EventArgs) + 'static>(&mut self, handler: T) -> &mut Self {
+        self.widget.add_default_handler_fn(handler);
+        self
+    }
     pub fn add_prop(&mut self, property: Property) -> &mut Self {
         self.widget.widget_mut().props.insert(property);
         for child in &mut self.widget.widget_mut().children {
@@ -454,8 +1248,131 @@ impl WidgetBuilder {
         self
     }
     pub fn set_name(&mut self, name: &str) -> &mut Self {
-        self.widget.widget_mut().name = name.to_owned();
-        self.widget.widget_mut().layout.name = Some(name.to_owned());
+        {
+            let mut widget = self.widget.widget_mut();
+            widget.name = name.to_owned();
+            if widget.layout_name.is_none() {
+                widget.layout.name = Some(name.to_owned());
+            }
+        }
+        self
+    }
+    /// Name the widget's layout variables in solver debug output, eg. `fmt_constraint`,
+    /// instead of using the widget's name. Useful to include context like the widget's parents,
+    /// eg. "dialog/button_row/ok_button", while keeping the widget's name short.
+    pub fn set_layout_debug_name(&mut self, name: &str) -> &mut Self {
+        {
+            let mut widget = self.widget.widget_mut();
+            widget.layout_name = Some(name.to_owned());
+            widget.layout.name = Some(name.to_owned());
+        }
+        self
+    }
+    /// Pad this widget inside its parent using the `widget_padding` of the Ui's theme
+    pub fn padding_from_theme(&mut self) -> &mut Self {
+        self.padding_from_theme_for(ThemePadding::Widget)
+    }
+    /// Pad this widget inside its parent using one of the Ui's theme padding values.
+    /// The padding is looked up when the widget is registered with the Ui.
+    pub fn padding_from_theme_for(&mut self, padding: ThemePadding) -> &mut Self {
+        self.widget.widget_mut().theme_padding = Some(padding);
+        self
+    }
+    /// Add constraints built from the Ui's theme, eg. paddings and default sizes that scale with its density.
+    /// `build` is called when the widget is registered, and again when the theme changes, to replace them.
+    pub fn add_theme_constraints<F>(&mut self, build: F) -> &mut Self
+        where F: Fn(&Theme) -> Vec<Box<ConstraintBuilder>> + 'static
+    {
+        self.widget.widget_mut().theme_constraints.push(ThemeConstraints::new(build));
+        self
+    }
+    /// Scale the font size of this text widget with the density of the Ui's theme, from `font_size` at the
+    /// default density, unless a different font size is set on the draw state before the widget is registered
+    pub fn font_size_from_theme(&mut self, font_size: f32) -> &mut Self {
+        self.widget.widget_mut().theme_font_size = Some(font_size);
+        self
+    }
+    /// Expand the area that receives mouse input by `padding` on every side,
+    /// without affecting layout or drawing. Useful for small widgets that are hard to hit.
+    pub fn hit_padding(&mut self, padding: f32) -> &mut Self {
+        self.widget.widget_mut().hit_padding = padding;
+        self
+    }
+    /// Attach application data to the widget, see `WidgetRef::set_user_data`
+    pub fn set_user_data<T: 'static>(&mut self, data: T) -> &mut Self {
+        self.widget.widget_mut().set_user_data(data);
+        self
+    }
+    /// Send the widget a `WidgetResizedEvent` each time its width or height changes,
+    /// eg. to reflow its contents, without handling every `LayoutUpdated`
+    pub fn respond_to_resize(&mut self, respond_to_resize: bool) -> &mut Self {
+        self.widget.widget_mut().tracks_resize = respond_to_resize;
+        self
+    }
+    /// Only send the widget a `WidgetResizedEvent` when its size rounded to whole pixels changes,
+    /// ignoring sub-pixel changes, eg. while the window is dragged, for widgets that reallocate buffers
+    /// the size of their bounds. Turning it on also turns on `respond_to_resize`.
+    pub fn respond_to_pixel_resize(&mut self, whole_pixels: bool) -> &mut Self {
+        {
+            let mut widget = self.widget.widget_mut();
+            widget.tracks_resize |= whole_pixels;
+            widget.resize_whole_pixels = whole_pixels;
+        }
+        self
+    }
+    /// Let the cursor pass through this widget, see `WidgetRef::set_pointer_transparent`
+    pub fn set_pointer_transparent(&mut self, pointer_transparent: bool) -> &mut Self {
+        self.widget.widget_mut().pointer_transparent = pointer_transparent;
+        self
+    }
+    /// Clip the widget's children to a shape, see `WidgetRef::set_clip_shape`
+    pub fn set_clip_shape(&mut self, shape: ClipShape) -> &mut Self {
+        self.widget.widget_mut().clip_shape = Some(shape);
+        self
+    }
+    /// Keep this widget drawn over its siblings and receiving input first, even over siblings added after it,
+    /// eg. for a popup. Siblings that are also kept on top are in the order they were added.
+    pub fn set_keep_on_top(&mut self, keep_on_top: bool) -> &mut Self {
+        self.widget.widget_mut().keep_on_top = keep_on_top;
+        self
+    }
+    /// Only hit the widget's children inside its clip shape, see `WidgetRef::set_clip_hit_testing`
+    pub fn set_clip_hit_testing(&mut self, clip_hit_testing: bool) -> &mut Self {
+        self.widget.widget_mut().clip_hit_testing = clip_hit_testing;
+        self
+    }
+    /// Move focusable widgets with a lower tab index before this one in the tab order, and higher ones after it,
+    /// eg. to tab down the columns of a grid. Widgets with the same index, 0 by default, are in tree order.
+    pub fn tab_index(&mut self, tab_index: i32) -> &mut Self {
+        self.widget.widget_mut().tab_index = tab_index;
+        self
+    }
+    /// Start out invisible, still taking up space, until `WidgetRef::set_visible` is called
+    pub fn initially_hidden(&mut self) -> &mut Self {
+        self.widget.widget_mut().visible = false;
+        self
+    }
+    /// Start out hidden and left out of the layout, taking up no space, until the layout is shown
+    pub fn initially_collapsed(&mut self) -> &mut Self {
+        self.layout().hide();
+        self
+    }
+    /// Center this widget in `container` on both axes, with `STRONG` strength
+    pub fn center_in<T: LayoutRef>(&mut self, container: &T) -> &mut Self {
+        let constraints = self.layout_ref().center_in(&container.layout_ref());
+        self.layout().add(constraints);
+        self
+    }
+    /// Center this widget horizontally in `container`, with `STRONG` strength
+    pub fn center_horizontally_in<T: LayoutRef>(&mut self, container: &T) -> &mut Self {
+        let constraints = self.layout_ref().center_horizontally_in(&container.layout_ref());
+        self.layout().add(constraints);
+        self
+    }
+    /// Center this widget vertically in `container`, with `STRONG` strength
+    pub fn center_vertically_in<T: LayoutRef>(&mut self, container: &T) -> &mut Self {
+        let constraints = self.layout_ref().center_vertically_in(&container.layout_ref());
+        self.layout().add(constraints);
         self
     }
 }
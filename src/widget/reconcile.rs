@@ -0,0 +1,244 @@
+//! Update a subtree of widgets to match a description of what it should contain, a tree of `WidgetSpec`s,
+//! changing only what's different, eg. to rebuild the view of state kept outside of the widgets
+//! each time that state changes, without losing the state of the widgets themselves.
+
+use std::collections::HashMap;
+
+use widget::{WidgetBuilder, WidgetRef};
+
+/// Identifies the widget a spec describes, between one reconciliation and the next
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct SpecId {
+    kind: String,
+    key: Option<String>,
+}
+
+/// A description of a widget and its children. Specs are matched with the widgets built from them
+/// by their kind and key, see `reconcile`, widgets that match are kept and have their props applied again.
+pub struct WidgetSpec {
+    pub kind: String,
+    pub key: Option<String>,
+    pub children: Vec<WidgetSpec>,
+    build: Box<Fn() -> WidgetBuilder>,
+    props: Option<Box<Fn(&mut WidgetRef)>>,
+}
+
+impl WidgetSpec {
+    /// A spec for widgets built by `build`, `kind` should be different for widgets built in different ways,
+    /// so a widget is never reused for a spec it wasn't built from
+    pub fn new<F>(kind: &str, build: F) -> Self
+        where F: Fn() -> WidgetBuilder + 'static
+    {
+        WidgetSpec {
+            kind: kind.to_owned(),
+            key: None,
+            children: Vec::new(),
+            build: Box::new(build),
+            props: None,
+        }
+    }
+    /// Identify the widget among its siblings by `key` instead of by its position, eg. for items in a list
+    /// that can be inserted, removed or reordered. Keys should be unique among the children of a spec.
+    pub fn set_key(&mut self, key: &str) -> &mut Self {
+        self.key = Some(key.to_owned());
+        self
+    }
+    /// Apply the spec's props to the widget, eg. by updating it's draw state,
+    /// called when the widget is built, and each time it's matched with this spec
+    pub fn set_props<F>(&mut self, props: F) -> &mut Self
+        where F: Fn(&mut WidgetRef) + 'static
+    {
+        self.props = Some(Box::new(props));
+        self
+    }
+    pub fn add_child(&mut self, child: WidgetSpec) -> &mut Self {
+        self.children.push(child);
+        self
+    }
+    fn id(&self) -> SpecId {
+        SpecId {
+            kind: self.kind.clone(),
+            key: self.key.clone(),
+        }
+    }
+    fn build(&self) -> WidgetRef {
+        let mut widget: WidgetRef = (self.build)().into();
+        widget.widget_mut().spec_id = Some(self.id());
+        reconcile(&mut widget, self);
+        widget
+    }
+}
+
+// a change to the children of a widget, to match them with a list of specs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ChildChange {
+    // remove the child at this index in the children before any changes
+    Remove(usize),
+    // move the child at index `from` in the children before any changes,
+    // to index `to` in the children as they are once it's removed
+    Move { from: usize, to: usize },
+    // build a new child from the spec at index `spec`, and insert it at index `to`
+    Insert { spec: usize, to: usize },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ChildDiff {
+    // for each spec, the index of the child it matches, if any
+    matches: Vec<Option<usize>>,
+    // the changes that make the children match the specs, in the order they're applied,
+    // the removals first, empty if the children already match
+    changes: Vec<ChildChange>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    Live(usize),
+    New(usize),
+}
+
+// match the ids of the `live` children of a widget with the ids of the `specs` for them, and find the changes
+// that remove, move and insert children so they're in the order of the specs, see `reconcile`
+fn diff_children(live: &[SpecId], specs: &[SpecId]) -> ChildDiff {
+    let mut keyed = HashMap::new();
+    for (index, id) in live.iter().enumerate() {
+        if id.key.is_some() {
+            keyed.entry(id).or_insert(index);
+        }
+    }
+    let mut used = vec![false; live.len()];
+    let matches: Vec<Option<usize>> = specs.iter().enumerate().map(|(index, spec)| {
+        let found = if spec.key.is_some() {
+            keyed.get(spec).cloned()
+        } else if index < live.len() && live[index] == *spec {
+            Some(index)
+        } else {
+            None
+        };
+        match found {
+            Some(found) if !used[found] => {
+                used[found] = true;
+                Some(found)
+            }
+            _ => None,
+        }
+    }).collect();
+
+    let mut changes: Vec<ChildChange> = (0..live.len()).filter(|&index| !used[index]).map(ChildChange::Remove).collect();
+    let mut order: Vec<Slot> = (0..live.len()).filter(|&index| used[index]).map(Slot::Live).collect();
+    let kept: Vec<usize> = matches.iter().filter_map(|matched| *matched).collect();
+    let stays = longest_increasing(&kept);
+    let slot = |index: usize| matches[index].map(Slot::Live).unwrap_or(Slot::New(index));
+    let mut kept_index = 0;
+    for (index, matched) in matches.iter().enumerate() {
+        // everything not staying put is placed right after the previous spec's child
+        let after_previous = |order: &Vec<Slot>| {
+            if index == 0 {
+                0
+            } else {
+                order.iter().position(|other| *other == slot(index - 1)).unwrap() + 1
+            }
+        };
+        match *matched {
+            Some(from) => {
+                kept_index += 1;
+                if !stays[kept_index - 1] {
+                    order.retain(|other| *other != Slot::Live(from));
+                    let to = after_previous(&order);
+                    order.insert(to, Slot::Live(from));
+                    changes.push(ChildChange::Move { from: from, to: to });
+                }
+            }
+            None => {
+                let to = after_previous(&order);
+                order.insert(to, Slot::New(index));
+                changes.push(ChildChange::Insert { spec: index, to: to });
+            }
+        }
+    }
+    ChildDiff {
+        matches: matches,
+        changes: changes,
+    }
+}
+
+// which elements of `seq` are part of a longest increasing subsequence
+fn longest_increasing(seq: &[usize]) -> Vec<bool> {
+    // the last element of the best subsequence of each length, and the element before each element in its subsequence
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = Vec::with_capacity(seq.len());
+    for index in 0..seq.len() {
+        let length = match tails.binary_search_by(|&tail| seq[tail].cmp(&seq[index])) {
+            Ok(length) | Err(length) => length,
+        };
+        previous.push(if length > 0 { Some(tails[length - 1]) } else { None });
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+    let mut members = vec![false; seq.len()];
+    let mut next = tails.last().cloned();
+    while let Some(index) = next {
+        members[index] = true;
+        next = previous[index];
+    }
+    members
+}
+
+/// Update `widget` and its descendants to match `spec`: apply the spec's props to the widget,
+/// then match its children with the spec's children. A keyed spec matches the child with the same kind and key,
+/// wherever it is, and an unkeyed spec matches the child at the same index if it's also unkeyed and the same kind.
+/// Matching children are kept and reconciled with their specs, the others are removed, and new children are built
+/// for specs without one. Children that are already in order relative to each other aren't moved,
+/// so inserting or removing one child only changes that child.
+/// Children that weren't built from a spec, like the parts of a widget added by its builder, are left alone.
+pub fn reconcile(widget: &mut WidgetRef, spec: &WidgetSpec) {
+    if let Some(ref props) = spec.props {
+        props(widget);
+    }
+    let live: Vec<WidgetRef> = widget.children().into_iter().filter(|child| child.widget().spec_id.is_some()).collect();
+    let live_ids: Vec<SpecId> = live.iter().map(|child| child.widget().spec_id.clone().unwrap()).collect();
+    let spec_ids: Vec<SpecId> = spec.children.iter().map(|child| child.id()).collect();
+    let diff = diff_children(&live_ids, &spec_ids);
+
+    // the children built from specs, in their current order
+    let mut order = live.clone();
+    for change in &diff.changes {
+        match *change {
+            ChildChange::Remove(index) => {
+                order.retain(|other| *other != live[index]);
+                widget.remove_child(live[index].clone());
+            }
+            ChildChange::Move { from, to } => {
+                let child = &live[from];
+                order.retain(|other| other != child);
+                let index = child_index(widget, &order, to, Some(child));
+                order.insert(to, child.clone());
+                widget.move_child(child, index);
+            }
+            ChildChange::Insert { spec: spec_index, to } => {
+                let child = spec.children[spec_index].build();
+                let index = child_index(widget, &order, to, None);
+                order.insert(to, child.clone());
+                widget.insert_child(index, child);
+            }
+        }
+    }
+    for (child_spec, matched) in spec.children.iter().zip(diff.matches) {
+        if let Some(index) = matched {
+            reconcile(&mut live[index].clone(), child_spec);
+        }
+    }
+}
+
+// the index in all of the widget's children, except `moving`, of position `to` among the children built from specs
+fn child_index(widget: &WidgetRef, order: &[WidgetRef], to: usize, moving: Option<&WidgetRef>) -> usize {
+    let children: Vec<WidgetRef> = widget.children().into_iter().filter(|child| Some(child) != moving).collect();
+    let index_of = |child: &WidgetRef| children.iter().position(|other| other == child);
+    if to < order.len() {
+        index_of(&order[to]).unwrap_or(children.len())
+    } else {
+        order.last().and_then(|last| index_of(last)).map(|index| index + 1).unwrap_or(children.len())
+    }
+}
@@ -0,0 +1,149 @@
+use text_layout::Align;
+use webrender_api::{ImageRendering, LayoutSize, PrimitiveInfo};
+
+use layout::constraint::*;
+use widget::WidgetBuilder;
+use widget::draw::Draw;
+use draw::ellipse::{clip_ellipse, draw_circle};
+use draw::text::TextState;
+use render::RenderBuilder;
+use resources::resources;
+use geometry::{Rect, RectExt, Point, Size};
+use color::*;
+
+const AVATAR_SIZE: f32 = 40.0;
+/// Size of the initials, as a fraction of the avatar's size
+const INITIALS_SIZE: f32 = 0.4;
+/// Size of the presence indicator, as a fraction of the avatar's size
+const INDICATOR_SIZE: f32 = 0.3;
+const INDICATOR_BORDER: f32 = 2.0;
+
+// the initials of the first two words of `name`, in upper case
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(|initial| initial.to_uppercase())
+        .collect()
+}
+
+// the background color of an avatar without an image, the same name always gets the same color
+fn name_color(name: &str) -> Color {
+    // a fixed hash, so the color doesn't change between runs or builds
+    let hash = name.chars().fold(0u32, |hash, char| hash.wrapping_mul(31).wrapping_add(char as u32));
+    Color::from_hsl((hash % 360) as f32, 0.5, 0.45)
+}
+
+/// A user's picture cropped to a circle, or their initials in a circle if there's no picture,
+/// with an optional presence indicator over the bottom right.
+/// `image` is the name of an image resource, like the file `ImageBuilder` loads.
+pub struct AvatarWidget {
+    pub image: Option<String>,
+    pub name: String,
+    pub size: f32,
+    pub show_indicator: bool,
+    pub indicator_color: Color,
+}
+
+impl Default for AvatarWidget {
+    fn default() -> Self {
+        AvatarWidget {
+            image: None,
+            name: String::new(),
+            size: AVATAR_SIZE,
+            show_indicator: false,
+            indicator_color: GREEN,
+        }
+    }
+}
+
+impl Draw for AvatarWidget {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        let diameter = bounds.width().min(bounds.height());
+        let center = bounds.center();
+        let circle = Rect::new(Point::new(center.x - diameter / 2.0, center.y - diameter / 2.0), Size::new(diameter, diameter));
+        if let Some(ref image) = self.image {
+            let key = resources().get_image(image).key;
+            let info = PrimitiveInfo::with_clip(circle.typed(), clip_ellipse(circle));
+            renderer.builder.push_image(
+                &info,
+                circle.size.typed(),
+                LayoutSize::zero(),
+                ImageRendering::Auto,
+                key,
+            );
+        } else {
+            draw_circle(renderer, center, diameter / 2.0, name_color(&self.name));
+            let mut text = TextState::new(&initials(&self.name));
+            text.font_size = diameter * INITIALS_SIZE;
+            text.text_color = WHITE;
+            text.align = Align::Middle;
+            let text_size = text.measure();
+            let text_bounds = Rect::new(Point::new(center.x - text_size.width / 2.0, center.y - text_size.height / 2.0), text_size);
+            text.draw(text_bounds, crop_to, renderer);
+        }
+        if self.show_indicator {
+            // a ring in the background color around the dot separates it from the avatar
+            let radius = diameter * INDICATOR_SIZE / 2.0;
+            let indicator_center = Point::new(circle.right() - radius, circle.bottom() - radius);
+            draw_circle(renderer, indicator_center, radius, WHITE);
+            draw_circle(renderer, indicator_center, radius - INDICATOR_BORDER, self.indicator_color);
+        }
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        (cursor - bounds.center()).length() <= bounds.width().min(bounds.height()) / 2.0
+    }
+}
+
+/// A square widget the size of the avatar. Change the avatar once it's built with `WidgetRef::update`:
+/// `avatar.update(|state: &mut AvatarWidget| state.show_indicator = true)`
+pub struct AvatarBuilder {
+    pub widget: WidgetBuilder,
+    avatar: AvatarWidget,
+}
+
+impl AvatarBuilder {
+    /// An avatar showing the initials of `name`
+    pub fn new(name: &str) -> Self {
+        let mut avatar = AvatarWidget::default();
+        avatar.name = name.to_owned();
+        AvatarBuilder::with_avatar(avatar)
+    }
+    /// An avatar showing the image resource `image`, cropped to a circle
+    pub fn from_image(image: &str) -> Self {
+        let mut avatar = AvatarWidget::default();
+        avatar.image = Some(image.to_owned());
+        AvatarBuilder::with_avatar(avatar)
+    }
+    fn with_avatar(avatar: AvatarWidget) -> Self {
+        AvatarBuilder {
+            widget: WidgetBuilder::new("avatar"),
+            avatar: avatar,
+        }
+    }
+    /// The name the initials are taken from, if there's no image
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.avatar.name = name.to_owned();
+        self
+    }
+    pub fn set_size(&mut self, size: f32) -> &mut Self {
+        self.avatar.size = size;
+        self
+    }
+    /// Show a presence indicator dot in `color`
+    pub fn set_indicator(&mut self, color: Color) -> &mut Self {
+        self.avatar.show_indicator = true;
+        self.avatar.indicator_color = color;
+        self
+    }
+}
+
+widget_builder!(AvatarBuilder);
+impl Into<WidgetBuilder> for AvatarBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let avatar_size = self.avatar.size;
+        self.widget.layout().add(size(Size::new(avatar_size, avatar_size)));
+        self.widget.set_draw_state(self.avatar);
+        self.widget
+    }
+}
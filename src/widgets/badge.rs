@@ -0,0 +1,138 @@
+use text_layout::Align;
+
+use layout::constraint::*;
+use widget::WidgetBuilder;
+use widget::draw::Draw;
+use draw::ellipse::draw_circle;
+use draw::text::TextState;
+use render::RenderBuilder;
+use resources::WidgetId;
+use ui::WidgetAttachedEvent;
+use geometry::{Rect, RectExt, Point, Size};
+use color::*;
+
+const BADGE_DIAMETER: f32 = 20.0;
+const BADGE_FONT_SIZE: f32 = 11.0;
+
+/// A count drawn as a number in a small circle, nothing is drawn while the count is zero.
+/// Change the count with `WidgetRef::update`, which redraws the badge:
+/// `badge.update(|state: &mut BadgeWidget| state.set_count(3))`
+pub struct BadgeWidget {
+    pub count: u32,
+    /// Larger counts are shown as this number followed by a plus
+    pub max_display: u32,
+    pub color: Color,
+    pub text_color: Color,
+}
+
+impl Default for BadgeWidget {
+    fn default() -> Self {
+        BadgeWidget {
+            count: 0,
+            max_display: 99,
+            color: RED,
+            text_color: WHITE,
+        }
+    }
+}
+
+impl BadgeWidget {
+    pub fn set_count(&mut self, count: u32) {
+        self.count = count;
+    }
+    /// The text shown in the badge, empty while the count is zero
+    pub fn label(&self) -> String {
+        if self.count == 0 {
+            String::new()
+        } else if self.count > self.max_display {
+            format!("{}+", self.max_display)
+        } else {
+            self.count.to_string()
+        }
+    }
+}
+
+impl Draw for BadgeWidget {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        if self.count == 0 {
+            return;
+        }
+        let radius = bounds.width().min(bounds.height()) / 2.0;
+        draw_circle(renderer, bounds.center(), radius, self.color);
+
+        let mut text = TextState::new(&self.label());
+        text.font_size = BADGE_FONT_SIZE;
+        text.text_color = self.text_color;
+        text.align = Align::Middle;
+        let text_size = text.measure();
+        let center = bounds.center();
+        let text_bounds = Rect::new(Point::new(center.x - text_size.width / 2.0, center.y - text_size.height / 2.0), text_size);
+        text.draw(text_bounds, crop_to, renderer);
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        self.count > 0 && (cursor - bounds.center()).length() <= bounds.width().min(bounds.height()) / 2.0
+    }
+}
+
+/// A notification count over the top right corner of another widget, the target.
+/// Add the badge after the target, somewhere it can extend past the target's corner,
+/// like the target's parent or the root, so it's drawn over the target.
+pub struct BadgeBuilder {
+    pub widget: WidgetBuilder,
+    badge: BadgeWidget,
+    target: Option<WidgetId>,
+}
+
+impl BadgeBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("badge");
+        widget.layout().add(size(Size::new(BADGE_DIAMETER, BADGE_DIAMETER)));
+        BadgeBuilder {
+            widget: widget,
+            badge: BadgeWidget::default(),
+            target: None,
+        }
+    }
+    /// The widget the badge is placed over, it has to be attached before the badge
+    pub fn target(&mut self, target: WidgetId) -> &mut Self {
+        self.target = Some(target);
+        self
+    }
+    pub fn set_count(&mut self, count: u32) -> &mut Self {
+        self.badge.count = count;
+        self
+    }
+    pub fn set_max_display(&mut self, max_display: u32) -> &mut Self {
+        self.badge.max_display = max_display;
+        self
+    }
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.badge.color = color;
+        self
+    }
+}
+
+widget_builder!(BadgeBuilder);
+impl Into<WidgetBuilder> for BadgeBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        if let Some(target) = self.target {
+            // the target's layout is only known to the ui, once the badge is attached
+            self.widget.add_handler_fn(move |_: &WidgetAttachedEvent, args| {
+                if let Some(target) = args.ui.get_widget(target) {
+                    // centered on the target's corner
+                    let offset = -BADGE_DIAMETER / 2.0;
+                    args.widget.update_layout(|layout| {
+                        layout.add(constraints![
+                            align_right(&target).padding(offset),
+                            align_top(&target).padding(offset),
+                        ]);
+                    });
+                } else {
+                    warn!("badge target {:?} isn't attached", target);
+                }
+            });
+        }
+        self.widget.set_draw_state(self.badge);
+        self.widget
+    }
+}
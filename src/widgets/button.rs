@@ -1,18 +1,26 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::time::Duration;
+
 use glutin;
 
 use text_layout::Align;
 use cassowary::strength::*;
 
 use layout::constraint::*;
-use event::EventArgs;
-use widget::WidgetBuilder;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef, WidgetWeak};
 use widget::property::Property;
 use widget::property::states::*;
 use widgets::text::TextBuilder;
+use widgets::edit_text::{TextUpdated, text_change_handle};
+use widgets::drag::{DragEvent, DragState};
 use input::mouse::{WidgetMouseButton, ClickEvent};
+use input::keyboard::{WidgetKeyboardInput, is_activation_key};
 use draw::rect::{RectState, RectStyle};
-use draw::text::TextStyle;
-use geometry::Size;
+use draw::text::{TextState, TextStyle};
+use geometry::{Size, Point, RectExt};
+use ui::AnimationFrame;
 use color::*;
 
 static COLOR_BUTTON_DEFAULT: Color = GRAY_80;
@@ -44,6 +52,24 @@ lazy_static! {
     };
 }
 
+// space beside and above and below the label of a button, at the default density
+const LABEL_PADDING_HORIZONTAL: f32 = 20.0;
+const LABEL_PADDING_VERTICAL: f32 = 10.0;
+
+// pad `label` inside `button` by the default label padding, scaled by the density of the theme
+fn add_label_padding(label: &mut WidgetBuilder, button: &WidgetBuilder) {
+    let button = button.widget_ref().layout_vars();
+    label.add_theme_constraints(move |theme| {
+        let (horizontal, vertical) = (theme.scale(LABEL_PADDING_HORIZONTAL), theme.scale(LABEL_PADDING_VERTICAL));
+        constraints![
+            bound_left(&button).padding(horizontal),
+            bound_right(&button).padding(horizontal),
+            bound_top(&button).padding(vertical),
+            bound_bottom(&button).padding(vertical),
+        ]
+    });
+}
+
 // show whether button is held down or not
 fn button_handle_mouse_down(event: &WidgetMouseButton, mut args: EventArgs) {
     if !args.widget.props().contains(&Property::Inactive) {
@@ -55,28 +81,75 @@ fn button_handle_mouse_down(event: &WidgetMouseButton, mut args: EventArgs) {
     }
 }
 
-pub enum ToggleEvent {
-    On,
-    Off,
+// Space or Enter click a focused push button
+fn push_button_handle_keys(event: &WidgetKeyboardInput, args: EventArgs) {
+    if is_activation_key(event) && !args.widget.props().contains(&Property::Inactive) {
+        let bounds = args.widget.bounds();
+        args.widget.event(ClickEvent {
+            position: bounds.center(),
+            local_position: Point::new(bounds.width() / 2.0, bounds.height() / 2.0),
+        });
+    }
+}
+
+/// Sent by a toggle button when it's turned on or off, with whether it's now on
+#[derive(Clone, Copy, Debug)]
+pub struct ToggleEvent(pub bool);
+
+// turn a toggle button on or off, sends a `ToggleEvent` if that changes it
+struct SetToggled(bool);
+// the toggle button was clicked, or activated with the keyboard
+struct ToggleButtonActivated;
+
+fn toggle_button_set_toggled(event: &SetToggled, mut args: EventArgs) {
+    let &SetToggled(toggled) = event;
+    if args.widget.props().contains(&Property::Activated) == toggled {
+        return;
+    }
+    if toggled {
+        args.widget.add_prop(Property::Activated);
+    } else {
+        args.widget.remove_prop(Property::Activated);
+    }
+    args.widget.event(ToggleEvent(toggled));
+}
+
+/// A set of toggle buttons where only one can be on at a time, turning one on turns the others off.
+/// Clicking the button that's on leaves it on.
+#[derive(Clone)]
+pub struct ButtonGroup {
+    // weak, the buttons' handlers hold the group
+    buttons: Rc<RefCell<Vec<WidgetWeak>>>,
 }
-// show whether toggle button is activated
-fn toggle_button_handle_mouse(event: &WidgetMouseButton, mut args: EventArgs) {
-    if let WidgetMouseButton(glutin::ElementState::Released, _) = *event {
-        let activated = args.widget.props().contains(&Property::Activated);
-        if activated {
-            args.widget.event(ToggleEvent::Off);
-            args.widget.remove_prop(Property::Activated);
-        } else {
-            args.widget.event(ToggleEvent::On);
-            args.widget.add_prop(Property::Activated);
+impl ButtonGroup {
+    pub fn new() -> Self {
+        ButtonGroup {
+            buttons: Rc::new(RefCell::new(Vec::new())),
         }
     }
+    /// Index of the button that's on, in the order the buttons that haven't been dropped were added to the group
+    pub fn toggled_index(&self) -> Option<usize> {
+        self.buttons().iter().position(|button| button.props().contains(&Property::Activated))
+    }
+    fn buttons(&self) -> Vec<WidgetRef> {
+        self.buttons.borrow().iter().filter_map(WidgetWeak::upgrade).collect()
+    }
+    fn add_button(&self, button: &WidgetRef) {
+        let mut buttons = self.buttons.borrow_mut();
+        buttons.retain(|button| button.upgrade().is_some());
+        buttons.push(button.downgrade());
+    }
 }
 
+/// A button that stays on or off when clicked, shown with the `ACTIVATED` style when it's on
 pub struct ToggleButtonBuilder {
     pub widget: WidgetBuilder,
+    toggled: bool,
+    label_on: Option<String>,
+    label_off: Option<String>,
+    group: Option<ButtonGroup>,
 }
-widget_wrapper!(ToggleButtonBuilder);
+widget_builder!(ToggleButtonBuilder);
 
 impl ToggleButtonBuilder {
     pub fn new() -> Self {
@@ -84,41 +157,155 @@ impl ToggleButtonBuilder {
         widget
             .set_draw_state_with_style(RectState::new(), STYLE_BUTTON.clone())
             .add_handler_fn(button_handle_mouse_down)
-            .add_handler_fn(toggle_button_handle_mouse);
-        widget.layout().add(constraints![
-            min_size(Size::new(70.0, 30.0)),
-            shrink(),
-        ]);
+            .add_handler_fn(toggle_button_set_toggled)
+            .make_tab_focusable();
+        widget.layout().add(shrink());
+        widget.add_theme_constraints(|theme| {
+            constraints![min_size(Size::new(theme.scale(70.0), theme.scale(30.0)))]
+        });
 
-        ToggleButtonBuilder { widget: widget }
+        ToggleButtonBuilder {
+            widget: widget,
+            toggled: false,
+            label_on: None,
+            label_off: None,
+            group: None,
+        }
     }
-    pub fn set_text(&mut self, on_text: &'static str, off_text: &'static str) -> &mut Self {
-
-        let style = style!(parent: STYLE_BUTTON_TEXT,
-            TextStyle::Text: selector!(off_text.to_owned(),
-                ACTIVATED: on_text.to_owned()),
-            TextStyle::Align: Align::Middle);
-        let mut button_text_widget = TextBuilder::new_with_style(style);
-        button_text_widget.set_name("button_text");
-        button_text_widget.layout().add(constraints![
-            bound_left(&self.widget).padding(20.0),
-            bound_right(&self.widget).padding(20.0),
-            bound_top(&self.widget).padding(10.0),
-            bound_bottom(&self.widget).padding(10.0),
-            center(&self.widget),
-        ]);
-
-        self.widget.add_child(button_text_widget);
+    pub fn set_text(&mut self, on_text: &str, off_text: &str) -> &mut Self {
+        self.label_when_on(on_text).label_when_off(off_text)
+    }
+    /// The label shown while the button is on, if there's no label for when it's off, it's used for both
+    pub fn label_when_on(&mut self, label: &str) -> &mut Self {
+        self.label_on = Some(label.to_owned());
+        self
+    }
+    /// The label shown while the button is off, if there's no label for when it's on, it's used for both
+    pub fn label_when_off(&mut self, label: &str) -> &mut Self {
+        self.label_off = Some(label.to_owned());
+        self
+    }
+    /// Start with the button on, without sending a `ToggleEvent`
+    pub fn initially_toggled(&mut self, toggled: bool) -> &mut Self {
+        self.toggled = toggled;
+        self
+    }
+    pub fn set_group(&mut self, group: &ButtonGroup) -> &mut Self {
+        self.group = Some(group.clone());
         self
     }
     pub fn on_toggle<F>(&mut self, callback: F) -> &mut Self
-        where F: Fn(&ToggleEvent, EventArgs) + 'static
+        where F: Fn(bool, EventArgs) + 'static
     {
-        self.widget.add_handler_fn(callback);
+        self.widget.add_handler_fn(move |event: &ToggleEvent, args| {
+            callback(event.0, args);
+        });
         self
     }
 }
 
+impl Into<WidgetBuilder> for ToggleButtonBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let in_group = self.group.is_some();
+        self.widget.add_handler_fn(|event: &WidgetMouseButton, args| {
+            if let WidgetMouseButton(glutin::ElementState::Released, _) = *event {
+                args.widget.event(ToggleButtonActivated);
+            }
+        });
+        self.widget.add_default_handler_fn(|event: &WidgetKeyboardInput, args| {
+            if is_activation_key(event) && !args.widget.props().contains(&Property::Inactive) {
+                args.widget.event(ToggleButtonActivated);
+            }
+        });
+        self.widget.add_handler_fn(move |_: &ToggleButtonActivated, args| {
+            let toggled = args.widget.props().contains(&Property::Activated);
+            // a button in a group is only turned off by turning another one on
+            if !(in_group && toggled) {
+                args.widget.event(SetToggled(!toggled));
+            }
+        });
+        if let Some(group) = self.group {
+            group.add_button(&self.widget.widget_ref());
+            self.widget.add_handler_fn(move |event: &ToggleEvent, args| {
+                if event.0 {
+                    for button in group.buttons() {
+                        if button != args.widget {
+                            button.event(SetToggled(false));
+                        }
+                    }
+                }
+            });
+        }
+
+        if self.label_on.is_some() || self.label_off.is_some() {
+            let label_on = self.label_on.clone().or(self.label_off.clone()).unwrap();
+            let label_off = self.label_off.or(self.label_on).unwrap();
+            let style = style!(parent: STYLE_BUTTON_TEXT,
+                TextStyle::Text: selector!(label_off,
+                    ACTIVATED: label_on),
+                TextStyle::Align: Align::Middle);
+            let mut button_text_widget = TextBuilder::new_with_style(style);
+            button_text_widget.set_name("button_text");
+            button_text_widget.layout().add(center(&self.widget));
+            add_label_padding(&mut button_text_widget, &self.widget);
+            self.widget.add_child(button_text_widget);
+        }
+        // added after the label, so it's styled as on too
+        if self.toggled {
+            self.widget.add_prop(Property::Activated);
+        }
+        self.widget
+    }
+}
+
+// time a button is held before it starts repeating, and between repeats
+const HOLD_REPEAT_DELAY_MS: u64 = 400;
+const HOLD_REPEAT_INTERVAL_MS: u64 = 50;
+
+/// Sent by a button with repeat on hold enabled when it's pressed, then repeatedly
+/// while it stays held down, see `PushButtonBuilder::enable_repeat_on_hold`
+#[derive(Clone, Copy, Debug)]
+pub struct HoldRepeatEvent;
+
+enum HoldRepeatInput {
+    Press,
+    Release,
+    Frame(Duration),
+}
+
+struct HoldRepeatHandler {
+    // time left until the next repeat, while the button is held
+    until_repeat: Option<Duration>,
+}
+impl EventHandler<HoldRepeatInput> for HoldRepeatHandler {
+    fn handle(&mut self, event: &HoldRepeatInput, args: EventArgs) {
+        match *event {
+            HoldRepeatInput::Press => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                args.widget.event(HoldRepeatEvent);
+                self.until_repeat = Some(Duration::from_millis(HOLD_REPEAT_DELAY_MS));
+                args.ui.start_animation(args.widget.id());
+            }
+            HoldRepeatInput::Release => {
+                self.until_repeat = None;
+                args.ui.stop_animation(args.widget.id());
+            }
+            HoldRepeatInput::Frame(elapsed) => {
+                if let Some(until_repeat) = self.until_repeat {
+                    if elapsed >= until_repeat {
+                        args.widget.event(HoldRepeatEvent);
+                        self.until_repeat = Some(Duration::from_millis(HOLD_REPEAT_INTERVAL_MS));
+                    } else {
+                        self.until_repeat = Some(until_repeat - elapsed);
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub struct PushButtonBuilder {
     pub widget: WidgetBuilder,
 }
@@ -129,15 +316,91 @@ impl PushButtonBuilder {
         let mut widget = WidgetBuilder::new("push_button");
         widget
             .set_draw_state_with_style(RectState::new(), STYLE_BUTTON.clone())
-            .add_handler_fn(button_handle_mouse_down);
+            .add_handler_fn(button_handle_mouse_down)
+            .add_default_handler_fn(push_button_handle_keys)
+            .make_tab_focusable();
 
-        widget.layout().add(constraints![
-            min_size(Size::new(100.0, 50.0)).strength(STRONG),
-            shrink(),
-        ]);
+        widget.layout().add(shrink());
+        widget.add_theme_constraints(|theme| {
+            constraints![min_size(Size::new(theme.scale(100.0), theme.scale(50.0))).strength(STRONG)]
+        });
 
         PushButtonBuilder { widget: widget }
     }
+    /// Create a button sized to fit `label`, with the default padding around the text,
+    /// scaled by the density of the theme
+    pub fn with_label(label: &str) -> Self {
+        Self::label_button(label, None)
+    }
+    /// Create a button sized to fit `label`, with `padding` on each side of the text.
+    /// The button resizes if its label is changed by sending it `TextUpdated`.
+    pub fn with_label_padding(label: &str, padding: Size) -> Self {
+        Self::label_button(label, Some(padding))
+    }
+    fn label_button(label: &str, padding: Option<Size>) -> Self {
+        let mut widget = WidgetBuilder::new("push_button");
+        widget
+            .set_draw_state_with_style(RectState::new(), STYLE_BUTTON.clone())
+            .add_handler_fn(button_handle_mouse_down)
+            .add_default_handler_fn(push_button_handle_keys)
+            .make_tab_focusable();
+        widget.layout().add(shrink());
+
+        // the label is kept in the draw state rather than the style,
+        // so restyling the button doesn't undo a `TextUpdated`
+        let style = style!(parent: STYLE_BUTTON_TEXT, TextStyle::Align: Align::Middle);
+        let mut button_text_widget = TextBuilder::new(label);
+        button_text_widget
+            .set_name("button_text")
+            .set_draw_state_with_style(TextState::new(label), style)
+            .add_handler_fn(text_change_handle);
+        button_text_widget.layout().add(center(&widget));
+        match padding {
+            Some(padding) => button_text_widget.layout().add(constraints![
+                bound_left(&widget).padding(padding.width),
+                bound_right(&widget).padding(padding.width),
+                bound_top(&widget).padding(padding.height),
+                bound_bottom(&widget).padding(padding.height),
+            ]),
+            None => add_label_padding(&mut button_text_widget, &widget),
+        }
+
+        let text_ref = button_text_widget.widget_ref();
+        widget.add_handler_fn(move |event: &TextUpdated, _| {
+            text_ref.event(TextUpdated(event.0.clone()));
+        });
+        widget.add_child(button_text_widget);
+        PushButtonBuilder { widget: widget }
+    }
+    /// Send `HoldRepeatEvent` when the button is pressed, and repeatedly while it's held,
+    /// for buttons that step a value, like the buttons of a spin box.
+    /// Space or Enter also send it instead of clicking the button, repeating with the key.
+    pub fn enable_repeat_on_hold(&mut self) -> &mut Self {
+        self.widget
+            .make_draggable()
+            .add_handler(HoldRepeatHandler { until_repeat: None })
+            .add_handler_fn(|event: &DragEvent, args| {
+                // the drag ends when the mouse is released, even outside the button
+                match event.state {
+                    DragState::Start if event.button == glutin::MouseButton::Left => args.widget.event(HoldRepeatInput::Press),
+                    DragState::End | DragState::Cancel => args.widget.event(HoldRepeatInput::Release),
+                    _ => (),
+                }
+            })
+            .add_handler_fn(|event: &AnimationFrame, args| {
+                args.widget.event(HoldRepeatInput::Frame(event.0));
+            })
+            .add_handler_fn(|event: &WidgetKeyboardInput, args| {
+                if is_activation_key(event) {
+                    if !args.widget.props().contains(&Property::Inactive) {
+                        args.widget.event(HoldRepeatEvent);
+                    }
+                    // replaces the default click
+                    *args.handled = true;
+                }
+            });
+        self
+    }
     pub fn set_text(&mut self, text: &'static str) -> &mut Self {
 
         let style = style!(parent: STYLE_BUTTON_TEXT,
@@ -146,13 +409,8 @@ impl PushButtonBuilder {
 
         let mut button_text_widget = TextBuilder::new_with_style(style);
         button_text_widget.set_name("button_text");
-        button_text_widget.layout().add(constraints![
-            bound_left(&self.widget).padding(20.0),
-            bound_right(&self.widget).padding(20.0),
-            bound_top(&self.widget).padding(10.0),
-            bound_bottom(&self.widget).padding(10.0),
-            center(&self.widget),
-        ]);
+        button_text_widget.layout().add(center(&self.widget));
+        add_label_padding(&mut button_text_widget, &self.widget);
 
         self.widget.add_child(button_text_widget);
         self
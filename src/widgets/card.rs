@@ -0,0 +1,146 @@
+use cassowary::strength::*;
+
+use layout::{Layout, LayoutContainer};
+use layout::constraint::*;
+use widget::WidgetBuilder;
+use widget::draw::Draw;
+use draw::rect::RectState;
+use render::RenderBuilder;
+use geometry::{Rect, RectExt, Point, Size, Vector};
+use color::*;
+
+const DEFAULT_ELEVATION: u8 = 1;
+const DEFAULT_CORNER_RADIUS: f32 = 4.0;
+const DEFAULT_PADDING: f32 = 8.0;
+
+// how far the shadow of a card with `elevation` extends past its right and bottom edges
+fn shadow_extent(elevation: u8) -> f32 {
+    3.0 * elevation as f32
+}
+
+// the part of a card's bounds covered by the card itself, the rest is left for the shadow
+fn card_surface(bounds: Rect, elevation: u8) -> Rect {
+    let extent = shadow_extent(elevation);
+    Rect::new(bounds.origin, Size::new((bounds.width() - extent).max(0.0), (bounds.height() - extent).max(0.0)))
+}
+
+/// A raised surface with rounded corners, casting a shadow down and to the right,
+/// which is larger and further from the card the higher its elevation.
+/// The shadow is drawn inside the widget's bounds, along its right and bottom edges.
+pub struct CardWidget {
+    pub elevation: u8,
+    pub background_color: Color,
+    pub corner_radius: f32,
+}
+
+impl Default for CardWidget {
+    fn default() -> Self {
+        CardWidget {
+            elevation: DEFAULT_ELEVATION,
+            background_color: WHITE,
+            corner_radius: DEFAULT_CORNER_RADIUS,
+        }
+    }
+}
+
+impl Draw for CardWidget {
+    fn draw(&mut self, bounds: Rect, crop_to: Rect, renderer: &mut RenderBuilder) {
+        let surface = card_surface(bounds, self.elevation);
+        if self.elevation > 0 {
+            // there's no blur, a larger translucent rect offset from the card stands in for a soft shadow
+            let elevation = self.elevation as f32;
+            let shadow = surface.inflate(elevation, elevation).translate(&Vector::new(2.0 * elevation, 2.0 * elevation));
+            let mut shadow_rect = RectState {
+                background_color: SHADOW,
+                corner_radius: Some(self.corner_radius + elevation),
+                border: None,
+            };
+            shadow_rect.draw(shadow, crop_to, renderer);
+        }
+        let mut surface_rect = RectState {
+            background_color: self.background_color,
+            corner_radius: Some(self.corner_radius),
+            border: None,
+        };
+        surface_rect.draw(surface, crop_to, renderer);
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        card_surface(bounds, self.elevation).contains(&cursor)
+    }
+}
+
+// fills the card's surface with its children, inset by the padding
+struct CardContainer {
+    padding: f32,
+    shadow: f32,
+}
+
+impl LayoutContainer for CardContainer {
+    fn add_child(&mut self, parent: &mut Layout, child: &mut Layout) {
+        let (padding, far_padding) = (self.padding, self.padding + self.shadow);
+        child.add(constraints![
+            bound_left(&parent).padding(padding),
+            bound_top(&parent).padding(padding),
+            bound_right(&parent).padding(far_padding),
+            bound_bottom(&parent).padding(far_padding),
+            align_left(&parent).padding(padding).strength(STRONG),
+            align_top(&parent).padding(padding).strength(STRONG),
+            align_right(&parent).padding(far_padding).strength(STRONG),
+            align_bottom(&parent).padding(far_padding).strength(STRONG),
+        ]);
+    }
+}
+
+/// A card containing other widgets, which are placed inside the card, inset by its padding.
+/// Set the elevation and padding before adding children, children already added keep their old placement.
+pub struct CardBuilder {
+    pub widget: WidgetBuilder,
+    card: CardWidget,
+    padding: f32,
+}
+
+impl CardBuilder {
+    pub fn new() -> Self {
+        let mut builder = CardBuilder {
+            widget: WidgetBuilder::new("card"),
+            card: CardWidget::default(),
+            padding: DEFAULT_PADDING,
+        };
+        builder.update_container();
+        builder
+    }
+    pub fn elevation(&mut self, elevation: u8) -> &mut Self {
+        self.card.elevation = elevation;
+        self.update_container();
+        self
+    }
+    /// Space between the edges of the card and its children
+    pub fn padding(&mut self, padding: f32) -> &mut Self {
+        self.padding = padding;
+        self.update_container();
+        self
+    }
+    pub fn set_background_color(&mut self, color: Color) -> &mut Self {
+        self.card.background_color = color;
+        self
+    }
+    pub fn set_corner_radius(&mut self, corner_radius: f32) -> &mut Self {
+        self.card.corner_radius = corner_radius;
+        self
+    }
+    fn update_container(&mut self) {
+        let container = CardContainer {
+            padding: self.padding,
+            shadow: shadow_extent(self.card.elevation),
+        };
+        self.widget.layout().set_container(container);
+    }
+}
+
+widget_builder!(CardBuilder);
+impl Into<WidgetBuilder> for CardBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        self.widget.set_draw_state(self.card);
+        self.widget
+    }
+}
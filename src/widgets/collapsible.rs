@@ -0,0 +1,197 @@
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use input::mouse::ClickEvent;
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use color::*;
+
+static COLOR_PANEL_HEADER: Color = GRAY_80;
+static COLOR_PANEL_HEADER_MOUSEOVER: Color = GRAY_70;
+
+lazy_static! {
+    pub static ref STYLE_PANEL_HEADER: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(COLOR_PANEL_HEADER,
+            MOUSEOVER: COLOR_PANEL_HEADER_MOUSEOVER))
+    };
+    pub static ref STYLE_PANEL_HEADER_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: BLACK)
+    };
+}
+
+/// Sent to a collapsible panel to show or hide its body
+pub enum CollapsiblePanelEvent {
+    Expand,
+    Collapse,
+    Toggle,
+}
+
+/// Sent by a collapsible panel after its body is shown or hidden, true if it is now expanded
+pub struct PanelExpanded(pub bool);
+
+struct CollapsiblePanelHandler {
+    body: WidgetRef,
+    expanded: bool,
+}
+impl EventHandler<CollapsiblePanelEvent> for CollapsiblePanelHandler {
+    fn handle(&mut self, event: &CollapsiblePanelEvent, args: EventArgs) {
+        let expanded = match *event {
+            CollapsiblePanelEvent::Expand => true,
+            CollapsiblePanelEvent::Collapse => false,
+            CollapsiblePanelEvent::Toggle => !self.expanded,
+        };
+        if expanded != self.expanded {
+            // hiding the body removes it from the layout, so the panel shrinks to the header
+            self.body.update_layout(|layout| {
+                if expanded {
+                    layout.show();
+                } else {
+                    layout.hide();
+                }
+            });
+            self.expanded = expanded;
+            args.widget.event(PanelExpanded(expanded));
+        }
+    }
+}
+
+/// A panel with a clickable header that shows or hides the panel's body
+pub struct CollapsiblePanelBuilder {
+    pub widget: WidgetBuilder,
+    header: WidgetBuilder,
+    body: WidgetBuilder,
+    expanded: bool,
+}
+
+impl CollapsiblePanelBuilder {
+    pub fn new(title: &str) -> Self {
+        let mut widget = WidgetBuilder::new("collapsible_panel");
+        widget.layout().no_container();
+        widget.layout().add(shrink_vertical());
+
+        let mut header = WidgetBuilder::new("collapsible_panel_header");
+        header
+            .set_draw_state_with_style(RectState::new(), STYLE_PANEL_HEADER.clone())
+            .enable_hover();
+        let style = style!(parent: STYLE_PANEL_HEADER_TEXT, TextStyle::Text: title.to_owned());
+        let mut title_widget = TextBuilder::new_with_style(style);
+        title_widget.set_name("collapsible_panel_title");
+        title_widget.layout().add(constraints![
+            align_left(&header).padding(5.0),
+            bound_top(&header).padding(5.0),
+            bound_bottom(&header).padding(5.0),
+        ]);
+        header.add_child(title_widget);
+
+        let body = WidgetBuilder::new("collapsible_panel_body");
+
+        CollapsiblePanelBuilder {
+            widget: widget,
+            header: header,
+            body: body,
+            expanded: true,
+        }
+    }
+    /// Add a widget to the body of the panel
+    pub fn add_content<C: Into<WidgetBuilder>>(&mut self, content: C) -> &mut Self {
+        self.body.add_child(content.into());
+        self
+    }
+    /// Set whether the panel starts out expanded, defaults to true
+    pub fn set_expanded(&mut self, expanded: bool) -> &mut Self {
+        self.expanded = expanded;
+        self
+    }
+    pub fn on_toggle<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&PanelExpanded, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
+
+widget_builder!(CollapsiblePanelBuilder);
+impl Into<WidgetBuilder> for CollapsiblePanelBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let mut header = self.header;
+        let mut body = self.body;
+        header.layout().add(constraints![
+            align_top(&self.widget),
+            align_left(&self.widget),
+            align_right(&self.widget),
+            bound_bottom(&self.widget),
+        ]);
+        body.layout().add(constraints![
+            align_below(&header),
+            align_left(&self.widget),
+            align_right(&self.widget),
+            align_bottom(&self.widget),
+        ]);
+        if !self.expanded {
+            body.layout().hide();
+        }
+
+        let widget_ref = self.widget.widget_ref();
+        header.add_handler_fn(move |_: &ClickEvent, args| {
+            widget_ref.event(CollapsiblePanelEvent::Toggle);
+            *args.handled = true;
+        });
+        self.widget.add_handler(CollapsiblePanelHandler {
+            body: body.widget_ref(),
+            expanded: self.expanded,
+        });
+
+        self.widget.add_child(header);
+        self.widget.add_child(body);
+        self.widget
+    }
+}
+
+struct AccordionPanelExpanded(WidgetRef);
+
+struct AccordionHandler {
+    expanded: Option<WidgetRef>,
+}
+impl EventHandler<AccordionPanelExpanded> for AccordionHandler {
+    fn handle(&mut self, event: &AccordionPanelExpanded, _: EventArgs) {
+        let &AccordionPanelExpanded(ref panel) = event;
+        if let Some(ref expanded) = self.expanded {
+            if expanded != panel {
+                expanded.event(CollapsiblePanelEvent::Collapse);
+            }
+        }
+        self.expanded = Some(panel.clone());
+    }
+}
+
+/// A vertical stack of collapsible panels, where expanding one panel collapses the others
+pub struct AccordionBuilder {
+    pub widget: WidgetBuilder,
+}
+widget_wrapper!(AccordionBuilder);
+
+impl AccordionBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("accordion");
+        widget
+            .add_handler(AccordionHandler { expanded: None })
+            .linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+        AccordionBuilder {
+            widget: widget,
+        }
+    }
+    pub fn add_panel(&mut self, mut panel: CollapsiblePanelBuilder) -> &mut Self {
+        let accordion_ref = self.widget.widget_ref();
+        panel.on_toggle(move |event: &PanelExpanded, args| {
+            if event.0 {
+                accordion_ref.event(AccordionPanelExpanded(args.widget));
+            }
+        });
+        panel.widget.layout().add(match_width(&self.widget));
+        self.widget.add_child(panel);
+        self
+    }
+}
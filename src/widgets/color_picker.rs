@@ -0,0 +1,330 @@
+use std::f32::consts::PI;
+
+use glutin;
+use webrender_api::{PrimitiveInfo, ImageKey, ImageRendering, LayoutSize};
+
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::draw::Draw;
+use widgets::drag::{DragEvent, DragState};
+use widgets::edit_text::{EditTextBuilder, TextUpdated};
+use widgets::palette::ColorChangedEvent;
+use draw::ellipse::draw_circle;
+use draw::text::TextState;
+use draw::wedge::{clockwise_angle, point_inside_wedge};
+use input::keyboard::WidgetKeyboardInput;
+use render::RenderBuilder;
+use resources::resources;
+use layout::constraint::*;
+use geometry::{Rect, RectExt, Point, Size, Vector};
+use color::*;
+
+const WHEEL_SIZE: f32 = 160.0;
+const SQUARE_SIZE: f32 = 110.0;
+/// Radius of the hole in the hue wheel, as a fraction of its outer radius
+const WHEEL_INNER_RADIUS: f32 = 0.75;
+/// Name of the image the hue wheel is drawn with, see `Resources::add_image`
+const HUE_WHEEL_IMAGE: &str = "color_picker_hue_wheel";
+/// Number of pixels in each row and column of the hue wheel image, scaled to the wheel's bounds
+const HUE_WHEEL_PIXELS: u32 = 320;
+/// Number of cells in each row and column of the saturation/value square
+const SQUARE_CELLS: usize = 24;
+const MARKER_RADIUS: f32 = 5.0;
+
+// a white ring filled with the picked color, marking the picked point
+fn draw_marker(renderer: &mut RenderBuilder, center: Point, color: Color) {
+    draw_circle(renderer, center, MARKER_RADIUS, WHITE);
+    draw_circle(renderer, center, MARKER_RADIUS - 2.0, color);
+}
+
+/// A ring of every hue, clockwise from red at the top, with a marker at the picked hue
+pub struct HueWheelState {
+    pub hue: f32,
+}
+
+impl HueWheelState {
+    fn radii(bounds: Rect) -> (f32, f32) {
+        let outer = bounds.width().min(bounds.height()) / 2.0;
+        (outer * WHEEL_INNER_RADIUS, outer)
+    }
+}
+
+// the RGBA pixels of the hue wheel, transparent outside the ring, with edges blended over a pixel
+fn hue_wheel_pixels() -> Vec<u8> {
+    let size = HUE_WHEEL_PIXELS as f32;
+    let bounds = Rect::new(Point::zero(), Size::new(size, size));
+    let (inner_radius, outer_radius) = HueWheelState::radii(bounds);
+    let mut pixels = Vec::with_capacity((HUE_WHEEL_PIXELS * HUE_WHEEL_PIXELS * 4) as usize);
+    for row in 0..HUE_WHEEL_PIXELS {
+        for column in 0..HUE_WHEEL_PIXELS {
+            let offset = Point::new(column as f32 + 0.5, row as f32 + 0.5) - bounds.center();
+            let distance = offset.length();
+            let coverage = (outer_radius - distance + 0.5).min(distance - inner_radius + 0.5).max(0.0).min(1.0);
+            let (red, green, blue, _) = Color::from_hsv(clockwise_angle(offset).to_degrees(), 1.0, 1.0).to_rgba();
+            pixels.extend_from_slice(&[red, green, blue, (coverage * 255.0).round() as u8]);
+        }
+    }
+    pixels
+}
+
+// the hue wheel image, generated the first time it's drawn
+fn hue_wheel_image() -> ImageKey {
+    let mut res = resources();
+    if !res.images.contains_key(HUE_WHEEL_IMAGE) {
+        res.add_image(HUE_WHEEL_IMAGE, HUE_WHEEL_PIXELS, HUE_WHEEL_PIXELS, hue_wheel_pixels());
+    }
+    res.get_image(HUE_WHEEL_IMAGE).key
+}
+
+impl Draw for HueWheelState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        // there's no conic gradient primitive, so the ring is drawn as an image
+        let (wheel_size, center) = (bounds.width().min(bounds.height()), bounds.center());
+        let wheel = Rect::new(Point::new(center.x - wheel_size / 2.0, center.y - wheel_size / 2.0), Size::new(wheel_size, wheel_size));
+        let info = PrimitiveInfo::new(wheel.typed());
+        renderer.builder.push_image(
+            &info,
+            wheel.size.typed(),
+            LayoutSize::zero(),
+            ImageRendering::Auto,
+            hue_wheel_image(),
+        );
+        let (inner_radius, outer_radius) = HueWheelState::radii(bounds);
+        let angle = self.hue.to_radians();
+        let offset = Vector::new(angle.sin(), -angle.cos()) * ((inner_radius + outer_radius) / 2.0);
+        draw_marker(renderer, bounds.center() + offset, Color::from_hsv(self.hue, 1.0, 1.0));
+    }
+    fn is_under_cursor(&self, bounds: Rect, cursor: Point) -> bool {
+        let (inner_radius, outer_radius) = HueWheelState::radii(bounds);
+        point_inside_wedge(cursor - bounds.center(), inner_radius, outer_radius, 0.0, 2.0 * PI)
+    }
+}
+
+/// Every saturation, increasing to the right, and value, increasing upwards, of one hue,
+/// with a marker at the picked saturation and value
+pub struct SaturationValueState {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl Draw for SaturationValueState {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        // drawn as a grid of cells, each the color at its center
+        let cell_size = Size::new(bounds.width() / SQUARE_CELLS as f32, bounds.height() / SQUARE_CELLS as f32);
+        for row in 0..SQUARE_CELLS {
+            for column in 0..SQUARE_CELLS {
+                let saturation = (column as f32 + 0.5) / SQUARE_CELLS as f32;
+                let value = 1.0 - (row as f32 + 0.5) / SQUARE_CELLS as f32;
+                let origin = Point::new(bounds.left() + column as f32 * cell_size.width, bounds.top() + row as f32 * cell_size.height);
+                let info = PrimitiveInfo::new(Rect::new(origin, cell_size).typed());
+                renderer.builder.push_rect(&info, Color::from_hsv(self.hue, saturation, value).into());
+            }
+        }
+        let marker = Point::new(bounds.left() + self.saturation * bounds.width(), bounds.top() + (1.0 - self.value) * bounds.height());
+        draw_marker(renderer, marker, Color::from_hsv(self.hue, self.saturation, self.value));
+    }
+}
+
+// hue in degrees of the point `position` on a hue wheel with `bounds`
+fn wheel_hue(bounds: Rect, position: Point) -> f32 {
+    clockwise_angle(position - bounds.center()).to_degrees() % 360.0
+}
+
+// saturation and value of the point `position` on a saturation/value square with `bounds`,
+// clamped to the square
+fn square_saturation_value(bounds: Rect, position: Point) -> (f32, f32) {
+    let clamp = |value: f32| value.max(0.0).min(1.0);
+    let saturation = clamp((position.x - bounds.left()) / bounds.width());
+    let value = clamp(1.0 - (position.y - bounds.top()) / bounds.height());
+    (saturation, value)
+}
+
+// the hue, saturation and value a picker shows for `color`. The hue of grays and the saturation
+// of black can't be recovered from the color, so the previous ones are kept, so picking a gray
+// or black doesn't move the other markers.
+fn picker_hsv(color: Color, previous: (f32, f32, f32)) -> (f32, f32, f32) {
+    let (hue, saturation, value) = color.to_hsv();
+    let (previous_hue, previous_saturation, _) = previous;
+    if value == 0.0 {
+        (previous_hue, previous_saturation, value)
+    } else if saturation == 0.0 {
+        (previous_hue, saturation, value)
+    } else {
+        (hue, saturation, value)
+    }
+}
+
+/// Set the color of a color picker, without sending a `ColorChangedEvent`
+pub struct SetPickerColor(pub Color);
+
+enum ColorPickerInput {
+    Hue(f32),
+    SaturationValue(f32, f32),
+    // the hex text was entered
+    Hex,
+    SetColor(Color),
+}
+
+struct ColorPickerHandler {
+    hsv: (f32, f32, f32),
+    wheel: WidgetRef,
+    square: WidgetRef,
+    hex_text: WidgetRef,
+}
+
+impl ColorPickerHandler {
+    fn color(&self) -> Color {
+        let (hue, saturation, value) = self.hsv;
+        Color::from_hsv(hue, saturation, value)
+    }
+}
+
+impl EventHandler<ColorPickerInput> for ColorPickerHandler {
+    fn handle(&mut self, event: &ColorPickerInput, args: EventArgs) {
+        let old_color = self.color();
+        let text = self.hex_text.drawable::<TextState>().map_or(String::new(), |state| state.text.clone());
+        match *event {
+            ColorPickerInput::Hue(hue) => self.hsv.0 = hue,
+            ColorPickerInput::SaturationValue(saturation, value) => {
+                self.hsv.1 = saturation;
+                self.hsv.2 = value;
+            }
+            // invalid text is replaced with the current color below
+            ColorPickerInput::Hex => if let Ok(color) = Color::from_hex(text.trim()) {
+                self.hsv = picker_hsv(color, self.hsv);
+            },
+            ColorPickerInput::SetColor(color) => self.hsv = picker_hsv(color, self.hsv),
+        }
+        let (hue, saturation, value) = self.hsv;
+        self.wheel.update(|state: &mut HueWheelState| state.hue = hue);
+        self.square.update(|state: &mut SaturationValueState| {
+            state.hue = hue;
+            state.saturation = saturation;
+            state.value = value;
+        });
+        let color = self.color();
+        let hex = color.to_hex();
+        if hex != text {
+            self.hex_text.event(TextUpdated(hex));
+        }
+        if let ColorPickerInput::SetColor(_) = *event {
+            return;
+        }
+        if color != old_color {
+            args.widget.event(ColorChangedEvent(color));
+        }
+    }
+}
+
+/// A hue wheel and a saturation/value square to pick a color with the mouse, and a field to enter it in hex.
+/// Sends a `ColorChangedEvent` as the picked color changes.
+pub struct ColorPickerBuilder {
+    pub widget: WidgetBuilder,
+    pub edit_text: EditTextBuilder,
+    color: Color,
+}
+widget_builder!(ColorPickerBuilder);
+
+impl ColorPickerBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("color_picker");
+        widget.layout().no_container();
+        widget.layout().add(shrink());
+
+        let mut edit_text = EditTextBuilder::new();
+        edit_text
+            .set_single_line(true)
+            .set_max_length(9)
+            .set_input_filter(|char| char == '#' || char.is_digit(16));
+        ColorPickerBuilder {
+            widget: widget,
+            edit_text: edit_text,
+            color: WHITE,
+        }
+    }
+    /// The color picked initially, without sending a `ColorChangedEvent`
+    pub fn initial(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+    pub fn on_color_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ColorChangedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for ColorPickerBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, mut edit_text) = (self.widget, self.edit_text);
+        let picker_ref = widget.widget_ref();
+        let hsv = picker_hsv(self.color, (0.0, 0.0, 0.0));
+        let (hue, saturation, value) = hsv;
+
+        let mut wheel = WidgetBuilder::with_draw_state("color_picker_wheel", HueWheelState { hue: hue });
+        wheel.layout().add(constraints![
+            align_top(&widget),
+            align_left(&widget),
+            size(Size::new(WHEEL_SIZE, WHEEL_SIZE)),
+        ]);
+        let wheel_picker = picker_ref.clone();
+        wheel.make_draggable().add_handler_fn(move |event: &DragEvent, args| {
+            if let DragState::Start | DragState::Moved = event.state {
+                wheel_picker.event(ColorPickerInput::Hue(wheel_hue(args.widget.bounds(), event.position)));
+            }
+        });
+
+        let square_state = SaturationValueState { hue: hue, saturation: saturation, value: value };
+        let mut square = WidgetBuilder::with_draw_state("color_picker_square", square_state);
+        square.layout().add(constraints![
+            align_to_right_of(&wheel).padding(10.0),
+            center_vertical(&wheel),
+            bound_right(&widget),
+            size(Size::new(SQUARE_SIZE, SQUARE_SIZE)),
+        ]);
+        let square_picker = picker_ref.clone();
+        square.make_draggable().add_handler_fn(move |event: &DragEvent, args| {
+            if let DragState::Start | DragState::Moved = event.state {
+                let (saturation, value) = square_saturation_value(args.widget.bounds(), event.position);
+                square_picker.event(ColorPickerInput::SaturationValue(saturation, value));
+            }
+        });
+
+        edit_text.text_widget.set_draw_state(TextState::new(&self.color.to_hex()));
+        edit_text.layout().add(constraints![
+            align_below(&wheel).padding(10.0),
+            align_left(&widget),
+            bound_bottom(&widget),
+            min_width(100.0),
+            min_height(30.0),
+        ]);
+        let hex_picker = picker_ref.clone();
+        edit_text.on_text_committed(move |_, _| {
+            hex_picker.event(ColorPickerInput::Hex);
+        });
+        let hex_picker = picker_ref.clone();
+        edit_text.add_default_handler_fn(move |event: &WidgetKeyboardInput, _| {
+            if let WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::Return)) = *event {
+                hex_picker.event(ColorPickerInput::Hex);
+            }
+        });
+
+        widget.add_handler_fn(|event: &SetPickerColor, args| {
+            args.widget.event(ColorPickerInput::SetColor(event.0));
+        });
+        widget.add_handler(ColorPickerHandler {
+            hsv: hsv,
+            wheel: wheel.widget_ref(),
+            square: square.widget_ref(),
+            hex_text: edit_text.text_widget.widget_ref(),
+        });
+
+        widget
+            .add_child(wheel)
+            .add_child(square)
+            .add_child(edit_text);
+        widget
+    }
+}
@@ -0,0 +1,276 @@
+use std::rc::Rc;
+
+use glutin;
+
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use input::mouse::{ClickEvent, ContextMenuEvent, MouseOverEvent, WidgetMouseButton};
+use input::keyboard::{WidgetKeyboardInput, KeyboardInputEvent};
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation, ItemAlignment};
+use geometry::Point;
+use resources::WidgetId;
+use ui::Ui;
+use color::*;
+
+static COLOR_MENU_ITEM: Color = GRAY_90;
+static COLOR_MENU_ITEM_MOUSEOVER: Color = BLUE_HIGHLIGHT;
+static COLOR_MENU_TEXT_INACTIVE: Color = GRAY_60;
+
+lazy_static! {
+    pub static ref STYLE_MENU_ITEM: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(COLOR_MENU_ITEM,
+            INACTIVE: COLOR_MENU_ITEM,
+            MOUSEOVER: COLOR_MENU_ITEM_MOUSEOVER))
+    };
+    pub static ref STYLE_MENU_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: selector!(BLACK, INACTIVE: COLOR_MENU_TEXT_INACTIVE))
+    };
+}
+
+/// An item of a context menu, disabled items are shown greyed out and can't be selected
+pub struct ContextMenuItem {
+    pub label: String,
+    pub enabled: bool,
+    action: ItemAction,
+}
+
+enum ItemAction {
+    Select(Box<Fn(&mut Ui)>),
+    // builds the items of the submenu each time it opens
+    Submenu(Rc<Fn(&mut ContextMenuBuilder)>),
+}
+
+// close the menu, after running the action of the selected item if any
+struct CloseContextMenu;
+
+// the mouse moved over an item of the panel at `depth`, the top level panel is at depth 0.
+// closes the submenus of the other items of that panel and opens the item's own, if it has one
+struct SubmenuHover {
+    depth: usize,
+    item: WidgetRef,
+    build_menu: Option<Rc<Fn(&mut ContextMenuBuilder)>>,
+}
+
+// the open submenu panels, the panel at depth `n + 1` is `panels[n]`, along with the item that opened it
+struct SubmenuHandler {
+    panels: Vec<(WidgetId, WidgetRef)>,
+    on_item_selected: Option<Rc<Fn(&str, &mut Ui)>>,
+}
+
+impl EventHandler<SubmenuHover> for SubmenuHandler {
+    fn handle(&mut self, event: &SubmenuHover, mut args: EventArgs) {
+        let SubmenuHover { depth, ref item, ref build_menu } = *event;
+        if self.panels.get(depth).map_or(false, |&(opened_by, _)| opened_by == item.id()) {
+            return;
+        }
+        while self.panels.len() > depth {
+            let (_, panel) = self.panels.pop().unwrap();
+            args.widget.remove_child(panel);
+        }
+        if let Some(ref build_menu) = *build_menu {
+            let mut submenu = ContextMenuBuilder::new();
+            build_menu(&mut submenu);
+            let mut panel = build_panel(&args.widget, submenu.items, depth + 1, self.on_item_selected.clone());
+            panel.layout().add(constraints![
+                align_to_right_of(item),
+                align_top(item),
+            ]);
+            self.panels.push((item.id(), panel.widget_ref()));
+            args.widget.add_child(panel);
+        }
+    }
+}
+
+fn build_panel(menu_ref: &WidgetRef, items: Vec<ContextMenuItem>, depth: usize, on_item_selected: Option<Rc<Fn(&str, &mut Ui)>>) -> WidgetBuilder {
+    let mut panel = WidgetBuilder::new("context_menu_panel");
+    panel.set_draw_state(RectState { background_color: COLOR_MENU_ITEM, border: Some((1.0, GRAY_60)), ..RectState::default() });
+    panel.add_theme_constraints(|theme| constraints![min_width(theme.scale(120.0))]);
+    let mut layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
+    layout_settings.item_align = ItemAlignment::Fill;
+    panel.linear_layout(layout_settings);
+    // presses on the items shouldn't reach the menu and close it
+    panel.add_handler_fn(|_: &WidgetMouseButton, args| {
+        *args.handled = true;
+    });
+
+    for item in items {
+        let mut item_widget = WidgetBuilder::with_draw_state_and_style("context_menu_item",
+            RectState::new(), STYLE_MENU_ITEM.clone());
+        let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: item.label.clone());
+        let mut label = TextBuilder::new_with_style(style);
+        label.set_name("context_menu_label");
+        let item_vars = item_widget.widget_ref().layout_vars();
+        let label_vars = label.widget_ref().layout_vars();
+        label.add_theme_constraints(move |theme| constraints![
+            bound_by(&item_vars).padding(theme.scale(5.0)),
+            align_left(&item_vars).padding(theme.scale(10.0)),
+        ]);
+        item_widget.add_child(label);
+        let build_menu = match item.action {
+            ItemAction::Submenu(ref build_menu) => {
+                let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: ">".to_owned());
+                let mut arrow = TextBuilder::new_with_style(style);
+                arrow.set_name("context_menu_arrow");
+                let item_vars = item_widget.widget_ref().layout_vars();
+                arrow.add_theme_constraints(move |theme| constraints![
+                    bound_by(&item_vars).padding(theme.scale(5.0)),
+                    align_right(&item_vars).padding(theme.scale(10.0)),
+                    align_to_right_of(&label_vars).padding(theme.scale(20.0)),
+                ]);
+                item_widget.add_child(arrow);
+                Some(build_menu.clone())
+            }
+            ItemAction::Select(_) => None,
+        };
+        let hover_menu_ref = menu_ref.clone();
+        let hover_build_menu = if item.enabled { build_menu } else { None };
+        item_widget.add_handler_fn(move |event: &MouseOverEvent, args| {
+            if let MouseOverEvent::Over = *event {
+                hover_menu_ref.event(SubmenuHover {
+                    depth: depth,
+                    item: args.widget.clone(),
+                    build_menu: hover_build_menu.clone(),
+                });
+            }
+        });
+        if item.enabled {
+            item_widget.enable_hover();
+            if let ItemAction::Select(on_select) = item.action {
+                let menu_ref = menu_ref.clone();
+                let label = item.label;
+                let on_item_selected = on_item_selected.clone();
+                item_widget.add_handler_fn(move |_: &ClickEvent, args| {
+                    on_select(args.ui);
+                    if let Some(ref on_item_selected) = on_item_selected {
+                        on_item_selected(&label, args.ui);
+                    }
+                    menu_ref.event(CloseContextMenu);
+                    *args.handled = true;
+                });
+            } else {
+                // a submenu opens on hover, clicking it shouldn't close the menu
+                item_widget.add_handler_fn(|_: &ClickEvent, args| {
+                    *args.handled = true;
+                });
+            }
+        } else {
+            item_widget.add_prop(Property::Inactive);
+        }
+        panel.add_child(item_widget);
+    }
+    panel
+}
+
+/// A list of actions, usually opened at the cursor in response to a `ContextMenuEvent`.
+/// Items can open submenus of more actions, to the right of the item, when the mouse moves over them.
+/// The menu closes when an item is selected, or it's dismissed by pressing outside of it or escape.
+pub struct ContextMenuBuilder {
+    pub widget: WidgetBuilder,
+    items: Vec<ContextMenuItem>,
+    on_item_selected: Option<Rc<Fn(&str, &mut Ui)>>,
+}
+
+impl ContextMenuBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("context_menu");
+        // covers the whole window, so a press anywhere outside the menu closes it
+        widget.set_draw_state(RectState { background_color: TRANSPARENT, ..RectState::default() });
+        widget.layout().no_container();
+        ContextMenuBuilder {
+            widget: widget,
+            items: Vec::new(),
+            on_item_selected: None,
+        }
+    }
+    /// Add an item that calls `on_select` when it's selected
+    pub fn add_item<F>(&mut self, label: &str, enabled: bool, on_select: F) -> &mut Self
+        where F: Fn(&mut Ui) + 'static
+    {
+        self.items.push(ContextMenuItem {
+            label: label.to_owned(),
+            enabled: enabled,
+            action: ItemAction::Select(Box::new(on_select)),
+        });
+        self
+    }
+    /// Add an item that opens a submenu, `build_menu` adds the submenu's items each time it opens
+    pub fn add_submenu<F>(&mut self, label: &str, enabled: bool, build_menu: F) -> &mut Self
+        where F: Fn(&mut ContextMenuBuilder) + 'static
+    {
+        self.items.push(ContextMenuItem {
+            label: label.to_owned(),
+            enabled: enabled,
+            action: ItemAction::Submenu(Rc::new(build_menu)),
+        });
+        self
+    }
+    /// Call `callback` with the label of the selected item, after the item's own action,
+    /// including items in submenus
+    pub fn on_item_selected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&str, &mut Ui) + 'static
+    {
+        self.on_item_selected = Some(Rc::new(callback));
+        self
+    }
+    /// The items added so far, to reorder, disable or remove some of them
+    pub fn items_mut(&mut self) -> &mut Vec<ContextMenuItem> {
+        &mut self.items
+    }
+    /// Show the menu with its top left corner at `position`, above every other widget
+    pub fn open(mut self, ui: &mut Ui, position: Point) -> WidgetRef {
+        let menu_ref = self.widget.widget_ref();
+
+        let mut panel = build_panel(&menu_ref, self.items, 0, self.on_item_selected.clone());
+        panel.layout().add(top_left(position));
+
+        self.widget.add_handler(SubmenuHandler {
+            panels: Vec::new(),
+            on_item_selected: self.on_item_selected,
+        });
+        self.widget.add_handler_fn(|_: &CloseContextMenu, args| {
+            args.ui.get_root().event(KeyboardInputEvent::FocusChange(None));
+            if let Some(mut parent) = args.widget.parent() {
+                parent.remove_child(args.widget.clone());
+            }
+        });
+        self.widget.add_handler_fn(|event: &WidgetMouseButton, args| {
+            if let WidgetMouseButton(glutin::ElementState::Pressed, _) = *event {
+                args.widget.event(CloseContextMenu);
+            }
+        });
+        self.widget.add_handler_fn(|event: &WidgetKeyboardInput, args| {
+            if let WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::Escape)) = *event {
+                args.widget.event(CloseContextMenu);
+            }
+        });
+        self.widget.add_child(panel);
+
+        // the last child of the root is drawn over everything else
+        ui.get_root().add_child(self.widget);
+        ui.get_root().event(KeyboardInputEvent::FocusChange(Some(menu_ref.clone())));
+        menu_ref
+    }
+}
+
+impl WidgetBuilder {
+    /// Open a context menu when this widget, or a child that doesn't have its own, is right clicked.
+    /// `build_menu` is called with the widget each time the menu opens, so items can be enabled based on its current state.
+    pub fn set_context_menu<F>(&mut self, build_menu: F) -> &mut Self
+        where F: Fn(&mut ContextMenuBuilder, &WidgetRef) + 'static
+    {
+        self.add_handler_fn(move |event: &ContextMenuEvent, args| {
+            let mut menu = ContextMenuBuilder::new();
+            build_menu(&mut menu, &args.widget);
+            if !menu.items.is_empty() {
+                menu.open(args.ui, event.position);
+            }
+            *args.handled = true;
+        })
+    }
+}
@@ -3,17 +3,23 @@ use glutin;
 use event::{EventHandler, EventArgs};
 use widget::{WidgetBuilder, WidgetRef};
 use input::mouse::{MouseMoved, MouseButton, WidgetMouseButton};
+use input::keyboard::KeyboardInput;
 use geometry::{Point, Vector};
 use app::App;
 
 #[derive(Debug, Clone)]
 pub struct DragEvent {
     pub state: DragState,
-    /// mouse position
+    /// the mouse button that started the drag
+    pub button: glutin::MouseButton,
+    /// mouse position in window coordinates
     pub position: Point,
+    /// mouse position relative to the top left of the dragged widget
+    pub local_position: Point,
     /// offset from drag start
     pub offset: Vector,
-    /// change since last DragEvent
+    /// change since last DragEvent, or when the drag is cancelled, the change back to where it started,
+    /// the negative of `offset`, so adding up the changes of a cancelled drag comes to nothing
     pub change: Vector,
 }
 
@@ -22,10 +28,13 @@ pub enum DragState {
     Start,
     Moved,
     End,
+    /// The drag was cancelled by pressing escape, the widget should go back to how it was at the start
+    Cancel,
 }
 
 struct DragInputHandler {
     widget: Option<WidgetRef>,
+    button: glutin::MouseButton,
     position: Point,
     start_position: Point,
     prev_position: Point,
@@ -35,15 +44,18 @@ impl DragInputHandler {
     pub fn new() -> Self {
         DragInputHandler {
             widget: None,
+            button: glutin::MouseButton::Left,
             position: Point::new(0.0, 0.0),
             start_position: Point::new(0.0, 0.0),
             prev_position: Point::new(0.0, 0.0),
         }
     }
-    pub fn drag_event(&self, state: DragState) -> DragEvent {
+    pub fn drag_event(&self, widget: &WidgetRef, state: DragState) -> DragEvent {
         DragEvent {
             state: state,
+            button: self.button,
             position: self.position,
+            local_position: widget.local_point(self.position),
             offset: self.position - self.start_position,
             change: self.position - self.prev_position,
         }
@@ -53,21 +65,34 @@ impl DragInputHandler {
 impl EventHandler<DragInputEvent> for DragInputHandler {
     fn handle(&mut self, event: &DragInputEvent, _: EventArgs) {
         match *event {
-            DragInputEvent::WidgetPressed(ref widget) => {
+            DragInputEvent::WidgetPressed(ref widget, button) => {
                 self.widget = Some(widget.clone());
+                self.button = button;
                 self.start_position = self.position;
-                widget.event(self.drag_event(DragState::Start));
+                widget.event(self.drag_event(widget, DragState::Start));
             }
             DragInputEvent::MouseMoved(point) => {
                 self.prev_position = self.position;
                 self.position = point;
                 if let Some(ref widget) = self.widget {
-                    widget.event(self.drag_event(DragState::Moved));
+                    widget.event(self.drag_event(widget, DragState::Moved));
                 }
             }
-            DragInputEvent::MouseReleased => {
+            DragInputEvent::MouseReleased(button) => {
+                // releasing another button doesn't end the drag
+                if button != self.button {
+                    return;
+                }
+                if let Some(widget) = self.widget.take() {
+                    widget.event(self.drag_event(&widget, DragState::End));
+                }
+            }
+            DragInputEvent::Cancel => {
+                // the release that follows is ignored, since there's no drag anymore
                 if let Some(widget) = self.widget.take() {
-                    widget.event(self.drag_event(DragState::End));
+                    let mut event = self.drag_event(&widget, DragState::Cancel);
+                    event.change = -event.offset;
+                    widget.event(event);
                 }
             }
         }
@@ -75,22 +100,35 @@ impl EventHandler<DragInputEvent> for DragInputHandler {
 }
 
 enum DragInputEvent {
-    WidgetPressed(WidgetRef),
+    WidgetPressed(WidgetRef, glutin::MouseButton),
     MouseMoved(Point),
-    MouseReleased,
+    MouseReleased(glutin::MouseButton),
+    Cancel,
 }
 
 impl WidgetBuilder {
     /// Make a widget receive drag events.
     pub fn make_draggable(&mut self) -> &mut Self {
         self.add_handler_fn(|event: &WidgetMouseButton, args| {
-            if let &WidgetMouseButton(glutin::ElementState::Pressed, _) = event {
-                let event = DragInputEvent::WidgetPressed(args.widget);
+            if let &WidgetMouseButton(glutin::ElementState::Pressed, button) = event {
+                let event = DragInputEvent::WidgetPressed(args.widget, button);
                 args.ui.event(event);
             }
         });
         self
     }
+    /// Make a widget receive drag events, only for drags with `button`
+    pub fn make_draggable_with(&mut self, drag_button: glutin::MouseButton) -> &mut Self {
+        self.add_handler_fn(move |event: &WidgetMouseButton, args| {
+            if let &WidgetMouseButton(glutin::ElementState::Pressed, button) = event {
+                if button == drag_button {
+                    let event = DragInputEvent::WidgetPressed(args.widget, button);
+                    args.ui.event(event);
+                }
+            }
+        });
+        self
+    }
 }
 
 impl App {
@@ -104,8 +142,13 @@ impl App {
             args.ui.event(DragInputEvent::MouseMoved(event.0));
         });
         self.add_handler_fn(|event: &MouseButton, args| {
-            if let &MouseButton(glutin::ElementState::Released, _) = event {
-                args.ui.event(DragInputEvent::MouseReleased);
+            if let &MouseButton(glutin::ElementState::Released, button) = event {
+                args.ui.event(DragInputEvent::MouseReleased(button));
+            }
+        });
+        self.add_handler_fn(|event: &KeyboardInput, args| {
+            if let &KeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::Escape)) = event {
+                args.ui.event(DragInputEvent::Cancel);
             }
         });
     }
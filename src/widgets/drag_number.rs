@@ -0,0 +1,143 @@
+use glutin;
+
+use event::{EventHandler, EventArgs};
+use widget::WidgetBuilder;
+use widget::property::Property;
+use widgets::spin_box::SpinBoxWidget;
+use widgets::text::TextBuilder;
+use input::mouse::{WidgetMouseButton, RelativeMotionEvent, PointerUnlocked};
+use draw::text::TextState;
+
+// the value of a drag number that's been dragged `moved` units to the right, or to the left if negative,
+// since it was `start`, it changes by one step for each whole unit, and is clamped to the range
+fn dragged_value(number: &SpinBoxWidget, start: f64, moved: f32) -> f64 {
+    number.clamp(start + moved.round() as f64 * number.step)
+}
+
+/// Sent from a drag number when its value changes
+#[derive(Debug, Clone)]
+pub struct DragNumberValueChanged(pub f64);
+
+enum DragNumberInputEvent {
+    Pressed,
+    Released,
+    Moved(f32),
+    Unlocked,
+}
+
+/// A number shown as a label, that's adjusted by pressing on it and dragging left or right, like the fields of 3D tools.
+/// The pointer is locked while it's dragged, see `Ui::lock_pointer`, so the cursor is hidden and the drag isn't stopped
+/// by the edge of the screen. Pressing escape stops the drag, keeping the value it was dragged to.
+pub struct DragNumberBuilder {
+    pub widget: WidgetBuilder,
+    number: SpinBoxWidget,
+}
+
+impl DragNumberBuilder {
+    pub fn new() -> Self {
+        DragNumberBuilder {
+            widget: TextBuilder::new(""),
+            number: SpinBoxWidget::default(),
+        }
+    }
+    pub fn range(&mut self, min: f64, max: f64) -> &mut Self {
+        self.number.min = min;
+        self.number.max = max;
+        self
+    }
+    /// The change in value for each unit the mouse moves
+    pub fn step(&mut self, step: f64) -> &mut Self {
+        self.number.step = step;
+        self
+    }
+    pub fn initial(&mut self, value: f64) -> &mut Self {
+        self.number.value = value;
+        self
+    }
+    pub fn set_format(&mut self, format: fn(f64) -> String) -> &mut Self {
+        self.number.format = format;
+        self
+    }
+    pub fn on_value_changed<F>(&mut self, on_value_changed: F) -> &mut Self
+        where F: Fn(f64, &mut EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(move |event: &DragNumberValueChanged, mut args| {
+            on_value_changed(event.0, &mut args);
+        });
+        self
+    }
+}
+
+widget_builder!(DragNumberBuilder);
+impl Into<WidgetBuilder> for DragNumberBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, mut number) = (self.widget, self.number);
+        number.value = number.clamp(number.value);
+        widget.set_name("drag_number");
+        widget.set_draw_state(TextState::new(&number.text()));
+        widget.add_handler_fn(|event: &WidgetMouseButton, args| {
+            match *event {
+                WidgetMouseButton(glutin::ElementState::Pressed, glutin::MouseButton::Left) => {
+                    args.widget.event(DragNumberInputEvent::Pressed);
+                    *args.handled = true;
+                }
+                WidgetMouseButton(glutin::ElementState::Released, glutin::MouseButton::Left) => {
+                    args.widget.event(DragNumberInputEvent::Released);
+                    *args.handled = true;
+                }
+                _ => (),
+            }
+        });
+        widget.add_handler_fn(|event: &RelativeMotionEvent, args| {
+            args.widget.event(DragNumberInputEvent::Moved(event.dx));
+        });
+        widget.add_handler_fn(|_: &PointerUnlocked, args| {
+            args.widget.event(DragNumberInputEvent::Unlocked);
+        });
+        widget.add_handler(DragNumberHandler {
+            number: number,
+            drag: None,
+        });
+        widget
+    }
+}
+
+struct DragNumberHandler {
+    number: SpinBoxWidget,
+    // the value when the drag started, and how far the mouse has moved since, while it's dragged
+    drag: Option<(f64, f32)>,
+}
+impl EventHandler<DragNumberInputEvent> for DragNumberHandler {
+    fn handle(&mut self, event: &DragNumberInputEvent, mut args: EventArgs) {
+        match *event {
+            DragNumberInputEvent::Pressed => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                self.drag = Some((self.number.value, 0.0));
+                args.ui.lock_pointer(args.widget.id());
+            }
+            DragNumberInputEvent::Released => {
+                self.drag = None;
+                if args.ui.pointer_lock() == Some(args.widget.id()) {
+                    args.ui.unlock_pointer();
+                }
+            }
+            DragNumberInputEvent::Unlocked => {
+                self.drag = None;
+            }
+            DragNumberInputEvent::Moved(dx) => {
+                if let Some((start, ref mut moved)) = self.drag {
+                    *moved += dx;
+                    let value = dragged_value(&self.number, start, *moved);
+                    if value != self.number.value {
+                        self.number.value = value;
+                        let text = self.number.text();
+                        args.widget.update(|state: &mut TextState| state.text = text);
+                        args.widget.event(DragNumberValueChanged(value));
+                    }
+                }
+            }
+        }
+    }
+}
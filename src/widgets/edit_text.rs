@@ -1,54 +1,686 @@
+use std::cmp;
+use std::collections::{VecDeque, HashSet};
+use std::time::{Duration, Instant};
+
+use glutin;
+
 use cassowary::Constraint;
 
 use layout::constraint::ConstraintBuilder;
 use layout::constraint::*;
 use widget::style::StyleUpdated;
-use widget::WidgetBuilder;
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::context_menu::ContextMenuBuilder;
 use widget::property::states::*;
-use ui::{WidgetAttachedEvent, WidgetDetachedEvent};
-use input::keyboard::{WidgetReceivedCharacter, KeyboardInputEvent};
+use input::keyboard::{WidgetReceivedCharacter, WidgetKeyboardInput, FocusGained, FocusLost};
 use draw::rect::{RectState, RectStyle};
-use draw::text::TextState;
+use draw::text::{TextState, TextSelectionOverlay, DEFAULT_FONT_SIZE, previous_word_start, next_word_end, word_at, index_at_x};
 use event::{EventHandler, EventArgs};
+use layout::LayoutUpdated;
+use resources::{clipboard_text, set_clipboard_text};
+use theme::ThemePadding;
+use widgets::drag::{DragEvent, DragState};
+use widgets::text::TextBuilder;
+use widgets::validator::{TextInputValidator, ValidationResult};
+use geometry::{Rect, Point};
+use ui::TimerEvent;
 use color::*;
 
 const BACKSPACE: char = '\u{8}';
+// received for Ctrl+V
+const PASTE: char = '\u{16}';
+// received for Ctrl+Z and Ctrl+Y
+const UNDO: char = '\u{1a}';
+const REDO: char = '\u{19}';
+// a second press within this long selects whole words
+pub const DOUBLE_CLICK_MILLIS: u64 = 500;
+/// How long the caret is shown, then hidden, while it blinks, unless set with `EditTextBuilder::set_caret_blink_interval`
+pub const CARET_BLINK_MILLIS: u64 = 530;
+/// Font size of the message shown below an edit text with a validator, at the default density of the theme
+const VALIDATION_FONT_SIZE: f32 = 16.0;
+const VALIDATION_SPACING: f32 = 2.0;
+
+/// Insert text into an edit text widget as if it had been typed, eg. when pasting.
+/// Each character is checked against the input filter and max length.
+pub struct InsertText(pub String);
+
+/// Sent to the edit text widget when typed or inserted characters are discarded,
+/// either by the input filter, the max length, or because they don't fit.
+#[derive(Clone, Debug)]
+pub struct InputRejectedEvent(pub String);
 
-fn edit_text_handle_char(event: &WidgetReceivedCharacter, mut args: EventArgs) {
-    let &WidgetReceivedCharacter(char) = event;
-    let text = {
-        let bounds = args.widget.bounds();
-        let draw_state = args.widget.draw_state();
-        let text_draw_state = draw_state.downcast_ref::<TextState>().unwrap();
-        let mut text = text_draw_state.text.clone();
-        match char {
-            BACKSPACE => {
-                text.pop();
-            }
-            _ => {
-                text.push(char);
-                if !text_draw_state.text_fits(&text, bounds) {
-                    text.pop();
+/// Sent to the edit text widget with its text when it loses focus, to validate or store what was entered
+#[derive(Clone, Debug)]
+pub struct TextCommitted(pub String);
+
+/// Sent to the edit text widget each time its text changes, if it has a validator, see `EditTextBuilder::set_validator`
+#[derive(Clone, Debug)]
+pub struct TextValidated(pub ValidationResult);
+
+enum EditTextInputEvent {
+    ReceivedCharacter(char),
+    InsertText(String),
+    Paste,
+    // delete the selection, for the cut menu item
+    DeleteSelection,
+    // the text was set by a `TextUpdated`, from the handler or from elsewhere
+    TextSet(String),
+    KeyboardInput(glutin::ElementState, Option<glutin::VirtualKeyCode>),
+    Drag(DragEvent),
+}
+
+enum CaretBlinkEvent {
+    Focused,
+    Blurred,
+    // the caret moved or the text changed, so it's shown again
+    Reset,
+    // the caret has been shown or hidden for an interval
+    Blink,
+}
+
+// blinks the caret of the selection overlay while the edit text is focused, and hides it otherwise,
+// with a timer for each blink, so the main loop can block in between
+struct CaretBlinkHandler {
+    interval: Option<Duration>,
+    focused: bool,
+    visible: bool,
+}
+impl CaretBlinkHandler {
+    // only updates the overlay when the caret is shown or hidden, so it isn't redrawn every frame
+    fn show(&mut self, args: &mut EventArgs, visible: bool) {
+        if self.visible != visible {
+            self.visible = visible;
+            args.update_drawable(|state: &mut TextSelectionOverlay| state.caret_visible = visible);
+        }
+    }
+    // shows the caret for a whole interval before it's hidden
+    fn restart(&mut self, args: &mut EventArgs) {
+        self.show(args, true);
+        if let Some(interval) = self.interval {
+            args.ui.start_timer(args.widget.id(), interval);
+        }
+    }
+}
+impl EventHandler<CaretBlinkEvent> for CaretBlinkHandler {
+    fn handle(&mut self, event: &CaretBlinkEvent, mut args: EventArgs) {
+        match *event {
+            CaretBlinkEvent::Focused => {
+                self.focused = true;
+                self.restart(&mut args);
+            }
+            CaretBlinkEvent::Blurred => {
+                self.focused = false;
+                self.show(&mut args, false);
+                args.ui.cancel_timer(args.widget.id());
+            }
+            CaretBlinkEvent::Reset => {
+                if self.focused {
+                    self.restart(&mut args);
+                }
+            }
+            CaretBlinkEvent::Blink => {
+                if let (true, Some(interval)) = (self.focused, self.interval) {
+                    let visible = !self.visible;
+                    self.show(&mut args, visible);
+                    args.ui.start_timer(args.widget.id(), interval);
                 }
             }
         }
-        text
+    }
+}
+
+// make multi-line text fit on one line, each line break is replaced by a space
+fn single_line_text(text: &str) -> String {
+    text.replace("\r\n", " ").replace(|char: char| char == '\n' || char == '\r', " ")
+}
+
+fn byte_index(text: &str, char_index: usize) -> usize {
+    text.char_indices().nth(char_index).map_or(text.len(), |(index, _)| index)
+}
+
+// the selected characters as a byte range of `text`, if any are selected
+fn selected_byte_range(text: &str, selection: Option<(usize, usize)>) -> Option<(usize, usize)> {
+    let (start, end) = match selection {
+        Some((start, end)) => (cmp::min(start, end), cmp::max(start, end)),
+        None => return None,
     };
-    args.widget.update(|state: &mut TextState| {
-        state.text = text.clone()
-    });
-    args.widget.event(TextUpdated(text.clone()));
+    let (start, end) = (byte_index(text, start), byte_index(text, end));
+    if start < end { Some((start, end)) } else { None }
+}
+
+// character index of the start of the line containing `index`
+fn line_start(text: &str, index: usize) -> usize {
+    let chars: Vec<char> = text.chars().take(index).collect();
+    chars.iter().rposition(|char| *char == '\n').map_or(0, |newline| newline + 1)
+}
+// character index of the end of the line containing `index`, before the line break
+fn line_end(text: &str, index: usize) -> usize {
+    index + text.chars().skip(index).take_while(|char| *char != '\n').count()
+}
+
+// the character index nearest `point`, relative to the text bounds
+fn index_at_point(glyph_positions: &[f32], line_height: f32, point: Point) -> usize {
+    let target_line = if line_height > 0.0 { (point.y / line_height).max(0.0) as usize } else { 0 };
+    // a line starts where the glyph edges go back to the left
+    let line_starts = (1..glyph_positions.len()).filter(|&index| glyph_positions[index] < glyph_positions[index - 1]);
+    let mut line_starts = Some(0).into_iter().chain(line_starts);
+    match line_starts.nth(target_line) {
+        Some(start) => {
+            let end = line_starts.next().unwrap_or(glyph_positions.len());
+            start + index_at_x(&glyph_positions[start..end], point.x)
+        }
+        None => glyph_positions.len().saturating_sub(1),
+    }
+}
+
+// the text, caret and selection of an edit text while it handles an input event, indices are in characters
+struct TextEdit {
+    text: String,
+    caret: usize,
+    // the end of the selection opposite the caret, if there is a selection
+    anchor: Option<usize>,
+}
+impl TextEdit {
+    fn new(text: &str, caret: usize, selection: Option<(usize, usize)>) -> Self {
+        let length = text.chars().count();
+        let caret = cmp::min(caret, length);
+        // the caret stays at the end of the selection it moved, otherwise the selection was made some other way,
+        // eg. with `SetSelection`, and the caret is put at its end
+        let (caret, anchor) = match selection {
+            Some((start, end)) if caret == start => (start, Some(end)),
+            Some((start, end)) if caret == end => (end, Some(start)),
+            Some((start, end)) => (cmp::max(start, end), Some(cmp::min(start, end))),
+            None => (caret, None),
+        };
+        TextEdit {
+            text: text.to_owned(),
+            caret: cmp::min(caret, length),
+            anchor: anchor.map(|anchor| cmp::min(anchor, length)),
+        }
+    }
+    fn selection(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.caret => Some((cmp::min(anchor, self.caret), cmp::max(anchor, self.caret))),
+            _ => None,
+        }
+    }
+    fn move_caret(&mut self, to: usize, extend_selection: bool) {
+        if extend_selection {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.caret);
+            }
+        } else {
+            self.anchor = None;
+        }
+        self.caret = cmp::min(to, self.text.chars().count());
+    }
+    fn delete(&mut self, start: usize, end: usize) {
+        let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+        let (start_byte, end_byte) = (byte_index(&self.text, start), byte_index(&self.text, end));
+        self.text = format!("{}{}", &self.text[..start_byte], &self.text[end_byte..]);
+        self.caret = start;
+        self.anchor = None;
+    }
+    fn delete_selection(&mut self) -> bool {
+        match self.selection() {
+            Some((start, end)) => {
+                self.delete(start, end);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// a single edit of a text, indices are in characters
+#[derive(Clone, Debug, PartialEq)]
+struct TextChange {
+    start: usize,
+    removed: String,
+    inserted: String,
+    caret_before: usize,
+    caret_after: usize,
+}
+impl TextChange {
+    // the change from `old` to `new`, an edit made at `edit_start` is assumed to not start after it,
+    // so inserting a repeated letter is recorded where it was typed
+    fn between(old: &str, new: &str, edit_start: usize, caret_before: usize, caret_after: usize) -> Self {
+        let (old_chars, new_chars): (Vec<char>, Vec<char>) = (old.chars().collect(), new.chars().collect());
+        let max_prefix = cmp::min(edit_start, cmp::min(old_chars.len(), new_chars.len()));
+        let prefix = old_chars.iter().zip(new_chars.iter()).take(max_prefix).take_while(|&(a, b)| a == b).count();
+        let max_suffix = cmp::min(old_chars.len(), new_chars.len()) - prefix;
+        let suffix = old_chars.iter().rev().zip(new_chars.iter().rev()).take(max_suffix).take_while(|&(a, b)| a == b).count();
+        TextChange {
+            start: prefix,
+            removed: old_chars[prefix..old_chars.len() - suffix].iter().collect(),
+            inserted: new_chars[prefix..new_chars.len() - suffix].iter().collect(),
+            caret_before: caret_before,
+            caret_after: caret_after,
+        }
+    }
+    // replace `from` with `to` at the start of the change
+    fn replace(text: &str, start: usize, from: &str, to: &str) -> String {
+        let start_byte = byte_index(text, start);
+        let end_byte = byte_index(text, start + from.chars().count());
+        format!("{}{}{}", &text[..start_byte], to, &text[end_byte..])
+    }
+}
+
+// the edits made to a text, for undo and redo.
+// characters typed one after another are undone together, up to the end of a word and the whitespace after it
+#[derive(Clone, Debug, Default)]
+struct TextEditHistory {
+    undo: Vec<TextChange>,
+    redo: Vec<TextChange>,
+    // the last change was typed, and more typing can be added to it
+    typing: bool,
+}
+impl TextEditHistory {
+    fn new() -> Self {
+        TextEditHistory::default()
+    }
+    // add a change, `typed` if it's a single typed character, clears the changes that can be redone
+    fn record(&mut self, change: TextChange, typed: bool) {
+        self.redo.clear();
+        let coalesce = typed && self.typing && change.removed.is_empty() && match self.undo.last() {
+            Some(last) => {
+                // a new word starts a new step, the whitespace after a word is part of it
+                let word_started = last.inserted.ends_with(char::is_whitespace) && !change.inserted.starts_with(char::is_whitespace);
+                last.caret_after == change.caret_before && change.start == last.start + last.inserted.chars().count() && !word_started
+            }
+            None => false,
+        };
+        if coalesce {
+            let last = self.undo.last_mut().unwrap();
+            last.inserted.push_str(&change.inserted);
+            last.caret_after = change.caret_after;
+        } else {
+            self.undo.push(change);
+        }
+        self.typing = typed;
+    }
+    // undo the last change made to `text`, returns the text before it and the caret position
+    fn undo(&mut self, text: &str) -> Option<(String, usize)> {
+        self.typing = false;
+        self.undo.pop().map(|change| {
+            let text = TextChange::replace(text, change.start, &change.inserted, &change.removed);
+            let caret = change.caret_before;
+            self.redo.push(change);
+            (text, caret)
+        })
+    }
+    // make the last undone change to `text` again, returns the text after it and the caret position
+    fn redo(&mut self, text: &str) -> Option<(String, usize)> {
+        self.typing = false;
+        self.redo.pop().map(|change| {
+            let text = TextChange::replace(text, change.start, &change.removed, &change.inserted);
+            let caret = change.caret_after;
+            self.undo.push(change);
+            (text, caret)
+        })
+    }
+    fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.typing = false;
+    }
+}
+
+struct EditTextInputHandler {
+    selection_widget: WidgetRef,
+    read_only: bool,
+    single_line: bool,
+    max_length: Option<usize>,
+    input_filter: Option<Box<Fn(char) -> bool>>,
+    caret: usize,
+    last_press: Option<Instant>,
+    // the word a double click drag started on, the selection is extended by whole words from it
+    drag_word: Option<(usize, usize)>,
+    history: TextEditHistory,
+    // texts sent in `TextUpdated` by this handler that haven't come back as `TextSet` yet,
+    // any other text is set from elsewhere, and clears the history
+    sent_texts: VecDeque<String>,
+}
+impl EditTextInputHandler {
+    fn accepts(&self, char: char) -> bool {
+        self.input_filter.as_ref().map_or(true, |filter| filter(char))
+    }
+    // inserts each accepted character of `input` at the caret, replacing the selection,
+    // stopping at the max length, returns the characters that were rejected
+    fn insert(&self, edit: &mut TextEdit, input: &str, text_draw_state: &TextState, bounds: Rect) -> String {
+        if self.read_only || input.is_empty() {
+            return input.to_owned();
+        }
+        edit.delete_selection();
+        let mut rejected = String::new();
+        let mut length = edit.text.chars().count();
+        for char in input.chars() {
+            let full = self.max_length.map_or(false, |max_length| length >= max_length);
+            if full || !self.accepts(char) {
+                rejected.push(char);
+                continue;
+            }
+            let index = byte_index(&edit.text, edit.caret);
+            edit.text.insert(index, char);
+            if text_draw_state.text_fits(&edit.text, bounds) {
+                length += 1;
+                edit.caret += 1;
+            } else {
+                edit.text.remove(index);
+                rejected.push(char);
+            }
+        }
+        rejected
+    }
+    // delete the selection, or if there isn't one, from the caret to `to`
+    fn delete_to(&self, edit: &mut TextEdit, to: usize) {
+        if !self.read_only && !edit.delete_selection() {
+            let caret = edit.caret;
+            edit.delete(caret, to);
+        }
+    }
+    fn handle_key(&mut self, edit: &mut TextEdit, key: glutin::VirtualKeyCode, shift: bool, ctrl: bool) {
+        let caret = edit.caret;
+        match key {
+            glutin::VirtualKeyCode::Left => {
+                let to = match edit.selection() {
+                    Some((start, _)) if !shift && !ctrl => start,
+                    _ if ctrl => previous_word_start(&edit.text, caret),
+                    _ => caret.saturating_sub(1),
+                };
+                edit.move_caret(to, shift);
+            }
+            glutin::VirtualKeyCode::Right => {
+                let to = match edit.selection() {
+                    Some((_, end)) if !shift && !ctrl => end,
+                    _ if ctrl => next_word_end(&edit.text, caret),
+                    _ => caret + 1,
+                };
+                edit.move_caret(to, shift);
+            }
+            glutin::VirtualKeyCode::Home => {
+                let to = line_start(&edit.text, caret);
+                edit.move_caret(to, shift);
+            }
+            glutin::VirtualKeyCode::End => {
+                let to = line_end(&edit.text, caret);
+                edit.move_caret(to, shift);
+            }
+            glutin::VirtualKeyCode::Back if ctrl => {
+                let to = previous_word_start(&edit.text, caret);
+                self.delete_to(edit, to);
+            }
+            glutin::VirtualKeyCode::Delete => {
+                let to = if ctrl { next_word_end(&edit.text, caret) } else { caret + 1 };
+                let to = cmp::min(to, edit.text.chars().count());
+                self.delete_to(edit, to);
+            }
+            _ => (),
+        }
+    }
+    fn handle_drag(&mut self, edit: &mut TextEdit, event: &DragEvent, text_draw_state: &TextState, bounds: Rect, shift: bool) {
+        if event.button != glutin::MouseButton::Left {
+            return;
+        }
+        let glyph_positions = text_draw_state.glyph_positions(bounds);
+        let index = index_at_point(&glyph_positions, text_draw_state.line_height(), event.local_position);
+        match event.state {
+            DragState::Start => {
+                let now = Instant::now();
+                let double_click = self.last_press.map_or(false, |last_press| {
+                    now.duration_since(last_press) < Duration::from_millis(DOUBLE_CLICK_MILLIS)
+                });
+                self.last_press = Some(now);
+                if double_click {
+                    let (start, end) = word_at(&edit.text, index);
+                    edit.anchor = Some(start);
+                    edit.caret = end;
+                    self.drag_word = Some((start, end));
+                } else {
+                    // shift-click extends the selection to the click
+                    edit.move_caret(index, shift);
+                    self.drag_word = None;
+                }
+            }
+            DragState::Moved => {
+                if let Some((word_start, word_end)) = self.drag_word {
+                    let (start, end) = word_at(&edit.text, index);
+                    if start < word_start {
+                        edit.anchor = Some(word_end);
+                        edit.caret = start;
+                    } else {
+                        edit.anchor = Some(word_start);
+                        edit.caret = cmp::max(end, word_end);
+                    }
+                } else {
+                    // the selection goes from where the drag started to the pointer
+                    edit.move_caret(index, true);
+                }
+            }
+            DragState::End | DragState::Cancel => {
+                self.drag_word = None;
+            }
+        }
+    }
+}
+impl EventHandler<EditTextInputEvent> for EditTextInputHandler {
+    fn handle(&mut self, event: &EditTextInputEvent, mut args: EventArgs) {
+        if let EditTextInputEvent::KeyboardInput(glutin::ElementState::Released, _) = *event {
+            return;
+        }
+        if let EditTextInputEvent::TextSet(ref text) = *event {
+            if self.sent_texts.front() == Some(text) {
+                self.sent_texts.pop_front();
+            } else {
+                self.history.clear();
+                self.sent_texts.clear();
+            }
+            return;
+        }
+        // modifiers held while the event is handled
+        let (shift, ctrl) = (args.ui.shift_pressed(), args.ui.ctrl_pressed());
+        let selection = self.selection_widget.drawable::<TextSelectionOverlay>().and_then(|state| state.selection);
+        let caret_before;
+        let edit_start;
+        // undo and redo aren't recorded, and typed characters are undone together
+        let (mut from_history, mut typed) = (false, false);
+        let (old_text, edit, rejected) = {
+            let bounds = args.widget.bounds();
+            let text_draw_state = match args.drawable::<TextState>() {
+                Some(text_draw_state) => text_draw_state,
+                None => return,
+            };
+            let mut edit = TextEdit::new(&text_draw_state.text, self.caret, selection);
+            caret_before = edit.caret;
+            edit_start = edit.selection().map_or(edit.caret, |(start, _)| start);
+            let rejected = match *event {
+                // Ctrl+Backspace is handled as a key press, to delete a word
+                EditTextInputEvent::ReceivedCharacter(BACKSPACE) if ctrl => String::new(),
+                EditTextInputEvent::ReceivedCharacter(BACKSPACE) => {
+                    let to = edit.caret.saturating_sub(1);
+                    self.delete_to(&mut edit, to);
+                    String::new()
+                }
+                EditTextInputEvent::ReceivedCharacter(PASTE) | EditTextInputEvent::Paste => {
+                    let mut pasted = clipboard_text().unwrap_or_default();
+                    if self.single_line {
+                        pasted = single_line_text(&pasted);
+                    }
+                    self.insert(&mut edit, &pasted, &text_draw_state, bounds)
+                }
+                EditTextInputEvent::ReceivedCharacter(char) if char == UNDO || char == REDO => {
+                    let restored = if self.read_only {
+                        None
+                    } else if char == UNDO {
+                        self.history.undo(&edit.text)
+                    } else {
+                        self.history.redo(&edit.text)
+                    };
+                    if let Some((text, caret)) = restored {
+                        edit.text = text;
+                        edit.move_caret(caret, false);
+                        from_history = true;
+                    }
+                    String::new()
+                }
+                // other control characters, eg. from Ctrl+letter or Delete, aren't inserted
+                EditTextInputEvent::ReceivedCharacter(char) if char.is_control() && char != '\r' && char != '\n' => String::new(),
+                EditTextInputEvent::ReceivedCharacter(char) => {
+                    typed = true;
+                    self.insert(&mut edit, &char.to_string(), &text_draw_state, bounds)
+                }
+                EditTextInputEvent::DeleteSelection => {
+                    if !self.read_only {
+                        edit.delete_selection();
+                    }
+                    String::new()
+                }
+                EditTextInputEvent::TextSet(_) => String::new(),
+                EditTextInputEvent::InsertText(ref input) => {
+                    self.insert(&mut edit, input, &text_draw_state, bounds)
+                }
+                EditTextInputEvent::KeyboardInput(_, Some(key)) => {
+                    self.handle_key(&mut edit, key, shift, ctrl);
+                    String::new()
+                }
+                EditTextInputEvent::KeyboardInput(_, None) => String::new(),
+                EditTextInputEvent::Drag(ref drag_event) => {
+                    self.handle_drag(&mut edit, drag_event, &text_draw_state, bounds, shift);
+                    String::new()
+                }
+            };
+            (text_draw_state.text.clone(), edit, rejected)
+        };
+        self.caret = edit.caret;
+        let caret = edit.caret;
+        self.selection_widget.update_drawable(|state: &mut TextSelectionOverlay| state.caret = Some(caret));
+        if caret != caret_before || edit.text != old_text || edit.selection() != selection {
+            self.selection_widget.event(CaretBlinkEvent::Reset);
+        }
+        if let Some(edit_text) = args.widget.parent() {
+            if edit.selection() != selection {
+                edit_text.event(SetSelection(edit.selection()));
+            }
+            if !rejected.is_empty() {
+                edit_text.event(InputRejectedEvent(rejected));
+            }
+        }
+        if edit.text != old_text {
+            if !from_history {
+                let change = TextChange::between(&old_text, &edit.text, edit_start, caret_before, edit.caret);
+                self.history.record(change, typed);
+            }
+            self.sent_texts.push_back(edit.text.clone());
+            let text = edit.text;
+            args.update_drawable(|state: &mut TextState| {
+                state.text = text.clone()
+            });
+            args.widget.event(TextUpdated(text.clone()));
+        }
+    }
 }
 
+/// The text of an edit text changed. Sending it to the text widget sets the text, and clears the undo history.
 pub struct TextUpdated(pub String);
 
+/// Set the range of selected glyphs in an edit text widget, `None` clears the selection
+pub struct SetSelection(pub Option<(usize, usize)>);
+
+// keep the selection overlay in sync with the text and bounds of the text widget
+fn update_selection_overlay(text_widget: WidgetRef, mut selection_widget: WidgetRef) {
+    let (glyph_positions, line_height) = {
+        let bounds = text_widget.bounds();
+        let text_draw_state = match text_widget.drawable::<TextState>() {
+            Some(text_draw_state) => text_draw_state,
+            None => return,
+        };
+        (text_draw_state.glyph_positions(bounds), text_draw_state.line_height())
+    };
+    selection_widget.update_drawable(|state: &mut TextSelectionOverlay| {
+        state.glyph_positions = glyph_positions;
+        state.line_height = line_height;
+    });
+}
+
+/// The state of an edit text widget when its context menu is opened,
+/// used to enable the default items, and passed to `EditTextBuilder::extend_context_menu`
+#[derive(Clone, Debug)]
+pub struct EditTextMenuState {
+    pub text: String,
+    /// Selected characters, start inclusive, end exclusive
+    pub selection: Option<(usize, usize)>,
+    pub read_only: bool,
+    pub has_clipboard_text: bool,
+}
+impl EditTextMenuState {
+    fn selected_range(&self) -> Option<(usize, usize)> {
+        selected_byte_range(&self.text, self.selection)
+    }
+    pub fn selected_text(&self) -> Option<&str> {
+        self.selected_range().map(|(start, end)| &self.text[start..end])
+    }
+    /// The text with the selected characters removed
+    pub fn text_without_selection(&self) -> String {
+        match self.selected_range() {
+            Some((start, end)) => format!("{}{}", &self.text[..start], &self.text[end..]),
+            None => self.text.clone(),
+        }
+    }
+    pub fn can_cut(&self) -> bool {
+        !self.read_only && self.can_copy()
+    }
+    pub fn can_copy(&self) -> bool {
+        self.selected_range().is_some()
+    }
+    pub fn can_paste(&self) -> bool {
+        !self.read_only && self.has_clipboard_text
+    }
+    pub fn can_select_all(&self) -> bool {
+        !self.text.is_empty()
+    }
+}
+
+// Cut, Copy, Paste and Select All, enabled based on the state of the edit text
+fn add_default_menu_items(menu: &mut ContextMenuBuilder, state: &EditTextMenuState, edit_text: WidgetRef, text_widget: WidgetRef) {
+    let (cut_state, cut_text_widget) = (state.clone(), text_widget.clone());
+    menu.add_item("Cut", state.can_cut(), move |_| {
+        if let Some(selected) = cut_state.selected_text() {
+            set_clipboard_text(selected);
+        }
+        cut_text_widget.event(EditTextInputEvent::DeleteSelection);
+    });
+    let copy_state = state.clone();
+    menu.add_item("Copy", state.can_copy(), move |_| {
+        if let Some(selected) = copy_state.selected_text() {
+            set_clipboard_text(selected);
+        }
+    });
+    menu.add_item("Paste", state.can_paste(), move |_| {
+        text_widget.event(EditTextInputEvent::Paste);
+    });
+    let char_count = state.text.chars().count();
+    menu.add_item("Select All", state.can_select_all(), move |_| {
+        edit_text.event(SetSelection(Some((0, char_count))));
+    });
+}
+
 pub fn text_change_handle(event: &TextUpdated, mut args: EventArgs) {
-    args.widget.update(|state: &mut TextState| state.text = event.0.clone());
+    args.update_drawable(|state: &mut TextState| state.text = event.0.clone());
 }
 
 pub struct EditTextBuilder {
     pub widget: WidgetBuilder,
     pub text_widget: WidgetBuilder,
+    selection_widget: WidgetBuilder,
+    read_only: bool,
+    single_line: bool,
+    max_length: Option<usize>,
+    input_filter: Option<Box<Fn(char) -> bool>>,
+    validator: Option<Box<TextInputValidator>>,
+    extend_context_menu: Option<Box<Fn(&mut ContextMenuBuilder, &EditTextMenuState)>>,
+    caret_blink_interval: Option<Duration>,
+    // the constraints the edit text is created with, the ones added to it after are the caller's
+    created_constraints: HashSet<Constraint>,
 }
 
 impl EditTextBuilder {
@@ -61,46 +693,299 @@ impl EditTextBuilder {
         let mut widget = WidgetBuilder::new("edit_text");
         widget
             .set_draw_state_with_style(RectState::new(), rect_style)
-            .add_handler_fn(|_: &WidgetAttachedEvent, args| {
-                args.ui.event(KeyboardInputEvent::AddFocusable(args.widget));
-            })
-            .add_handler_fn(|_: &WidgetDetachedEvent, args| {
-                args.ui.event(KeyboardInputEvent::RemoveFocusable(args.widget));
-            })
-            .make_focusable();
+            .make_tab_focusable();
+
+        // the caret is hidden until the edit text is focused
+        let selection_state = TextSelectionOverlay { caret: Some(0), caret_visible: false, ..TextSelectionOverlay::new() };
+        let mut selection_widget = WidgetBuilder::with_draw_state("edit_text_selection", selection_state);
+        let selection_ref = selection_widget.widget_ref();
+        widget.add_handler_fn(move |event: &SetSelection, _| {
+            selection_ref.clone().update_drawable(|state: &mut TextSelectionOverlay| state.selection = event.0);
+        });
+
+        let selection_ref = selection_widget.widget_ref();
+        widget.add_handler_fn(move |_: &FocusGained, _| {
+            selection_ref.event(CaretBlinkEvent::Focused);
+        });
+        let selection_ref = selection_widget.widget_ref();
+        widget.add_handler_fn(move |_: &FocusLost, _| {
+            selection_ref.event(CaretBlinkEvent::Blurred);
+        });
+        selection_widget.add_handler_fn(|_: &TimerEvent, args| {
+            args.widget.event(CaretBlinkEvent::Blink);
+        });
 
         let mut text_widget = WidgetBuilder::new("edit_text_text");
+        let text_ref = text_widget.widget_ref();
+        widget.add_handler_fn(move |_: &FocusLost, args| {
+            let text = text_ref.drawable::<TextState>().map_or(String::new(), |state| state.text.clone());
+            args.widget.event(TextCommitted(text));
+        });
         text_widget
             .set_draw_state(TextState::default())
+            .font_size_from_theme(DEFAULT_FONT_SIZE)
             .add_handler(TextUpdatedHandler::default())
-            .add_handler_fn(edit_text_handle_char)
-            .add_handler_fn(text_change_handle);
+            .add_handler_fn(|event: &WidgetReceivedCharacter, args| {
+                args.widget.event(EditTextInputEvent::ReceivedCharacter(event.0));
+            })
+            .add_handler_fn(|event: &WidgetKeyboardInput, args| {
+                args.widget.event(EditTextInputEvent::KeyboardInput(event.0, event.2));
+            })
+            .add_handler_fn(|event: &InsertText, args| {
+                args.widget.event(EditTextInputEvent::InsertText(event.0.clone()));
+            })
+            .add_handler_fn(|event: &DragEvent, args| {
+                args.widget.event(EditTextInputEvent::Drag(event.clone()));
+            })
+            .add_handler_fn(text_change_handle)
+            .make_draggable();
+        let selection_ref = selection_widget.widget_ref();
+        text_widget.add_handler_fn(move |_: &StyleUpdated, args| {
+            update_selection_overlay(args.widget, selection_ref.clone());
+        });
+        let selection_ref = selection_widget.widget_ref();
+        text_widget.add_handler_fn(move |_: &LayoutUpdated, args| {
+            update_selection_overlay(args.widget, selection_ref.clone());
+        });
+        let selection_ref = selection_widget.widget_ref();
+        text_widget.add_handler_fn(move |event: &TextUpdated, args| {
+            update_selection_overlay(args.widget.clone(), selection_ref.clone());
+            args.widget.event(EditTextInputEvent::TextSet(event.0.clone()));
+        });
 
-        text_widget.layout().add(constraints![
-            align_left(&widget).padding(5.0),
-            align_top(&widget).padding(5.0),
-            bound_by(&widget).padding(5.0),
-        ]);
+        let edit_text_vars = widget.widget_ref().layout_vars();
+        text_widget.add_theme_constraints(move |theme| {
+            let padding = theme.padding(ThemePadding::Input);
+            constraints![
+                align_left(&edit_text_vars).padding(padding),
+                align_top(&edit_text_vars).padding(padding),
+                bound_by(&edit_text_vars).padding(padding),
+            ]
+        });
+        selection_widget.layout().add(match_layout(&text_widget));
+        let created_constraints = widget.layout().added_constraints().clone();
 
         EditTextBuilder {
             widget: widget,
             text_widget: text_widget,
+            selection_widget: selection_widget,
+            read_only: false,
+            single_line: false,
+            max_length: None,
+            input_filter: None,
+            validator: None,
+            extend_context_menu: None,
+            caret_blink_interval: Some(Duration::from_millis(CARET_BLINK_MILLIS)),
+            created_constraints: created_constraints,
         }
     }
 
+    /// Reject typed and inserted text, the text can still be selected and copied
+    pub fn set_read_only(&mut self, read_only: bool) -> &mut Self {
+        self.read_only = read_only;
+        self
+    }
+    /// Replace line breaks in pasted text with spaces
+    pub fn set_single_line(&mut self, single_line: bool) -> &mut Self {
+        self.single_line = single_line;
+        self
+    }
+    /// Limit the number of characters (not bytes) that can be entered
+    pub fn set_max_length(&mut self, max_length: usize) -> &mut Self {
+        self.max_length = Some(max_length);
+        self
+    }
+    /// How long the caret is shown, then hidden, while it blinks, `None` for a caret that doesn't blink.
+    /// It's shown again whenever it moves or the text changes, so it doesn't blink while typing.
+    pub fn set_caret_blink_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+        self.caret_blink_interval = interval;
+        self
+    }
+    /// Only accept typed or inserted characters for which `filter` returns true
+    pub fn set_input_filter<F>(&mut self, filter: F) -> &mut Self
+        where F: Fn(char) -> bool + 'static
+    {
+        self.input_filter = Some(Box::new(filter));
+        self
+    }
+    /// Check the text with `validator` each time it changes, the border takes the color of the result,
+    /// and its message is shown below the edit text, see `ValidationResult`.
+    /// The message is shown in a widget wrapping the edit text, which is what the builder builds,
+    /// constraints added to the builder's layout constrain the wrapping widget.
+    pub fn set_validator<V>(&mut self, validator: V) -> &mut Self
+        where V: TextInputValidator + 'static
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+    pub fn on_text_validated<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&TextValidated, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    /// Change the context menu after the default items are added, `callback` can add items,
+    /// or change or remove the default ones using `ContextMenuBuilder::items_mut`
+    pub fn extend_context_menu<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&mut ContextMenuBuilder, &EditTextMenuState) + 'static
+    {
+        self.extend_context_menu = Some(Box::new(callback));
+        self
+    }
+    pub fn on_input_rejected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&InputRejectedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+
     pub fn on_text_changed<F>(&mut self, callback: F) -> &mut Self
         where F: Fn(&TextUpdated, EventArgs) + 'static
     {
         self.text_widget.add_handler_fn(callback);
         self
     }
+    /// Called with the text when the edit text loses focus
+    pub fn on_text_committed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&TextCommitted, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
 }
 
 widget_builder!(EditTextBuilder);
 impl Into<WidgetBuilder> for EditTextBuilder {
     fn into(mut self) -> WidgetBuilder {
+        self.text_widget.add_handler(EditTextInputHandler {
+            selection_widget: self.selection_widget.widget_ref(),
+            read_only: self.read_only,
+            single_line: self.single_line,
+            max_length: self.max_length,
+            input_filter: self.input_filter,
+            caret: 0,
+            last_press: None,
+            drag_word: None,
+            history: TextEditHistory::new(),
+            sent_texts: VecDeque::new(),
+        });
+        self.selection_widget.add_handler(CaretBlinkHandler {
+            interval: self.caret_blink_interval,
+            focused: false,
+            visible: false,
+        });
+        let read_only = self.read_only;
+        let extend_context_menu = self.extend_context_menu;
+        let text_ref = self.text_widget.widget_ref();
+        let selection_ref = self.selection_widget.widget_ref();
+        self.widget.set_context_menu(move |menu, edit_text| {
+            let state = EditTextMenuState {
+                text: text_ref.drawable::<TextState>().map_or(String::new(), |state| state.text.clone()),
+                selection: selection_ref.drawable::<TextSelectionOverlay>().and_then(|state| state.selection),
+                read_only: read_only,
+                has_clipboard_text: clipboard_text().is_some(),
+            };
+            add_default_menu_items(menu, &state, edit_text.clone(), text_ref.clone());
+            if let Some(ref extend_context_menu) = extend_context_menu {
+                extend_context_menu(menu, &state);
+            }
+        });
+        let mut validation_message = None;
+        if let Some(validator) = self.validator.take() {
+            let edit_text = self.widget.widget_ref();
+            self.text_widget.add_handler_fn(move |event: &TextUpdated, _| {
+                edit_text.event(TextValidated(validator.validate(&event.0)));
+            });
+            let mut message = TextBuilder::new("");
+            message
+                .set_name("edit_text_validation_message")
+                .font_size_from_theme(VALIDATION_FONT_SIZE);
+            self.widget.add_handler(ValidationStyleHandler {
+                result: None,
+                message: message.widget_ref(),
+            });
+            self.widget.add_handler_fn(|event: &TextValidated, args| {
+                args.widget.event(ValidationStyleEvent::Validated(event.0.clone()));
+            });
+            self.widget.add_handler_fn(|_: &StyleUpdated, args| {
+                args.widget.event(ValidationStyleEvent::StyleUpdated);
+            });
+            validation_message = Some(message);
+        }
+        // added first so the selection is drawn behind the text
+        self.widget.add_child(self.selection_widget);
         self.widget.add_child(self.text_widget);
-        self.widget
+        match validation_message {
+            Some(message) => with_validation_message(self.widget, message, &self.created_constraints),
+            None => self.widget,
+        }
+    }
+}
+
+// the edit text, above the message from its validator, which is collapsed while there's no message
+fn with_validation_message(mut edit_text: WidgetBuilder, mut message: WidgetBuilder,
+                           created_constraints: &HashSet<Constraint>) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new("validated_edit_text");
+    widget.layout().no_container();
+    widget.layout().add(shrink_vertical());
+    // the constraints added to the edit text by the caller constrain the widget that's built instead
+    {
+        let mut edit_text_layout = edit_text.layout();
+        let added = edit_text_layout.added_constraints().difference(created_constraints).cloned().collect();
+        edit_text_layout.move_constraints(added, &mut widget.layout());
+    }
+    edit_text.layout().add(constraints![
+        align_top(&widget),
+        align_left(&widget),
+        align_right(&widget),
+        bound_bottom(&widget),
+    ]);
+    message.layout().add(constraints![
+        align_below(&edit_text).padding(VALIDATION_SPACING),
+        align_left(&widget),
+        align_bottom(&widget),
+    ]);
+    message.layout().hide();
+    widget.add_child(edit_text).add_child(message);
+    widget
+}
+
+enum ValidationStyleEvent {
+    Validated(ValidationResult),
+    // the style of the border was applied again, eg. when the edit text was focused
+    StyleUpdated,
+}
+
+// colors the border of an edit text with the result of its validator, and shows the message
+struct ValidationStyleHandler {
+    result: Option<ValidationResult>,
+    message: WidgetRef,
+}
+
+impl EventHandler<ValidationStyleEvent> for ValidationStyleHandler {
+    fn handle(&mut self, event: &ValidationStyleEvent, mut args: EventArgs) {
+        if let ValidationStyleEvent::Validated(ref result) = *event {
+            let had_message = self.result.as_ref().and_then(|result| result.message()).is_some();
+            let (text, color) = (result.message().unwrap_or("").to_owned(), result.color());
+            self.message.update(|state: &mut TextState| {
+                state.text = text;
+                state.text_color = color;
+            });
+            let has_message = result.message().is_some();
+            if has_message != had_message {
+                self.message.update_layout(|layout| if has_message { layout.show() } else { layout.hide() });
+            }
+            self.result = Some(result.clone());
+        }
+        let border = match self.result {
+            Some(ref result) => Some((1.0, result.color())),
+            None => return,
+        };
+        // the border is set over the style, once it's set updating it again does nothing, and sends no `StyleUpdated`
+        let current = args.widget.drawable::<RectState>().map(|state| state.border);
+        if current != Some(border) {
+            args.widget.update(|state: &mut RectState| state.border = border);
+        }
     }
 }
 
@@ -110,16 +995,15 @@ struct TextUpdatedHandler {
 }
 impl EventHandler<StyleUpdated> for TextUpdatedHandler {
     fn handle(&mut self, _: &StyleUpdated, mut args: EventArgs) {
+        let line_height = match args.drawable::<TextState>() {
+            Some(text_draw_state) => text_draw_state.line_height(),
+            None => return,
+        };
         args.widget.update_layout(|layout| {
             for constraint in self.size_constraints.drain(..) {
                 layout.remove_constraint(constraint);
             }
         });
-        let line_height = {
-            let draw_state = args.widget.draw_state();
-            let text_draw_state = draw_state.downcast_ref::<TextState>().unwrap();
-            text_draw_state.line_height()
-        };
         let size_constraints = min_height(line_height).build(&args.widget.layout_vars());
         args.widget.update_layout(|layout| {
             layout.add(size_constraints.clone())
@@ -0,0 +1,126 @@
+use std::rc::Rc;
+
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::button::PushButtonBuilder;
+use widgets::text::TextBuilder;
+use draw::rect::RectState;
+use draw::text::TextStyle;
+use layout::ExactFrame;
+use layout::constraint::*;
+use color::*;
+
+const PANEL_PADDING: f32 = 10.0;
+
+/// Reports that a widget has failed, eg. a plugin panel that couldn't load its data.
+/// Send it up from the widget with `WidgetRef::event_bubble_up`, so the nearest `ErrorBoundaryBuilder` replaces
+/// its content with an error panel showing the message. Handler panics are reported the same way,
+/// when they're caught, see `Ui::set_catch_handler_panics`.
+#[derive(Clone, Debug)]
+pub struct WidgetErrorEvent(pub String);
+
+type ContentFactory = Rc<Fn() -> Result<WidgetBuilder, String>>;
+
+enum BoundaryInput {
+    Build,
+    Failed(String),
+}
+
+struct ErrorBoundaryHandler {
+    factory: ContentFactory,
+}
+
+impl EventHandler<BoundaryInput> for ErrorBoundaryHandler {
+    fn handle(&mut self, event: &BoundaryInput, mut args: EventArgs) {
+        for child in args.widget.children() {
+            args.widget.remove_child(child);
+        }
+        let content = match *event {
+            BoundaryInput::Build => build_content(&self.factory, &args.widget),
+            BoundaryInput::Failed(ref message) => error_panel(&args.widget, message),
+        };
+        args.widget.add_child(content);
+    }
+}
+
+fn build_content(factory: &ContentFactory, boundary: &WidgetRef) -> WidgetBuilder {
+    match factory() {
+        Ok(content) => content,
+        Err(message) => error_panel(boundary, &message),
+    }
+}
+
+// shows the error, with a button to build the content again
+fn error_panel(boundary: &WidgetRef, message: &str) -> WidgetBuilder {
+    let mut panel = WidgetBuilder::with_draw_state("error_panel", RectState {
+        background_color: Color::from_rgba(0xFB, 0xE3, 0xE3, 0xFF),
+        corner_radius: None,
+        border: Some((1.0, RED)),
+    });
+    panel.layout().no_container();
+
+    let mut text = TextBuilder::new_with_style(style!(
+        TextStyle::Text: message.to_owned(),
+        TextStyle::TextColor: Color::from_rgba(0x90, 0x10, 0x10, 0xFF)));
+    text.set_name("error_message");
+    text.layout().add(constraints![
+        align_top(&panel).padding(PANEL_PADDING),
+        align_left(&panel).padding(PANEL_PADDING),
+        bound_by(&panel).padding(PANEL_PADDING),
+    ]);
+
+    let mut retry = PushButtonBuilder::with_label("Retry");
+    retry.set_name("error_retry");
+    retry.layout().add(constraints![
+        below(&text).padding(PANEL_PADDING),
+        align_left(&panel).padding(PANEL_PADDING),
+        bound_by(&panel).padding(PANEL_PADDING),
+    ]);
+    // the panel is a descendant of the boundary, so it only holds a weak reference to it
+    let boundary = boundary.downgrade();
+    retry.on_click(move |_, _| {
+        if let Some(boundary) = boundary.upgrade() {
+            boundary.event(BoundaryInput::Build);
+        }
+    });
+
+    panel.add_child(text);
+    panel.add_child(retry);
+    panel
+}
+
+/// Contains a subtree that might fail, so a failure only replaces that subtree, instead of breaking the rest of the UI.
+/// The content is built by a factory, and replaced by an error panel if the factory returns an error,
+/// or a descendant sends a `WidgetErrorEvent`. The panel's Retry button calls the factory again.
+pub struct ErrorBoundaryBuilder {
+    pub widget: WidgetBuilder,
+    factory: ContentFactory,
+}
+
+impl ErrorBoundaryBuilder {
+    /// A boundary around the content `factory` builds, the content fills the boundary
+    pub fn new<F>(factory: F) -> Self
+        where F: Fn() -> Result<WidgetBuilder, String> + 'static
+    {
+        let mut widget = WidgetBuilder::new("error_boundary");
+        widget.layout().set_container(ExactFrame);
+        ErrorBoundaryBuilder {
+            widget: widget,
+            factory: Rc::new(factory),
+        }
+    }
+}
+
+widget_builder!(ErrorBoundaryBuilder);
+impl Into<WidgetBuilder> for ErrorBoundaryBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let content = build_content(&self.factory, &self.widget.widget_ref());
+        self.widget.add_handler(ErrorBoundaryHandler { factory: self.factory });
+        self.widget.add_handler_fn(|event: &WidgetErrorEvent, args| {
+            args.widget.event(BoundaryInput::Failed(event.0.clone()));
+            *args.handled = true;
+        });
+        self.widget.add_child(content);
+        self.widget
+    }
+}
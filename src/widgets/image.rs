@@ -8,8 +8,7 @@ impl ImageBuilder {
     pub fn new(file: &str) -> WidgetBuilder {
         let image_draw_state = ImageState::new(file);
         let image_size = image_draw_state.measure();
-        let mut widget = WidgetBuilder::new("image");
-        widget.set_draw_state(image_draw_state);
+        let mut widget = WidgetBuilder::with_draw_state("image", image_draw_state);
         widget.layout().add(size(image_size));
         widget
     }
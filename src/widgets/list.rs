@@ -1,13 +1,21 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use glutin;
+
 use event::{EventArgs, EventHandler};
 use widget::{WidgetBuilder, WidgetRef};
 use widget::property::Property;
 use widget::property::states::*;
 use widgets::text::TextBuilder;
+use widgets::scroll::ScrollOffsetChanged;
 use draw::rect::{RectState, RectStyle};
 use draw::text::TextStyle;
 use input::mouse::ClickEvent;
+use input::keyboard::{WidgetKeyboardInput, KeyboardInputEvent};
 use layout::constraint::*;
 use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use geometry::{Rect, RectExt, Vector};
 use color::*;
 
 pub struct ListItemSelected {
@@ -15,6 +23,16 @@ pub struct ListItemSelected {
 }
 pub struct ItemSelected;
 
+/// Sent to a list when its selection changes, with the indices of the selected items
+pub struct SelectionChanged(pub Vec<usize>);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionMode {
+    Single,
+    /// Ctrl-click toggles items, Shift-click and Shift-Up/Down select a range
+    Multiple,
+}
+
 static COLOR_LIST_ITEM_DEFAULT: Color = GRAY_30;
 static COLOR_LIST_ITEM_MOUSEOVER: Color = GRAY_60;
 static COLOR_LIST_ITEM_SELECTED: Color = BLUE_HIGHLIGHT;
@@ -30,28 +48,170 @@ lazy_static! {
     };
 }
 
-pub struct ListHandler {
-    selected: Option<WidgetRef>,
+// a header and the items below it, see `ListBuilder::add_section`
+struct ListSection {
+    section: WidgetRef,
+    header: WidgetRef,
+    items: WidgetRef,
+}
+
+/// The selected items of a list, can be kept to query the selection after the list is built
+#[derive(Clone)]
+pub struct ListSelection {
+    list: WidgetRef,
+    selected: Rc<RefCell<Vec<WidgetRef>>>,
+    sections: Rc<RefCell<Vec<ListSection>>>,
+}
+impl ListSelection {
+    fn new(list: WidgetRef) -> Self {
+        ListSelection {
+            list: list,
+            selected: Rc::new(RefCell::new(Vec::new())),
+            sections: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+    /// Indices of the selected items in the list, in ascending order.
+    /// The items of sections are counted in order with the items outside of them, the headers aren't counted.
+    pub fn selected_indices(&self) -> Vec<usize> {
+        let selected = self.selected.borrow();
+        self.items().iter().enumerate()
+            .filter(|&(_, item)| selected.contains(item))
+            .map(|(index, _)| index)
+            .collect()
+    }
+    // the items of the list, with the items of each section in place of the section
+    fn items(&self) -> Vec<WidgetRef> {
+        let sections = self.sections.borrow();
+        let mut items = Vec::new();
+        for child in self.list.children() {
+            match sections.iter().find(|section| section.section == child) {
+                Some(section) => items.extend(section.items.children()),
+                None => items.push(child),
+            }
+        }
+        items
+    }
+}
+
+// how far down the header of a section is moved to keep it at the top of the visible area of a scrolling list,
+// given the bounds of the section, including the header, the height of the header and the top of the visible area.
+// The header stays at the top of the section until it's scrolled past, and never leaves the section,
+// so it's pushed out by the next section's header.
+fn sticky_header_offset(section: Rect, header_height: f32, viewport_top: f32) -> f32 {
+    let max_offset = f32::max(0.0, section.height() - header_height);
+    f32::min(max_offset, f32::max(0.0, viewport_top - section.top()))
+}
+
+enum ListInputEvent {
+    ItemClicked(WidgetRef),
+    Deselect,
+    KeyboardInput(glutin::ElementState, Option<glutin::VirtualKeyCode>),
+}
+
+struct ListHandler {
+    mode: SelectionMode,
+    selection: ListSelection,
+    // start of a Shift range selection
+    anchor: Option<WidgetRef>,
+    // last item clicked or moved to with the keyboard
+    cursor: Option<WidgetRef>,
 }
 impl ListHandler {
-    pub fn new() -> Self {
-        ListHandler { selected: None }
+    fn new(mode: SelectionMode, selection: ListSelection) -> Self {
+        ListHandler {
+            mode: mode,
+            selection: selection,
+            anchor: None,
+            cursor: None,
+        }
+    }
+    fn select_range(&mut self, items: &[WidgetRef], to: usize) {
+        let from = self.anchor.as_ref().and_then(|anchor| items.iter().position(|item| item == anchor)).unwrap_or(to);
+        let range = if from < to { from..to + 1 } else { to..from + 1 };
+        self.set_selected(items[range].to_vec());
+    }
+    // shift extends the selection from the anchor, ctrl toggles the item
+    fn select_item(&mut self, items: &[WidgetRef], index: usize, shift: bool, ctrl: bool) {
+        let item = items[index].clone();
+        if self.mode == SelectionMode::Multiple && shift {
+            self.select_range(items, index);
+        } else if self.mode == SelectionMode::Multiple && ctrl {
+            let mut selected = self.selection.selected.borrow().clone();
+            if let Some(pos) = selected.iter().position(|selected| *selected == item) {
+                selected.remove(pos);
+            } else {
+                selected.push(item.clone());
+            }
+            self.set_selected(selected);
+            self.anchor = Some(item.clone());
+        } else {
+            self.set_selected(vec![item.clone()]);
+            self.anchor = Some(item.clone());
+        }
+        self.cursor = Some(item);
+    }
+    fn set_selected(&mut self, selected: Vec<WidgetRef>) {
+        let mut old_selected = self.selection.selected.borrow_mut();
+        for item in old_selected.iter_mut() {
+            if !selected.contains(item) {
+                item.remove_prop(Property::Selected);
+            }
+        }
+        for item in &selected {
+            if !old_selected.contains(item) {
+                item.clone().add_prop(Property::Selected);
+            }
+        }
+        *old_selected = selected;
     }
 }
-impl EventHandler<ListItemSelected> for ListHandler {
-    fn handle(&mut self, event: &ListItemSelected, _: EventArgs) {
-        let selected = event.widget.clone();
-        if selected != self.selected {
-            if let Some(ref mut old_selected) = self.selected {
-                old_selected.remove_prop(Property::Selected);
+impl EventHandler<ListInputEvent> for ListHandler {
+    fn handle(&mut self, event: &ListInputEvent, args: EventArgs) {
+        let items = self.selection.items();
+        let old_selection = self.selection.selected_indices();
+        match *event {
+            ListInputEvent::ItemClicked(ref item) => {
+                if let Some(index) = items.iter().position(|child| child == item) {
+                    self.select_item(&items, index, args.ui.shift_pressed(), args.ui.ctrl_pressed());
+                }
+            }
+            ListInputEvent::Deselect => {
+                self.set_selected(Vec::new());
+                self.anchor = None;
+                self.cursor = None;
+            }
+            ListInputEvent::KeyboardInput(state, key) => {
+                let pressed = state == glutin::ElementState::Pressed;
+                match key {
+                    Some(glutin::VirtualKeyCode::Up) | Some(glutin::VirtualKeyCode::Down) if pressed && !items.is_empty() => {
+                        let cursor = self.cursor.as_ref().and_then(|cursor| items.iter().position(|item| item == cursor));
+                        let index = match (cursor, key) {
+                            (Some(cursor), Some(glutin::VirtualKeyCode::Up)) => cursor.saturating_sub(1),
+                            (Some(cursor), _) => cursor.saturating_add(1).min(items.len() - 1),
+                            (None, _) => 0,
+                        };
+                        // arrow keys move the selection, ctrl is only meaningful for clicks
+                        self.select_item(&items, index, args.ui.shift_pressed(), false);
+                    }
+                    _ => (),
+                }
             }
         }
-        self.selected = selected;
+        let selection = self.selection.selected_indices();
+        if selection != old_selection {
+            let primary = match self.cursor {
+                Some(ref cursor) if self.selection.selected.borrow().contains(cursor) => Some(cursor.clone()),
+                _ => None,
+            };
+            args.widget.event(ListItemSelected { widget: primary });
+            args.widget.event(SelectionChanged(selection));
+        }
     }
 }
 
 fn list_handle_deselect(_: &ClickEvent, args: EventArgs) {
-    args.widget.event(ListItemSelected { widget: None });
+    args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(args.widget.clone())));
+    args.widget.event(ListInputEvent::Deselect);
 }
 
 pub struct ListItemHandler {
@@ -63,32 +223,45 @@ impl ListItemHandler {
     }
 }
 impl EventHandler<ClickEvent> for ListItemHandler {
-    fn handle(&mut self, _: &ClickEvent, mut args: EventArgs) {
-        if !args.widget.props().contains(&Property::Selected) {
-            args.widget.add_prop(Property::Selected);
-            let event = ListItemSelected { widget: Some(args.widget) };
-            self.list_id.event(event);
-            *args.handled = true;
-        }
+    fn handle(&mut self, _: &ClickEvent, args: EventArgs) {
+        args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(self.list_id.clone())));
+        self.list_id.event(ListInputEvent::ItemClicked(args.widget));
+        *args.handled = true;
     }
 }
 
 pub struct ListBuilder {
     pub widget: WidgetBuilder,
+    selection_mode: SelectionMode,
+    selection: ListSelection,
 }
-widget_wrapper!(ListBuilder);
+widget_builder!(ListBuilder);
 
 impl ListBuilder {
     pub fn new() -> Self {
         let mut widget = WidgetBuilder::new("list");
         let layout_settings = LinearLayoutSettings::new(Orientation::Vertical);
-        widget.add_handler(ListHandler::new())
-              .add_handler_fn(list_handle_deselect)
+        widget.add_handler_fn(list_handle_deselect)
+              .add_handler_fn(|event: &WidgetKeyboardInput, args| {
+                  let &WidgetKeyboardInput(state, _, key) = event;
+                  args.widget.event(ListInputEvent::KeyboardInput(state, key));
+              })
               .linear_layout(layout_settings);
+        let selection = ListSelection::new(widget.widget_ref());
         ListBuilder {
             widget: widget,
+            selection_mode: SelectionMode::Single,
+            selection: selection,
         }
     }
+    pub fn set_selection_mode(&mut self, selection_mode: SelectionMode) -> &mut Self {
+        self.selection_mode = selection_mode;
+        self
+    }
+    /// Handle to the list's selection, to get the selected items at any time
+    pub fn selection(&self) -> ListSelection {
+        self.selection.clone()
+    }
     pub fn on_item_selected<F>(&mut self, on_item_selected: F) -> &mut Self
         where F: Fn(Option<WidgetRef>, EventArgs) + 'static
     {
@@ -100,6 +273,12 @@ impl ListBuilder {
         });
         self
     }
+    pub fn on_selection_changed<F>(&mut self, on_selection_changed: F) -> &mut Self
+        where F: Fn(&SelectionChanged, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(on_selection_changed);
+        self
+    }
     pub fn set_contents<C, I, F>(&mut self, contents: C, build: F)
         where C: Iterator<Item=I>,
               F: Fn(I, &mut ListBuilder) -> WidgetBuilder,
@@ -112,6 +291,65 @@ impl ListBuilder {
             self.widget.add_child(widget);
         }
     }
+    /// Add a section of items below `header`, eg. the contacts starting with a letter, the items are built like in
+    /// `set_contents`, and selected like the items outside of sections.
+    /// When the list is the content of a scroll widget, the header is kept at the top once the section is scrolled up,
+    /// until the next section's header pushes it out. It's only drawn there, see `WidgetRef::set_draw_offset`,
+    /// so it should have a background to cover the items it's drawn over.
+    pub fn add_section<C, I, F>(&mut self, mut header: WidgetBuilder, contents: C, build: F)
+        where C: Iterator<Item=I>,
+              F: Fn(I, &mut ListBuilder) -> WidgetBuilder,
+    {
+        let mut section = WidgetBuilder::new("list_section");
+        section.layout().add(match_width(&self.widget));
+        let mut items = WidgetBuilder::new("list_section_items");
+        items.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+        header.layout().add(constraints![
+            align_top(&section),
+            align_left(&section),
+            align_right(&section),
+        ]);
+        items.layout().add(constraints![
+            align_below(&header),
+            align_left(&section),
+            align_right(&section),
+            align_bottom(&section),
+        ]);
+        for item in contents {
+            let mut widget = build(item, self);
+            widget
+                .set_name("list_item")
+                .list_item(&self.widget.widget_ref());
+            items.add_child(widget);
+        }
+        self.selection.sections.borrow_mut().push(ListSection {
+            section: section.widget_ref(),
+            header: header.widget_ref(),
+            items: items.widget_ref(),
+        });
+        // the header is drawn over the items it's moved down to
+        section.add_child(items);
+        section.add_child(header);
+        self.widget.add_child(section);
+    }
+}
+
+impl Into<WidgetBuilder> for ListBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let sections = self.selection.sections.clone();
+        self.widget.add_handler_fn(move |event: &ScrollOffsetChanged, args| {
+            for section in sections.borrow().iter() {
+                let offset = sticky_header_offset(section.section.bounds(), section.header.bounds().height(), event.viewport.top());
+                let offset = Vector::new(0.0, offset);
+                if section.header.draw_offset() != offset {
+                    section.header.clone().set_draw_offset(offset);
+                    args.ui.redraw();
+                }
+            }
+        });
+        self.widget.add_handler(ListHandler::new(self.selection_mode, self.selection));
+        self.widget
+    }
 }
 
 impl WidgetBuilder {
@@ -0,0 +1,80 @@
+use event::EventArgs;
+use widget::WidgetBuilder;
+use widgets::context_menu::{ContextMenuBuilder, STYLE_MENU_ITEM, STYLE_MENU_TEXT};
+use widgets::text::TextBuilder;
+use draw::rect::RectState;
+use draw::text::TextStyle;
+use input::mouse::ClickEvent;
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use geometry::{Point, RectExt};
+use color::*;
+
+/// Sent by a menu bar when an item of one of its menus, or of their submenus, is selected
+#[derive(Clone, Debug)]
+pub struct MenuActionEvent {
+    /// Title of the menu the item is in
+    pub menu: String,
+    /// Label of the selected item
+    pub item: String,
+}
+
+/// A row of menu titles, usually along the top of the window, each opening a menu of actions below it when clicked.
+/// The menus are context menus, they close when an item is selected, or by pressing outside of them or escape.
+pub struct MenuBarBuilder {
+    pub widget: WidgetBuilder,
+}
+widget_wrapper!(MenuBarBuilder);
+
+impl MenuBarBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::with_draw_state("menu_bar", RectState { background_color: GRAY_90, ..RectState::default() });
+        widget.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        MenuBarBuilder { widget: widget }
+    }
+    /// Add a menu after the existing ones, `build_menu` adds the menu's items each time it opens.
+    /// Items selected in the menu send a `MenuActionEvent` to the menu bar, after their own action.
+    pub fn add_menu<F>(&mut self, title: &str, build_menu: F) -> &mut Self
+        where F: Fn(&mut ContextMenuBuilder) + 'static
+    {
+        let mut title_widget = WidgetBuilder::with_draw_state_and_style("menu_bar_title",
+            RectState::new(), STYLE_MENU_ITEM.clone());
+        let style = style!(parent: STYLE_MENU_TEXT, TextStyle::Text: title.to_owned());
+        let mut label = TextBuilder::new_with_style(style);
+        label.set_name("menu_bar_label");
+        let title_vars = title_widget.widget_ref().layout_vars();
+        label.add_theme_constraints(move |theme| constraints![
+            bound_by(&title_vars).padding(theme.scale(5.0)),
+            align_left(&title_vars).padding(theme.scale(10.0)),
+            align_right(&title_vars).padding(theme.scale(10.0)),
+        ]);
+        title_widget.add_child(label);
+        title_widget.enable_hover();
+
+        let bar_ref = self.widget.widget_ref();
+        let title = title.to_owned();
+        title_widget.add_handler_fn(move |_: &ClickEvent, args| {
+            let mut menu = ContextMenuBuilder::new();
+            build_menu(&mut menu);
+            let bar_ref = bar_ref.clone();
+            let title = title.clone();
+            menu.on_item_selected(move |item, _| {
+                bar_ref.event(MenuActionEvent {
+                    menu: title.clone(),
+                    item: item.to_owned(),
+                });
+            });
+            let bounds = args.widget.bounds();
+            menu.open(args.ui, Point::new(bounds.left(), bounds.bottom()));
+            *args.handled = true;
+        });
+        self.widget.add_child(title_widget);
+        self
+    }
+    pub fn on_action<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&MenuActionEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
@@ -1,8 +1,29 @@
 pub mod button;
+pub mod card;
+pub mod collapsible;
+pub mod color_picker;
+pub mod context_menu;
 pub mod scroll;
+pub mod separator;
+pub mod avatar;
+pub mod badge;
 pub mod drag;
+pub mod drag_number;
+pub mod error_boundary;
 pub mod list;
+pub mod menu_bar;
+pub mod notification;
 pub mod slider;
+pub mod spacer;
+pub mod spin_box;
+pub mod table;
 pub mod edit_text;
 pub mod image;
+pub mod palette;
+pub mod pie_menu;
+pub mod popup;
+pub mod split_pane;
 pub mod text;
+pub mod tree;
+pub mod validator;
+pub mod viewport;
@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::text::TextBuilder;
+use draw::rect::RectState;
+use draw::text::{TextState, TextStyle};
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use ui::{AnimationFrame, WidgetAttachedEvent};
+use event::{EventArgs, EventHandler};
+use color::*;
+
+/// Width of each banner
+const BANNER_WIDTH: f32 = 360.0;
+/// Space between the banners, and between the top of the window and the first banner
+const BANNER_SPACING: f32 = 8.0;
+const BANNER_PADDING: f32 = 10.0;
+/// Time taken for a banner to fade out once its timeout has passed
+const FADE_MILLIS: u64 = 300;
+
+/// What a notification is about, which decides the color of its banner
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationKind {
+    pub fn background_color(&self) -> Color {
+        match *self {
+            NotificationKind::Info => Color::from_rgba(0x2F, 0x6F, 0xC0, 0xFF),
+            NotificationKind::Success => Color::from_rgba(0x2E, 0x8B, 0x4A, 0xFF),
+            NotificationKind::Warning => Color::from_rgba(0xD0, 0x8A, 0x10, 0xFF),
+            NotificationKind::Error => Color::from_rgba(0xC0, 0x30, 0x30, 0xFF),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_secs() * 1000 + (duration.subsec_nanos() / 1_000_000) as u64
+}
+
+/// The opacity of a banner `elapsed` after it's shown, fully opaque until `timeout`,
+/// then fading out linearly, reaching zero once it's faded out and should be removed
+pub fn banner_opacity(elapsed: Duration, timeout: Duration) -> f32 {
+    if elapsed <= timeout {
+        return 1.0;
+    }
+    let fading = millis(elapsed - timeout);
+    (1.0 - fading as f32 / FADE_MILLIS as f32).max(0.0)
+}
+
+fn with_opacity(color: Color, opacity: f32) -> Color {
+    let (red, green, blue, alpha) = color.to_rgba();
+    Color::from_rgba(red, green, blue, (alpha as f32 * opacity).round() as u8)
+}
+
+/// A message shown in a colored banner, usually with `Ui::show_notification`, which stacks it
+/// below the top edge of the window with the other notifications.
+/// Banners with a timeout fade out and remove themselves once it's passed, the others stay until they're removed.
+pub struct NotificationBannerWidget {
+    pub message: String,
+    pub kind: NotificationKind,
+    pub timeout: Option<Duration>,
+}
+
+impl NotificationBannerWidget {
+    pub fn new(message: &str, kind: NotificationKind, timeout: Option<Duration>) -> Self {
+        NotificationBannerWidget {
+            message: message.to_owned(),
+            kind: kind,
+            timeout: timeout,
+        }
+    }
+}
+
+struct FadeHandler {
+    timeout: Duration,
+    elapsed: Duration,
+    background_color: Color,
+    text: WidgetRef,
+}
+
+impl EventHandler<AnimationFrame> for FadeHandler {
+    fn handle(&mut self, event: &AnimationFrame, mut args: EventArgs) {
+        self.elapsed += event.0;
+        let opacity = banner_opacity(self.elapsed, self.timeout);
+        if opacity == 0.0 {
+            args.ui.stop_animation(args.widget.id());
+            args.widget.remove_widget();
+        } else if opacity < 1.0 {
+            let background_color = with_opacity(self.background_color, opacity);
+            args.widget.update(|state: &mut RectState| state.background_color = background_color);
+            self.text.update(|state: &mut TextState| state.text_color = with_opacity(WHITE, opacity));
+        }
+    }
+}
+
+impl Into<WidgetBuilder> for NotificationBannerWidget {
+    fn into(self) -> WidgetBuilder {
+        let background_color = self.kind.background_color();
+        let mut widget = WidgetBuilder::with_draw_state("notification_banner", RectState {
+            background_color: background_color,
+            corner_radius: Some(4.0),
+            border: None,
+        });
+        widget.layout().add(width(BANNER_WIDTH));
+
+        let mut text = TextBuilder::new_with_style(style!(
+            TextStyle::Text: self.message,
+            TextStyle::TextColor: WHITE));
+        text.set_name("notification_text");
+        text.layout().add(constraints![
+            bound_by(&widget).padding(BANNER_PADDING),
+            align_left(&widget).padding(BANNER_PADDING),
+            align_top(&widget).padding(BANNER_PADDING),
+        ]);
+        if let Some(timeout) = self.timeout {
+            widget.add_handler(FadeHandler {
+                timeout: timeout,
+                elapsed: Duration::from_millis(0),
+                background_color: background_color,
+                text: text.widget_ref(),
+            });
+            widget.add_handler_fn(|_: &WidgetAttachedEvent, args| {
+                args.ui.start_animation(args.widget.id());
+            });
+        }
+        widget.add_child(text);
+        widget
+    }
+}
+
+/// Keeps the banners shown with `Ui::show_notification` in a column below the top edge of the window,
+/// on a layer over the rest of the root's children
+#[derive(Default)]
+pub struct NotificationManager {
+    // covers the root, holding the column of banners, created with the first banner
+    layer: Option<WidgetRef>,
+    column: Option<WidgetRef>,
+}
+
+impl NotificationManager {
+    pub fn new() -> Self {
+        NotificationManager::default()
+    }
+    /// Add `banner` below the banners already shown, returns the banner's widget, eg. to remove it early
+    pub fn show(&mut self, root: &mut WidgetRef, banner: NotificationBannerWidget) -> WidgetRef {
+        if self.layer.is_none() {
+            let mut layer = WidgetBuilder::new("notification_layer");
+            layer.layout().no_container();
+            let mut settings = LinearLayoutSettings::new(Orientation::Vertical);
+            settings.padding = BANNER_SPACING;
+            let mut column = WidgetBuilder::new("notification_column");
+            column.linear_layout(settings);
+            column.layout().add(constraints![
+                align_top(&layer).padding(BANNER_SPACING),
+                center_horizontal(&layer),
+                shrink(),
+            ]);
+            self.column = Some(column.widget_ref());
+            layer.add_child(column);
+            let layer: WidgetRef = layer.into();
+            root.add_child(layer.clone());
+            self.layer = Some(layer);
+        }
+        // keep the banners over widgets added to the root since the last one was shown
+        self.layer.as_mut().unwrap().bring_to_front();
+        let banner: WidgetBuilder = banner.into();
+        let banner_ref = banner.widget_ref();
+        self.column.as_mut().unwrap().add_child(banner);
+        banner_ref
+    }
+    /// The banners currently shown, from the top
+    pub fn banners(&self) -> Vec<WidgetRef> {
+        self.column.as_ref().map(|column| column.children()).unwrap_or_else(Vec::new)
+    }
+}
@@ -0,0 +1,247 @@
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use draw::rect::{RectState, RectStyle};
+use input::mouse::{ClickEvent, MouseOverEvent};
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use layout::LayoutUpdated;
+use geometry::{Size, RectExt};
+use color::*;
+
+/// Width and height of each swatch
+const SWATCH_SIZE: f32 = 24.0;
+/// Number of swatch columns before the palette's width is known
+const INITIAL_COLUMNS: usize = 8;
+/// Number of recently used colors shown below the palette
+pub const MAX_RECENT_COLORS: usize = 8;
+
+lazy_static! {
+    pub static ref STYLE_SWATCH: Vec<RectStyle> = {
+        style!(RectStyle::Border: selector!(None, SELECTED: Some((2.0, WHITE))))
+    };
+}
+
+/// Sent by a palette when the mouse moves over a swatch, to preview its color
+pub struct ColorHoveredEvent(pub Color);
+/// Sent by a palette when a swatch is clicked
+pub struct ColorChangedEvent(pub Color);
+
+// the number of swatch columns that fit in `width`, at least one
+fn palette_columns(width: f32) -> usize {
+    let columns = (width / SWATCH_SIZE).floor();
+    if columns >= 1.0 { columns as usize } else { 1 }
+}
+
+// move `color` to the front of the recently used colors,
+// without duplicates, dropping the oldest past `MAX_RECENT_COLORS`
+fn push_recent_color(recent: &mut Vec<Color>, color: Color) {
+    recent.retain(|recent_color| *recent_color != color);
+    recent.insert(0, color);
+    recent.truncate(MAX_RECENT_COLORS);
+}
+
+enum PaletteEvent {
+    LayoutUpdated,
+    Hover(Color),
+    Select(Color),
+}
+
+fn build_swatch(color: Color, selected: bool, palette: &WidgetRef) -> WidgetBuilder {
+    let style = style!(parent: STYLE_SWATCH, RectStyle::BackgroundColor: color);
+    let mut swatch = WidgetBuilder::with_draw_state_and_style("palette_swatch", RectState::new(), style);
+    if selected {
+        swatch.add_prop(Property::Selected);
+    }
+    let palette_ref = palette.clone();
+    swatch.add_handler_fn(move |event: &MouseOverEvent, _| {
+        if let MouseOverEvent::Over = *event {
+            palette_ref.event(PaletteEvent::Hover(color));
+        }
+    });
+    let palette_ref = palette.clone();
+    swatch.add_handler_fn(move |_: &ClickEvent, args| {
+        palette_ref.event(PaletteEvent::Select(color));
+        *args.handled = true;
+    });
+    swatch
+}
+
+// the swatches of a palette, with each swatch filling a cell of the grid
+fn build_grid(colors: &[Color], columns: usize, selected: Option<Color>, palette: &WidgetRef, holder: &WidgetRef)
+    -> (WidgetBuilder, Vec<(Color, WidgetRef)>)
+{
+    let mut grid = WidgetBuilder::new("palette_grid");
+    grid.grid(columns);
+    let rows = (colors.len() + columns - 1) / columns;
+    grid.layout().add(constraints![
+        match_layout(holder),
+        height(rows as f32 * SWATCH_SIZE),
+    ]);
+    let mut swatches = Vec::new();
+    for &color in colors {
+        let swatch = build_swatch(color, Some(color) == selected, palette);
+        swatches.push((color, swatch.widget_ref()));
+        grid.add_child(swatch);
+    }
+    (grid, swatches)
+}
+
+struct PaletteHandler {
+    colors: Vec<Color>,
+    recent: Vec<Color>,
+    selected: Option<Color>,
+    columns: usize,
+    grid_holder: WidgetRef,
+    grid: WidgetRef,
+    grid_swatches: Vec<(Color, WidgetRef)>,
+    recent_row: WidgetRef,
+    recent_swatches: Vec<(Color, WidgetRef)>,
+}
+impl PaletteHandler {
+    // the grid layout has a fixed number of columns, so the grid is replaced when the columns change
+    fn rebuild_grid(&mut self, palette: &WidgetRef) {
+        let (grid, swatches) = build_grid(&self.colors, self.columns, self.selected, palette, &self.grid_holder);
+        let old_grid = ::std::mem::replace(&mut self.grid, grid.widget_ref());
+        self.grid_holder.remove_child(old_grid);
+        self.grid_holder.add_child(grid);
+        self.grid_swatches = swatches;
+    }
+    fn rebuild_recent(&mut self, palette: &WidgetRef) {
+        for (_, swatch) in self.recent_swatches.drain(..) {
+            self.recent_row.remove_child(swatch);
+        }
+        for &color in &self.recent {
+            let mut swatch = build_swatch(color, Some(color) == self.selected, palette);
+            swatch.layout().add(size(Size::new(SWATCH_SIZE, SWATCH_SIZE)));
+            self.recent_swatches.push((color, swatch.widget_ref()));
+            self.recent_row.add_child(swatch);
+        }
+        let show = !self.recent.is_empty();
+        self.recent_row.update_layout(|layout| {
+            if show {
+                layout.show();
+            } else {
+                layout.hide();
+            }
+        });
+    }
+}
+impl EventHandler<PaletteEvent> for PaletteHandler {
+    fn handle(&mut self, event: &PaletteEvent, args: EventArgs) {
+        match *event {
+            PaletteEvent::LayoutUpdated => {
+                let columns = palette_columns(args.widget.bounds().width());
+                if columns != self.columns {
+                    self.columns = columns;
+                    self.rebuild_grid(&args.widget);
+                }
+            }
+            PaletteEvent::Hover(color) => {
+                args.widget.event(ColorHoveredEvent(color));
+            }
+            PaletteEvent::Select(color) => {
+                self.selected = Some(color);
+                for &mut (swatch_color, ref mut swatch) in &mut self.grid_swatches {
+                    if swatch_color == color {
+                        swatch.add_prop(Property::Selected);
+                    } else {
+                        swatch.remove_prop(Property::Selected);
+                    }
+                }
+                push_recent_color(&mut self.recent, color);
+                self.rebuild_recent(&args.widget);
+                args.widget.event(ColorChangedEvent(color));
+            }
+        }
+    }
+}
+
+/// A grid of color swatches to pick from, with a row of the recently picked colors below it.
+/// The number of columns adapts to the width of the palette.
+pub struct PaletteBuilder {
+    pub widget: WidgetBuilder,
+    colors: Vec<Color>,
+    selected: Option<Color>,
+}
+widget_builder!(PaletteBuilder);
+
+impl PaletteBuilder {
+    pub fn new(colors: Vec<Color>) -> Self {
+        let mut widget = WidgetBuilder::new("palette");
+        widget.layout().no_container();
+        widget.layout().add(shrink_vertical());
+        PaletteBuilder {
+            widget: widget,
+            colors: colors,
+            selected: None,
+        }
+    }
+    /// Select `color` initially, without sending a `ColorChangedEvent`
+    pub fn set_selected(&mut self, color: Color) -> &mut Self {
+        self.selected = Some(color);
+        self
+    }
+    pub fn on_color_hovered<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ColorHoveredEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    pub fn on_color_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ColorChangedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
+
+impl Into<WidgetBuilder> for PaletteBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let palette_ref = self.widget.widget_ref();
+        let columns = ::std::cmp::max(1, ::std::cmp::min(self.colors.len(), INITIAL_COLUMNS));
+
+        let mut grid_holder = WidgetBuilder::new("palette_grid_holder");
+        grid_holder.layout().no_container();
+        grid_holder.layout().add(constraints![
+            align_top(&self.widget),
+            align_left(&self.widget),
+            align_right(&self.widget),
+            bound_bottom(&self.widget),
+        ]);
+        let (grid, grid_swatches) = build_grid(&self.colors, columns, self.selected, &palette_ref, &grid_holder.widget_ref());
+
+        let mut recent_row = WidgetBuilder::new("palette_recent");
+        recent_row.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        recent_row.layout().add(constraints![
+            align_below(&grid_holder).padding(4.0),
+            align_left(&self.widget),
+            bound_right(&self.widget),
+            bound_bottom(&self.widget),
+            height(SWATCH_SIZE),
+        ]);
+        // shown once a color has been picked
+        recent_row.layout().hide();
+
+        self.widget.add_handler(PaletteHandler {
+            colors: self.colors,
+            recent: Vec::new(),
+            selected: self.selected,
+            columns: columns,
+            grid_holder: grid_holder.widget_ref(),
+            grid: grid.widget_ref(),
+            grid_swatches: grid_swatches,
+            recent_row: recent_row.widget_ref(),
+            recent_swatches: Vec::new(),
+        });
+        self.widget.add_handler_fn(|_: &LayoutUpdated, args| {
+            args.widget.event(PaletteEvent::LayoutUpdated);
+        });
+
+        grid_holder.add_child(grid);
+        self.widget.add_child(grid_holder);
+        self.widget.add_child(recent_row);
+        self.widget
+    }
+}
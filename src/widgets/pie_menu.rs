@@ -0,0 +1,197 @@
+use std::f32::consts::PI;
+
+use glutin;
+use cassowary::strength::*;
+use cassowary::WeightedRelation::*;
+
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use draw::rect::RectState;
+use draw::wedge::{WedgeState, WedgeStyle, clockwise_angle};
+use draw::text::TextStyle;
+use input::mouse::{ClickEvent, WidgetMouseButton};
+use input::keyboard::{WidgetKeyboardInput, KeyboardInputEvent};
+use layout::constraint::*;
+use layout::LAYOUT;
+use geometry::{Point, Size, Vector};
+use ui::Ui;
+use color::*;
+
+/// Radius of the hole in the middle of a pie menu, as a fraction of the menu's radius
+const PIE_INNER_RADIUS: f32 = 0.3;
+
+static COLOR_PIE_WEDGE: Color = GRAY_80;
+static COLOR_PIE_WEDGE_MOUSEOVER: Color = GRAY_60;
+
+lazy_static! {
+    pub static ref STYLE_PIE_WEDGE: Vec<WedgeStyle> = {
+        style!(
+            WedgeStyle::BackgroundColor: selector!(COLOR_PIE_WEDGE,
+                MOUSEOVER: COLOR_PIE_WEDGE_MOUSEOVER),
+            WedgeStyle::InnerRadius: PIE_INNER_RADIUS)
+    };
+    pub static ref STYLE_PIE_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: BLACK)
+    };
+}
+
+/// Sent by a pie menu when one of its items is selected, with the index of the item
+pub struct PieItemSelectedEvent(pub usize);
+
+/// The angles, clockwise from straight up, between which item `index` of a pie menu with `count` items is drawn.
+/// Item 0 is centered straight up, the rest follow clockwise.
+pub fn wedge_angles(index: usize, count: usize) -> (f32, f32) {
+    let wedge_angle = 2.0 * PI / count as f32;
+    let middle = index as f32 * wedge_angle;
+    (middle - wedge_angle / 2.0, middle + wedge_angle / 2.0)
+}
+
+/// The item of a pie menu with `count` items at `offset` from the center of the menu, if any
+pub fn wedge_at(offset: Vector, inner_radius: f32, outer_radius: f32, count: usize) -> Option<usize> {
+    let distance = offset.length();
+    if count == 0 || distance < inner_radius || distance > outer_radius {
+        return None;
+    }
+    let wedge_angle = 2.0 * PI / count as f32;
+    let index = ((clockwise_angle(offset) + wedge_angle / 2.0) / wedge_angle) as usize;
+    Some(index % count)
+}
+
+fn number_key_index(key: glutin::VirtualKeyCode) -> Option<usize> {
+    let keys = [
+        glutin::VirtualKeyCode::Key1, glutin::VirtualKeyCode::Key2, glutin::VirtualKeyCode::Key3,
+        glutin::VirtualKeyCode::Key4, glutin::VirtualKeyCode::Key5, glutin::VirtualKeyCode::Key6,
+        glutin::VirtualKeyCode::Key7, glutin::VirtualKeyCode::Key8, glutin::VirtualKeyCode::Key9,
+    ];
+    keys.iter().position(|number_key| *number_key == key)
+}
+
+enum PieMenuEvent {
+    Select(usize),
+    Close,
+}
+
+struct PieMenuHandler {
+    item_count: usize,
+}
+impl EventHandler<PieMenuEvent> for PieMenuHandler {
+    fn handle(&mut self, event: &PieMenuEvent, args: EventArgs) {
+        if let PieMenuEvent::Select(index) = *event {
+            if index >= self.item_count {
+                return;
+            }
+            args.widget.event(PieItemSelectedEvent(index));
+        }
+        args.ui.get_root().event(KeyboardInputEvent::FocusChange(None));
+        if let Some(mut parent) = args.widget.parent() {
+            parent.remove_child(args.widget.clone());
+        }
+    }
+}
+
+/// A ring of options around a point, usually opened at the cursor.
+/// An item is selected by clicking it, releasing the mouse over it, or pressing its number key.
+pub struct PieMenuBuilder {
+    pub widget: WidgetBuilder,
+    items: Vec<String>,
+    radius: f32,
+}
+
+impl PieMenuBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("pie_menu");
+        // covers the whole window, so a press anywhere outside the ring closes the menu
+        widget.set_draw_state(RectState { background_color: TRANSPARENT, ..RectState::default() });
+        widget.layout().no_container();
+        PieMenuBuilder {
+            widget: widget,
+            items: Vec::new(),
+            radius: 100.0,
+        }
+    }
+    pub fn add_item(&mut self, label: &str) -> &mut Self {
+        self.items.push(label.to_owned());
+        self
+    }
+    pub fn set_radius(&mut self, radius: f32) -> &mut Self {
+        self.radius = radius;
+        self
+    }
+    pub fn on_item_selected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&PieItemSelectedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    /// Show the menu centered on `position`, above every other widget,
+    /// until an item is selected, or the menu is dismissed by pressing outside of it or escape.
+    pub fn open(mut self, ui: &mut Ui, position: Point) -> WidgetRef {
+        let radius = self.radius;
+        let item_count = self.items.len();
+        let menu_ref = self.widget.widget_ref();
+
+        let mut ring = WidgetBuilder::new("pie_menu_ring");
+        ring.layout().add(constraints![
+            top_left(Point::new(position.x - radius, position.y - radius)),
+            size(Size::new(radius * 2.0, radius * 2.0)),
+        ]);
+        // presses on the wedges shouldn't reach the menu and close it
+        ring.add_handler_fn(|_: &WidgetMouseButton, args| {
+            *args.handled = true;
+        });
+
+        let label_radius = radius * (1.0 + PIE_INNER_RADIUS) / 2.0;
+        for (index, item) in self.items.drain(..).enumerate() {
+            let (start_angle, end_angle) = wedge_angles(index, item_count);
+            let mut wedge = WidgetBuilder::with_draw_state_and_style(&format!("pie_menu_wedge_{}", index),
+                WedgeState::new(start_angle, end_angle), STYLE_PIE_WEDGE.clone());
+            wedge.enable_hover();
+            // labels are centered in their wedge, but can extend past the ring's bounds
+            wedge.layout().no_container();
+            let menu_ref = menu_ref.clone();
+            wedge.add_handler_fn(move |_: &ClickEvent, args| {
+                menu_ref.event(PieMenuEvent::Select(index));
+                *args.handled = true;
+            });
+
+            let style = style!(parent: STYLE_PIE_TEXT, TextStyle::Text: item);
+            let mut label = TextBuilder::new_with_style(style);
+            label.set_name("pie_menu_label");
+            let angle = (start_angle + end_angle) / 2.0;
+            let label_center = Point::new(radius + angle.sin() * label_radius, radius - angle.cos() * label_radius);
+            {
+                let ref ring = ring.layout().vars;
+                label.layout().add(constraints![
+                    LAYOUT.left + LAYOUT.right | EQ(REQUIRED) | ring.left * 2.0 + (label_center.x * 2.0) as f64,
+                    LAYOUT.top + LAYOUT.bottom | EQ(REQUIRED) | ring.top * 2.0 + (label_center.y * 2.0) as f64,
+                ]);
+            }
+            wedge.add_child(label);
+            ring.add_child(wedge);
+        }
+
+        self.widget.add_handler(PieMenuHandler { item_count: item_count });
+        self.widget.add_handler_fn(|event: &WidgetMouseButton, args| {
+            if let WidgetMouseButton(glutin::ElementState::Pressed, _) = *event {
+                args.widget.event(PieMenuEvent::Close);
+            }
+        });
+        self.widget.add_handler_fn(|event: &WidgetKeyboardInput, args| {
+            if let WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(key)) = *event {
+                if key == glutin::VirtualKeyCode::Escape {
+                    args.widget.event(PieMenuEvent::Close);
+                } else if let Some(index) = number_key_index(key) {
+                    args.widget.event(PieMenuEvent::Select(index));
+                }
+            }
+        });
+        self.widget.add_child(ring);
+
+        // the last child of the root is drawn over everything else
+        ui.get_root().add_child(self.widget);
+        ui.get_root().event(KeyboardInputEvent::FocusChange(Some(menu_ref.clone())));
+        menu_ref
+    }
+}
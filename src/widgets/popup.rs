@@ -0,0 +1,232 @@
+//! Widgets shown next to another widget, over the rest of the UI, eg. the list of a dropdown
+//! or the calendar of a date picker, that close when the mouse is pressed outside of them.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use glutin;
+
+use widget::{WidgetBuilder, WidgetRef};
+use event::EventArgs;
+use draw::rect::RectState;
+use input::mouse::WidgetMouseButton;
+use layout::LayoutUpdated;
+use layout::constraint::*;
+use geometry::{Point, Rect, RectExt, Size};
+use resources::WidgetId;
+use ui::{Ui, WidgetDetachedEvent};
+use color::*;
+
+/// Which side of its anchor a popup is shown on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Below,
+    Above,
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn opposite(&self) -> Side {
+        match *self {
+            Side::Below => Side::Above,
+            Side::Above => Side::Below,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+    // the top left corner of a popup of `size` on this side of `anchor`, lined up with its left or top edge
+    fn place(&self, anchor: Rect, size: Size) -> Point {
+        match *self {
+            Side::Below => Point::new(anchor.left(), anchor.bottom()),
+            Side::Above => Point::new(anchor.left(), anchor.top() - size.height),
+            Side::Left => Point::new(anchor.left() - size.width, anchor.top()),
+            Side::Right => Point::new(anchor.right(), anchor.top()),
+        }
+    }
+    // if a popup placed on this side at `position` is inside the window, along the axis it's placed on
+    fn fits(&self, position: Point, size: Size, window: Size) -> bool {
+        match *self {
+            Side::Below | Side::Above => position.y >= 0.0 && position.y + size.height <= window.height,
+            Side::Left | Side::Right => position.x >= 0.0 && position.x + size.width <= window.width,
+        }
+    }
+}
+
+/// The top left corner of a popup of `size`, shown on `side` of `anchor` in a window of size `window`.
+/// If it doesn't fit there, it's moved to the opposite side if it fits there instead,
+/// then it's moved back inside the window if it's past an edge, as far as it can be.
+pub fn popup_position(anchor: Rect, size: Size, side: Side, window: Size) -> Point {
+    let mut position = side.place(anchor, size);
+    if !side.fits(position, size, window) {
+        let opposite = side.opposite().place(anchor, size);
+        if side.opposite().fits(opposite, size, window) {
+            position = opposite;
+        }
+    }
+    Point::new(
+        position.x.min(window.width - size.width).max(0.0),
+        position.y.min(window.height - size.height).max(0.0))
+}
+
+/// Sent to a popup to close it, eg. once an item of a dropdown's list is selected
+pub struct DismissPopup;
+
+// sent to a popup when the layout of its anchor changes
+struct AnchorLayoutUpdated;
+
+// sent to an anchor when a popup is opened next to it, with the ids of the popup and its overlay
+struct PopupOpened(WidgetId, WidgetId);
+
+// give `anchor` handlers that pass its layout changes and removal on to the popups opened next to it,
+// only the first time a popup is opened there, by id so the anchor doesn't keep them alive
+fn track_anchor(anchor: &mut WidgetRef) {
+    if anchor.handler_types().contains(&TypeId::of::<PopupOpened>()) {
+        return;
+    }
+    let popups: Rc<RefCell<Vec<(WidgetId, WidgetId)>>> = Rc::new(RefCell::new(Vec::new()));
+    {
+        let popups = popups.clone();
+        anchor.add_handler_fn(move |event: &PopupOpened, args| {
+            let PopupOpened(popup_id, overlay_id) = *event;
+            let mut popups = popups.borrow_mut();
+            // forget the popups that have been closed since
+            popups.retain(|&(id, _)| args.ui.get_widget(id).is_some());
+            popups.push((popup_id, overlay_id));
+        });
+    }
+    {
+        let popups = popups.clone();
+        anchor.add_handler_fn(move |_: &LayoutUpdated, args| {
+            let mut popups = popups.borrow_mut();
+            popups.retain(|&(id, _)| {
+                if let Some(popup) = args.ui.get_widget(id) {
+                    popup.event(AnchorLayoutUpdated);
+                    true
+                } else {
+                    false
+                }
+            });
+        });
+    }
+    anchor.add_handler_fn(move |_: &WidgetDetachedEvent, args| {
+        for (_, overlay_id) in popups.borrow_mut().drain(..) {
+            if let Some(overlay) = args.ui.get_widget(overlay_id) {
+                overlay.event(DismissPopup);
+            }
+        }
+    });
+}
+
+// move `popup` to `side` of the widget `anchor`, if it's been laid out somewhere else
+fn place_popup(args: EventArgs, anchor: WidgetId, side: Side) {
+    if let Some(anchor_bounds) = args.ui.get_widget_bounds(anchor) {
+        let bounds = args.widget.bounds();
+        let position = popup_position(anchor_bounds, bounds.size, side, args.ui.get_root_dims());
+        if (position.x - bounds.left()).abs() > 0.5 || (position.y - bounds.top()).abs() > 0.5 {
+            args.widget.update_layout(|layout| {
+                layout.edit_left().set(position.x);
+                layout.edit_top().set(position.y);
+            });
+        }
+    }
+}
+
+/// An open popup, returned by `PopupBuilder::open`
+#[derive(Clone)]
+pub struct PopupWidget {
+    widget: WidgetRef,
+}
+
+impl PopupWidget {
+    /// The widget covering the window that holds the popup, the popup is removed with it
+    pub fn widget_ref(&self) -> WidgetRef {
+        self.widget.clone()
+    }
+    pub fn id(&self) -> WidgetId {
+        self.widget.id()
+    }
+    /// Close the popup, the same as pressing outside of it
+    pub fn dismiss(&self) {
+        self.widget.event(DismissPopup);
+    }
+}
+
+/// A panel shown next to an anchor widget, over every other widget, add its contents to `widget`.
+/// It follows the anchor when it moves or resizes. It's closed when the mouse is pressed outside of it,
+/// when it's sent `DismissPopup`, or when the anchor is removed.
+pub struct PopupBuilder {
+    pub widget: WidgetBuilder,
+    anchor: Option<(WidgetId, Side)>,
+    on_dismiss: Option<Box<Fn(&mut Ui)>>,
+}
+
+impl PopupBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("popup");
+        widget.layout().add(shrink());
+        PopupBuilder {
+            widget: widget,
+            anchor: None,
+            on_dismiss: None,
+        }
+    }
+    /// Show the popup on `side` of the widget `anchor_id`, on the opposite side if there's only room there
+    pub fn anchor(&mut self, anchor_id: WidgetId, side: Side) -> &mut Self {
+        self.anchor = Some((anchor_id, side));
+        self
+    }
+    /// Called when the popup is closed, by pressing outside of it or with `DismissPopup`
+    pub fn on_dismiss<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&mut Ui) + 'static
+    {
+        self.on_dismiss = Some(Box::new(callback));
+        self
+    }
+    /// Show the popup next to its anchor, above every other widget, even those added after it
+    pub fn open(mut self, ui: &mut Ui) -> PopupWidget {
+        let (anchor, side) = self.anchor.expect("Popup has no anchor");
+        // covers the whole window, so a press anywhere outside the popup closes it
+        let mut overlay = WidgetBuilder::new("popup_overlay");
+        overlay
+            .set_draw_state(RectState { background_color: TRANSPARENT, ..RectState::default() })
+            .set_keep_on_top(true);
+        overlay.layout().no_container();
+        let overlay_ref = overlay.widget_ref();
+
+        // placed once its size is known, and again whenever it or the anchor changes
+        let anchor_bounds = ui.get_widget_bounds(anchor).unwrap_or_else(Rect::zero);
+        let position = popup_position(anchor_bounds, Size::zero(), side, ui.get_root_dims());
+        self.widget.layout().edit_left().set(position.x);
+        self.widget.layout().edit_top().set(position.y);
+        self.widget.add_handler_fn(move |_: &LayoutUpdated, args| place_popup(args, anchor, side));
+        self.widget.add_handler_fn(move |_: &AnchorLayoutUpdated, args| place_popup(args, anchor, side));
+        if let Some(mut anchor_ref) = ui.get_widget(anchor) {
+            track_anchor(&mut anchor_ref);
+            anchor_ref.event(PopupOpened(self.widget.id(), overlay_ref.id()));
+        }
+        // presses inside the popup shouldn't reach the overlay and close it
+        self.widget.add_handler_fn(|_: &WidgetMouseButton, args| {
+            *args.handled = true;
+        });
+
+        let on_dismiss = self.on_dismiss;
+        overlay.add_handler_fn(move |_: &DismissPopup, args| {
+            if let Some(ref on_dismiss) = on_dismiss {
+                on_dismiss(args.ui);
+            }
+            args.ui.remove_widget(args.widget.id());
+        });
+        overlay.add_handler_fn(|event: &WidgetMouseButton, args| {
+            if let WidgetMouseButton(glutin::ElementState::Pressed, _) = *event {
+                args.widget.event(DismissPopup);
+            }
+        });
+        overlay.add_child(self.widget);
+
+        // the last child of the root is drawn over everything else, it's kept last
+        ui.get_root().add_child(overlay);
+        PopupWidget { widget: overlay_ref }
+    }
+}
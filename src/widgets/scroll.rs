@@ -7,16 +7,24 @@ use event::{EventArgs, EventHandler};
 use widget::{WidgetBuilder, WidgetRef};
 use widgets::slider::{SliderBuilder, SetSliderValue};
 use geometry::{Size, Vector, Rect, RectExt};
-use layout::{LayoutUpdated, LAYOUT};
+use layout::{LayoutUpdated, LayoutVars, LAYOUT};
+use layout::linear_layout::Orientation;
 use input::mouse::WidgetMouseWheel;
 use draw::rect::{RectState, RectStyle};
 use color::*;
 
+/// Height of the shadows cast on the content by a pinned header or footer
+const PINNED_SHADOW_HEIGHT: f32 = 3.0;
+
 pub struct ScrollBuilder {
     widget: WidgetBuilder,
     content_holder: WidgetBuilder,
     content: Option<WidgetBuilder>,
     scrollbars: Option<(WidgetBuilder, SliderBuilder, SliderBuilder)>,
+    header: Option<WidgetBuilder>,
+    footer: Option<WidgetBuilder>,
+    near_end_distance: Option<f32>,
+    alignment: (ContentAlignment, ContentAlignment),
 }
 impl ScrollBuilder {
     pub fn new() -> Self {
@@ -30,6 +38,10 @@ impl ScrollBuilder {
             content_holder: content_holder,
             content: None,
             scrollbars: None,
+            header: None,
+            footer: None,
+            near_end_distance: None,
+            alignment: (ContentAlignment::Start, ContentAlignment::Start),
         }
     }
     pub fn add_content<C: Into<WidgetBuilder>>(&mut self, widget: C) -> &mut Self {
@@ -43,7 +55,6 @@ impl ScrollBuilder {
         scrollbar_h.layout().add(constraints![
             align_bottom(&self.widget),
             align_left(&self.widget),
-            align_below(&self.content_holder),
         ]);
         let mut scrollbar_v = SliderBuilder::new();
         scrollbar_v.set_name("scrollbar_v");
@@ -63,8 +74,7 @@ impl ScrollBuilder {
             widget_ref.event(ScrollParentEvent::ScrollBarMovedY(value));
         });
         let corner_style = style!(RectStyle::BackgroundColor: GRAY_70);
-        let mut corner = WidgetBuilder::new("corner");
-        corner.set_draw_state_with_style(RectState::new(), corner_style);
+        let mut corner = WidgetBuilder::with_draw_state_and_style("corner", RectState::new(), corner_style);
         corner.layout().add(constraints![
             align_bottom(&self.widget),
             align_right(&self.widget),
@@ -77,6 +87,42 @@ impl ScrollBuilder {
         self.scrollbars = Some((corner, scrollbar_h, scrollbar_v));
         self
     }
+    /// Pin `header` above the content, it stays at the top of the scroll widget while the content scrolls
+    /// vertically, and casts a shadow on the content once it's scrolled down.
+    /// It scrolls horizontally with the content, its left edge following the content's, so the columns of a header row
+    /// stay aligned with the columns of the content, eg. the header of a table, see `TableBuilder::into_header_and_body`.
+    pub fn set_header<C: Into<WidgetBuilder>>(&mut self, header: C) -> &mut Self {
+        self.header = Some(header.into());
+        self
+    }
+    /// Pin `footer` below the content, like a header set with `set_header`, it casts a shadow on the content
+    /// until it's scrolled to the end
+    pub fn set_footer<C: Into<WidgetBuilder>>(&mut self, footer: C) -> &mut Self {
+        self.footer = Some(footer.into());
+        self
+    }
+    /// Where to place content that is smaller than the scroll widget, horizontally and vertically.
+    /// Once the content is larger it scrolls as usual.
+    pub fn set_content_alignment(&mut self, horizontal: ContentAlignment, vertical: ContentAlignment) -> &mut Self {
+        self.alignment = (horizontal, vertical);
+        self
+    }
+    /// Send `ScrollNearEndEvent` when the content is scrolled to within `distance` of its end
+    pub fn set_near_end_distance(&mut self, distance: f32) -> &mut Self {
+        self.near_end_distance = Some(distance);
+        self
+    }
+    /// Called when the content is scrolled near its end, see `set_near_end_distance`.
+    /// Uses a distance of 100 unless one has been set.
+    pub fn on_scroll_near_end<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ScrollNearEndEvent, EventArgs) + 'static
+    {
+        if self.near_end_distance.is_none() {
+            self.near_end_distance = Some(100.0);
+        }
+        self.widget.add_handler_fn(callback);
+        self
+    }
 }
 impl Into<WidgetBuilder> for ScrollBuilder {
     fn into(mut self) -> WidgetBuilder {
@@ -85,6 +131,7 @@ impl Into<WidgetBuilder> for ScrollBuilder {
             widget_ref.event(ScrollParentEvent::ContainerLayoutUpdated);
         });
         let mut content = self.content.expect("Scroll bar has no content");
+        let content_vars = content.layout().vars.clone();
         let widget_ref = self.content_holder.widget_ref();
         content.add_handler_fn(move |_: &LayoutUpdated, args| {
             widget_ref.event(ScrollParentEvent::ContentLayoutUpdated(args.widget.bounds()));
@@ -93,10 +140,13 @@ impl Into<WidgetBuilder> for ScrollBuilder {
             match_layout(&self.widget).strength(STRONG)
         ]);
         {
+            // content that's aligned away from the start can be moved past the top left corner of the holder
+            let bound_left = if self.alignment.0 == ContentAlignment::Start { REQUIRED } else { STRONG };
+            let bound_top = if self.alignment.1 == ContentAlignment::Start { REQUIRED } else { STRONG };
             let ref content_holder = self.content_holder.layout().vars;
             content.layout().add(constraints![
-                LAYOUT.left | LE(REQUIRED) | content_holder.left,
-                LAYOUT.top | LE(REQUIRED) | content_holder.top,
+                LAYOUT.left | LE(bound_left) | content_holder.left,
+                LAYOUT.top | LE(bound_top) | content_holder.top,
                 LAYOUT.left | EQ(WEAK) | content_holder.left,
                 LAYOUT.top | EQ(WEAK) | content_holder.top,
                 LAYOUT.right | GE(STRONG) | content_holder.right,
@@ -105,25 +155,64 @@ impl Into<WidgetBuilder> for ScrollBuilder {
         }
 
         let mut scroll_parent_handler = ScrollParent::new(&mut content.widget_ref());
+        scroll_parent_handler.alignment = self.alignment;
         if let Some((ref mut corner, ref mut scrollbar_h, ref mut scrollbar_v)) = self.scrollbars {
             scroll_parent_handler.scrollbars = Some(ScrollBars::new(scrollbar_h, scrollbar_v, corner.widget_ref()));
         }
+        if let Some(distance) = self.near_end_distance {
+            scroll_parent_handler.near_end = Some((NearEndTrigger::new(distance), NearEndTrigger::new(distance)));
+        }
+        let holder_vars = self.content_holder.layout().vars.clone();
+        let mut header = self.header.map(|header| pinned_row("scroll_header", header, &holder_vars, &content_vars));
+        let mut footer = self.footer.map(|footer| pinned_row("scroll_footer", footer, &holder_vars, &content_vars));
+        let header_shadow = header.as_ref().map(|header| {
+            let mut shadow = pinned_shadow("scroll_header_shadow", &holder_vars);
+            shadow.layout().add(align_below(header));
+            shadow
+        });
+        let footer_shadow = footer.as_ref().map(|footer| {
+            let mut shadow = pinned_shadow("scroll_footer_shadow", &holder_vars);
+            shadow.layout().add(align_above(footer));
+            shadow
+        });
+        scroll_parent_handler.pinned_shadows = (
+            header_shadow.as_ref().map(|shadow| shadow.widget_ref()),
+            footer_shadow.as_ref().map(|shadow| shadow.widget_ref()));
         self.content_holder.add_handler(scroll_parent_handler);
         self.content_holder.add_handler_fn(|event: &WidgetMouseWheel, args| {
             args.widget.event(ScrollParentEvent::WidgetMouseWheel(event.clone()));
         });
         self.content_holder.add_child(content);
-        if self.scrollbars.is_some() {
-            self.content_holder.layout().add(constraints![
-                align_left(&self.widget),
-                align_top(&self.widget),
-            ]);
+
+        // from the top: the header, the content holder, the footer, then the horizontal scrollbar
+        self.content_holder.layout().add(align_left(&self.widget));
+        if self.scrollbars.is_none() {
+            self.content_holder.layout().add(align_right(&self.widget));
+        }
+        if let Some(ref mut header) = header {
+            header.layout().add(align_top(&self.widget));
+            self.content_holder.layout().add(align_below(header));
         } else {
-            self.content_holder.layout().add(constraints![
-                match_layout(&self.widget),
-            ]);
+            self.content_holder.layout().add(align_top(&self.widget));
+        }
+        if let Some(ref mut footer) = footer {
+            footer.layout().add(align_below(&self.content_holder));
+        }
+        if let Some((_, ref mut scrollbar_h, _)) = self.scrollbars {
+            scrollbar_h.layout().add(align_below(footer.as_ref().unwrap_or(&self.content_holder)));
+        } else if let Some(ref mut footer) = footer {
+            footer.layout().add(align_bottom(&self.widget));
+        } else {
+            self.content_holder.layout().add(align_bottom(&self.widget));
         }
+
         self.widget.add_child(self.content_holder);
+        // the pinned rows and their shadows are drawn over the content
+        for pinned in vec![header, footer, header_shadow, footer_shadow] {
+            if let Some(pinned) = pinned {
+                self.widget.add_child(pinned);
+            }
+        }
         if let Some((corner, scrollbar_h, scrollbar_v)) = self.scrollbars {
             self.widget.add_child(corner);
             self.widget.add_child(scrollbar_h);
@@ -134,6 +223,45 @@ impl Into<WidgetBuilder> for ScrollBuilder {
 }
 widget_builder!(ScrollBuilder);
 
+// holds a pinned header or footer, as wide as the content holder, so the row is cropped to it as it scrolls horizontally
+fn pinned_row(name: &str, mut row: WidgetBuilder, content_holder: &LayoutVars, content: &LayoutVars) -> WidgetBuilder {
+    let mut holder = WidgetBuilder::new(name);
+    holder.layout().no_container();
+    holder.layout().add(constraints![
+        align_left(content_holder),
+        align_right(content_holder),
+        match_height(&row),
+    ]);
+    row.layout().add(constraints![
+        align_top(&holder),
+        LAYOUT.left | EQ(REQUIRED) | content.left,
+    ]);
+    holder.add_child(row);
+    holder
+}
+
+fn pinned_shadow(name: &str, content_holder: &LayoutVars) -> WidgetBuilder {
+    let mut shadow = WidgetBuilder::with_draw_state(name, RectState {
+        background_color: SHADOW,
+        corner_radius: None,
+        border: None,
+    });
+    shadow.layout().add(constraints![
+        align_left(content_holder),
+        align_right(content_holder),
+        height(PINNED_SHADOW_HEIGHT),
+    ]);
+    shadow.layout().hide();
+    shadow
+}
+
+// whether the shadows of a pinned header and footer are shown, given the distance the content is scrolled
+// and how far it can be scrolled in total. The header's shadow is shown once the content is scrolled from the start,
+// and the footer's until it's scrolled to the end, so neither is shown for content that doesn't need scrolling.
+fn pinned_shadows(scrolled: f32, scrollable_length: f32) -> (bool, bool) {
+    (scrolled > 0.5, scrolled < scrollable_length - 0.5)
+}
+
 #[allow(dead_code)]
 struct ScrollBars {
     scrollbar_h: WidgetRef,
@@ -154,6 +282,90 @@ impl ScrollBars {
     }
 }
 
+/// Where content that is smaller than its scroll widget is placed, along one axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContentAlignment {
+    Start,
+    Center,
+    End,
+}
+impl ContentAlignment {
+    // the offset of the content along one axis, given its current offset and how far it can be scrolled.
+    // content smaller than the scroll widget has a negative `scrollable_length`, and is aligned,
+    // otherwise the current offset is kept within the scrollable range
+    fn content_offset(&self, offset: f32, scrollable_length: f32) -> f32 {
+        if scrollable_length < 0.0 {
+            let extra_space = -scrollable_length;
+            match *self {
+                ContentAlignment::Start => 0.0,
+                ContentAlignment::Center => extra_space / 2.0,
+                ContentAlignment::End => extra_space,
+            }
+        } else {
+            f32::min(0.0, f32::max(-scrollable_length, offset))
+        }
+    }
+}
+
+/// Sent by a scroll widget when its content is scrolled to within the near end distance of its end.
+/// Sent once each time the end is approached, again only after scrolling away or the content growing.
+#[derive(Clone, Debug)]
+pub struct ScrollNearEndEvent {
+    pub axis: Orientation,
+    /// Distance left to scroll before the end of the content
+    pub remaining_px: f32,
+}
+
+// decides when to send a `ScrollNearEndEvent` for one axis of a scroll widget
+#[derive(Clone, Debug)]
+struct NearEndTrigger {
+    distance: f32,
+    armed: bool,
+    scrollable_length: f32,
+}
+impl NearEndTrigger {
+    fn new(distance: f32) -> Self {
+        NearEndTrigger {
+            distance: distance,
+            armed: true,
+            scrollable_length: 0.0,
+        }
+    }
+    // update with the distance scrolled, and how far the content can be scrolled in total.
+    // Returns the remaining distance if the end has just been approached.
+    // Content that doesn't need scrolling never triggers.
+    fn update(&mut self, scrolled: f32, scrollable_length: f32) -> Option<f32> {
+        if scrollable_length > self.scrollable_length {
+            self.armed = true;
+        }
+        self.scrollable_length = scrollable_length;
+        if scrollable_length <= 0.0 {
+            return None;
+        }
+        let remaining = f32::max(0.0, scrollable_length - scrolled);
+        if remaining > self.distance {
+            self.armed = true;
+            None
+        } else if self.armed {
+            self.armed = false;
+            Some(remaining)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sent to the content of a scroll widget after it's laid out at a new offset, or the area it's visible in changes,
+/// so once per frame while it's scrolling. Lets the content adjust to the part of it that's visible,
+/// eg. a list keeping the header of the section at the top in view, see `ListBuilder::add_section`.
+#[derive(Clone, Debug)]
+pub struct ScrollOffsetChanged {
+    /// The position of the content's top left corner relative to the visible area's, negative once it's scrolled
+    pub offset: Vector,
+    /// The area of the scroll widget the content is visible in, below a pinned header and above a pinned footer
+    pub viewport: Rect,
+}
+
 enum ScrollParentEvent {
     ContainerLayoutUpdated,
     ContentLayoutUpdated(Rect),
@@ -170,6 +382,12 @@ struct ScrollParent {
     scrollable_area: Size,
     offset: Vector,
     pub scrollbars: Option<ScrollBars>,
+    near_end: Option<(NearEndTrigger, NearEndTrigger)>,
+    alignment: (ContentAlignment, ContentAlignment),
+    // the shadows of the pinned header and footer
+    pinned_shadows: (Option<WidgetRef>, Option<WidgetRef>),
+    // the offset and viewport last sent to the content
+    sent_offset: Option<(Vector, Rect)>,
 }
 impl ScrollParent {
     fn new(scrollable: &mut WidgetRef) -> Self {
@@ -182,6 +400,24 @@ impl ScrollParent {
             scrollable_area: Size::zero(),
             offset: Vector::zero(),
             scrollbars: None,
+            near_end: None,
+            alignment: (ContentAlignment::Start, ContentAlignment::Start),
+            pinned_shadows: (None, None),
+            sent_offset: None,
+        }
+    }
+    // move content smaller than the container to its alignment, and content that has grown
+    // past the container back inside the scrollable range
+    fn align_content(&mut self) {
+        let offset_x = self.alignment.0.content_offset(self.offset.x, self.scrollable_area.width);
+        if (offset_x - self.offset.x).abs() > 0.5 {
+            self.offset.x = offset_x;
+            self.move_content_x();
+        }
+        let offset_y = self.alignment.1.content_offset(self.offset.y, self.scrollable_area.height);
+        if (offset_y - self.offset.y).abs() > 0.5 {
+            self.offset.y = offset_y;
+            self.move_content_y();
         }
     }
     fn move_content_x(&mut self) {
@@ -202,6 +438,34 @@ impl ScrollParent {
             scrollbars.scrollbar_h.event(SetSliderValue(offset_x));
         }
     }
+    // `scroll_widget` is the parent of the content holder, which receives the events
+    fn check_near_end(&mut self, scroll_widget: Option<WidgetRef>) {
+        if let (Some(ref mut near_end), Some(scroll_widget)) = (self.near_end.as_mut(), scroll_widget) {
+            if let Some(remaining) = near_end.0.update(-self.offset.x, self.scrollable_area.width) {
+                scroll_widget.event(ScrollNearEndEvent { axis: Orientation::Horizontal, remaining_px: remaining });
+            }
+            if let Some(remaining) = near_end.1.update(-self.offset.y, self.scrollable_area.height) {
+                scroll_widget.event(ScrollNearEndEvent { axis: Orientation::Vertical, remaining_px: remaining });
+            }
+        }
+    }
+    // called once the content is laid out, so its bounds match the offset
+    fn send_offset(&mut self) {
+        let content_offset = self.content_rect.origin - self.container_rect.origin;
+        if self.sent_offset != Some((content_offset, self.container_rect)) {
+            self.sent_offset = Some((content_offset, self.container_rect));
+            self.scrollable.event(ScrollOffsetChanged { offset: content_offset, viewport: self.container_rect });
+        }
+    }
+    fn update_pinned_shadows(&mut self) {
+        let (header_shadow, footer_shadow) = pinned_shadows(-self.offset.y, self.scrollable_area.height);
+        if let Some(ref mut shadow) = self.pinned_shadows.0 {
+            set_shown(shadow, header_shadow);
+        }
+        if let Some(ref mut shadow) = self.pinned_shadows.1 {
+            set_shown(shadow, footer_shadow);
+        }
+    }
     fn move_slider_y(&mut self) {
         if let Some(ref mut scrollbars) = self.scrollbars {
             let offset_y = -self.offset.y / self.scrollable_area.height;
@@ -226,6 +490,7 @@ impl EventHandler<ScrollParentEvent> for ScrollParent {
                 if content_offset != self.offset || scrollable_area != self.scrollable_area {
                     self.offset = content_offset;
                     self.scrollable_area = scrollable_area;
+                    self.align_content();
                     if self.scrollable_area.width > 0.0 {
                         self.move_slider_x();
                     }
@@ -288,6 +553,9 @@ impl EventHandler<ScrollParentEvent> for ScrollParent {
                 }
                 self.width_ratio = width_ratio;
                 self.height_ratio = height_ratio;
+                self.check_near_end(args.widget.parent());
+                self.update_pinned_shadows();
+                self.send_offset();
             }
             ScrollParentEvent::WidgetMouseWheel(ref mouse_wheel) => {
                 let scroll = get_scroll(mouse_wheel.0);
@@ -301,18 +569,29 @@ impl EventHandler<ScrollParentEvent> for ScrollParent {
                     self.move_content_y();
                     self.move_slider_y();
                 }
+                self.check_near_end(args.widget.parent());
+                self.update_pinned_shadows();
             }
             ScrollParentEvent::ScrollBarMovedX(ref offset) => {
                 self.offset.x = -offset * self.scrollable_area.width;
                 self.move_content_x();
+                self.check_near_end(args.widget.parent());
+                self.update_pinned_shadows();
             }
             ScrollParentEvent::ScrollBarMovedY(ref offset) => {
                 self.offset.y = -offset * self.scrollable_area.height;
                 self.move_content_y();
+                self.check_near_end(args.widget.parent());
+                self.update_pinned_shadows();
             }
         }
     }
 }
+fn set_shown(widget: &mut WidgetRef, shown: bool) {
+    if widget.layout().hidden == shown {
+        widget.update_layout(|layout| if shown { layout.show() } else { layout.hide() });
+    }
+}
 fn get_scroll(event: glutin::MouseScrollDelta) -> Vector {
     let vec = match event {
         glutin::MouseScrollDelta::LineDelta(x, y) => {
@@ -0,0 +1,88 @@
+use webrender_api::PrimitiveInfo;
+
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use layout::linear_layout::Orientation;
+use widget::WidgetBuilder;
+use widget::draw::Draw;
+use render::RenderBuilder;
+use ui::WidgetAttachedEvent;
+use geometry::{Rect, RectExt};
+use color::*;
+
+/// Draws a line filling its bounds, the thickness is kept by the separator's layout
+pub struct SeparatorWidget {
+    pub orientation: Orientation,
+    pub color: Color,
+    pub thickness: f32,
+}
+
+impl Draw for SeparatorWidget {
+    fn draw(&mut self, bounds: Rect, _: Rect, renderer: &mut RenderBuilder) {
+        let info = PrimitiveInfo::new(bounds.typed());
+        renderer.builder.push_rect(&info, self.color.into());
+    }
+}
+
+/// A line dividing sections of a menu, toolbar or panel. A horizontal separator
+/// is as wide as its parent, a vertical one is as tall as its parent.
+pub struct SeparatorBuilder {
+    pub widget: WidgetBuilder,
+    orientation: Orientation,
+    color: Color,
+    thickness: f32,
+}
+
+impl SeparatorBuilder {
+    pub fn new(orientation: Orientation) -> Self {
+        SeparatorBuilder {
+            widget: WidgetBuilder::new("separator"),
+            orientation: orientation,
+            color: GRAY_70,
+            thickness: 1.0,
+        }
+    }
+    pub fn horizontal() -> Self {
+        SeparatorBuilder::new(Orientation::Horizontal)
+    }
+    pub fn vertical() -> Self {
+        SeparatorBuilder::new(Orientation::Vertical)
+    }
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.color = color;
+        self
+    }
+    pub fn set_thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = thickness;
+        self
+    }
+}
+
+widget_builder!(SeparatorBuilder);
+impl Into<WidgetBuilder> for SeparatorBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let orientation = self.orientation;
+        match orientation {
+            Orientation::Horizontal => self.widget.layout().add(height(self.thickness).strength(REQUIRED)),
+            Orientation::Vertical => self.widget.layout().add(width(self.thickness).strength(REQUIRED)),
+        }
+        // the parent isn't known until the separator is added to it
+        self.widget.add_handler_fn(move |_: &WidgetAttachedEvent, args| {
+            if let Some(parent) = args.widget.parent() {
+                args.widget.update_layout(|layout| {
+                    match orientation {
+                        Orientation::Horizontal => layout.add(match_width(&parent).strength(STRONG)),
+                        Orientation::Vertical => layout.add(match_height(&parent).strength(STRONG)),
+                    }
+                });
+            }
+        });
+        self.widget.set_draw_state(SeparatorWidget {
+            orientation: self.orientation,
+            color: self.color,
+            thickness: self.thickness,
+        });
+        self.widget
+    }
+}
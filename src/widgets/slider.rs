@@ -1,9 +1,11 @@
 use std::ops::Range;
 
+use glutin;
 use cassowary::strength::*;
 
 use layout::constraint::*;
 use input::mouse::ClickEvent;
+use input::keyboard::WidgetKeyboardInput;
 use event::{EventHandler, EventArgs};
 use widget::{WidgetBuilder, WidgetRef};
 use widget::property::Property;
@@ -44,6 +46,11 @@ pub struct SliderBuilder {
     pub handle_color: Color,
     pub highlight: Option<Color>,
     pub width: f32,
+    /// How much the arrow keys change the value, ten times as much with shift held.
+    /// Defaults to a hundredth of the range.
+    pub step: Option<f32>,
+    /// Whether the slider is in the tab order, and can be adjusted with the arrow keys
+    pub keyboard_focusable: bool,
 }
 
 impl SliderBuilder {
@@ -65,6 +72,8 @@ impl SliderBuilder {
             handle_color: GRAY_80,
             highlight: Some(BLUE_HIGHLIGHT),
             width: 30.0,
+            step: None,
+            keyboard_focusable: true,
         }
     }
     pub fn make_vertical(&mut self) -> &mut Self {
@@ -80,6 +89,7 @@ impl SliderBuilder {
         self.handle_color = GRAY_70;
         self.highlight = None;
         self.width = 15.0;
+        self.keyboard_focusable = false;
         self
     }
     pub fn set_width(&mut self, width: f32) -> &mut Self {
@@ -94,6 +104,10 @@ impl SliderBuilder {
         self.range = range;
         self
     }
+    pub fn set_step(&mut self, step: f32) -> &mut Self {
+        self.step = Some(step);
+        self
+    }
     pub fn on_value_changed<F>(&mut self, on_value_changed: F) -> &mut Self
         where F: Fn(f32, &mut EventArgs) + 'static
     {
@@ -138,11 +152,8 @@ impl Into<WidgetBuilder> for SliderBuilder {
         } else {
             bar_style.clone()
         };
-        let mut slider_bar_pre = WidgetBuilder::new("slider_bar_pre");
-        slider_bar_pre.set_draw_state_with_style(RectState::new(), pre_style);
-
-        let mut slider_bar_post = WidgetBuilder::new("slider_bar_post");
-        slider_bar_post.set_draw_state_with_style(RectState::new(), bar_style);
+        let mut slider_bar_pre = WidgetBuilder::with_draw_state_and_style("slider_bar_pre", RectState::new(), pre_style);
+        let mut slider_bar_post = WidgetBuilder::with_draw_state_and_style("slider_bar_post", RectState::new(), bar_style);
 
         let (bar_width, bar_padding) = match self.bar_style {
             BarStyle::Wide => (self.width, 0.0),
@@ -218,6 +229,12 @@ impl Into<WidgetBuilder> for SliderBuilder {
         widget.add_handler_fn(move |event: &SetSliderValue, args| {
             args.widget.event(SliderInputEvent::SetValue(event.0));
         });
+        if self.keyboard_focusable {
+            let step = self.step.unwrap_or((self.range.end - self.range.start) / 100.0);
+            widget
+                .make_tab_focusable()
+                .add_default_handler(SliderKeyHandler { orientation: orientation, step: step });
+        }
         let widget_ref = widget.widget_ref();
         widget.add_handler(SliderHandler::new(orientation, self.range, widget_ref.clone(), slider_handle.widget_ref()));
 
@@ -245,6 +262,32 @@ enum SliderInputEvent {
     Drag(DragEvent),
     Click(Point),
     SetValue(f32),
+    // change the value by this much, from the arrow keys
+    Step(f32),
+}
+
+// the arrow keys move the handle of a focused slider in their direction, by a step, or ten with shift held
+struct SliderKeyHandler {
+    orientation: Orientation,
+    step: f32,
+}
+impl EventHandler<WidgetKeyboardInput> for SliderKeyHandler {
+    fn handle(&mut self, event: &WidgetKeyboardInput, args: EventArgs) {
+        let &WidgetKeyboardInput(state, _, key) = event;
+        let direction = match (key, self.orientation) {
+            (Some(glutin::VirtualKeyCode::Left), _) => -1.0,
+            (Some(glutin::VirtualKeyCode::Right), _) => 1.0,
+            (Some(glutin::VirtualKeyCode::Up), Orientation::Horizontal) => 1.0,
+            (Some(glutin::VirtualKeyCode::Down), Orientation::Horizontal) => -1.0,
+            (Some(glutin::VirtualKeyCode::Up), Orientation::Vertical) => -1.0,
+            (Some(glutin::VirtualKeyCode::Down), Orientation::Vertical) => 1.0,
+            _ => return,
+        };
+        if state == glutin::ElementState::Pressed {
+            let steps = if args.ui.shift_pressed() { 10.0 } else { 1.0 };
+            args.widget.event(SliderInputEvent::Step(direction * steps * self.step));
+        }
+    }
 }
 
 struct SliderHandler {
@@ -313,6 +356,12 @@ impl SliderHandler {
         let max = slider_range.end - handle_size / 2.0;
         f32::min(f32::max(handle_pos, min), max)
     }
+    fn move_handle_to_value(&self, value: f32) {
+        let value = (value - self.range.start) / (self.range.end - self.range.start);
+        let range_of_motion = self.slider_size() - self.handle_size();
+        let handle_start = self.slider_range().start + value * range_of_motion;
+        self.move_handle_to(handle_start);
+    }
     fn move_handle_to(&self, handle_start: f32) {
         self.handle_ref.update_layout(|layout| {
             if let Orientation::Horizontal = self.orientation {
@@ -340,6 +389,15 @@ impl EventHandler<SliderInputEvent> for SliderHandler {
                 if *state == DragState::Start {
                     self.drag_start_pos = self.handle_range().start;
                     self.drag_start_val = self.get_value(self.handle_range().start + self.handle_size() / 2.0);
+                } else if *state == DragState::Cancel {
+                    self.move_handle_to(self.drag_start_pos);
+                    let event = SliderEvent {
+                        value: self.drag_start_val,
+                        offset: 0.0,
+                        dragging: false,
+                    };
+                    self.slider_ref.event(event);
+                    self.last_val = self.drag_start_val;
                 } else {
                     let handle_start = self.drag_start_pos + offset;
                     self.move_handle_to(handle_start);
@@ -381,12 +439,24 @@ impl EventHandler<SliderInputEvent> for SliderHandler {
             SliderInputEvent::SetValue(value) => {
                 if value.is_finite() {
                     self.last_val = value;
-                    let value = (value - self.range.start) / (self.range.end - self.range.start);
-                    let range_of_motion = self.slider_size() - self.handle_size();
-                    let handle_start = self.slider_range().start + value * range_of_motion;
-                    self.move_handle_to(handle_start);
+                    self.move_handle_to_value(value);
                 }
             }
+            SliderInputEvent::Step(change) => {
+                if args.widget.props().contains(&Property::Inactive) {
+                    return;
+                }
+                let (min, max) = (self.range.start.min(self.range.end), self.range.start.max(self.range.end));
+                let value = (self.last_val + change).max(min).min(max);
+                self.move_handle_to_value(value);
+                let event = SliderEvent {
+                    value: value,
+                    offset: value - self.last_val,
+                    dragging: false,
+                };
+                self.slider_ref.event(event);
+                self.last_val = value;
+            }
         }
     }
 }
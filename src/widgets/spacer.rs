@@ -0,0 +1,42 @@
+use cassowary::strength::*;
+
+use layout::constraint::*;
+use widget::WidgetBuilder;
+use geometry::Size;
+
+/// Empty space in a layout, it isn't drawn and doesn't handle any events
+pub struct SpacerBuilder {
+    pub widget: WidgetBuilder,
+}
+widget_wrapper!(SpacerBuilder);
+
+impl SpacerBuilder {
+    /// Space of a fixed size
+    pub fn fixed(width: f32, height: f32) -> Self {
+        let mut widget = WidgetBuilder::new("spacer");
+        widget.layout().add(size(Size::new(width, height)));
+        SpacerBuilder { widget: widget }
+    }
+    /// In a horizontal `LinearLayout`, takes up the width left over by the other items,
+    /// eg. to push the items after it to the right end
+    pub fn expand_horizontal() -> Self {
+        let mut widget = WidgetBuilder::new("spacer");
+        {
+            let mut layout = widget.layout();
+            layout.set_flex_weight(1.0);
+            // not required, so it can be stretched by `ItemAlignment::Fill`
+            layout.add(height(0.0).strength(STRONG));
+        }
+        SpacerBuilder { widget: widget }
+    }
+    /// In a vertical `LinearLayout`, takes up the height left over by the other items
+    pub fn expand_vertical() -> Self {
+        let mut widget = WidgetBuilder::new("spacer");
+        {
+            let mut layout = widget.layout();
+            layout.set_flex_weight(1.0);
+            layout.add(width(0.0).strength(STRONG));
+        }
+        SpacerBuilder { widget: widget }
+    }
+}
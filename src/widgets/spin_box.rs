@@ -0,0 +1,224 @@
+use std::cell::Cell;
+
+use glutin;
+
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation, ItemAlignment};
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widgets::button::{PushButtonBuilder, HoldRepeatEvent};
+use widgets::edit_text::{EditTextBuilder, TextUpdated};
+use input::keyboard::WidgetKeyboardInput;
+use draw::text::TextState;
+
+// the value and settings of a spin box, also used by drag numbers
+#[derive(Clone, Copy)]
+pub(crate) struct SpinBoxWidget {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    // formats the value for display, the displayed text has to parse back to a value
+    pub format: fn(f64) -> String,
+}
+
+// rounded to hide the error added up by repeatedly adding steps like 0.1
+fn default_format(value: f64) -> String {
+    format!("{}", (value * 1_000_000.0).round() / 1_000_000.0)
+}
+
+impl Default for SpinBoxWidget {
+    fn default() -> Self {
+        SpinBoxWidget {
+            value: 0.0,
+            min: ::std::f64::MIN,
+            max: ::std::f64::MAX,
+            step: 1.0,
+            format: default_format,
+        }
+    }
+}
+impl SpinBoxWidget {
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.max(self.min).min(self.max)
+    }
+    // the value after moving `steps` steps up, or down if negative, clamped to the range
+    pub fn stepped(&self, steps: f64) -> f64 {
+        self.clamp(self.value + steps * self.step)
+    }
+    // the value entered as `text`, clamped to the range, or the current value if it isn't a number
+    pub fn parse(&self, text: &str) -> f64 {
+        match text.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() => self.clamp(value),
+            _ => self.value,
+        }
+    }
+    pub fn text(&self) -> String {
+        (self.format)(self.value)
+    }
+}
+
+/// Sent from a spin box when its value changes, by the buttons, the keyboard, or entering a new value
+#[derive(Debug, Clone)]
+pub struct SpinBoxValueChangedEvent(pub f64);
+
+/// Set the value of a spin box, clamped to its range
+pub struct SetSpinBoxValue(pub f64);
+
+#[derive(Debug)]
+enum SpinBoxInputEvent {
+    Step(f64),
+    // the text was edited and the spin box lost focus, or enter was pressed
+    Parse,
+    SetValue(f64),
+}
+
+/// A numeric value shown in an edit text, with buttons to step it down and up, that keep stepping while held.
+/// The arrow keys also step the value while the text is focused, ten steps at a time with shift held.
+pub struct SpinBoxBuilder {
+    pub widget: WidgetBuilder,
+    pub edit_text: EditTextBuilder,
+    spin_box: SpinBoxWidget,
+}
+
+impl SpinBoxBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("spin_box");
+        let mut layout_settings = LinearLayoutSettings::new(Orientation::Horizontal);
+        layout_settings.item_align = ItemAlignment::Fill;
+        layout_settings.padding = 2.0;
+        widget.linear_layout(layout_settings);
+
+        let mut edit_text = EditTextBuilder::new();
+        edit_text.set_input_filter(|char| char.is_digit(10) || "+-.eE".contains(char));
+        edit_text.add_theme_constraints(|theme| constraints![
+            min_width(theme.scale(60.0)),
+            min_height(theme.scale(30.0)),
+        ]);
+        SpinBoxBuilder {
+            widget: widget,
+            edit_text: edit_text,
+            spin_box: SpinBoxWidget::default(),
+        }
+    }
+    pub fn range(&mut self, min: f64, max: f64) -> &mut Self {
+        self.spin_box.min = min;
+        self.spin_box.max = max;
+        self
+    }
+    pub fn step(&mut self, step: f64) -> &mut Self {
+        self.spin_box.step = step;
+        self
+    }
+    pub fn initial(&mut self, value: f64) -> &mut Self {
+        self.spin_box.value = value;
+        self
+    }
+    pub fn set_format(&mut self, format: fn(f64) -> String) -> &mut Self {
+        self.spin_box.format = format;
+        self
+    }
+    pub fn on_value_changed<F>(&mut self, on_value_changed: F) -> &mut Self
+        where F: Fn(f64, &mut EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(move |event: &SpinBoxValueChangedEvent, mut args| {
+            on_value_changed(event.0, &mut args);
+        });
+        self
+    }
+}
+
+widget_builder!(SpinBoxBuilder);
+impl Into<WidgetBuilder> for SpinBoxBuilder {
+    fn into(self) -> WidgetBuilder {
+        let (mut widget, mut edit_text, mut spin_box) = (self.widget, self.edit_text, self.spin_box);
+        spin_box.value = spin_box.clamp(spin_box.value);
+        let widget_ref = widget.widget_ref();
+
+        edit_text.text_widget.set_draw_state(TextState::new(&spin_box.text()));
+        let spin_box_ref = widget_ref.clone();
+        let shift = Cell::new(false);
+        edit_text.add_default_handler_fn(move |event: &WidgetKeyboardInput, _| {
+            if let WidgetKeyboardInput(state, _, Some(key)) = *event {
+                let pressed = state == glutin::ElementState::Pressed;
+                // ten steps at a time with shift held
+                let steps = if shift.get() { 10.0 } else { 1.0 };
+                match key {
+                    glutin::VirtualKeyCode::LShift | glutin::VirtualKeyCode::RShift => shift.set(pressed),
+                    glutin::VirtualKeyCode::Up if pressed => spin_box_ref.event(SpinBoxInputEvent::Step(steps)),
+                    glutin::VirtualKeyCode::Down if pressed => spin_box_ref.event(SpinBoxInputEvent::Step(-steps)),
+                    glutin::VirtualKeyCode::Return if pressed => spin_box_ref.event(SpinBoxInputEvent::Parse),
+                    _ => (),
+                }
+            }
+        });
+        let spin_box_ref = widget_ref.clone();
+        edit_text.on_text_committed(move |_, _| {
+            spin_box_ref.event(SpinBoxInputEvent::Parse);
+        });
+
+        let mut down_button = PushButtonBuilder::with_label("-");
+        down_button
+            .enable_repeat_on_hold()
+            .set_name("spin_box_down");
+        let spin_box_ref = widget_ref.clone();
+        down_button.add_handler_fn(move |_: &HoldRepeatEvent, _| {
+            spin_box_ref.event(SpinBoxInputEvent::Step(-1.0));
+        });
+        let mut up_button = PushButtonBuilder::with_label("+");
+        up_button
+            .enable_repeat_on_hold()
+            .set_name("spin_box_up");
+        let spin_box_ref = widget_ref.clone();
+        up_button.add_handler_fn(move |_: &HoldRepeatEvent, _| {
+            spin_box_ref.event(SpinBoxInputEvent::Step(1.0));
+        });
+
+        widget.add_handler_fn(|event: &SetSpinBoxValue, args| {
+            args.widget.event(SpinBoxInputEvent::SetValue(event.0));
+        });
+        widget.add_handler(SpinBoxHandler {
+            spin_box: spin_box,
+            text_widget: edit_text.text_widget.widget_ref(),
+        });
+
+        widget
+            .add_child(edit_text)
+            .add_child(down_button)
+            .add_child(up_button);
+        widget
+    }
+}
+
+struct SpinBoxHandler {
+    spin_box: SpinBoxWidget,
+    text_widget: WidgetRef,
+}
+impl EventHandler<SpinBoxInputEvent> for SpinBoxHandler {
+    fn handle(&mut self, event: &SpinBoxInputEvent, args: EventArgs) {
+        if args.widget.props().contains(&Property::Inactive) {
+            return;
+        }
+        let text = self.text_widget.drawable::<TextState>().map_or(String::new(), |state| state.text.clone());
+        let old_value = self.spin_box.value;
+        let value = match *event {
+            SpinBoxInputEvent::Step(steps) => {
+                // a value that was typed but not entered yet is stepped from
+                self.spin_box.value = self.spin_box.parse(&text);
+                self.spin_box.stepped(steps)
+            }
+            SpinBoxInputEvent::Parse => self.spin_box.parse(&text),
+            SpinBoxInputEvent::SetValue(value) => self.spin_box.clamp(value),
+        };
+        self.spin_box.value = value;
+        // also replaces text that wasn't a valid number
+        let value_text = self.spin_box.text();
+        if value_text != text {
+            self.text_widget.event(TextUpdated(value_text));
+        }
+        if value != old_value {
+            args.widget.event(SpinBoxValueChangedEvent(value));
+        }
+    }
+}
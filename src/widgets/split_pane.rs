@@ -0,0 +1,218 @@
+use layout::constraint::*;
+use layout::linear_layout::Orientation;
+use layout::LayoutUpdated;
+use event::{EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::drag::{DragEvent, DragState};
+use draw::rect::{RectState, RectStyle};
+use geometry::RectExt;
+use color::*;
+
+/// Two panes side by side (`Horizontal`) or stacked (`Vertical`), separated by
+/// a splitter that can be dragged to change the share of space given to each.
+pub struct SplitPaneBuilder {
+    pub widget: WidgetBuilder,
+    orientation: Orientation,
+    first: Option<WidgetBuilder>,
+    second: Option<WidgetBuilder>,
+    min_sizes: (f32, f32),
+    splitter_size: f32,
+    ratio: f32,
+}
+
+impl SplitPaneBuilder {
+    pub fn new(orientation: Orientation) -> Self {
+        let mut widget = WidgetBuilder::new("split_pane");
+        widget.layout().no_container();
+        SplitPaneBuilder {
+            widget: widget,
+            orientation: orientation,
+            first: None,
+            second: None,
+            min_sizes: (0.0, 0.0),
+            splitter_size: 6.0,
+            ratio: 0.5,
+        }
+    }
+    /// Set the left or top pane
+    pub fn set_first<C: Into<WidgetBuilder>>(&mut self, widget: C) -> &mut Self {
+        self.first = Some(widget.into());
+        self
+    }
+    /// Set the right or bottom pane
+    pub fn set_second<C: Into<WidgetBuilder>>(&mut self, widget: C) -> &mut Self {
+        self.second = Some(widget.into());
+        self
+    }
+    /// Minimum size of each pane along the split axis, the splitter can't be dragged past these
+    pub fn set_min_sizes(&mut self, first: f32, second: f32) -> &mut Self {
+        self.min_sizes = (first, second);
+        self
+    }
+    /// Initial share of the space given to the first pane, from 0.0 to 1.0
+    pub fn set_ratio(&mut self, ratio: f32) -> &mut Self {
+        self.ratio = ratio;
+        self
+    }
+    pub fn set_splitter_size(&mut self, splitter_size: f32) -> &mut Self {
+        self.splitter_size = splitter_size;
+        self
+    }
+}
+
+widget_builder!(SplitPaneBuilder);
+impl Into<WidgetBuilder> for SplitPaneBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let mut first = self.first.expect("Split pane has no first pane");
+        let mut second = self.second.expect("Split pane has no second pane");
+
+        let mut splitter = WidgetBuilder::new("splitter");
+        splitter
+            .set_draw_state_with_style(RectState::new(), style!(RectStyle::BackgroundColor: GRAY_70))
+            .hit_padding(4.0);
+
+        let (min_first, min_second) = self.min_sizes;
+        match self.orientation {
+            Orientation::Horizontal => {
+                first.layout().add(constraints![
+                    align_left(&self.widget),
+                    align_top(&self.widget),
+                    align_bottom(&self.widget),
+                    min_width(min_first),
+                ]);
+                splitter.layout().add(constraints![
+                    align_to_right_of(&first),
+                    align_top(&self.widget),
+                    align_bottom(&self.widget),
+                    width(self.splitter_size),
+                ]);
+                second.layout().add(constraints![
+                    align_to_right_of(&splitter),
+                    align_right(&self.widget),
+                    align_top(&self.widget),
+                    align_bottom(&self.widget),
+                    min_width(min_second),
+                ]);
+            }
+            Orientation::Vertical => {
+                first.layout().add(constraints![
+                    align_top(&self.widget),
+                    align_left(&self.widget),
+                    align_right(&self.widget),
+                    min_height(min_first),
+                ]);
+                splitter.layout().add(constraints![
+                    align_below(&first),
+                    align_left(&self.widget),
+                    align_right(&self.widget),
+                    height(self.splitter_size),
+                ]);
+                second.layout().add(constraints![
+                    align_below(&splitter),
+                    align_bottom(&self.widget),
+                    align_left(&self.widget),
+                    align_right(&self.widget),
+                    min_height(min_second),
+                ]);
+            }
+        }
+
+        let widget_ref = self.widget.widget_ref();
+        splitter
+            .add_handler_fn(move |event: &DragEvent, _| {
+                widget_ref.event(SplitPaneEvent::Drag(event.clone()));
+            })
+            .make_draggable();
+        self.widget.add_handler_fn(|_: &LayoutUpdated, args| {
+            args.widget.event(SplitPaneEvent::LayoutUpdated);
+        });
+        self.widget.add_handler(SplitPaneHandler {
+            orientation: self.orientation,
+            ratio: self.ratio,
+            min_sizes: self.min_sizes,
+            splitter_size: self.splitter_size,
+            first: first.widget_ref(),
+            drag_start_size: 0.0,
+        });
+
+        self.widget.add_child(first);
+        self.widget.add_child(second);
+        self.widget.add_child(splitter);
+        self.widget
+    }
+}
+
+enum SplitPaneEvent {
+    Drag(DragEvent),
+    LayoutUpdated,
+}
+
+struct SplitPaneHandler {
+    orientation: Orientation,
+    ratio: f32,
+    min_sizes: (f32, f32),
+    splitter_size: f32,
+    first: WidgetRef,
+    drag_start_size: f32,
+}
+impl SplitPaneHandler {
+    // space shared by the two panes
+    fn available_size(&self, split_pane: &WidgetRef) -> f32 {
+        let bounds = split_pane.bounds();
+        let size = match self.orientation {
+            Orientation::Horizontal => bounds.width(),
+            Orientation::Vertical => bounds.height(),
+        };
+        f32::max(0.0, size - self.splitter_size)
+    }
+    fn first_size(&self) -> f32 {
+        let bounds = self.first.bounds();
+        match self.orientation {
+            Orientation::Horizontal => bounds.width(),
+            Orientation::Vertical => bounds.height(),
+        }
+    }
+    fn resize_first(&mut self, available: f32, size: f32) {
+        let (min_first, min_second) = self.min_sizes;
+        let max = f32::max(min_first, available - min_second);
+        let size = f32::min(f32::max(size, min_first), max);
+        if available > 0.0 {
+            self.ratio = size / available;
+        }
+        let orientation = self.orientation;
+        self.first.update_layout(|layout| {
+            if let Orientation::Horizontal = orientation {
+                layout.edit_width().set(size);
+            } else {
+                layout.edit_height().set(size);
+            }
+        });
+    }
+}
+impl EventHandler<SplitPaneEvent> for SplitPaneHandler {
+    fn handle(&mut self, event: &SplitPaneEvent, args: EventArgs) {
+        let available = self.available_size(&args.widget);
+        match *event {
+            SplitPaneEvent::LayoutUpdated => {
+                // keep the same ratio as the split pane is resized
+                let size = self.ratio * available;
+                self.resize_first(available, size);
+            }
+            SplitPaneEvent::Drag(ref event) => {
+                let offset = match self.orientation {
+                    Orientation::Horizontal => event.offset.x,
+                    Orientation::Vertical => event.offset.y,
+                };
+                if event.state == DragState::Start {
+                    self.drag_start_size = self.first_size();
+                } else if event.state == DragState::Cancel {
+                    let size = self.drag_start_size;
+                    self.resize_first(available, size);
+                } else {
+                    let size = self.drag_start_size + offset;
+                    self.resize_first(available, size);
+                }
+            }
+        }
+    }
+}
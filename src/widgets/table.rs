@@ -0,0 +1,858 @@
+use std::rc::Rc;
+use std::cmp::{self, Ordering};
+use std::time::{Duration, Instant};
+
+use glutin;
+use cassowary::strength::*;
+
+use event::{self, Target, EventHandler, EventArgs};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use widgets::edit_text::{EditTextBuilder, DOUBLE_CLICK_MILLIS};
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextState;
+use input::mouse::ClickEvent;
+use input::keyboard::{WidgetKeyboardInput, KeyboardInputEvent};
+use layout::LayoutUpdated;
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use widgets::scroll::ScrollOffsetChanged;
+use geometry::{Rect, RectExt};
+use ui::Ui;
+use color::*;
+
+const ROW_HEIGHT: f32 = 36.0;
+const CELL_PADDING: f32 = 5.0;
+
+/// The direction rows are sorted in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Compares the values of two cells of a column, to sort the rows by
+pub type CellComparator = Rc<Fn(&str, &str) -> Ordering>;
+/// Decides from the values of a row's cells whether the row is shown
+pub type RowFilter = Rc<Fn(&[String]) -> bool>;
+
+/// The cells of a table, the order they're shown in, and which one is being edited, kept in step with the table's widgets.
+/// Rows are identified by the index they're stored at, which is the order they were added in,
+/// less any rows removed before them, whatever order they're shown in.
+pub struct TableModel {
+    rows: Vec<Vec<String>>,
+    editable: Vec<bool>,
+    comparators: Vec<Option<CellComparator>>,
+    sort: Option<(usize, SortOrder)>,
+    filter: Option<RowFilter>,
+    // the rows shown, sorted and filtered, in the order they're shown
+    view: Vec<usize>,
+    editing: Option<(usize, usize)>,
+}
+
+impl TableModel {
+    /// A table without rows, with a column for each entry of `editable`, which is whether that column's cells can be edited
+    pub fn new(editable: Vec<bool>) -> Self {
+        let columns = editable.len();
+        TableModel {
+            rows: Vec::new(),
+            editable: editable,
+            comparators: vec![None; columns],
+            sort: None,
+            filter: None,
+            view: Vec::new(),
+            editing: None,
+        }
+    }
+    pub fn columns(&self) -> usize {
+        self.editable.len()
+    }
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+    pub fn cell(&self, row: usize, col: usize) -> &str {
+        &self.rows[row][col]
+    }
+    pub fn row(&self, row: usize) -> &[String] {
+        &self.rows[row]
+    }
+    /// Add a row after the last one, missing cells are empty and extra cells are dropped
+    pub fn add_row(&mut self, mut cells: Vec<String>) {
+        cells.resize(self.columns(), String::new());
+        self.rows.push(cells);
+        self.update_view();
+    }
+    /// Remove a row, editing of a cell in it stops without committing,
+    /// and the cell being edited in a later row moves up with its row
+    pub fn remove_row(&mut self, row: usize) {
+        self.rows.remove(row);
+        self.editing = match self.editing {
+            Some((editing_row, _)) if editing_row == row => None,
+            Some((editing_row, col)) if editing_row > row => Some((editing_row - 1, col)),
+            editing => editing,
+        };
+        self.update_view();
+    }
+    /// Let the rows be sorted by a column, using `comparator` to compare its cells
+    pub fn set_comparator<F>(&mut self, col: usize, comparator: F)
+        where F: Fn(&str, &str) -> Ordering + 'static
+    {
+        self.comparators[col] = Some(Rc::new(comparator));
+    }
+    /// The column the rows are sorted by, and in which direction
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+    /// Sort by a column, the rows are shown in the order they were added in if `sort` is `None`,
+    /// or if the column has no comparator
+    pub fn set_sort(&mut self, sort: Option<(usize, SortOrder)>) {
+        self.sort = sort.and_then(|(col, order)| {
+            self.comparators.get(col).and_then(|comparator| comparator.as_ref()).map(|_| (col, order))
+        });
+        self.update_view();
+    }
+    /// Move to the next sort for a column, as when clicking its header: ascending, descending then unsorted,
+    /// starting with ascending when it's not the column sorted by. Returns false if the column has no comparator.
+    pub fn cycle_sort(&mut self, col: usize) -> bool {
+        if self.comparators.get(col).map_or(true, |comparator| comparator.is_none()) {
+            return false;
+        }
+        let sort = match self.sort {
+            Some((sort_col, SortOrder::Ascending)) if sort_col == col => Some((col, SortOrder::Descending)),
+            Some((sort_col, SortOrder::Descending)) if sort_col == col => None,
+            _ => Some((col, SortOrder::Ascending)),
+        };
+        self.set_sort(sort);
+        true
+    }
+    /// Only show the rows `filter` returns true for, or every row if `filter` is `None`
+    pub fn set_filter(&mut self, filter: Option<RowFilter>) {
+        self.filter = filter;
+        self.update_view();
+    }
+    /// The rows shown, filtered and sorted, in the order they're shown.
+    /// Sorting is stable, rows with equal cells stay in the order they were added in.
+    pub fn view(&self) -> &[usize] {
+        &self.view
+    }
+    fn update_view(&mut self) {
+        let mut view: Vec<usize> = match self.filter {
+            Some(ref filter) => (0..self.rows.len()).filter(|&row| filter(&self.rows[row])).collect(),
+            None => (0..self.rows.len()).collect(),
+        };
+        if let Some((col, order)) = self.sort {
+            if let Some(ref comparator) = self.comparators[col] {
+                let rows = &self.rows;
+                view.sort_by(|&a, &b| {
+                    let ordering = comparator(&rows[a][col], &rows[b][col]);
+                    if order == SortOrder::Descending { ordering.reverse() } else { ordering }
+                });
+            }
+        }
+        self.view = view;
+    }
+    /// The cell being edited, as `(row, column)`
+    pub fn editing(&self) -> Option<(usize, usize)> {
+        self.editing
+    }
+    /// Start editing a cell, returns false if it can't be edited, isn't shown, or another cell is already being edited
+    pub fn start_edit(&mut self, row: usize, col: usize) -> bool {
+        if self.editing.is_some() || !self.view.contains(&row) || !self.editable.get(col).cloned().unwrap_or(false) {
+            return false;
+        }
+        self.editing = Some((row, col));
+        true
+    }
+    /// Store `value` in the cell being edited and stop editing it, returns the cell, if one was being edited.
+    /// The rows aren't sorted or filtered again until rows are added or removed, or the sort or filter changes,
+    /// so the row doesn't move away as it's edited.
+    pub fn commit(&mut self, value: &str) -> Option<(usize, usize)> {
+        let editing = self.editing.take();
+        if let Some((row, col)) = editing {
+            self.rows[row][col] = value.to_owned();
+        }
+        editing
+    }
+    /// Stop editing without changing the cell, returns the cell, if one was being edited
+    pub fn cancel(&mut self) -> Option<(usize, usize)> {
+        self.editing.take()
+    }
+    /// The first editable cell shown after `(row, col)`, along the row then down the table, in the order rows are shown
+    pub fn next_editable(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        let position = self.view.iter().position(|&shown| shown == row)?;
+        let next_in_row = (col + 1..self.columns()).find(|&col| self.editable[col]).map(|col| (row, col));
+        let first_editable = (0..self.columns()).find(|&col| self.editable[col]);
+        next_in_row.or_else(|| {
+            let next_row = self.view.get(position + 1)?;
+            first_editable.map(|col| (*next_row, col))
+        })
+    }
+}
+
+/// Sent by a table when an edited value is committed, with the cell's new value
+#[derive(Clone, Debug)]
+pub struct CellEditedEvent {
+    pub row: usize,
+    pub col: usize,
+    pub value: String,
+}
+
+/// Sent by a cell editor with `event_bubble_up` to finish editing, it's handled by the cell being edited.
+/// Editors made with `text_cell_editor` and `number_cell_editor` already send these.
+#[derive(Clone, Debug)]
+pub enum CellEditorEvent {
+    /// Store the value and close the editor, eg. when enter is pressed
+    Commit(String),
+    /// Store the value and close the editor, leaving focus where it is, for when the editor loses focus
+    Blur(String),
+    /// Store the value and start editing the next editable cell, eg. when tab is pressed
+    CommitAndNext(String),
+    /// Close the editor, keeping the cell's value
+    Cancel,
+}
+
+/// Sent by a table when the column it's sorted by, or the direction, changes, `None` if it's no longer sorted
+#[derive(Clone, Debug)]
+pub struct SortChangedEvent(pub Option<(usize, SortOrder)>);
+
+/// Only show the rows of a table that the filter returns true for, or every row if it's `None`.
+/// Rows that are filtered out are hidden, they're still updated by edits and can be removed.
+pub struct SetFilterEvent(pub Option<RowFilter>);
+impl SetFilterEvent {
+    pub fn new<F>(filter: F) -> Self
+        where F: Fn(&[String]) -> bool + 'static
+    {
+        SetFilterEvent(Some(Rc::new(filter)))
+    }
+    pub fn clear() -> Self {
+        SetFilterEvent(None)
+    }
+}
+
+/// Compares cells by their text, to sort a column alphabetically
+pub fn compare_text(a: &str, b: &str) -> Ordering {
+    a.cmp(b)
+}
+
+/// Compares cells by the numbers in them, cells that aren't numbers are sorted after those that are, by their text
+pub fn compare_numbers(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// Start editing a cell, unless another cell is being edited
+pub struct EditTableCell {
+    pub row: usize,
+    pub col: usize,
+}
+/// Add a row of cells after the last row
+pub struct AddTableRow(pub Vec<String>);
+/// Remove a row, closing the editor without committing if one of its cells is being edited
+pub struct RemoveTableRow(pub usize);
+
+enum TableInput {
+    Click(WidgetRef),
+    // F2 was pressed while the cell was focused
+    EditKey(WidgetRef),
+    Edit(usize, usize),
+    Editor(WidgetRef, CellEditorEvent),
+    AddRow(Vec<String>),
+    RemoveRow(usize),
+    // a header was clicked
+    Sort(usize),
+    Filter(Option<RowFilter>),
+    // the area the table is visible in changed, or the table moved
+    Viewport(Rect),
+    LayoutUpdated,
+}
+
+/// Makes the editor for a cell from the cell's value
+pub type CellEditorFactory = Rc<Fn(&str) -> WidgetBuilder>;
+
+struct TableColumn {
+    title: String,
+    width: f32,
+    editor: Option<CellEditorFactory>,
+    comparator: Option<CellComparator>,
+}
+
+// a cell's widget and the label showing its value, hidden while it's being edited
+#[derive(Clone)]
+struct TableCell {
+    widget: WidgetRef,
+    label: WidgetRef,
+}
+
+// The table is virtualized: there are only rows of widgets for the rows that are visible, in the window and in the
+// viewport of the scroll widget the table is the content of, if it is. The first row of widgets shows shown row `first`
+// and so on, with spacers above and below them as tall as the rows that aren't built, so the table is as tall as if
+// every row was. Sorting and filtering only change the text of the cells. While scrolling, the rows of widgets that
+// scroll out of view are moved to the other end and show the rows scrolling into view, the others keep their rows.
+struct TableHandler {
+    model: TableModel,
+    editors: Vec<Option<CellEditorFactory>>,
+    widths: Vec<f32>,
+    rows: Vec<(WidgetRef, Vec<TableCell>)>,
+    // the index in the model's view of the row shown by the first row of widgets
+    first: usize,
+    // the spacers above and below the rows of widgets, and the heights they were last given
+    spacers: (WidgetRef, WidgetRef),
+    spacer_heights: (f32, f32),
+    // the area the table is visible in, from the scroll widget it's the content of
+    viewport: Option<Rect>,
+    // the labels showing the sort arrow in each header cell
+    sort_arrows: Vec<WidgetRef>,
+    table: WidgetRef,
+    // the open editor and the cell it's in
+    editor: Option<(WidgetRef, TableCell)>,
+    // the cell whose editor was last closed, so a `CommitAndNext` can arrive after
+    // the `Blur` from the same key press, since tab also moves the focus
+    last_edited: Option<WidgetRef>,
+    last_click: Option<(WidgetRef, Instant)>,
+}
+
+fn row_height(ui: &Ui) -> f32 {
+    ui.theme().scale(ROW_HEIGHT)
+}
+
+impl TableHandler {
+    // the row of the model and column shown by a cell
+    fn position(&self, cell: &WidgetRef) -> Option<(usize, usize)> {
+        self.rows.iter().enumerate().filter_map(|(index, &(_, ref cells))| {
+            let col = cells.iter().position(|table_cell| table_cell.widget == *cell)?;
+            self.model.view().get(self.first + index).map(|&row| (row, col))
+        }).next()
+    }
+    // the cell showing a cell of the model, if its row is shown and in view
+    fn table_cell(&self, row: usize, col: usize) -> Option<TableCell> {
+        let index = self.model.view().iter().position(|&shown| shown == row)?;
+        if index < self.first {
+            return None;
+        }
+        self.rows.get(index - self.first).map(|&(_, ref cells)| cells[col].clone())
+    }
+    // a row of widgets, added before the spacer below the rows
+    fn add_row_widgets(&mut self) -> (WidgetRef, Vec<TableCell>) {
+        let mut row_widget = WidgetBuilder::new("table_row");
+        row_widget.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        let mut cells = Vec::new();
+        for &width in &self.widths {
+            let (cell, table_cell) = data_cell(width, self.table.clone());
+            cells.push(table_cell);
+            row_widget.add_child(cell);
+        }
+        let row_ref = row_widget.widget_ref();
+        let index = self.child_index(&self.spacers.1);
+        self.table.insert_child(index, row_widget);
+        (row_ref, cells)
+    }
+    fn child_index(&self, child: &WidgetRef) -> usize {
+        self.table.children().iter().position(|other| other == child).unwrap()
+    }
+    // show the row of the model that the row of widgets at `index` is now showing
+    fn show_row(&mut self, index: usize) {
+        let row = self.model.view()[self.first + index];
+        for (col, cell) in self.rows[index].1.iter_mut().enumerate() {
+            let value = self.model.cell(row, col).to_owned();
+            cell.label.update(|state: &mut TextState| state.text = value);
+        }
+    }
+    // the range of shown rows that are visible, in the window and the viewport
+    fn visible_rows(&self, ui: &Ui) -> (usize, usize) {
+        let shown = self.model.view().len();
+        let row_height = row_height(ui);
+        let visible = match self.viewport {
+            Some(viewport) => ui.get_root().bounds().intersection(&viewport),
+            None => Some(ui.get_root().bounds()),
+        };
+        let visible = match visible {
+            Some(visible) if row_height > 0.0 => visible,
+            _ => return (0, 0),
+        };
+        let top = self.spacers.0.bounds().origin.y;
+        let first = ((visible.top() - top) / row_height).floor().max(0.0) as usize;
+        let end = ((visible.bottom() - top) / row_height).ceil().max(0.0) as usize;
+        (cmp::min(first, shown), cmp::min(end, shown))
+    }
+    // show the shown rows from `first` to `end`, reusing the rows of widgets of the rows that scrolled out of view,
+    // adding rows of widgets if more rows are visible, and removing those no longer needed
+    fn set_window(&mut self, first: usize, end: usize) {
+        let (old_first, old_end) = (self.first, self.first + self.rows.len());
+        if (first, end) == (old_first, old_end) {
+            return;
+        }
+        // an edit of a row scrolling out of view is cancelled, the row's widgets will show another row
+        if let Some(index) = self.editing_index() {
+            if index < first || index >= end {
+                self.model.cancel();
+                self.close_editor();
+            }
+        }
+        let mut old_rows: Vec<Option<(WidgetRef, Vec<TableCell>)>> = self.rows.drain(..).map(Some).collect();
+        let mut free: Vec<(WidgetRef, Vec<TableCell>)> = (old_first..old_end)
+            .filter(|&index| index < first || index >= end)
+            .map(|index| old_rows[index - old_first].take().unwrap())
+            .collect();
+        let mut rows = Vec::new();
+        let mut recycled = Vec::new();
+        for index in first..end {
+            let row = if index >= old_first && index < old_end {
+                old_rows[index - old_first].take().unwrap()
+            } else {
+                recycled.push(index - first);
+                free.pop().unwrap_or_else(|| self.add_row_widgets())
+            };
+            rows.push(row);
+        }
+        for (row_ref, _) in free {
+            self.table.remove_child(row_ref);
+        }
+        // keep the rows of widgets in the order of the rows they show
+        let start = self.child_index(&self.spacers.0) + 1;
+        for (offset, &(ref row_ref, _)) in rows.iter().enumerate() {
+            if self.child_index(row_ref) != start + offset {
+                self.table.move_child(row_ref, start + offset);
+            }
+        }
+        self.rows = rows;
+        self.first = first;
+        for index in recycled {
+            self.show_row(index);
+        }
+        self.last_click = None;
+    }
+    // make the spacers as tall as the rows above and below the rows of widgets
+    fn update_spacers(&mut self, row_height: f32) {
+        let below = self.model.view().len() - self.first - self.rows.len();
+        let heights = (self.first as f32 * row_height, below as f32 * row_height);
+        if heights != self.spacer_heights {
+            self.spacer_heights = heights;
+            self.spacers.0.update_layout(|layout| { layout.edit_height().set(heights.0); });
+            self.spacers.1.update_layout(|layout| { layout.edit_height().set(heights.1); });
+        }
+    }
+    // build the rows of widgets for the rows that are visible, after the table is laid out or scrolled
+    fn update_window(&mut self, ui: &Ui) {
+        let (first, end) = self.visible_rows(ui);
+        self.set_window(first, end);
+        self.update_spacers(row_height(ui));
+    }
+    // show the rows of the model in the rows of widgets, after the rows, sort or filter have changed
+    fn update_rows(&mut self, row_height: f32) {
+        let shown = self.model.view().len();
+        let (first, end) = (cmp::min(self.first, shown), cmp::min(self.first + self.rows.len(), shown));
+        self.set_window(first, end);
+        for index in 0..self.rows.len() {
+            self.show_row(index);
+        }
+        self.update_spacers(row_height);
+        self.last_click = None;
+    }
+    fn update_sort_arrows(&mut self) {
+        let sort = self.model.sort();
+        for (col, arrow) in self.sort_arrows.iter_mut().enumerate() {
+            let text = match sort {
+                Some((sort_col, SortOrder::Ascending)) if sort_col == col => "^",
+                Some((sort_col, SortOrder::Descending)) if sort_col == col => "v",
+                _ => "",
+            };
+            arrow.update(|state: &mut TextState| state.text = text.to_owned());
+        }
+    }
+    // where the row being edited is shown
+    fn editing_index(&self) -> Option<usize> {
+        let (row, _) = self.model.editing()?;
+        self.model.view().iter().position(|&shown| shown == row)
+    }
+    // change the rows, sort or filter, an edit in progress is cancelled if its row moves or is removed,
+    // so the editor doesn't end up over another row
+    fn update_model<F: FnOnce(&mut TableModel)>(&mut self, f: F, ui: &Ui) {
+        let editing_index = self.editing_index();
+        f(&mut self.model);
+        if self.editing_index() != editing_index {
+            self.model.cancel();
+            self.close_editor();
+        }
+        self.last_edited = None;
+        self.update_rows(row_height(ui));
+    }
+    fn start_edit(&mut self, row: usize, col: usize) {
+        let factory = match self.editors.get(col) {
+            Some(&Some(ref factory)) => factory.clone(),
+            _ => return,
+        };
+        // only rows in view have widgets to edit in
+        let mut cell = match self.table_cell(row, col) {
+            Some(cell) => cell,
+            None => return,
+        };
+        if !self.model.start_edit(row, col) {
+            return;
+        }
+        let mut editor = factory(self.model.cell(row, col));
+        let cell_vars = cell.widget.layout_vars();
+        editor.layout().add(constraints![
+            align_left(&cell_vars).strength(STRONG),
+            align_top(&cell_vars).strength(STRONG),
+            align_right(&cell_vars).strength(STRONG),
+            align_bottom(&cell_vars).strength(STRONG),
+        ]);
+        let editor_ref = editor.widget_ref();
+        cell.label.set_visible(false);
+        cell.widget.add_child(editor);
+        event::event(Target::Root, KeyboardInputEvent::FocusChange(Some(editor_ref.clone())));
+        self.editor = Some((editor_ref, cell));
+        self.last_edited = None;
+    }
+    // remove the editor from the cell that was being edited, and show the cell's label again, returns the cell
+    fn close_editor(&mut self) -> Option<TableCell> {
+        let (editor, mut cell) = self.editor.take()?;
+        cell.widget.remove_child(editor);
+        cell.label.set_visible(true);
+        self.last_edited = Some(cell.widget.clone());
+        Some(cell)
+    }
+    fn editor_event(&mut self, cell: WidgetRef, event: &CellEditorEvent, args: &mut EventArgs) {
+        let position = self.position(&cell);
+        if position.is_none() || position != self.model.editing() {
+            // the editor of a cell that was just closed, by the same key press,
+            // or by removing its row, only tab still needs handling
+            if let CellEditorEvent::CommitAndNext(_) = *event {
+                if self.last_edited.as_ref() == Some(&cell) {
+                    if let Some((row, col)) = position {
+                        self.edit_next(row, col);
+                    }
+                }
+            }
+            return;
+        }
+        let (row, col) = position.unwrap();
+        match *event {
+            CellEditorEvent::Commit(ref value) |
+            CellEditorEvent::Blur(ref value) |
+            CellEditorEvent::CommitAndNext(ref value) => {
+                self.model.commit(value);
+                if let Some(mut cell) = self.close_editor() {
+                    let value = value.clone();
+                    cell.label.update(|state: &mut TextState| state.text = value);
+                }
+                args.widget.event(CellEditedEvent { row: row, col: col, value: value.clone() });
+            }
+            CellEditorEvent::Cancel => {
+                self.model.cancel();
+                self.close_editor();
+            }
+        }
+        match *event {
+            CellEditorEvent::Commit(_) | CellEditorEvent::Cancel => {
+                args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(cell)));
+            }
+            CellEditorEvent::CommitAndNext(_) => self.edit_next(row, col),
+            CellEditorEvent::Blur(_) => (),
+        }
+    }
+    fn edit_next(&mut self, row: usize, col: usize) {
+        if let Some((row, col)) = self.model.next_editable(row, col) {
+            self.start_edit(row, col);
+        }
+    }
+}
+
+impl EventHandler<TableInput> for TableHandler {
+    fn handle(&mut self, event: &TableInput, mut args: EventArgs) {
+        match *event {
+            TableInput::Click(ref cell) => {
+                let position = self.position(cell);
+                if position.is_none() || position == self.model.editing() {
+                    return;
+                }
+                let now = Instant::now();
+                let double_click = self.last_click.as_ref().map_or(false, |&(ref last_cell, last_click)| {
+                    last_cell == cell && now.duration_since(last_click) < Duration::from_millis(DOUBLE_CLICK_MILLIS)
+                });
+                if double_click {
+                    self.last_click = None;
+                    let (row, col) = position.unwrap();
+                    self.start_edit(row, col);
+                } else {
+                    self.last_click = Some((cell.clone(), now));
+                    args.ui.get_root().event(KeyboardInputEvent::FocusChange(Some(cell.clone())));
+                }
+            }
+            TableInput::EditKey(ref cell) => {
+                if let Some((row, col)) = self.position(cell) {
+                    self.start_edit(row, col);
+                }
+            }
+            TableInput::Edit(row, col) => self.start_edit(row, col),
+            TableInput::Editor(ref cell, ref editor_event) => self.editor_event(cell.clone(), editor_event, &mut args),
+            TableInput::AddRow(ref cells) => self.update_model(|model| model.add_row(cells.clone()), args.ui),
+            TableInput::RemoveRow(row) => {
+                // an edit of the removed row is dropped, anything its editor sends afterwards is ignored
+                if row < self.model.rows() {
+                    self.update_model(|model| model.remove_row(row), args.ui);
+                }
+            }
+            TableInput::Sort(col) => {
+                let old_sort = self.model.sort();
+                self.update_model(|model| { model.cycle_sort(col); }, args.ui);
+                if self.model.sort() != old_sort {
+                    self.update_sort_arrows();
+                    args.widget.event(SortChangedEvent(self.model.sort()));
+                }
+            }
+            TableInput::Filter(ref filter) => self.update_model(|model| model.set_filter(filter.clone()), args.ui),
+            TableInput::Viewport(viewport) => {
+                self.viewport = Some(viewport);
+                self.update_window(args.ui);
+            }
+            TableInput::LayoutUpdated => self.update_window(args.ui),
+        }
+    }
+}
+
+fn cell_widget(name: &str, value: &str, column_width: f32) -> (WidgetBuilder, WidgetBuilder) {
+    let default_border = Some((1.0, GRAY_80));
+    let focused_border = Some((1.0, BLUE));
+    let rect_style = style!(
+        RectStyle::Border: selector!(default_border, FOCUSED: focused_border),
+        RectStyle::CornerRadius: None);
+    let mut cell = WidgetBuilder::with_draw_state_and_style(name, RectState::new(), rect_style);
+    cell.layout().no_container();
+    cell.add_theme_constraints(move |theme| constraints![
+        width(theme.scale(column_width)),
+        height(theme.scale(ROW_HEIGHT)),
+    ]);
+    let mut label = TextBuilder::new(value);
+    label.set_name("table_cell_text");
+    let cell_vars = cell.widget_ref().layout_vars();
+    label.add_theme_constraints(move |theme| constraints![
+        align_left(&cell_vars).padding(theme.scale(CELL_PADDING)),
+        center_vertical(&cell_vars),
+    ]);
+    (cell, label)
+}
+
+fn data_cell(column_width: f32, table: WidgetRef) -> (WidgetBuilder, TableCell) {
+    let (mut cell, label) = cell_widget("table_cell", "", column_width);
+    let table_cell = TableCell {
+        widget: cell.widget_ref(),
+        label: label.widget_ref(),
+    };
+    let click_table = table.clone();
+    cell.add_handler_fn(move |_: &ClickEvent, args| {
+        click_table.event(TableInput::Click(args.widget));
+    });
+    let key_table = table.clone();
+    cell.add_handler_fn(move |event: &WidgetKeyboardInput, args| {
+        if let WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(glutin::VirtualKeyCode::F2)) = *event {
+            key_table.event(TableInput::EditKey(args.widget));
+        }
+    });
+    cell.add_handler_fn(move |event: &CellEditorEvent, args| {
+        table.event(TableInput::Editor(args.widget, event.clone()));
+        *args.handled = true;
+    });
+    cell.add_child(label);
+    (cell, table_cell)
+}
+
+/// An edit text for a cell, enter commits, escape cancels and tab commits and edits the next editable cell
+pub fn text_cell_editor(value: &str) -> WidgetBuilder {
+    text_editor(value, EditTextBuilder::new())
+}
+
+/// An edit text for a cell that only accepts numbers, with the keys of `text_cell_editor`
+pub fn number_cell_editor(value: &str) -> WidgetBuilder {
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_input_filter(|char| char.is_digit(10) || char == '.' || char == '-');
+    text_editor(value, edit_text)
+}
+
+fn text_editor(value: &str, mut edit_text: EditTextBuilder) -> WidgetBuilder {
+    edit_text.set_single_line(true);
+    edit_text.text_widget.set_draw_state(TextState::new(value));
+    edit_text.set_name("table_cell_editor");
+    edit_text.on_text_committed(|event, args| {
+        args.widget.event_bubble_up(CellEditorEvent::Blur(event.0.clone()));
+    });
+    let text_ref = edit_text.text_widget.widget_ref();
+    edit_text.add_handler_fn(move |event: &WidgetKeyboardInput, args| {
+        if let WidgetKeyboardInput(glutin::ElementState::Pressed, _, Some(key)) = *event {
+            let text = text_ref.drawable::<TextState>().map_or(String::new(), |state| state.text.clone());
+            let editor_event = match key {
+                glutin::VirtualKeyCode::Return => CellEditorEvent::Commit(text),
+                glutin::VirtualKeyCode::Tab => CellEditorEvent::CommitAndNext(text),
+                glutin::VirtualKeyCode::Escape => CellEditorEvent::Cancel,
+                _ => return,
+            };
+            args.widget.event_bubble_up(editor_event);
+        }
+    });
+    edit_text.into()
+}
+
+/// Rows of text in fixed width columns, under a row of column titles.
+/// Cells of editable columns are edited in place, by double clicking them or pressing F2 while they're focused,
+/// which replaces the cell's text with an editor, inside the cell, so the row keeps its layout.
+/// Enter or the editor losing focus commits the value, sending a `CellEditedEvent`, escape cancels,
+/// and tab commits and edits the next editable cell.
+/// Clicking the header of a column with a comparator sorts the rows by it, ascending, then descending, then unsorted,
+/// and rows can be filtered with a `SetFilterEvent`. Both only change which row each row of widgets shows,
+/// no widgets are added or removed.
+/// Only the rows in view have widgets, the part of the table in the window, and in the scroll widget it's the content of,
+/// if it is, so tables with many rows are cheap to lay out, sort and scroll. Rows out of view can't be edited.
+pub struct TableBuilder {
+    pub widget: WidgetBuilder,
+    columns: Vec<TableColumn>,
+    rows: Vec<Vec<String>>,
+}
+widget_builder!(TableBuilder);
+
+impl TableBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("table");
+        widget.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+        TableBuilder {
+            widget: widget,
+            columns: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+    /// Add a column that can't be edited, after the existing ones
+    pub fn add_column(&mut self, title: &str, width: f32) -> &mut Self {
+        self.columns.push(TableColumn { title: title.to_owned(), width: width, editor: None, comparator: None });
+        self
+    }
+    /// Add an editable column, after the existing ones. `editor` makes the widget a cell is edited with, from the cell's value,
+    /// eg. `text_cell_editor`. Custom editors send `CellEditorEvent`s with `event_bubble_up` to finish editing.
+    pub fn add_editable_column<F>(&mut self, title: &str, width: f32, editor: F) -> &mut Self
+        where F: Fn(&str) -> WidgetBuilder + 'static
+    {
+        self.columns.push(TableColumn { title: title.to_owned(), width: width, editor: Some(Rc::new(editor)), comparator: None });
+        self
+    }
+    /// Let the rows be sorted by column `col`, by clicking its header, using `comparator` to compare its cells,
+    /// eg. `compare_text` or `compare_numbers`
+    pub fn set_comparator<F>(&mut self, col: usize, comparator: F) -> &mut Self
+        where F: Fn(&str, &str) -> Ordering + 'static
+    {
+        self.columns[col].comparator = Some(Rc::new(comparator));
+        self
+    }
+    /// Add a row, once all the columns are added, see `AddTableRow` to add rows later
+    pub fn add_row(&mut self, cells: Vec<String>) -> &mut Self {
+        self.rows.push(cells);
+        self
+    }
+    pub fn on_cell_edited<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&CellEditedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    pub fn on_sort_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&SortChangedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    /// Build the row of column titles separately from the rest of the table, eg. to pin it above the rows
+    /// in a scroll widget, with the body as the content and the header set with `ScrollBuilder::set_header`.
+    /// The columns have the same widths in both, so they line up as long as the left edges of the two do.
+    pub fn into_header_and_body(self) -> (WidgetBuilder, WidgetBuilder) {
+        let (header, sort_arrows) = self.build_header();
+        (header, self.build_body(sort_arrows))
+    }
+    // the header row, and the labels showing the sort arrow in each of its cells
+    fn build_header(&self) -> (WidgetBuilder, Vec<WidgetRef>) {
+        let mut header = WidgetBuilder::with_draw_state("table_header", RectState { background_color: GRAY_90, ..RectState::default() });
+        header.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+        let mut sort_arrows = Vec::new();
+        for (col, column) in self.columns.iter().enumerate() {
+            let (mut cell, label) = cell_widget("table_header_cell", &column.title, column.width);
+            let mut arrow = TextBuilder::new("");
+            arrow.set_name("table_sort_arrow");
+            let cell_vars = cell.widget_ref().layout_vars();
+            arrow.add_theme_constraints(move |theme| constraints![
+                align_right(&cell_vars).padding(theme.scale(CELL_PADDING)),
+                center_vertical(&cell_vars),
+            ]);
+            sort_arrows.push(arrow.widget_ref());
+            let table = self.widget.widget_ref();
+            cell.add_handler_fn(move |_: &ClickEvent, _| {
+                table.event(TableInput::Sort(col));
+            });
+            cell.add_child(label).add_child(arrow);
+            header.add_child(cell);
+        }
+        (header, sort_arrows)
+    }
+    fn build_body(mut self, sort_arrows: Vec<WidgetRef>) -> WidgetBuilder {
+        let mut model = TableModel::new(self.columns.iter().map(|column| column.editor.is_some()).collect());
+        for (col, column) in self.columns.iter().enumerate() {
+            if let Some(ref comparator) = column.comparator {
+                let comparator = comparator.clone();
+                model.set_comparator(col, move |a, b| comparator(a, b));
+            }
+        }
+        for cells in self.rows {
+            model.add_row(cells);
+        }
+        // the rows of widgets are built once the table is laid out and it's known which rows are visible
+        let mut above = WidgetBuilder::new("table_rows_above");
+        let mut below = WidgetBuilder::new("table_rows_below");
+        let below_height = model.view().len() as f32 * ROW_HEIGHT;
+        below.layout().edit_height().set(below_height);
+        above.layout().edit_height().set(0.0);
+        let handler = TableHandler {
+            model: model,
+            editors: self.columns.iter().map(|column| column.editor.clone()).collect(),
+            widths: self.columns.iter().map(|column| column.width).collect(),
+            rows: Vec::new(),
+            first: 0,
+            spacers: (above.widget_ref(), below.widget_ref()),
+            spacer_heights: (0.0, below_height),
+            viewport: None,
+            sort_arrows: sort_arrows,
+            table: self.widget.widget_ref(),
+            editor: None,
+            last_edited: None,
+            last_click: None,
+        };
+        self.widget.add_child(above).add_child(below);
+        self.widget.add_handler(handler);
+        self.widget.add_handler_fn(|_: &LayoutUpdated, args| {
+            args.widget.event(TableInput::LayoutUpdated);
+        });
+        self.widget.add_handler_fn(|event: &ScrollOffsetChanged, args| {
+            args.widget.event(TableInput::Viewport(event.viewport));
+        });
+        self.widget.add_handler_fn(|event: &SetFilterEvent, args| {
+            args.widget.event(TableInput::Filter(event.0.clone()));
+        });
+        self.widget.add_handler_fn(|event: &EditTableCell, args| {
+            args.widget.event(TableInput::Edit(event.row, event.col));
+        });
+        self.widget.add_handler_fn(|event: &AddTableRow, args| {
+            args.widget.event(TableInput::AddRow(event.0.clone()));
+        });
+        self.widget.add_handler_fn(|event: &RemoveTableRow, args| {
+            args.widget.event(TableInput::RemoveRow(event.0));
+        });
+        self.widget
+    }
+}
+
+impl Into<WidgetBuilder> for TableBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let (header, sort_arrows) = self.build_header();
+        self.widget.add_child(header);
+        self.build_body(sort_arrows)
+    }
+}
@@ -1,50 +1,78 @@
 use cassowary::Constraint;
+use cassowary::strength::*;
 
 use widget::WidgetBuilder;
-use widget::style::StyleUpdated;
-use draw::text::{TextState, TextStyle};
+use draw::text::{TextState, TextStyle, StyledRun, DEFAULT_FONT_SIZE};
 use event::{EventHandler, EventArgs};
 use layout::constraint::*;
+use geometry::Size;
+use ui::MeasureEvent;
 
+/// A text widget, sized to fit its text. Set `TextState::shrink_to_fit` to let the width be constrained
+/// to less than that, drawing the text smaller so it still fits on one line, eg. for numbers in fixed size tiles.
 pub struct TextBuilder;
 
 impl TextBuilder {
     pub fn new(text: &str) -> WidgetBuilder {
-        let text_draw_state = TextState::new(text);
-        let mut widget = WidgetBuilder::new(text);
-        widget.set_draw_state(text_draw_state);
-        widget.add_handler(TextUpdatedHandler { size_constraints: Vec::new() });
+        let mut widget = WidgetBuilder::with_draw_state(text, TextState::new(text));
+        widget
+            .add_handler(TextUpdatedHandler::default())
+            .font_size_from_theme(DEFAULT_FONT_SIZE);
         widget
     }
     pub fn new_with_style(style: Vec<TextStyle>) -> WidgetBuilder {
-        let text_draw_state = TextState::default();
-        let mut widget = WidgetBuilder::new("text");
-        widget.set_draw_state_with_style(text_draw_state, style);
-        widget.add_handler(TextUpdatedHandler::default());
+        let mut widget = WidgetBuilder::with_draw_state_and_style("text", TextState::default(), style);
+        widget
+            .add_handler(TextUpdatedHandler::default())
+            .font_size_from_theme(DEFAULT_FONT_SIZE);
+        widget
+    }
+    /// Text made of runs with their own color, font or font size, eg. to highlight some of the words,
+    /// laid out and wrapped as one block of text, see `TextState::runs`
+    pub fn new_with_runs(runs: Vec<StyledRun>) -> WidgetBuilder {
+        let mut widget = WidgetBuilder::with_draw_state("text", TextState::with_runs(runs));
+        widget
+            .add_handler(TextUpdatedHandler::default())
+            .font_size_from_theme(DEFAULT_FONT_SIZE);
         widget
     }
 }
 
 #[derive(Default)]
 struct TextUpdatedHandler {
+    size: Option<Size>,
+    shrink_to_fit: bool,
     size_constraints: Vec<Constraint>,
 }
-impl EventHandler<StyleUpdated> for TextUpdatedHandler {
-    fn handle(&mut self, _: &StyleUpdated, mut args: EventArgs) {
+impl EventHandler<MeasureEvent> for TextUpdatedHandler {
+    fn handle(&mut self, _: &MeasureEvent, mut args: EventArgs) {
+        let (text_size, shrink_to_fit) = match args.drawable::<TextState>() {
+            Some(text_draw_state) => (text_draw_state.measure(), text_draw_state.shrink_to_fit.is_some()),
+            None => return,
+        };
+        // only touch the solver if the text actually changed size
+        if self.size == Some(text_size) && self.shrink_to_fit == shrink_to_fit {
+            return;
+        }
         args.widget.update_layout(|layout| {
             for constraint in self.size_constraints.drain(..) {
                 layout.remove_constraint(constraint);
             }
         });
-        let text_size = {
-            let draw_state = args.widget.draw_state();
-            let text_draw_state = draw_state.downcast_ref::<TextState>().unwrap();
-            text_draw_state.measure()
+        let layout_vars = args.widget.layout_vars();
+        let size_constraints = if shrink_to_fit {
+            // text that can shrink only prefers its full width
+            let mut size_constraints = height(text_size.height).build(&layout_vars);
+            size_constraints.extend(width(text_size.width).strength(WEAK).build(&layout_vars));
+            size_constraints
+        } else {
+            size(text_size).build(&layout_vars)
         };
-        let size_constraints = size(text_size).build(&args.widget.layout_vars());
         args.widget.update_layout(|layout| {
             layout.add(size_constraints.clone());
         });
+        self.size = Some(text_size);
+        self.shrink_to_fit = shrink_to_fit;
         self.size_constraints = size_constraints;
     }
 }
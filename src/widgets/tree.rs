@@ -0,0 +1,243 @@
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widget::property::Property;
+use widget::property::states::*;
+use widgets::text::TextBuilder;
+use draw::rect::{RectState, RectStyle};
+use draw::text::TextStyle;
+use input::mouse::ClickEvent;
+use layout::constraint::*;
+use layout::linear_layout::{LinearLayoutSettings, Orientation};
+use color::*;
+
+/// Horizontal space per level of depth, also the width of the expand/collapse toggle
+const TREE_INDENT: f32 = 20.0;
+
+static COLOR_TREE_ROW_DEFAULT: Color = GRAY_30;
+static COLOR_TREE_ROW_MOUSEOVER: Color = GRAY_60;
+static COLOR_TREE_ROW_SELECTED: Color = BLUE_HIGHLIGHT;
+
+lazy_static! {
+    pub static ref STYLE_TREE_ROW: Vec<RectStyle> = {
+        style!(RectStyle::BackgroundColor: selector!(COLOR_TREE_ROW_DEFAULT,
+            SELECTED: COLOR_TREE_ROW_SELECTED,
+            MOUSEOVER: COLOR_TREE_ROW_MOUSEOVER))
+    };
+    pub static ref STYLE_TREE_TEXT: Vec<TextStyle> = {
+        style!(TextStyle::TextColor: WHITE)
+    };
+}
+
+/// Data for one node of a `TreeViewBuilder`, and its children
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+}
+impl TreeNode {
+    pub fn new(label: &str) -> Self {
+        TreeNode {
+            label: label.to_owned(),
+            children: Vec::new(),
+        }
+    }
+    pub fn with_child(mut self, child: TreeNode) -> Self {
+        self.children.push(child);
+        self
+    }
+}
+
+/// Index of a node at each level of the tree, starting from the top level nodes
+pub type NodePath = Vec<usize>;
+
+/// Sent to a tree view when a node is expanded
+pub struct NodeExpanded(pub NodePath);
+/// Sent to a tree view when a node is collapsed
+pub struct NodeCollapsed(pub NodePath);
+/// Sent to a tree view when a node is selected
+pub struct NodeSelected(pub NodePath);
+
+struct TreeRowSelected(NodePath, WidgetRef);
+
+struct TreeViewHandler {
+    selected: Option<WidgetRef>,
+}
+impl EventHandler<TreeRowSelected> for TreeViewHandler {
+    fn handle(&mut self, event: &TreeRowSelected, args: EventArgs) {
+        let &TreeRowSelected(ref path, ref row) = event;
+        if Some(row) != self.selected.as_ref() {
+            if let Some(ref mut old_selected) = self.selected {
+                old_selected.remove_prop(Property::Selected);
+            }
+            row.clone().add_prop(Property::Selected);
+            self.selected = Some(row.clone());
+            args.widget.event(NodeSelected(path.clone()));
+        }
+    }
+}
+
+struct ToggleNode;
+
+struct TreeNodeHandler {
+    tree: WidgetRef,
+    path: NodePath,
+    toggle: WidgetRef,
+    children: WidgetRef,
+    expanded: bool,
+}
+impl EventHandler<ToggleNode> for TreeNodeHandler {
+    fn handle(&mut self, _: &ToggleNode, _: EventArgs) {
+        self.expanded = !self.expanded;
+        // hidden layouts and their descendants are taken out of the solver,
+        // so collapsed branches don't cost anything to lay out
+        let expanded = self.expanded;
+        self.children.update_layout(|layout| {
+            if expanded {
+                layout.show();
+            } else {
+                layout.hide();
+            }
+        });
+        if expanded {
+            self.toggle.add_prop(Property::Activated);
+            self.tree.event(NodeExpanded(self.path.clone()));
+        } else {
+            self.toggle.remove_prop(Property::Activated);
+            self.tree.event(NodeCollapsed(self.path.clone()));
+        }
+    }
+}
+
+fn build_node(node: TreeNode, path: NodePath, tree: &WidgetRef) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new("tree_node");
+    widget.layout().no_container();
+    widget.layout().add(shrink_vertical());
+
+    let mut row = WidgetBuilder::new("tree_row");
+    row
+        .set_draw_state_with_style(RectState::new(), STYLE_TREE_ROW.clone())
+        .enable_hover();
+
+    let depth = path.len() - 1;
+    let mut toggle = WidgetBuilder::new("tree_toggle");
+    toggle.layout().add(constraints![
+        align_left(&row).padding(depth as f32 * TREE_INDENT),
+        align_top(&row),
+        align_bottom(&row),
+        width(TREE_INDENT),
+    ]);
+    if !node.children.is_empty() {
+        let style = style!(parent: STYLE_TREE_TEXT,
+            TextStyle::Text: selector!("+".to_owned(), ACTIVATED: "-".to_owned()));
+        let mut toggle_text = TextBuilder::new_with_style(style);
+        toggle_text.layout().add(center(&toggle));
+        toggle.add_child(toggle_text);
+        let widget_ref = widget.widget_ref();
+        toggle.add_handler_fn(move |_: &ClickEvent, args| {
+            widget_ref.event(ToggleNode);
+            *args.handled = true;
+        });
+    }
+
+    let style = style!(parent: STYLE_TREE_TEXT, TextStyle::Text: node.label);
+    let mut label = TextBuilder::new_with_style(style);
+    label.set_name("tree_label");
+    label.layout().add(constraints![
+        align_to_right_of(&toggle),
+        bound_top(&row).padding(2.0),
+        bound_bottom(&row).padding(2.0),
+    ]);
+
+    let tree_ref = tree.clone();
+    let row_path = path.clone();
+    row.add_handler_fn(move |_: &ClickEvent, args| {
+        tree_ref.event(TreeRowSelected(row_path.clone(), args.widget));
+        *args.handled = true;
+    });
+
+    let mut children = WidgetBuilder::new("tree_children");
+    children.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    for (index, child) in node.children.into_iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(index);
+        let mut child_widget = build_node(child, child_path, tree);
+        child_widget.layout().add(match_width(&children));
+        children.add_child(child_widget);
+    }
+    // nodes start out collapsed
+    children.layout().hide();
+
+    row.layout().add(constraints![
+        align_top(&widget),
+        align_left(&widget),
+        align_right(&widget),
+        bound_bottom(&widget),
+    ]);
+    children.layout().add(constraints![
+        align_below(&row),
+        align_left(&widget),
+        align_right(&widget),
+        align_bottom(&widget),
+    ]);
+
+    widget.add_handler(TreeNodeHandler {
+        tree: tree.clone(),
+        path: path,
+        toggle: toggle.widget_ref(),
+        children: children.widget_ref(),
+        expanded: false,
+    });
+
+    row.add_child(toggle);
+    row.add_child(label);
+    widget.add_child(row);
+    widget.add_child(children);
+    widget
+}
+
+/// Displays a hierarchy of `TreeNode`s, with rows that can be expanded to show their children
+pub struct TreeViewBuilder {
+    pub widget: WidgetBuilder,
+    node_count: usize,
+}
+widget_wrapper!(TreeViewBuilder);
+
+impl TreeViewBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("tree_view");
+        widget
+            .add_handler(TreeViewHandler { selected: None })
+            .linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+        TreeViewBuilder {
+            widget: widget,
+            node_count: 0,
+        }
+    }
+    /// Add a top level node
+    pub fn add_node(&mut self, node: TreeNode) -> &mut Self {
+        let path = vec![self.node_count];
+        self.node_count += 1;
+        let mut node_widget = build_node(node, path, &self.widget.widget_ref());
+        node_widget.layout().add(match_width(&self.widget));
+        self.widget.add_child(node_widget);
+        self
+    }
+    pub fn on_node_selected<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&NodeSelected, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    pub fn on_node_expanded<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&NodeExpanded, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+    pub fn on_node_collapsed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&NodeCollapsed, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
@@ -0,0 +1,115 @@
+//! Checking the text of an edit text as it's entered, see `EditTextBuilder::set_validator`.
+
+use regex::Regex;
+
+use color::*;
+
+/// Whether the text of an edit text is acceptable, with a message to show below it if not
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationResult {
+    Valid,
+    Invalid(String),
+    /// Acceptable, but probably not what was meant
+    Warning(String),
+}
+
+impl ValidationResult {
+    /// If the text is acceptable, text with a warning is
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            ValidationResult::Invalid(_) => false,
+            _ => true,
+        }
+    }
+    /// The message shown below the edit text, none for valid text
+    pub fn message(&self) -> Option<&str> {
+        match *self {
+            ValidationResult::Valid => None,
+            ValidationResult::Invalid(ref message) | ValidationResult::Warning(ref message) => Some(message),
+        }
+    }
+    /// The color of the edit text's border and message
+    pub fn color(&self) -> Color {
+        match *self {
+            ValidationResult::Valid => Color::from_rgba(0x2E, 0x8B, 0x4A, 0xFF),
+            ValidationResult::Invalid(_) => Color::from_rgba(0xC0, 0x30, 0x30, 0xFF),
+            ValidationResult::Warning(_) => Color::from_rgba(0xE0, 0x80, 0x10, 0xFF),
+        }
+    }
+}
+
+/// Checks the text of an edit text each time it changes
+pub trait TextInputValidator {
+    fn validate(&self, text: &str) -> ValidationResult;
+}
+
+impl<F> TextInputValidator for F where F: Fn(&str) -> ValidationResult {
+    fn validate(&self, text: &str) -> ValidationResult {
+        self(text)
+    }
+}
+
+/// Accepts a decimal number, optionally negative, with surrounding whitespace ignored
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NumericValidator;
+
+impl TextInputValidator for NumericValidator {
+    fn validate(&self, text: &str) -> ValidationResult {
+        match text.trim().parse::<f64>() {
+            // `parse` also accepts "inf" and "NaN"
+            Ok(number) if number.is_finite() => ValidationResult::Valid,
+            _ => ValidationResult::Invalid("Enter a number".to_owned()),
+        }
+    }
+}
+
+/// Accepts an email address, or nothing if `allow_empty` is set. Only the overall shape is checked,
+/// a name, an `@` and a domain, addresses without a top level domain are accepted with a warning.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmailValidator {
+    pub allow_empty: bool,
+}
+
+impl TextInputValidator for EmailValidator {
+    fn validate(&self, text: &str) -> ValidationResult {
+        let text = text.trim();
+        if text.is_empty() {
+            return if self.allow_empty {
+                ValidationResult::Valid
+            } else {
+                ValidationResult::Invalid("Enter an email address".to_owned())
+            };
+        }
+        let invalid = || ValidationResult::Invalid("Not a valid email address".to_owned());
+        let mut parts = text.split('@');
+        let (name, domain) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(name), Some(domain), None) => (name, domain),
+            _ => return invalid(),
+        };
+        if name.is_empty() || text.chars().any(char::is_whitespace) {
+            return invalid();
+        }
+        if domain.is_empty() || domain.split('.').any(str::is_empty) {
+            return invalid();
+        }
+        if !domain.contains('.') {
+            return ValidationResult::Warning("The domain has no ending, like .com".to_owned());
+        }
+        ValidationResult::Valid
+    }
+}
+
+/// Accepts text the regex matches. Anchor it with `^` and `$` to match the whole text,
+/// eg. `^[0-9]{5}$` for a five digit code, otherwise matching any part of the text is enough.
+#[derive(Clone, Debug)]
+pub struct RegexValidator(pub Regex);
+
+impl TextInputValidator for RegexValidator {
+    fn validate(&self, text: &str) -> ValidationResult {
+        if self.0.is_match(text) {
+            ValidationResult::Valid
+        } else {
+            ValidationResult::Invalid("Not in the expected format".to_owned())
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use glutin;
+
+use event::{EventArgs, EventHandler};
+use widget::{WidgetBuilder, WidgetRef};
+use widgets::drag::{DragEvent, DragState};
+use input::mouse::WidgetMouseWheel;
+use layout::LayoutUpdated;
+use layout::constraint::*;
+use geometry::{Rect, Point, Vector, ViewTransform};
+
+pub const DEFAULT_MIN_SCALE: f32 = 0.1;
+pub const DEFAULT_MAX_SCALE: f32 = 10.0;
+/// Change in scale for each line scrolled with the mouse wheel while zooming
+const ZOOM_STEP: f32 = 1.1;
+/// Distance panned for each line scrolled with the mouse wheel, the same as a `ScrollBuilder` scrolls
+const WHEEL_LINE_HEIGHT: f32 = 13.0;
+
+/// Sent by a viewport when it's panned or zoomed, with the new view, eg. to save and restore it with `SetViewEvent`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewChangedEvent {
+    /// Where the top left of the content is drawn, relative to the top left of the viewport
+    pub offset: Vector,
+    pub scale: f32,
+}
+
+/// Pan and zoom a viewport to a view, the scale is kept within the viewport's zoom limits
+pub struct SetViewEvent {
+    pub offset: Vector,
+    pub scale: f32,
+}
+
+/// Pan and zoom a viewport so all of its content fits, centered,
+/// and keep it fitted as the content or viewport are resized, until the view is next changed
+pub struct FitContentEvent;
+
+enum ViewportInput {
+    // change in offset
+    Pan(Vector),
+    // zoom by a factor, around a point in window coordinates
+    Zoom(Point, f32),
+    Set(ViewTransform),
+    Fit,
+    LayoutUpdated,
+}
+
+struct ViewportHandler {
+    view: ViewTransform,
+    min_scale: f32,
+    max_scale: f32,
+    content: WidgetRef,
+    // keep the content fitted on layout changes, until the view is changed some other way
+    fitting: bool,
+}
+
+impl ViewportHandler {
+    fn clamp_scale(&self, scale: f32) -> f32 {
+        scale.max(self.min_scale).min(self.max_scale)
+    }
+    fn fitted(&self, bounds: Rect) -> ViewTransform {
+        let content = self.content.bounds().translate(&-bounds.origin.to_vector());
+        ViewTransform::fit(content, bounds.size, self.min_scale, self.max_scale)
+    }
+}
+
+impl EventHandler<ViewportInput> for ViewportHandler {
+    fn handle(&mut self, event: &ViewportInput, mut args: EventArgs) {
+        let bounds = args.widget.bounds();
+        let view = match *event {
+            ViewportInput::Pan(change) => {
+                self.fitting = false;
+                ViewTransform::new(self.view.offset + change, self.view.scale)
+            }
+            ViewportInput::Zoom(anchor, factor) => {
+                self.fitting = false;
+                let scale = self.clamp_scale(self.view.scale * factor);
+                self.view.zoom_around((anchor - bounds.origin).to_point(), scale)
+            }
+            ViewportInput::Set(view) => {
+                self.fitting = false;
+                ViewTransform::new(view.offset, self.clamp_scale(view.scale))
+            }
+            ViewportInput::Fit => {
+                self.fitting = true;
+                self.fitted(bounds)
+            }
+            ViewportInput::LayoutUpdated => {
+                if !self.fitting {
+                    return;
+                }
+                self.fitted(bounds)
+            }
+        };
+        if view != self.view {
+            self.view = view;
+            args.widget.set_view_transform(Some(view));
+            args.widget.event(ViewChangedEvent {
+                offset: view.offset,
+                scale: view.scale,
+            });
+        }
+    }
+}
+
+fn wheel_lines(delta: glutin::MouseScrollDelta) -> Vector {
+    match delta {
+        glutin::MouseScrollDelta::LineDelta(x, y) => Vector::new(-x as f32, y as f32),
+        glutin::MouseScrollDelta::PixelDelta(x, y) => Vector::new(-x as f32, y as f32) / WHEEL_LINE_HEIGHT,
+    }
+}
+
+/// Shows one widget, the content, panned and zoomed, eg. for a node editor or image viewer.
+/// Drag with the middle mouse button or scroll to pan, and hold control while scrolling to zoom around the cursor.
+/// The content is laid out as usual at the top left of the viewport, panning and zooming only change
+/// how it's drawn, and hit tested, not its layout.
+pub struct ViewportBuilder {
+    pub widget: WidgetBuilder,
+    content: Option<WidgetBuilder>,
+    view: ViewTransform,
+    min_scale: f32,
+    max_scale: f32,
+    fit_content: bool,
+}
+
+impl ViewportBuilder {
+    pub fn new() -> Self {
+        let mut widget = WidgetBuilder::new("viewport");
+        widget.layout().no_container();
+        ViewportBuilder {
+            widget: widget,
+            content: None,
+            view: ViewTransform::identity(),
+            min_scale: DEFAULT_MIN_SCALE,
+            max_scale: DEFAULT_MAX_SCALE,
+            fit_content: false,
+        }
+    }
+    pub fn add_content<C: Into<WidgetBuilder>>(&mut self, widget: C) -> &mut Self {
+        self.content = Some(widget.into());
+        self
+    }
+    /// The smallest and largest scale the content can be zoomed to
+    pub fn set_zoom_limits(&mut self, min_scale: f32, max_scale: f32) -> &mut Self {
+        self.min_scale = min_scale;
+        self.max_scale = max_scale;
+        self
+    }
+    /// The initial view, eg. one saved from a `ViewChangedEvent`
+    pub fn set_view(&mut self, offset: Vector, scale: f32) -> &mut Self {
+        self.view = ViewTransform::new(offset, scale);
+        self.fit_content = false;
+        self
+    }
+    /// Initially fit the content in the viewport, see `FitContentEvent`
+    pub fn fit_content(&mut self) -> &mut Self {
+        self.fit_content = true;
+        self
+    }
+    pub fn on_view_changed<F>(&mut self, callback: F) -> &mut Self
+        where F: Fn(&ViewChangedEvent, EventArgs) + 'static
+    {
+        self.widget.add_handler_fn(callback);
+        self
+    }
+}
+
+widget_builder!(ViewportBuilder);
+impl Into<WidgetBuilder> for ViewportBuilder {
+    fn into(mut self) -> WidgetBuilder {
+        let mut content = self.content.expect("Viewport has no content");
+        content.layout().add(constraints![
+            align_left(&self.widget),
+            align_top(&self.widget),
+        ]);
+        let viewport_ref = self.widget.widget_ref();
+        content.add_handler_fn(move |_: &LayoutUpdated, _| {
+            viewport_ref.event(ViewportInput::LayoutUpdated);
+        });
+        self.widget.add_handler_fn(|_: &LayoutUpdated, args| {
+            args.widget.event(ViewportInput::LayoutUpdated);
+        });
+
+        let view = ViewTransform::new(self.view.offset, self.view.scale.max(self.min_scale).min(self.max_scale));
+        self.widget.widget_ref().set_view_transform(Some(view));
+        self.widget.add_handler(ViewportHandler {
+            view: view,
+            min_scale: self.min_scale,
+            max_scale: self.max_scale,
+            content: content.widget_ref(),
+            fitting: self.fit_content,
+        });
+
+        self.widget.make_draggable_with(glutin::MouseButton::Middle);
+        self.widget.add_handler_fn(|event: &DragEvent, args| {
+            if event.state == DragState::Moved {
+                args.widget.event(ViewportInput::Pan(event.change));
+            }
+        });
+        self.widget.add_handler_fn(|event: &WidgetMouseWheel, args| {
+            let lines = wheel_lines(event.0);
+            if args.ui.ctrl_pressed() {
+                let anchor = args.ui.cursor_position();
+                args.widget.event(ViewportInput::Zoom(anchor, ZOOM_STEP.powf(lines.y)));
+            } else {
+                args.widget.event(ViewportInput::Pan(lines * WHEEL_LINE_HEIGHT));
+            }
+            *args.handled = true;
+        });
+        self.widget.add_handler_fn(|event: &SetViewEvent, args| {
+            args.widget.event(ViewportInput::Set(ViewTransform::new(event.offset, event.scale)));
+        });
+        self.widget.add_handler_fn(|_: &FitContentEvent, args| {
+            args.widget.event(ViewportInput::Fit);
+        });
+
+        self.widget.add_child(content);
+        self.widget
+    }
+}
@@ -48,6 +48,18 @@ impl Window {
         let (width, height) = self.window.get_inner_size_pixels().unwrap();
         DeviceUintSize::new(width, height)
     }
+    /// Hide the cursor and keep it inside the window, while the mouse's movement is still reported
+    /// as device events, or show it again
+    pub fn set_cursor_grabbed(&self, grabbed: bool) {
+        let state = if grabbed { glutin::CursorState::Grab } else { glutin::CursorState::Normal };
+        if let Err(err) = self.window.set_cursor_state(state) {
+            warn!("Can't set cursor state: {}", err);
+        }
+    }
+    /// Move the cursor to `x`, `y` in window coordinates
+    pub fn set_cursor_position(&self, x: f32, y: f32) {
+        self.window.set_cursor_position(x as i32, y as i32).ok();
+    }
     pub fn size_f32(&self) -> LayoutSize {
         let (width, height) = self.window.get_inner_size_pixels().unwrap();
         LayoutSize::new(width as f32, height as f32)
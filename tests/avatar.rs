@@ -0,0 +1,49 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::widgets::avatar::{AvatarBuilder, AvatarWidget};
+
+// `avatar` with its top left corner at 100, 100
+fn placed(mut avatar: AvatarBuilder) -> (App, WidgetRef) {
+    avatar.layout().add(top_left(Point::new(100.0, 100.0)));
+    let avatar_ref = avatar.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(avatar);
+    (util::headless(root), avatar_ref)
+}
+
+fn hit(app: &mut App, x: f32, y: f32) -> Option<String> {
+    app.ui().widget_under_cursor(Point::new(x, y)).map(|widget| widget.name())
+}
+
+#[test]
+fn sized_to_avatar() {
+    let (_app, avatar) = placed(AvatarBuilder::new("Ada Lovelace"));
+    assert_eq!(avatar.bounds(), Rect::new(Point::new(100.0, 100.0), Size::new(40.0, 40.0)));
+    let mut image = AvatarBuilder::from_image("rust.png");
+    image.set_size(64.0);
+    let (_app, avatar) = placed(image);
+    assert_eq!(avatar.bounds().size, Size::new(64.0, 64.0));
+}
+
+#[test]
+fn only_circle_hit() {
+    let mut avatar = AvatarBuilder::new("Ada Lovelace");
+    avatar.set_indicator(GREEN);
+    let (mut app, mut avatar) = placed(avatar);
+    assert_eq!(hit(&mut app, 120.0, 120.0), Some("avatar".to_owned()));
+    // inside the bounds but outside the circle
+    assert_eq!(hit(&mut app, 102.0, 102.0), None);
+    assert_eq!(hit(&mut app, 138.0, 102.0), None);
+    // changing the avatar once it's built doesn't change its size
+    avatar.update(|state: &mut AvatarWidget| {
+        state.name = "Alan Turing".to_owned();
+        state.show_indicator = false;
+    });
+    util::frame(&mut app);
+    assert_eq!(avatar.bounds().size, Size::new(40.0, 40.0));
+    assert_eq!(hit(&mut app, 120.0, 120.0), Some("avatar".to_owned()));
+}
@@ -0,0 +1,50 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::widgets::badge::{BadgeBuilder, BadgeWidget};
+
+// a 100x50 target at 100, 100, with a badge showing `count` over it
+fn badged(count: u32) -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut target = WidgetBuilder::new("target");
+    target.layout().add(constraints![top_left(Point::new(100.0, 100.0)), size(Size::new(100.0, 50.0))]);
+    let mut badge = BadgeBuilder::new();
+    badge.target(target.id()).set_count(count);
+    let badge_ref = badge.widget_ref();
+    root.add_child(target).add_child(badge);
+    (util::headless(root), badge_ref)
+}
+
+fn hit(app: &mut App, point: Point) -> Option<String> {
+    app.ui().widget_under_cursor(point).map(|widget| widget.name())
+}
+
+fn label(badge: &WidgetRef) -> String {
+    let label = badge.drawable::<BadgeWidget>().unwrap().label();
+    label
+}
+
+#[test]
+fn centered_on_target_corner() {
+    let (mut app, badge) = badged(3);
+    assert_eq!(badge.bounds(), Rect::new(Point::new(190.0, 90.0), Size::new(20.0, 20.0)));
+    assert_eq!(label(&badge), "3");
+    assert_eq!(hit(&mut app, Point::new(200.0, 100.0)), Some("badge".to_owned()));
+}
+
+#[test]
+fn hidden_while_count_is_zero() {
+    let (mut app, mut badge) = badged(0);
+    assert_eq!(label(&badge), "");
+    assert_eq!(hit(&mut app, Point::new(200.0, 100.0)), None);
+    // larger counts than the badge shows are capped
+    badge.update(|state: &mut BadgeWidget| state.set_count(100));
+    util::frame(&mut app);
+    assert_eq!(label(&badge), "99+");
+    assert_eq!(hit(&mut app, Point::new(200.0, 100.0)), Some("badge".to_owned()));
+}
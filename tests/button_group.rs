@@ -0,0 +1,60 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::widgets::button::{ToggleButtonBuilder, ButtonGroup};
+
+fn toggled(button: &WidgetRef) -> bool {
+    button.props().contains(&Property::Activated)
+}
+
+#[test]
+fn one_button_on_at_a_time() {
+    let group = ButtonGroup::new();
+    let mut root = WidgetBuilder::new("root");
+    root.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+    let buttons: Vec<WidgetRef> = (0..3).map(|_| {
+        let mut button = ToggleButtonBuilder::new();
+        button.set_group(&group);
+        let button_ref = button.widget_ref();
+        root.add_child(button);
+        button_ref
+    }).collect();
+    let mut app = util::headless(root);
+    assert_eq!(group.toggled_index(), None);
+
+    util::click(&mut app, util::center(&buttons[1]));
+    assert_eq!(group.toggled_index(), Some(1));
+    util::click(&mut app, util::center(&buttons[0]));
+    assert_eq!(group.toggled_index(), Some(0));
+    assert!(!toggled(&buttons[1]));
+    // clicking the button that's on leaves it on
+    util::click(&mut app, util::center(&buttons[0]));
+    assert_eq!(group.toggled_index(), Some(0));
+}
+
+#[test]
+fn removed_buttons_are_dropped() {
+    let group = ButtonGroup::new();
+    let mut root = WidgetBuilder::new("root");
+    root.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+    let mut buttons: Vec<WidgetRef> = (0..2).map(|_| {
+        let mut button = ToggleButtonBuilder::new();
+        button.set_group(&group);
+        let button_ref = button.widget_ref();
+        root.add_child(button);
+        button_ref
+    }).collect();
+    let mut app = util::headless(root);
+
+    let removed = buttons.pop().unwrap();
+    let removed_weak = removed.downgrade();
+    app.ui().remove_widget(removed.id());
+    drop(removed);
+    util::frame(&mut app);
+    // the group doesn't keep the button alive
+    assert!(removed_weak.upgrade().is_none());
+    util::click(&mut app, util::center(&buttons[0]));
+    assert_eq!(group.toggled_index(), Some(0));
+}
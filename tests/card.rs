@@ -0,0 +1,45 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::widgets::card::CardBuilder;
+
+// a 200x100 card at 100, 100 with `elevation`, holding a child that fills it
+fn card(elevation: u8) -> (App, WidgetRef) {
+    let mut card = CardBuilder::new();
+    card.elevation(elevation).padding(10.0);
+    card.layout().add(constraints![top_left(Point::new(100.0, 100.0)), size(Size::new(200.0, 100.0))]);
+    let child = WidgetBuilder::new("child");
+    let child_ref = child.widget_ref();
+    card.add_child(child);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(card);
+    (util::headless(root), child_ref)
+}
+
+fn hit(app: &mut App, x: f32, y: f32) -> Option<String> {
+    app.ui().widget_under_cursor(Point::new(x, y)).map(|widget| widget.name())
+}
+
+#[test]
+fn children_leave_room_for_shadow() {
+    let (_app, child) = card(2);
+    // inset by the padding, and by the shadow along the right and bottom
+    assert_eq!(child.bounds(), Rect::new(Point::new(110.0, 110.0), Size::new(174.0, 74.0)));
+    let (_app, child) = card(0);
+    assert_eq!(child.bounds(), Rect::new(Point::new(110.0, 110.0), Size::new(180.0, 80.0)));
+}
+
+#[test]
+fn shadow_not_hit() {
+    let (mut app, _) = card(2);
+    assert_eq!(hit(&mut app, 102.0, 102.0), Some("card".to_owned()));
+    assert_eq!(hit(&mut app, 292.0, 150.0), Some("card".to_owned()));
+    // inside the bounds, but past the card's edge where its shadow is drawn
+    assert_eq!(hit(&mut app, 297.0, 150.0), None);
+    assert_eq!(hit(&mut app, 150.0, 197.0), None);
+}
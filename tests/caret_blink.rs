@@ -0,0 +1,88 @@
+extern crate limn;
+
+mod util;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::TextSelectionOverlay;
+use limn::widgets::edit_text::EditTextBuilder;
+
+// the overlay the caret of an edit text is drawn by
+fn selection_widget(widget: &WidgetRef) -> Option<WidgetRef> {
+    if widget.name() == "edit_text_selection" {
+        return Some(widget.clone());
+    }
+    widget.children().iter().filter_map(selection_widget).next()
+}
+
+fn caret_visible(edit_text: &WidgetRef) -> bool {
+    let selection = selection_widget(edit_text).unwrap();
+    let visible = selection.drawable::<TextSelectionOverlay>().unwrap().caret_visible;
+    visible
+}
+
+// two edit texts with a caret that blinks every 20ms, the first is focused
+fn edit_texts() -> (App, WidgetRef, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut edit_texts = Vec::new();
+    for _ in 0..2 {
+        let mut edit_text = EditTextBuilder::new();
+        edit_text.set_caret_blink_interval(Some(Duration::from_millis(20)));
+        let widget: WidgetRef = edit_text.into();
+        root.add_child(widget.clone());
+        edit_texts.push(widget);
+    }
+    let app = util::headless(root);
+    (app, edit_texts[0].clone(), edit_texts[1].clone())
+}
+
+// sleep until the next timer is due, then handle the frame it's sent in
+fn wait_for_timer(app: &mut App) {
+    let deadline = app.ui().next_timer().expect("No timer is waiting");
+    let now = Instant::now();
+    if deadline > now {
+        thread::sleep(deadline - now);
+    }
+    util::frame(app);
+}
+
+#[test]
+fn blinks_while_focused() {
+    let (mut app, first, second) = edit_texts();
+    assert!(caret_visible(&first));
+    assert!(!caret_visible(&second));
+    // the main loop isn't kept drawing frames in between blinks
+    assert!(!app.ui().animations_active());
+    assert!(app.ui().next_timer().is_some());
+    wait_for_timer(&mut app);
+    assert!(!caret_visible(&first));
+    wait_for_timer(&mut app);
+    assert!(caret_visible(&first));
+}
+
+#[test]
+fn shown_while_typing() {
+    let (mut app, first, _) = edit_texts();
+    wait_for_timer(&mut app);
+    assert!(!caret_visible(&first));
+    util::type_text(&mut app, "a");
+    assert!(caret_visible(&first));
+}
+
+#[test]
+fn hidden_once_blurred() {
+    let (mut app, first, second) = edit_texts();
+    util::type_text(&mut app, "\t");
+    assert!(!caret_visible(&first));
+    assert!(caret_visible(&second));
+    // only the focused edit text is still blinking
+    wait_for_timer(&mut app);
+    assert!(!caret_visible(&first));
+    assert!(!caret_visible(&second));
+    wait_for_timer(&mut app);
+    assert!(!caret_visible(&first));
+    assert!(caret_visible(&second));
+}
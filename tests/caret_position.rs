@@ -0,0 +1,84 @@
+extern crate limn;
+
+mod util;
+
+use limn::app::App;
+use limn::geometry::Point;
+use limn::widget::{WidgetBuilder, WidgetRef};
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::draw::text::{TextState, index_at_x, word_at};
+
+// the edges of glyphs 10 wide, starting at 0
+fn edges(chars: usize) -> Vec<f32> {
+    (0..chars + 1).map(|index| index as f32 * 10.0).collect()
+}
+
+#[test]
+fn nearest_edge() {
+    let positions = edges(4);
+    assert_eq!(index_at_x(&positions, 4.0), 0);
+    assert_eq!(index_at_x(&positions, 6.0), 1);
+    assert_eq!(index_at_x(&positions, 20.0), 2);
+    // halfway between two edges
+    assert_eq!(index_at_x(&positions, 15.0), 1);
+}
+
+#[test]
+fn outside_the_text() {
+    let positions = edges(4);
+    assert_eq!(index_at_x(&positions, -8.0), 0);
+    assert_eq!(index_at_x(&positions, 100.0), 4);
+    assert_eq!(index_at_x(&[], 12.0), 0);
+}
+
+#[test]
+fn multibyte_characters() {
+    let text = "héllo wörld";
+    let positions = edges(text.chars().count());
+    // the caret goes after the "ö", the 8th character, but the 10th byte
+    let index = index_at_x(&positions, 78.0);
+    assert_eq!(index, 8);
+    assert_eq!(text.chars().take(index).collect::<String>(), "héllo wö");
+    assert_eq!(word_at(text, index - 1), (6, 11));
+}
+
+// an edit text with `text` typed into it, and the widget the text is drawn in
+fn typed_edit_text(text: &str) -> (App, WidgetRef) {
+    let edit_text = EditTextBuilder::new();
+    let text_ref = edit_text.text_widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(edit_text);
+    let mut app = util::headless(root);
+    util::type_text(&mut app, text);
+    (app, text_ref)
+}
+
+fn text_start(text_ref: &WidgetRef) -> Point {
+    let bounds = text_ref.bounds();
+    Point::new(bounds.origin.x + 1.0, bounds.origin.y + bounds.size.height / 2.0)
+}
+
+fn text(text_ref: &WidgetRef) -> String {
+    text_ref.drawable::<TextState>().unwrap().text.clone()
+}
+
+#[test]
+fn click_moves_caret() {
+    let (mut app, text_ref) = typed_edit_text("hello world");
+    let start = text_start(&text_ref);
+    util::click(&mut app, start);
+    util::type_text(&mut app, "X");
+    assert_eq!(text(&text_ref), "Xhello world");
+}
+
+#[test]
+fn shift_click_extends_selection() {
+    let (mut app, text_ref) = typed_edit_text("hello world");
+    let start = text_start(&text_ref);
+    util::key(&mut app, "LShift", true);
+    util::click(&mut app, start);
+    util::key(&mut app, "LShift", false);
+    // the selection from the caret at the end to the click is replaced
+    util::type_text(&mut app, "X");
+    assert_eq!(text(&text_ref), "X");
+}
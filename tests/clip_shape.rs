@@ -0,0 +1,67 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::geometry::ClipShape;
+
+// a card at `rect` clipped to `shape`, with an image filling it
+fn card(shape: ClipShape, rect: Rect, clip_hit_testing: bool) -> App {
+    let mut root = WidgetBuilder::new("root");
+    let mut card = WidgetBuilder::with_draw_state("card", RectState::new());
+    card.layout().add(constraints![top_left(rect.origin), size(rect.size)]);
+    card.set_clip_shape(shape);
+    card.widget_ref().set_clip_hit_testing(clip_hit_testing);
+    let mut image = WidgetBuilder::with_draw_state("image", RectState::new());
+    image.layout().add(constraints![top_left(rect.origin), size(rect.size)]);
+    card.add_child(image);
+    root.add_child(card);
+    util::headless(root)
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect::new(Point::new(x, y), Size::new(width, height))
+}
+
+fn hit(app: &mut App, x: f32, y: f32) -> Option<String> {
+    app.ui().widget_under_cursor(Point::new(x, y)).map(|widget| widget.name())
+}
+
+#[test]
+fn clipped_corners_hit_the_card() {
+    let mut app = card(ClipShape::RoundedRect(10.0), rect(10.0, 10.0, 100.0, 50.0), true);
+    assert_eq!(hit(&mut app, 60.0, 35.0), Some("image".to_owned()));
+    // along the edges, away from the corners
+    assert_eq!(hit(&mut app, 10.5, 35.0), Some("image".to_owned()));
+    assert_eq!(hit(&mut app, 60.0, 10.5), Some("image".to_owned()));
+    // cut off by the top left and bottom right corners, the card itself isn't clipped
+    assert_eq!(hit(&mut app, 11.0, 11.0), Some("card".to_owned()));
+    assert_eq!(hit(&mut app, 109.0, 59.0), Some("card".to_owned()));
+    assert_eq!(hit(&mut app, 14.0, 14.0), Some("image".to_owned()));
+    assert_eq!(hit(&mut app, 5.0, 35.0), None);
+}
+
+#[test]
+fn corners_hit_without_clip_hit_testing() {
+    let mut app = card(ClipShape::RoundedRect(10.0), rect(10.0, 10.0, 100.0, 50.0), false);
+    assert_eq!(hit(&mut app, 11.0, 11.0), Some("image".to_owned()));
+}
+
+#[test]
+fn square_corners() {
+    let mut app = card(ClipShape::RoundedRect(0.0), rect(0.0, 0.0, 100.0, 50.0), true);
+    assert_eq!(hit(&mut app, 0.5, 0.5), Some("image".to_owned()));
+}
+
+#[test]
+fn ellipse() {
+    let mut app = card(ClipShape::Ellipse, rect(0.0, 0.0, 100.0, 100.0), true);
+    assert_eq!(hit(&mut app, 50.0, 50.0), Some("image".to_owned()));
+    assert_eq!(hit(&mut app, 50.0, 1.0), Some("image".to_owned()));
+    assert_eq!(hit(&mut app, 10.0, 10.0), Some("card".to_owned()));
+    assert_eq!(hit(&mut app, 20.0, 20.0), Some("image".to_owned()));
+}
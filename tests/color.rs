@@ -0,0 +1,83 @@
+extern crate limn;
+
+use limn::color::*;
+
+#[test]
+fn from_hex_web_colors() {
+    assert_eq!(Color::from_hex("#FF0000"), Ok(RED));
+    assert_eq!(Color::from_hex("#00ff00"), Ok(GREEN));
+    assert_eq!(Color::from_hex("#6060D0"), Ok(BLUE_HIGHLIGHT));
+    assert_eq!(Color::from_hex("#00000000"), Ok(TRANSPARENT));
+}
+
+#[test]
+fn from_hex_shorthand() {
+    assert_eq!(Color::from_hex("#000"), Ok(BLACK));
+    assert_eq!(Color::from_hex("#fff"), Ok(WHITE));
+    assert_eq!(Color::from_hex("#0ff"), Ok(CYAN));
+    assert_eq!(Color::from_hex("#0000"), Ok(TRANSPARENT));
+}
+
+#[test]
+fn from_hex_errors() {
+    assert_eq!(Color::from_hex("FF0000"), Err(ParseColorError::MissingHash));
+    assert_eq!(Color::from_hex("#"), Err(ParseColorError::InvalidLength(0)));
+    assert_eq!(Color::from_hex("#FF000"), Err(ParseColorError::InvalidLength(5)));
+    assert_eq!(Color::from_hex("#FF00GG"), Err(ParseColorError::InvalidDigit('G')));
+}
+
+#[test]
+fn to_hex_round_trip() {
+    assert_eq!(RED.to_hex(), "#FF0000");
+    assert_eq!(GRAY_50.to_hex(), "#808080");
+    // alpha isn't included
+    assert_eq!(TRANSPARENT.to_hex(), "#000000");
+    assert_eq!(Color::from_hex(&BLUE_HIGHLIGHT.to_hex()), Ok(BLUE_HIGHLIGHT));
+}
+
+#[test]
+fn from_hsl_primaries() {
+    assert_eq!(Color::from_hsl(0.0, 1.0, 0.5), RED);
+    assert_eq!(Color::from_hsl(120.0, 1.0, 0.5), GREEN);
+    assert_eq!(Color::from_hsl(240.0, 1.0, 0.5), BLUE);
+    // hue wraps around
+    assert_eq!(Color::from_hsl(-60.0, 1.0, 0.5), FUSCHIA);
+    assert_eq!(Color::from_hsl(0.0, 0.0, 0.0), BLACK);
+    assert_eq!(Color::from_hsl(0.0, 0.0, 1.0), WHITE);
+}
+
+#[test]
+fn to_hsl_round_trip() {
+    assert_eq!(CYAN.to_hsl(), (180.0, 1.0, 0.5));
+    assert_eq!(WHITE.to_hsl(), (0.0, 0.0, 1.0));
+    for color in &[RED, YELLOW, GRAY_30, BLUE_HIGHLIGHT] {
+        let (hue, saturation, lightness) = color.to_hsl();
+        assert_eq!(Color::from_hsl(hue, saturation, lightness), *color);
+    }
+}
+
+#[test]
+fn from_hsv_primaries() {
+    assert_eq!(Color::from_hsv(0.0, 1.0, 1.0), RED);
+    assert_eq!(Color::from_hsv(120.0, 1.0, 1.0), GREEN);
+    assert_eq!(Color::from_hsv(240.0, 1.0, 1.0), BLUE);
+    assert_eq!(Color::from_hsv(-60.0, 1.0, 1.0), FUSCHIA);
+    assert_eq!(Color::from_hsv(0.0, 0.0, 0.0), BLACK);
+    assert_eq!(Color::from_hsv(0.0, 0.0, 1.0), WHITE);
+}
+
+#[test]
+fn to_hsv_round_trip() {
+    assert_eq!(CYAN.to_hsv(), (180.0, 1.0, 1.0));
+    assert_eq!(BLACK.to_hsv(), (0.0, 0.0, 0.0));
+    for color in &[RED, YELLOW, GRAY_30, BLUE_HIGHLIGHT] {
+        let (hue, saturation, value) = color.to_hsv();
+        assert_eq!(Color::from_hsv(hue, saturation, value), *color);
+    }
+    // every hex color survives the trip through hsv
+    for hex in &["#123456", "#FEDCBA", "#010203", "#7F7F80"] {
+        let color = Color::from_hex(hex).unwrap();
+        let (hue, saturation, value) = color.to_hsv();
+        assert_eq!(Color::from_hsv(hue, saturation, value).to_hex(), *hex);
+    }
+}
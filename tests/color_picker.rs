@@ -0,0 +1,110 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::TextState;
+use limn::widgets::color_picker::{ColorPickerBuilder, SaturationValueState, SetPickerColor};
+
+struct Picker {
+    app: App,
+    widget: WidgetRef,
+    hex_text: WidgetRef,
+    // the colors sent in `ColorChangedEvent`s
+    changes: Rc<RefCell<Vec<Color>>>,
+}
+
+fn picker(initial: Color) -> Picker {
+    let mut picker = ColorPickerBuilder::new();
+    picker.initial(initial);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    {
+        let changes = changes.clone();
+        picker.on_color_changed(move |event, _| changes.borrow_mut().push(event.0));
+    }
+    let (widget, hex_text) = (picker.widget_ref(), picker.edit_text.text_widget.widget_ref());
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(picker);
+    Picker { app: util::headless(root), widget: widget, hex_text: hex_text, changes: changes }
+}
+
+impl Picker {
+    fn child(&self, name: &str) -> WidgetRef {
+        self.widget.children().into_iter().find(|child| child.name() == name).unwrap()
+    }
+    // the hue, saturation and value shown by the square
+    fn hsv(&self) -> (f32, f32, f32) {
+        let square = self.child("color_picker_square");
+        let hsv = {
+            let state = square.drawable::<SaturationValueState>().unwrap();
+            (state.hue, state.saturation, state.value)
+        };
+        hsv
+    }
+    fn hex(&self) -> String {
+        let hex = self.hex_text.drawable::<TextState>().unwrap().text.clone();
+        hex
+    }
+    fn set_color(&mut self, color: Color) {
+        self.widget.event(SetPickerColor(color));
+        util::frame(&mut self.app);
+    }
+}
+
+#[test]
+fn dragging_wheel_picks_hue_clockwise_from_top() {
+    let mut picker = picker(RED);
+    let center = util::center(&picker.child("color_picker_wheel"));
+    // on the ring, right of the center
+    util::press(&mut picker.app, center + Vector::new(70.0, 0.0));
+    assert!((picker.hsv().0 - 90.0).abs() < 0.01);
+    util::move_mouse(&mut picker.app, center + Vector::new(0.0, 70.0));
+    util::release(&mut picker.app);
+    let (hue, saturation, value) = picker.hsv();
+    assert!((hue - 180.0).abs() < 0.01);
+    assert_eq!((saturation, value), (1.0, 1.0));
+    let last_change = *picker.changes.borrow().last().unwrap();
+    assert_eq!(last_change, Color::from_hsv(hue, saturation, value));
+    assert_eq!(picker.hex(), last_change.to_hex());
+}
+
+#[test]
+fn square_picks_clamped_saturation_and_value() {
+    let mut picker = picker(RED);
+    let square = picker.child("color_picker_square").bounds();
+    util::press(&mut picker.app, square.origin + Vector::new(square.width() / 4.0, square.height() / 4.0));
+    let (_, saturation, value) = picker.hsv();
+    assert!((saturation - 0.25).abs() < 0.01 && (value - 0.75).abs() < 0.01);
+    // dragged past the bottom left corner
+    util::move_mouse(&mut picker.app, square.origin + Vector::new(-10.0, square.height() + 50.0));
+    util::release(&mut picker.app);
+    assert_eq!(picker.hsv(), (0.0, 0.0, 0.0));
+    assert_eq!(picker.changes.borrow().last(), Some(&BLACK));
+}
+
+#[test]
+fn hex_round_trips_through_picker() {
+    let mut picker = picker(WHITE);
+    for hex in &["#FF0000", "#123456", "#80FF40", "#808080", "#000000", "#FFFFFF"] {
+        picker.set_color(Color::from_hex(hex).unwrap());
+        assert_eq!(picker.hex(), *hex);
+    }
+    // setting the color doesn't send a change
+    assert!(picker.changes.borrow().is_empty());
+}
+
+#[test]
+fn grays_keep_previous_hue() {
+    let mut picker = picker(Color::from_hsv(200.0, 0.5, 0.5));
+    let (hue, saturation, _) = picker.hsv();
+    picker.set_color(GRAY_50);
+    assert_eq!((picker.hsv().0, picker.hsv().1), (hue, 0.0));
+    // black keeps the saturation too
+    picker.set_color(Color::from_hsv(200.0, 0.5, 0.5));
+    picker.set_color(BLACK);
+    assert_eq!(picker.hsv(), (hue, saturation, 0.0));
+}
@@ -0,0 +1,55 @@
+extern crate limn;
+
+mod util;
+
+use std::thread;
+
+use limn::prelude::*;
+use limn::widgets::text::TextBuilder;
+use limn::draw::text::TextState;
+
+#[test]
+fn commands_from_other_threads_applied() {
+    let mut list = WidgetBuilder::new("list");
+    list.layout().no_container();
+    let list_ref = list.widget_ref();
+    let status = TextBuilder::new("working");
+    let status_ref = status.widget_ref();
+    let removed = WidgetBuilder::new("removed");
+    let removed_ref = removed.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(list).add_child(status).add_child(removed);
+    let mut app = util::headless(root);
+
+    let sender = app.ui().command_sender();
+    let (list_id, status_id, removed_id) = (list_ref.id(), status_ref.id(), removed_ref.id());
+    let threads: Vec<_> = (0..4).map(|_| {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            sender.add_widget(list_id, || WidgetBuilder::new("added"));
+            sender.set_text(status_id, "done");
+            sender.remove_widget(removed_id);
+            // ignored, the widget is removed by the command before
+            sender.set_text(removed_id, "done");
+        })
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    // nothing changes until the commands are handled on the UI thread
+    assert!(list_ref.children().is_empty());
+    assert_eq!(status_ref.drawable::<TextState>().unwrap().text, "working");
+
+    util::frame(&mut app);
+    let added: Vec<String> = list_ref.children().iter().map(|child| child.name()).collect();
+    assert_eq!(added, vec!["added"; 4]);
+    for child in list_ref.children() {
+        assert!(app.ui().get_widget(child.id()).is_some());
+    }
+    assert_eq!(status_ref.drawable::<TextState>().unwrap().text, "done");
+    assert!(app.ui().get_widget(removed_id).is_none());
+
+    sender.run(|ui| ui.remove_widget(list_id));
+    util::frame(&mut app);
+    assert!(app.ui().get_widget(list_id).is_none());
+}
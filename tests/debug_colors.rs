@@ -0,0 +1,41 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+
+// `depth` widgets nested in each other, the outermost first
+fn nested(depth: usize) -> Vec<WidgetRef> {
+    let widgets: Vec<WidgetRef> = (0..depth).map(|level| WidgetBuilder::new(&format!("level_{}", level)).into()).collect();
+    for pair in widgets.windows(2) {
+        pair[0].clone().add_child(pair[1].clone());
+    }
+    widgets
+}
+
+#[test]
+fn outline_colors_rotate_by_depth() {
+    let widgets = nested(8);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(widgets[0].clone());
+    let mut app = util::headless(root);
+    // the ui's root is green, the root attached to it is one level below
+    assert_eq!(app.ui().get_root().debug_outline_color(), GREEN);
+    let colors: Vec<Color> = widgets.iter().map(|widget| widget.debug_outline_color()).collect();
+    assert_eq!(&colors[..2], &[YELLOW, FUSCHIA]);
+    assert!(colors[0] != colors[1]);
+    // repeating for deeper levels
+    assert_eq!(colors[6], colors[0]);
+    assert_eq!(colors[7], colors[1]);
+}
+
+#[test]
+fn set_color_used_at_any_depth() {
+    let mut widgets = nested(3);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(widgets[0].clone());
+    let _app = util::headless(root);
+    widgets[1].set_debug_color(WHITE);
+    assert_eq!(widgets[1].debug_outline_color(), WHITE);
+    assert_eq!(widgets[2].debug_outline_color(), RED);
+}
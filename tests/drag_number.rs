@@ -0,0 +1,69 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::widgets::drag_number::DragNumberBuilder;
+use limn::draw::text::TextState;
+
+// a drag number, pressed to start dragging it, with the value it was last changed to
+fn pressed_number(min: f64, max: f64, step: f64, initial: f64) -> (App, WidgetRef, Rc<Cell<f64>>) {
+    let mut root = WidgetBuilder::new("root");
+    let mut number = DragNumberBuilder::new();
+    let value = Rc::new(Cell::new(initial));
+    let changed = value.clone();
+    number.range(min, max).step(step).initial(initial).on_value_changed(move |value, _| changed.set(value));
+    let number_ref = number.widget_ref();
+    root.add_child(number);
+    let mut app = util::headless(root);
+    util::press(&mut app, util::center(&number_ref));
+    (app, number_ref, value)
+}
+
+fn drag(app: &mut App, dx: f32) {
+    app.ui().relative_motion(dx, 0.0);
+    util::frame(app);
+}
+
+#[test]
+fn one_step_per_unit() {
+    let (mut app, _, value) = pressed_number(0.0, 100.0, 0.5, 10.0);
+    drag(&mut app, 4.0);
+    assert_eq!(value.get(), 12.0);
+    drag(&mut app, -10.0);
+    assert_eq!(value.get(), 7.0);
+    // partial units are rounded to the nearest step of the whole drag
+    drag(&mut app, 0.4);
+    assert_eq!(value.get(), 7.0);
+    drag(&mut app, 0.2);
+    assert_eq!(value.get(), 7.5);
+}
+
+#[test]
+fn clamped_to_range() {
+    let (mut app, number_ref, value) = pressed_number(0.0, 10.0, 1.0, 5.0);
+    drag(&mut app, 20.0);
+    assert_eq!(value.get(), 10.0);
+    drag(&mut app, -40.0);
+    assert_eq!(value.get(), 0.0);
+    let text = number_ref.drawable::<TextState>().unwrap().text.clone();
+    assert_eq!(text, "0");
+}
+
+#[test]
+fn release_stops_drag() {
+    let (mut app, number_ref, value) = pressed_number(0.0, 100.0, 1.0, 10.0);
+    drag(&mut app, 3.0);
+    util::release(&mut app);
+    assert_eq!(app.ui().pointer_lock(), None);
+    drag(&mut app, 3.0);
+    assert_eq!(value.get(), 13.0);
+    // pressed again, the drag starts from the value it was dragged to
+    util::press(&mut app, util::center(&number_ref));
+    drag(&mut app, 2.0);
+    assert_eq!(value.get(), 15.0);
+}
@@ -0,0 +1,150 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::TextState;
+use limn::resources::{set_clipboard_text, use_app_clipboard};
+use limn::widgets::edit_text::{EditTextBuilder, EditTextMenuState};
+
+// the characters received for Ctrl+V, Ctrl+Z and Ctrl+Y
+const PASTE: &str = "\u{16}";
+const UNDO: &str = "\u{1a}";
+const REDO: &str = "\u{19}";
+
+// a focused edit text filling the window, and its text widget
+fn edit_text(single_line: bool) -> (App, WidgetRef) {
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_single_line(single_line);
+    edit_text_with(edit_text)
+}
+
+fn edit_text_with(edit_text: EditTextBuilder) -> (App, WidgetRef) {
+    let text = edit_text.text_widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(edit_text);
+    (util::headless(root), text)
+}
+
+fn text(text_widget: &WidgetRef) -> String {
+    let text = text_widget.drawable::<TextState>().unwrap().text.clone();
+    text
+}
+
+fn menu_state(text: &str, selection: Option<(usize, usize)>, read_only: bool) -> EditTextMenuState {
+    EditTextMenuState {
+        text: text.to_owned(),
+        selection: selection,
+        read_only: read_only,
+        has_clipboard_text: true,
+    }
+}
+
+#[test]
+fn copy_needs_selection() {
+    assert!(!menu_state("hello", None, false).can_copy());
+    assert!(!menu_state("hello", Some((2, 2)), false).can_copy());
+    assert!(menu_state("hello", Some((1, 3)), false).can_copy());
+}
+
+#[test]
+fn read_only_disables_cut_and_paste() {
+    let state = menu_state("hello", Some((1, 3)), true);
+    assert!(state.can_copy());
+    assert!(!state.can_cut());
+    assert!(!state.can_paste());
+    assert!(state.can_select_all());
+}
+
+#[test]
+fn selection_is_in_characters() {
+    // reversed selection, and multi-byte characters
+    let state = menu_state("héllo wörld", Some((8, 2)), false);
+    assert_eq!(state.selected_text(), Some("llo wö"));
+    assert_eq!(state.text_without_selection(), "hérld");
+    // selection past the end of the text
+    let state = menu_state("héllo", Some((3, 10)), false);
+    assert_eq!(state.selected_text(), Some("lo"));
+}
+
+#[test]
+fn pasted_line_breaks_become_spaces() {
+    use_app_clipboard();
+    set_clipboard_text("one\ntwo\r\nthree\rfour");
+    let (mut app, single_line) = edit_text(true);
+    util::type_text(&mut app, PASTE);
+    assert_eq!(text(&single_line), "one two three four");
+    // kept in text that can have several lines
+    let (mut app, multi_line) = edit_text(false);
+    util::type_text(&mut app, PASTE);
+    assert_eq!(text(&multi_line), "one\ntwo\r\nthree\rfour");
+}
+
+#[test]
+fn paste_keeps_allowed_characters() {
+    use_app_clipboard();
+    set_clipboard_text("a1b2 c3");
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_input_filter(|char| char.is_digit(10));
+    let (mut app, text_widget) = edit_text_with(edit_text);
+    util::type_text(&mut app, PASTE);
+    assert_eq!(text(&text_widget), "123");
+}
+
+#[test]
+fn paste_is_cut_to_max_length() {
+    use_app_clipboard();
+    set_clipboard_text("héllo wörld");
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_max_length(7);
+    let (mut app, text_widget) = edit_text_with(edit_text);
+    util::type_text(&mut app, "ab");
+    util::type_text(&mut app, PASTE);
+    assert_eq!(text(&text_widget), "abhéllo");
+    // full, nothing more is pasted
+    util::type_text(&mut app, PASTE);
+    assert_eq!(text(&text_widget), "abhéllo");
+}
+
+#[test]
+fn undo_removes_typed_words() {
+    let (mut app, text_widget) = edit_text(false);
+    util::type_text(&mut app, "héllo wörld");
+    util::type_text(&mut app, UNDO);
+    assert_eq!(text(&text_widget), "héllo ");
+    util::type_text(&mut app, UNDO);
+    assert_eq!(text(&text_widget), "");
+    util::type_text(&mut app, UNDO);
+    assert_eq!(text(&text_widget), "");
+    util::type_text(&mut app, REDO);
+    assert_eq!(text(&text_widget), "héllo ");
+}
+
+#[test]
+fn letter_typed_inside_text_is_undone() {
+    let (mut app, text_widget) = edit_text(false);
+    util::type_text(&mut app, "hello");
+    util::tap_key(&mut app, "Left");
+    util::tap_key(&mut app, "Left");
+    util::type_text(&mut app, "l");
+    assert_eq!(text(&text_widget), "helllo");
+    util::type_text(&mut app, UNDO);
+    assert_eq!(text(&text_widget), "hello");
+    // the caret is back where the letter was typed
+    util::type_text(&mut app, "p");
+    assert_eq!(text(&text_widget), "helplo");
+}
+
+#[test]
+fn new_change_clears_redo() {
+    let (mut app, text_widget) = edit_text(false);
+    util::type_text(&mut app, "héllo wörld");
+    util::type_text(&mut app, UNDO);
+    util::type_text(&mut app, "d");
+    util::type_text(&mut app, REDO);
+    assert_eq!(text(&text_widget), "héllo d");
+    // typing after an undo starts a new change
+    util::type_text(&mut app, UNDO);
+    assert_eq!(text(&text_widget), "héllo ");
+}
@@ -0,0 +1,100 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::app::App;
+use limn::widget::{WidgetBuilder, WidgetRef};
+use limn::widgets::error_boundary::{ErrorBoundaryBuilder, WidgetErrorEvent};
+use limn::resources::lookup_widget_by_name;
+use limn::draw::text::TextState;
+
+#[test]
+fn builds_content() {
+    let boundary = ErrorBoundaryBuilder::new(|| Ok(WidgetBuilder::new("plugin_panel")));
+    let boundary: WidgetRef = boundary.into();
+    let children = boundary.children();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].name(), "plugin_panel");
+}
+
+#[test]
+fn shows_error_panel_when_factory_fails() {
+    let boundary = ErrorBoundaryBuilder::new(|| Err("couldn't load plugin".to_owned()));
+    let boundary: WidgetRef = boundary.into();
+    let children = boundary.children();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].name(), "error_panel");
+    let names: Vec<String> = children[0].children().iter().map(|child| child.name()).collect();
+    assert_eq!(names, vec!["error_message".to_owned(), "error_retry".to_owned()]);
+}
+
+struct Crash;
+
+fn named(app: &mut App, name: &str) -> Option<WidgetRef> {
+    lookup_widget_by_name(name).and_then(|id| app.ui().get_widget(id))
+}
+
+// the child of the error panel in `boundary` named `name`,
+// not looked up by name since each error panel's children have the same names
+fn error_panel_child(boundary: &WidgetRef, name: &str) -> WidgetRef {
+    let panel = boundary.children()[0].clone();
+    assert_eq!(panel.name(), "error_panel");
+    panel.children().into_iter().find(|child| child.name() == name).unwrap()
+}
+
+#[test]
+fn panic_replaced_by_error_panel_until_retried() {
+    let builds = Rc::new(Cell::new(0));
+    let boundary = {
+        let builds = builds.clone();
+        ErrorBoundaryBuilder::new(move || {
+            builds.set(builds.get() + 1);
+            let mut chart = WidgetBuilder::new("plugin_chart");
+            chart.add_handler_fn(|_: &Crash, _| panic!("chart data missing"));
+            let mut panel = WidgetBuilder::new("plugin_panel");
+            panel.add_child(chart);
+            Ok(panel)
+        })
+    };
+    let boundary_ref = boundary.widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(boundary);
+    let mut app = util::headless(root);
+    app.ui().set_catch_handler_panics(true);
+
+    let chart = named(&mut app, "plugin_chart").unwrap();
+    chart.event(Crash);
+    util::frame(&mut app);
+    let children: Vec<String> = boundary_ref.children().iter().map(|child| child.name()).collect();
+    assert_eq!(children, vec!["error_panel".to_owned()]);
+    // the failed content is removed with its descendants
+    assert!(app.ui().get_widget(chart.id()).is_none());
+    assert!(named(&mut app, "plugin_panel").is_none());
+    assert!(named(&mut app, "plugin_chart").is_none());
+
+    let retry = error_panel_child(&boundary_ref, "error_retry");
+    util::click(&mut app, util::center(&retry));
+    assert_eq!(builds.get(), 2);
+    let children: Vec<String> = boundary_ref.children().iter().map(|child| child.name()).collect();
+    assert_eq!(children, vec!["plugin_panel".to_owned()]);
+    assert!(app.ui().get_widget(retry.id()).is_none());
+    assert!(named(&mut app, "plugin_chart").is_some());
+}
+
+#[test]
+fn error_event_shows_message() {
+    let boundary = ErrorBoundaryBuilder::new(|| Ok(WidgetBuilder::new("loader_panel")));
+    let boundary_ref = boundary.widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(boundary);
+    let mut app = util::headless(root);
+
+    named(&mut app, "loader_panel").unwrap().event_bubble_up(WidgetErrorEvent("couldn't load data".to_owned()));
+    util::frame(&mut app);
+    let message = error_panel_child(&boundary_ref, "error_message");
+    assert_eq!(message.drawable::<TextState>().unwrap().text, "couldn't load data");
+    assert!(named(&mut app, "loader_panel").is_none());
+}
@@ -0,0 +1,69 @@
+extern crate limn;
+extern crate glutin;
+
+mod util;
+
+use std::env;
+use std::fs::File;
+use std::io::{ErrorKind, Write};
+
+use limn::event::{EventRecorder, EventReplayer, RecordedEvent, RecordedInput, RecordedButton};
+use limn::input::{NAMED_KEYS, key_name, key_from_name};
+use limn::widget::WidgetBuilder;
+
+// save the inputs to a file and read them back
+fn round_trip(name: &str, inputs: Vec<RecordedInput>) -> Vec<RecordedEvent> {
+    let mut recorder = EventRecorder::new();
+    for input in inputs {
+        recorder.record(input);
+    }
+    let path = env::temp_dir().join(format!("limn_{}.events", name));
+    recorder.save_to_file(&path).unwrap();
+    let replayer = EventReplayer::from_file(&path).unwrap();
+    assert_eq!(replayer.events(), recorder.events());
+    replayer.events().to_vec()
+}
+
+#[test]
+fn events_round_trip_through_files() {
+    let inputs = vec![
+        RecordedInput::MouseMoved(10.5, -3.0),
+        RecordedInput::MouseButton { pressed: true, button: RecordedButton::Left },
+        RecordedInput::MouseButton { pressed: false, button: RecordedButton::Other(4) },
+        RecordedInput::MouseWheel { x: 0.0, y: -1.0, pixels: false },
+        RecordedInput::Key { pressed: true, scancode: 28, key: Some("Return".to_owned()) },
+        RecordedInput::Key { pressed: false, scancode: 99, key: None },
+        RecordedInput::Character(' '),
+        RecordedInput::Character('\n'),
+    ];
+    let events = round_trip("inputs", inputs.clone());
+    assert_eq!(events.into_iter().map(|event| event.input).collect::<Vec<_>>(), inputs);
+}
+
+#[test]
+fn malformed_files_are_rejected() {
+    let path = env::temp_dir().join("limn_malformed.events");
+    for line in &["0.5 moved 10", "{\"time\":0.5,\"input\":{\"Jump\":[1,2]}}", "{\"time\":\"soon\",\"input\":{\"Character\":\"a\"}}"] {
+        writeln!(File::create(&path).unwrap(), "{}", line).unwrap();
+        let error = EventReplayer::from_file(&path).err().expect(line);
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn every_key_round_trips() {
+    let mut app = util::headless(WidgetBuilder::new("root"));
+    for &key in NAMED_KEYS {
+        let name = key_name(key).unwrap();
+        assert_eq!(name, format!("{:?}", key));
+        assert_eq!(key_from_name(name), Some(key));
+
+        let input = RecordedInput::Key { pressed: true, scancode: 0, key: Some(name.to_owned()) };
+        let replayed = round_trip(name, vec![input]).pop().unwrap();
+        util::input(&mut app, replayed.input);
+        assert!(app.ui().key_pressed(key), "{} wasn't pressed when replayed", name);
+        util::key(&mut app, name, false);
+        assert!(!app.ui().key_pressed(key));
+    }
+    assert_eq!(key_from_name("NotAKey"), None);
+}
@@ -0,0 +1,66 @@
+extern crate limn;
+
+mod util;
+
+use std::thread;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+
+struct WorkDone(usize);
+struct Local;
+
+#[test]
+fn events_from_other_threads_handled() {
+    let handled = Rc::new(RefCell::new(Vec::new()));
+    let mut worker_panel = WidgetBuilder::new("worker_panel");
+    {
+        let handled = handled.clone();
+        worker_panel.add_handler_fn(move |event: &WorkDone, _| handled.borrow_mut().push(format!("panel {}", event.0)));
+    }
+    let panel_ref = worker_panel.widget_ref();
+    let removed = WidgetBuilder::new("removed");
+    let removed_ref = removed.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(worker_panel).add_child(removed);
+    let mut app = util::headless(root);
+    {
+        let handled = handled.clone();
+        app.add_handler_fn(move |event: &WorkDone, _| handled.borrow_mut().push(format!("root {}", event.0)));
+    }
+    {
+        let handled = handled.clone();
+        app.add_handler_fn(move |_: &Local, _| handled.borrow_mut().push("local".to_owned()));
+    }
+    app.ui().remove_widget(removed_ref.id());
+    util::frame(&mut app);
+
+    let sender = app.ui().event_sender();
+    let (panel_id, removed_id) = (panel_ref.id(), removed_ref.id());
+    let threads: Vec<_> = (0..4).map(|index| {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            sender.send(WorkDone(index));
+            sender.send_to(panel_id, WorkDone(index));
+            // dropped, the widget is gone
+            sender.send_to(removed_id, WorkDone(index));
+        })
+    }).collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    app.ui().event(Local);
+    assert!(handled.borrow().is_empty());
+
+    util::frame(&mut app);
+    let mut handled = handled.borrow().clone();
+    // sent from other threads, so handled before the event sent on the UI thread
+    assert_eq!(handled.pop(), Some("local".to_owned()));
+    handled.sort();
+    let mut expected: Vec<String> = (0..4).map(|index| format!("panel {}", index))
+        .chain((0..4).map(|index| format!("root {}", index)))
+        .collect();
+    expected.sort();
+    assert_eq!(handled, expected);
+}
@@ -0,0 +1,53 @@
+extern crate limn;
+
+mod util;
+
+use std::any::TypeId;
+
+use limn::event::{EventStats, EventTotals};
+use limn::widget::WidgetBuilder;
+
+struct Ping;
+struct Pong;
+
+fn totals_for<T: 'static>(stats: &EventStats) -> Option<EventTotals> {
+    stats.totals().into_iter().find(|totals| totals.type_id == TypeId::of::<T>())
+}
+
+#[test]
+fn totals_per_event_type() {
+    let mut widget = WidgetBuilder::new("pinged");
+    widget.add_handler_fn(|_: &Ping, _| ());
+    widget.add_handler_fn(|_: &Pong, _| ());
+    let widget_ref = widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(widget);
+    let mut app = util::headless(root);
+
+    let stats = EventStats::new();
+    app.ui().set_event_hook(Some(stats.hook()));
+    widget_ref.event(Ping);
+    widget_ref.event(Pong);
+    widget_ref.event(Ping);
+    util::frame(&mut app);
+
+    // counted separately even though they have the same name without the `nightly` feature
+    let ping = totals_for::<Ping>(&stats).unwrap();
+    assert_eq!(ping.count, 2);
+    assert!(ping.max_handler_time <= ping.handler_time);
+    assert_eq!(totals_for::<Pong>(&stats).unwrap().count, 1);
+
+    let totals = stats.totals();
+    for pair in totals.windows(2) {
+        assert!(pair[0].handler_time >= pair[1].handler_time);
+    }
+    let table = stats.table();
+    assert_eq!(table.lines().count(), totals.len() + 1);
+
+    stats.clear();
+    assert!(stats.totals().is_empty());
+    app.ui().set_event_hook(None);
+    widget_ref.event(Ping);
+    util::frame(&mut app);
+    assert!(stats.totals().is_empty());
+}
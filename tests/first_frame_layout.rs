@@ -0,0 +1,26 @@
+extern crate limn;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+
+#[test]
+fn many_widgets_laid_out_before_first_frame() {
+    let mut root = WidgetBuilder::new("root");
+    let mut column = WidgetBuilder::new("column");
+    column.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    let mut rows = Vec::new();
+    for index in 0..150 {
+        let mut row = WidgetBuilder::with_draw_state(&format!("row {}", index), RectState::new());
+        row.layout().add(size(Size::new(100.0, 2.0)));
+        rows.push(row.widget_ref());
+        column.add_child(row);
+    }
+    root.add_child(column);
+    // attached without a frame, as the main loop does before sizing the window to fit
+    let mut app = App::headless(Size::new(400.0, 300.0));
+    app.attach(root);
+    let tops: Vec<f32> = rows.iter().map(|row| row.bounds().top()).collect();
+    let expected: Vec<f32> = (0..150).map(|index| index as f32 * 2.0).collect();
+    assert_eq!(tops, expected);
+}
@@ -0,0 +1,47 @@
+extern crate limn;
+
+use limn::resources::{resources, InvalidFontError};
+use limn::draw::text::TextState;
+
+static MONOSPACE: &[u8] = include_bytes!("../assets/fonts/Hack/Hack-Regular.ttf");
+
+fn text_width(font: &str, text: &str) -> f32 {
+    let mut state = TextState::new(text);
+    state.font = font.to_owned();
+    state.measure().width
+}
+
+#[test]
+fn added_font_is_used() {
+    assert_eq!(resources().add_font("added_monospace", MONOSPACE.to_vec()), Ok(()));
+    assert_eq!(text_width("added_monospace", "iiii"), text_width("added_monospace", "mmmm"));
+    // not a font that's added, or a font file, so drawn with the default font, which isn't monospace
+    assert!(text_width("missing_font", "iiii") < text_width("missing_font", "mmmm"));
+}
+
+#[test]
+fn invalid_font_data_is_rejected() {
+    let error = Err(InvalidFontError("invalid_font".to_owned()));
+    assert_eq!(resources().add_font("invalid_font", vec![0; 64]), error);
+    assert_eq!(resources().add_font("invalid_font", vec![1]), error);
+    assert!(text_width("invalid_font", "iiii") < text_width("invalid_font", "mmmm"));
+
+    // the font added before is kept
+    resources().add_font("replaced_font", MONOSPACE.to_vec()).unwrap();
+    assert!(resources().add_font("replaced_font", b"not a font".to_vec()).is_err());
+    assert_eq!(text_width("replaced_font", "iiii"), text_width("replaced_font", "mmmm"));
+}
+
+#[test]
+fn corrupt_font_file_uses_default_font() {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use limn::resources::load_font;
+    let path = "assets/fonts/corrupt_font.ttf";
+    File::create(path).unwrap().write_all(&[0; 64]).unwrap();
+    let widths = (text_width("corrupt_font", "iiii"), text_width("corrupt_font", "mmmm"));
+    let loaded = load_font("corrupt_font");
+    fs::remove_file(path).unwrap();
+    assert!(widths.0 < widths.1);
+    assert!(loaded.is_ok());
+}
@@ -0,0 +1,92 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use limn::prelude::*;
+use limn::event::catch_handler_panic;
+use limn::ui::HandlerPanickedEvent;
+
+#[test]
+fn panic_message_is_returned() {
+    assert_eq!(catch_handler_panic(|| ()), Ok(()));
+    assert_eq!(catch_handler_panic(|| panic!("static message")), Err("static message".to_owned()));
+    let id = 3;
+    assert_eq!(catch_handler_panic(|| panic!("widget {} failed", id)), Err("widget 3 failed".to_owned()));
+}
+
+#[test]
+fn widget_usable_after_panic() {
+    let widget = WidgetBuilder::new("panicking_widget");
+    let widget_ref = widget.widget_ref();
+    let result = catch_handler_panic(|| widget_ref.update_layout(|_| panic!("panicked while updating layout")));
+    assert_eq!(result, Err("panicked while updating layout".to_owned()));
+    // the layout and widget aren't left borrowed
+    widget_ref.update_layout(|_| ());
+    assert_eq!(widget_ref.name(), "panicking_widget");
+    assert!(!widget_ref.is_poisoned());
+}
+
+struct Crash;
+struct Ping;
+
+#[test]
+fn queue_and_layout_survive_panic() {
+    let mut panicking = WidgetBuilder::new("panicking_widget");
+    panicking.add_handler_fn(|_: &Crash, args| {
+        args.widget.update_layout(|layout| layout.add(min_width(10.0)));
+        panic!("crashed");
+    });
+    let panicking_ref = panicking.widget_ref();
+    let pings = Rc::new(Cell::new(0));
+    let mut sibling = WidgetBuilder::new("sibling");
+    {
+        let pings = pings.clone();
+        sibling.add_handler_fn(move |_: &Ping, _| pings.set(pings.get() + 1));
+    }
+    let sibling_ref = sibling.widget_ref();
+    let panics = Rc::new(RefCell::new(Vec::new()));
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(panicking).add_child(sibling);
+    let mut app = util::headless(root);
+    {
+        let panics = panics.clone();
+        app.add_handler_fn(move |event: &HandlerPanickedEvent, _| {
+            panics.borrow_mut().push((event.widget.id(), event.message.clone()));
+        });
+    }
+    app.ui().set_catch_handler_panics(true);
+
+    // events queued before and after the panicking one are all handled
+    sibling_ref.event(Ping);
+    panicking_ref.event(Crash);
+    sibling_ref.event(Ping);
+    util::frame(&mut app);
+    assert_eq!(pings.get(), 2);
+    assert!(panicking_ref.is_poisoned());
+    assert_eq!(*panics.borrow(), vec![(panicking_ref.id(), "crashed".to_owned())]);
+    // a poisoned widget's handlers aren't called again
+    panicking_ref.event(Crash);
+    util::frame(&mut app);
+    assert_eq!(panics.borrow().len(), 1);
+
+    // the poisoned widget is still laid out with the rest
+    let full = Rect::new(Point::new(0.0, 0.0), Size::new(600.0, 500.0));
+    app.ui().resize_headless(full.size);
+    util::frame(&mut app);
+    assert_eq!(panicking_ref.bounds(), full);
+    assert_eq!(sibling_ref.bounds(), full);
+
+    app.ui().remove_widget(panicking_ref.id());
+    util::frame(&mut app);
+    assert!(app.ui().get_widget(panicking_ref.id()).is_none());
+    let full = Rect::new(Point::new(0.0, 0.0), Size::new(300.0, 200.0));
+    app.ui().resize_headless(full.size);
+    util::frame(&mut app);
+    assert_eq!(sibling_ref.bounds(), full);
+    sibling_ref.event(Ping);
+    util::frame(&mut app);
+    assert_eq!(pings.get(), 3);
+}
@@ -0,0 +1,91 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::draw::ellipse::EllipseState;
+
+// a widget drawn with `draw_state`, placed at `rect`
+fn placed<T: Draw + 'static>(name: &str, draw_state: T, rect: Rect, hit_padding: f32) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::with_draw_state(name, draw_state);
+    widget.hit_padding(hit_padding);
+    widget.layout().add(constraints![top_left(rect.origin), size(rect.size)]);
+    widget
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+    Rect::new(Point::new(x, y), Size::new(width, height))
+}
+
+fn hit(app: &mut App, x: f32, y: f32) -> Option<String> {
+    app.ui().widget_under_cursor(Point::new(x, y)).map(|widget| widget.name())
+}
+
+#[test]
+fn ellipse_corner_not_hit() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(WidgetBuilder::with_draw_state("background", RectState::new()));
+    root.add_child(placed("circle", EllipseState::new(), rect(100.0, 100.0, 100.0, 100.0), 10.0));
+    let mut app = util::headless(root);
+    assert_eq!(hit(&mut app, 150.0, 150.0), Some("circle".to_owned()));
+    // inside the bounding box but outside the circle, even with hit padding
+    assert_eq!(hit(&mut app, 103.0, 103.0), Some("background".to_owned()));
+    assert_eq!(hit(&mut app, 197.0, 197.0), Some("background".to_owned()));
+    // within the padding outside the bounds
+    assert_eq!(hit(&mut app, 95.0, 150.0), Some("circle".to_owned()));
+}
+
+#[test]
+fn padded_hit_above_visual_hit() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(WidgetBuilder::with_draw_state("pane", RectState::new()));
+    root.add_child(placed("splitter", RectState::new(), rect(200.0, 0.0, 2.0, 300.0), 4.0));
+    let mut app = util::headless(root);
+    assert_eq!(hit(&mut app, 197.0, 150.0), Some("splitter".to_owned()));
+    assert_eq!(hit(&mut app, 150.0, 150.0), Some("pane".to_owned()));
+}
+
+#[test]
+fn padded_hit_below_visual_hit() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(placed("splitter", RectState::new(), rect(200.0, 0.0, 2.0, 300.0), 4.0));
+    root.add_child(placed("pane", RectState::new(), rect(0.0, 0.0, 199.0, 300.0), 0.0));
+    let mut app = util::headless(root);
+    // the pane is drawn above the splitter's padding
+    assert_eq!(hit(&mut app, 198.0, 150.0), Some("pane".to_owned()));
+    assert_eq!(hit(&mut app, 203.0, 150.0), Some("splitter".to_owned()));
+}
+
+#[test]
+fn closest_padded_hit() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(placed("close_button", RectState::new(), rect(100.0, 100.0, 10.0, 10.0), 8.0));
+    root.add_child(placed("resize_zone", RectState::new(), rect(114.0, 100.0, 10.0, 10.0), 8.0));
+    let mut app = util::headless(root);
+    assert_eq!(hit(&mut app, 111.0, 105.0), Some("close_button".to_owned()));
+    assert_eq!(hit(&mut app, 113.0, 105.0), Some("resize_zone".to_owned()));
+    assert_eq!(hit(&mut app, 150.0, 150.0), None);
+}
+
+#[test]
+fn pointer_transparent_skipped() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(placed("button", RectState::new(), rect(100.0, 100.0, 100.0, 100.0), 0.0));
+    let mut overlay = placed("overlay", RectState::new(), rect(0.0, 0.0, 400.0, 300.0), 0.0);
+    overlay.set_pointer_transparent(true);
+    overlay.add_child(placed("badge", RectState::new(), rect(300.0, 0.0, 20.0, 20.0), 0.0));
+    root.add_child(overlay);
+    let mut app = util::headless(root);
+    assert_eq!(hit(&mut app, 150.0, 150.0), Some("button".to_owned()));
+    assert_eq!(hit(&mut app, 50.0, 50.0), None);
+    // its children can still be hit
+    assert_eq!(hit(&mut app, 310.0, 10.0), Some("badge".to_owned()));
+    let under: Vec<String> = app.ui().widgets_under_cursor(Point::new(150.0, 150.0)).map(|widget| widget.name()).collect();
+    assert_eq!(under, vec!["button"]);
+    let under: Vec<String> = app.ui().widgets_under_cursor(Point::new(310.0, 10.0)).map(|widget| widget.name()).collect();
+    assert_eq!(under, vec!["badge"]);
+}
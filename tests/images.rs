@@ -0,0 +1,44 @@
+extern crate limn;
+extern crate webrender_api;
+
+mod util;
+
+use webrender_api::ImageFormat;
+
+use limn::prelude::*;
+use limn::resources::resources;
+use limn::widgets::image::ImageBuilder;
+
+#[test]
+fn added_images_are_used() {
+    let (red, transparent) = ([255, 0, 0, 255], [0, 0, 0, 0]);
+    let pixels = red.iter().chain(transparent.iter()).cloned().collect();
+    resources().add_image("added_image", 2, 1, pixels);
+    let info = resources().get_image("added_image").info;
+    assert_eq!((info.width, info.height), (2, 1));
+    assert!(!info.is_opaque);
+}
+
+#[test]
+fn adding_image_replaces_it() {
+    resources().add_image("replaced_image", 1, 1, vec![0, 0, 255, 255]);
+    assert!(resources().get_image("replaced_image").info.is_opaque);
+    resources().add_image("replaced_image", 2, 2, vec![0; 16]);
+    let info = resources().get_image("replaced_image").info;
+    assert_eq!((info.width, info.height), (2, 2));
+    assert!(!info.is_opaque);
+}
+
+#[test]
+fn loaded_images_with_alpha_are_bgra() {
+    let mut root = WidgetBuilder::new("root");
+    let image = ImageBuilder::new("rust.png");
+    let image_ref = image.widget_ref();
+    root.add_child(image);
+    let _app = util::headless(root);
+    assert_eq!(image_ref.bounds().size, Size::new(144.0, 144.0));
+    // the logo's corners are transparent
+    let info = resources().get_image("rust.png").info;
+    assert_eq!(info.format, ImageFormat::BGRA8);
+    assert!(!info.is_opaque);
+}
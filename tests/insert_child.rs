@@ -0,0 +1,81 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+
+fn item(name: &str) -> WidgetBuilder {
+    let mut item = WidgetBuilder::with_draw_state(name, RectState::new());
+    item.layout().add(size(Size::new(100.0, 20.0)));
+    item.make_tab_focusable();
+    item
+}
+
+// a column of the items "a", "b" and "c"
+fn column() -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut column = WidgetBuilder::new("column");
+    column.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    column.add_child(item("a")).add_child(item("b")).add_child(item("c"));
+    let column_ref = column.widget_ref();
+    root.add_child(column);
+    (util::headless(root), column_ref)
+}
+
+fn names(column: &WidgetRef) -> Vec<String> {
+    column.children().iter().map(|child| child.name()).collect()
+}
+
+fn focused(column: &WidgetRef) -> String {
+    column.children().iter().find(|child| child.props().contains(&Property::Focused)).unwrap().name()
+}
+
+#[test]
+fn inserted_between_siblings() {
+    let (mut app, column) = column();
+    app.ui().add_widget_at(item("x"), column.id(), 1).unwrap();
+    util::frame(&mut app);
+    assert_eq!(names(&column), vec!["a", "x", "b", "c"]);
+    let tops: Vec<f32> = column.children().iter().map(|child| child.bounds().top()).collect();
+    assert_eq!(tops, vec![0.0, 20.0, 40.0, 60.0]);
+}
+
+#[test]
+fn inserted_in_tab_order() {
+    let (mut app, column) = column();
+    app.ui().add_widget_at(item("x"), column.id(), 1).unwrap();
+    util::frame(&mut app);
+    assert_eq!(focused(&column), "a");
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        util::type_text(&mut app, "\t");
+        order.push(focused(&column));
+    }
+    assert_eq!(order, vec!["x", "b", "c", "a"]);
+}
+
+#[test]
+fn index_past_end_appends() {
+    let (mut app, column) = column();
+    app.ui().add_widget_at(item("x"), column.id(), 10).unwrap();
+    util::frame(&mut app);
+    assert_eq!(names(&column), vec!["a", "b", "c", "x"]);
+    assert_eq!(column.children()[3].bounds().top(), 60.0);
+}
+
+#[test]
+fn inserted_first_is_drawn_under_siblings() {
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(WidgetBuilder::with_draw_state("front", RectState::new()));
+    let root_ref = root.widget_ref();
+    let mut app = util::headless(root);
+    app.ui().add_widget_at(WidgetBuilder::with_draw_state("back", RectState::new()), root_ref.id(), 0).unwrap();
+    util::frame(&mut app);
+    // both cover the window, the one drawn last is hit
+    assert_eq!(app.ui().widget_under_cursor(Point::new(200.0, 150.0)).map(|widget| widget.name()), Some("front".to_owned()));
+    app.ui().add_widget_at(WidgetBuilder::with_draw_state("top", RectState::new()), root_ref.id(), 2).unwrap();
+    util::frame(&mut app);
+    assert_eq!(app.ui().widget_under_cursor(Point::new(200.0, 150.0)).map(|widget| widget.name()), Some("top".to_owned()));
+}
@@ -0,0 +1,32 @@
+extern crate limn;
+
+use limn::color::*;
+use limn::geometry::{Rect, Point, Size};
+use limn::util::{interpolate_rects, interpolate_colors};
+use limn::util::easing::lerp;
+
+#[test]
+fn lerp_between_values() {
+    assert_eq!(lerp(10.0, 20.0, 0.0), 10.0);
+    assert_eq!(lerp(10.0, 20.0, 0.25), 12.5);
+    assert_eq!(lerp(10.0, 20.0, 1.0), 20.0);
+    assert_eq!(lerp(20.0, 10.0, 0.5), 15.0);
+}
+
+#[test]
+fn interpolate_rect_edges() {
+    let from = Rect::new(Point::new(0.0, 0.0), Size::new(100.0, 50.0));
+    let to = Rect::new(Point::new(100.0, 20.0), Size::new(200.0, 100.0));
+    assert_eq!(interpolate_rects(from, to, 0.0), from);
+    assert_eq!(interpolate_rects(from, to, 1.0), to);
+    assert_eq!(interpolate_rects(from, to, 0.5), Rect::new(Point::new(50.0, 10.0), Size::new(150.0, 75.0)));
+}
+
+#[test]
+fn interpolate_color_channels() {
+    assert_eq!(interpolate_colors(BLACK, WHITE, 0.0), BLACK);
+    assert_eq!(interpolate_colors(BLACK, WHITE, 1.0), WHITE);
+    assert_eq!(interpolate_colors(BLACK, WHITE, 0.5), Color::from_rgba(128, 128, 128, 255));
+    assert_eq!(interpolate_colors(TRANSPARENT, RED, 0.5), Color::from_rgba(128, 0, 0, 128));
+    assert_eq!(RED.to_rgba(), (255, 0, 0, 255));
+}
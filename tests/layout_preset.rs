@@ -0,0 +1,89 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+extern crate serde_json;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::layout::{LayoutPreset, PresetValue, VarType};
+
+// a sidebar 200 wide, resizable by the user
+fn sidebar() -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    let mut sidebar = WidgetBuilder::with_draw_state("sidebar", RectState::new());
+    sidebar.layout().add(constraints![top_left(Point::new(0.0, 0.0)), height(300.0)]);
+    sidebar.layout().edit_width().set(200.0);
+    let sidebar_ref = sidebar.widget_ref();
+    root.add_child(sidebar);
+    (util::headless(root), sidebar_ref)
+}
+
+fn resize(app: &mut App, sidebar: &WidgetRef, width: f32) {
+    sidebar.update_layout(|layout| {
+        layout.edit_width().set(width);
+    });
+    util::frame(app);
+}
+
+#[test]
+fn presets_restore_sizes() {
+    let (mut app, sidebar) = sidebar();
+    let coding = app.ui().capture_layout_preset(&["sidebar"]);
+    resize(&mut app, &sidebar, 100.0);
+    assert_eq!(sidebar.bounds().width(), 100.0);
+    let debugging = app.ui().capture_layout_preset(&["sidebar"]);
+
+    app.ui().apply_layout_preset(&coding, false);
+    util::frame(&mut app);
+    assert_eq!(sidebar.bounds().width(), 200.0);
+    app.ui().apply_layout_preset(&debugging, false);
+    util::frame(&mut app);
+    assert_eq!(sidebar.bounds().width(), 100.0);
+}
+
+#[test]
+fn animated_preset_moves_gradually() {
+    let (mut app, sidebar) = sidebar();
+    let coding = app.ui().capture_layout_preset(&["sidebar"]);
+    resize(&mut app, &sidebar, 100.0);
+    app.ui().apply_layout_preset(&coding, true);
+    util::frame(&mut app);
+    let width = sidebar.bounds().width();
+    assert!(width > 100.0 && width < 200.0, "width {}", width);
+    assert!(app.ui().animations_active());
+    // 16ms frames, past the 250ms the animation takes
+    for _ in 0..20 {
+        util::frame(&mut app);
+    }
+    assert_eq!(sidebar.bounds().width(), 200.0);
+    assert!(!app.ui().animations_active());
+}
+
+#[test]
+fn missing_widgets_skipped() {
+    let (mut app, sidebar) = sidebar();
+    let mut preset = app.ui().capture_layout_preset(&["sidebar", "inspector"]);
+    assert_eq!(preset.values.len(), 1);
+    assert_eq!(preset.values[0].key, "sidebar");
+    preset.values[0].value = 150.0;
+    let strength = preset.values[0].strength;
+    preset.values.insert(0, PresetValue { key: "inspector".to_owned(), var: VarType::Width, value: 300.0, strength: strength });
+    app.ui().apply_layout_preset(&preset, false);
+    util::frame(&mut app);
+    assert_eq!(sidebar.bounds().width(), 150.0);
+}
+
+#[test]
+fn presets_serialize() {
+    let (mut app, _) = sidebar();
+    let preset = app.ui().capture_layout_preset(&["sidebar"]);
+    let saved = serde_json::to_string(&preset).unwrap();
+    let loaded: LayoutPreset = serde_json::from_str(&saved).unwrap();
+    assert_eq!(loaded, preset);
+    assert_eq!(loaded.values[0].var, VarType::Width);
+    assert_eq!(loaded.values[0].value, 200.0);
+}
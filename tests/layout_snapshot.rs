@@ -0,0 +1,71 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+
+fn full(width: f32, height: f32) -> Rect {
+    Rect::new(Point::new(0.0, 0.0), Size::new(width, height))
+}
+
+// place `widget_ref` like a layout editor would
+fn place(app: &mut App, widget_ref: &WidgetRef, left: f32, top: f32) {
+    widget_ref.update_layout(|layout| {
+        layout.edit_left().set(left);
+        layout.edit_top().set(top);
+        layout.edit_width().set(100.0);
+        layout.edit_height().set(80.0);
+    });
+    util::frame(app);
+}
+
+#[test]
+fn restore_then_resize() {
+    let mut outer = WidgetBuilder::new("outer");
+    let panel = WidgetBuilder::new("panel");
+    let panel_ref = panel.widget_ref();
+    outer.add_child(panel);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(outer);
+    let mut app = util::headless(root);
+    assert_eq!(panel_ref.bounds(), full(400.0, 300.0));
+
+    let snapshot = app.ui().layout_snapshot();
+    app.ui().restore_layout(snapshot);
+    util::frame(&mut app);
+    assert_eq!(panel_ref.bounds(), full(400.0, 300.0));
+
+    // the restored values don't hold the panel at its old size
+    app.ui().resize_headless(Size::new(600.0, 500.0));
+    util::frame(&mut app);
+    assert_eq!(panel_ref.bounds(), full(600.0, 500.0));
+}
+
+#[test]
+fn restore_placed_widget() {
+    let mut canvas = WidgetBuilder::new("canvas");
+    canvas.layout().no_container();
+    let mut shape = WidgetBuilder::new("shape");
+    shape.layout().add(bound_by(&canvas));
+    let shape_ref = shape.widget_ref();
+    canvas.add_child(shape);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(canvas);
+    let mut app = util::headless(root);
+
+    place(&mut app, &shape_ref, 50.0, 40.0);
+    let placed = Rect::new(Point::new(50.0, 40.0), Size::new(100.0, 80.0));
+    assert_eq!(shape_ref.bounds(), placed);
+    let snapshot = app.ui().layout_snapshot();
+    place(&mut app, &shape_ref, 200.0, 150.0);
+    assert_eq!(shape_ref.bounds().origin, Point::new(200.0, 150.0));
+
+    app.ui().restore_layout(snapshot);
+    util::frame(&mut app);
+    assert_eq!(shape_ref.bounds(), placed);
+    // values that were already being edited keep the restored value
+    app.ui().resize_headless(Size::new(600.0, 500.0));
+    util::frame(&mut app);
+    assert_eq!(shape_ref.bounds(), placed);
+}
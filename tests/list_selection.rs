@@ -0,0 +1,77 @@
+extern crate limn;
+
+mod util;
+
+use limn::app::App;
+use limn::widget::{WidgetBuilder, WidgetRef};
+use limn::widgets::list::{ListBuilder, ListSelection, SelectionMode, default_text_adapter};
+
+fn list_app(mode: SelectionMode) -> (App, ListSelection, Vec<WidgetRef>) {
+    let mut list = ListBuilder::new();
+    list.set_selection_mode(mode);
+    let names = vec!["one", "two", "three", "four", "five"];
+    list.set_contents(names.into_iter().map(|name| name.to_owned()), default_text_adapter);
+    let selection = list.selection();
+    let list_ref = list.widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(list);
+    let app = util::headless(root);
+    let items = list_ref.children();
+    (app, selection, items)
+}
+
+fn click_item(app: &mut App, items: &[WidgetRef], index: usize) {
+    util::click(app, util::center(&items[index]));
+}
+
+#[test]
+fn modifier_clicks_extend_selection() {
+    let (mut app, selection, items) = list_app(SelectionMode::Multiple);
+    click_item(&mut app, &items, 1);
+    assert_eq!(selection.selected_indices(), vec![1]);
+
+    util::key(&mut app, "LShift", true);
+    click_item(&mut app, &items, 3);
+    util::key(&mut app, "LShift", false);
+    assert_eq!(selection.selected_indices(), vec![1, 2, 3]);
+
+    util::key(&mut app, "RControl", true);
+    click_item(&mut app, &items, 0);
+    click_item(&mut app, &items, 2);
+    util::key(&mut app, "RControl", false);
+    assert_eq!(selection.selected_indices(), vec![0, 1, 3]);
+
+    // with the modifiers released, a click selects only the item
+    click_item(&mut app, &items, 4);
+    assert_eq!(selection.selected_indices(), vec![4]);
+}
+
+#[test]
+fn arrow_keys_move_selection() {
+    let (mut app, selection, items) = list_app(SelectionMode::Multiple);
+    click_item(&mut app, &items, 1);
+    util::tap_key(&mut app, "Down");
+    assert_eq!(selection.selected_indices(), vec![2]);
+
+    util::key(&mut app, "RShift", true);
+    util::tap_key(&mut app, "Down");
+    util::tap_key(&mut app, "Down");
+    util::key(&mut app, "RShift", false);
+    assert_eq!(selection.selected_indices(), vec![2, 3, 4]);
+
+    // ctrl doesn't toggle items moved to with the keyboard
+    util::key(&mut app, "LControl", true);
+    util::tap_key(&mut app, "Up");
+    util::key(&mut app, "LControl", false);
+    assert_eq!(selection.selected_indices(), vec![3]);
+}
+
+#[test]
+fn single_selection_ignores_modifiers() {
+    let (mut app, selection, items) = list_app(SelectionMode::Single);
+    click_item(&mut app, &items, 0);
+    util::key(&mut app, "LShift", true);
+    click_item(&mut app, &items, 2);
+    util::key(&mut app, "LShift", false);
+    assert_eq!(selection.selected_indices(), vec![2]);
+}
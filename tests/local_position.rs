@@ -0,0 +1,73 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::input::mouse::ClickEvent;
+use limn::widgets::drag::{DragEvent, DragState};
+
+// the local position of each click and drag the target gets, and the handler's conversion of the global one
+type Positions = Rc<RefCell<Vec<(Point, Point, Point)>>>;
+
+// a target 40x20 at (100, 50) in a panel covering the window, which draws it moved by `draw_offset`
+fn target(draw_offset: Vector) -> (App, WidgetRef, Positions) {
+    let positions: Positions = Rc::new(RefCell::new(Vec::new()));
+    let mut root = WidgetBuilder::new("root");
+    let mut panel = WidgetBuilder::new("panel");
+    let mut target = WidgetBuilder::with_draw_state("target", RectState::new());
+    target.layout().add(constraints![
+        top_left(Point::new(100.0, 50.0)),
+        size(Size::new(40.0, 20.0)),
+    ]);
+    let click_positions = positions.clone();
+    target.add_handler_fn(move |event: &ClickEvent, args| {
+        click_positions.borrow_mut().push((event.local_position, args.to_local(event.position), args.to_global(event.local_position)));
+    });
+    let drag_positions = positions.clone();
+    target.make_draggable().add_handler_fn(move |event: &DragEvent, args| {
+        if event.state == DragState::Moved {
+            drag_positions.borrow_mut().push((event.local_position, args.to_local(event.position), args.to_global(event.local_position)));
+        }
+    });
+    let target_ref = target.widget_ref();
+    panel.add_child(target);
+    let mut panel_ref = panel.widget_ref();
+    root.add_child(panel);
+    let app = util::headless(root);
+    panel_ref.set_draw_offset(draw_offset);
+    (app, target_ref, positions)
+}
+
+#[test]
+fn click_relative_to_widget() {
+    let (mut app, _, positions) = target(Vector::zero());
+    util::click(&mut app, Point::new(110.0, 55.0));
+    assert_eq!(*positions.borrow(), vec![(Point::new(10.0, 5.0), Point::new(10.0, 5.0), Point::new(110.0, 55.0))]);
+}
+
+#[test]
+fn drag_relative_to_widget() {
+    let (mut app, _, positions) = target(Vector::zero());
+    util::press(&mut app, Point::new(105.0, 55.0));
+    util::move_mouse(&mut app, Point::new(130.0, 80.0));
+    util::release(&mut app);
+    // past the widget's edge, still relative to its top left
+    assert_eq!(positions.borrow()[0], (Point::new(30.0, 30.0), Point::new(30.0, 30.0), Point::new(130.0, 80.0)));
+}
+
+#[test]
+fn relative_to_where_widget_is_drawn() {
+    // drawn 30 up from where it's laid out, like content scrolled by a draw offset
+    let (mut app, target, positions) = target(Vector::new(0.0, -30.0));
+    assert_eq!(target.bounds().origin, Point::new(100.0, 50.0));
+    util::click(&mut app, Point::new(110.0, 25.0));
+    assert_eq!(*positions.borrow(), vec![(Point::new(10.0, 5.0), Point::new(10.0, 5.0), Point::new(110.0, 25.0))]);
+    assert_eq!(target.global_point(Point::new(10.0, 5.0)), Point::new(110.0, 25.0));
+}
@@ -0,0 +1,122 @@
+extern crate limn;
+extern crate glutin;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use glutin::MouseButton;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::{RecordedInput, RecordedButton};
+use limn::input::mouse::MouseMoved;
+use limn::widgets::drag::{DragEvent, DragState};
+
+type Drags = Rc<RefCell<Vec<(DragState, MouseButton)>>>;
+
+// a 100x100 widget made draggable by `make_draggable`, recording the drag events sent to it
+fn draggable(make_draggable: fn(&mut WidgetBuilder)) -> (App, WidgetRef, Drags) {
+    let mut root = WidgetBuilder::new("root");
+    let mut widget = WidgetBuilder::with_draw_state("draggable", RectState::new());
+    widget.layout().add(size(Size::new(100.0, 100.0)));
+    make_draggable(&mut widget);
+    let drags = Rc::new(RefCell::new(Vec::new()));
+    {
+        let drags = drags.clone();
+        widget.add_handler_fn(move |event: &DragEvent, _| drags.borrow_mut().push((event.state.clone(), event.button)));
+    }
+    let widget_ref = widget.widget_ref();
+    root.add_child(widget);
+    (util::headless(root), widget_ref, drags)
+}
+
+fn button(app: &mut App, button: RecordedButton, pressed: bool) {
+    util::input(app, RecordedInput::MouseButton { pressed: pressed, button: button });
+}
+
+#[test]
+fn drags_report_their_button() {
+    let (mut app, widget, drags) = draggable(|widget| { widget.make_draggable(); });
+    let start = util::center(&widget);
+    util::move_mouse(&mut app, start);
+    button(&mut app, RecordedButton::Right, true);
+    util::move_mouse(&mut app, start + Vector::new(10.0, 0.0));
+    // releasing another button doesn't end the drag
+    button(&mut app, RecordedButton::Left, false);
+    assert_eq!(*drags.borrow(), vec![(DragState::Start, MouseButton::Right), (DragState::Moved, MouseButton::Right)]);
+    button(&mut app, RecordedButton::Right, false);
+    assert_eq!(drags.borrow().last(), Some(&(DragState::End, MouseButton::Right)));
+}
+
+#[test]
+fn drags_only_with_chosen_button() {
+    let (mut app, widget, drags) = draggable(|widget| { widget.make_draggable_with(MouseButton::Right); });
+    util::press(&mut app, util::center(&widget));
+    util::move_mouse(&mut app, util::center(&widget) + Vector::new(10.0, 0.0));
+    util::release(&mut app);
+    assert!(drags.borrow().is_empty());
+
+    util::move_mouse(&mut app, util::center(&widget));
+    button(&mut app, RecordedButton::Right, true);
+    button(&mut app, RecordedButton::Right, false);
+    assert_eq!(*drags.borrow(), vec![(DragState::Start, MouseButton::Right), (DragState::End, MouseButton::Right)]);
+}
+
+#[test]
+fn pressed_buttons_seen_while_moving() {
+    let (mut app, widget, _) = draggable(|_| {});
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let seen = seen.clone();
+        app.add_handler_fn(move |_: &MouseMoved, args| seen.borrow_mut().push(args.ui.pressed_mouse_buttons().to_vec()));
+    }
+    let point = util::center(&widget);
+    util::move_mouse(&mut app, point);
+    button(&mut app, RecordedButton::Right, true);
+    button(&mut app, RecordedButton::Left, true);
+    util::move_mouse(&mut app, point + Vector::new(5.0, 0.0));
+    button(&mut app, RecordedButton::Right, false);
+    util::move_mouse(&mut app, point);
+    util::release(&mut app);
+    util::move_mouse(&mut app, point + Vector::new(5.0, 0.0));
+    assert_eq!(*seen.borrow(), vec![
+        vec![],
+        vec![MouseButton::Right, MouseButton::Left],
+        vec![MouseButton::Left],
+        vec![],
+    ]);
+    assert!(!app.ui().mouse_button_pressed(MouseButton::Left));
+}
+
+#[test]
+fn cancelled_drag_changes_add_up_to_nothing() {
+    let mut root = WidgetBuilder::new("root");
+    let mut widget = WidgetBuilder::with_draw_state("draggable", RectState::new());
+    widget.layout().add(size(Size::new(100.0, 100.0)));
+    widget.make_draggable();
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    {
+        let changes = changes.clone();
+        widget.add_handler_fn(move |event: &DragEvent, _| {
+            if event.state != DragState::Start {
+                changes.borrow_mut().push((event.state.clone(), event.change));
+            }
+        });
+    }
+    let widget_ref = widget.widget_ref();
+    root.add_child(widget);
+    let mut app = util::headless(root);
+    let start = util::center(&widget_ref);
+    util::press(&mut app, start);
+    util::move_mouse(&mut app, start + Vector::new(10.0, 0.0));
+    util::move_mouse(&mut app, start + Vector::new(25.0, 5.0));
+    util::tap_key(&mut app, "Escape");
+    util::release(&mut app);
+    let changes = changes.borrow();
+    assert_eq!(changes.last(), Some(&(DragState::Cancel, Vector::new(-25.0, -5.0))));
+    let total = changes.iter().fold(Vector::zero(), |total, &(_, change)| total + change);
+    assert_eq!(total, Vector::zero());
+}
@@ -0,0 +1,110 @@
+extern crate limn;
+
+mod util;
+
+use std::time::Duration;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::widgets::notification::{banner_opacity, NotificationKind};
+
+fn app() -> App {
+    util::headless(WidgetBuilder::new("root"))
+}
+
+fn opacity(banner: &WidgetRef) -> u8 {
+    let alpha = banner.drawable::<RectState>().unwrap().background_color.to_rgba().3;
+    alpha
+}
+
+#[test]
+fn opaque_until_timeout() {
+    let timeout = Duration::from_secs(3);
+    assert_eq!(banner_opacity(Duration::from_millis(0), timeout), 1.0);
+    assert_eq!(banner_opacity(Duration::from_secs(3), timeout), 1.0);
+}
+
+#[test]
+fn fades_out_after_timeout() {
+    let timeout = Duration::from_secs(3);
+    let halfway = banner_opacity(Duration::from_millis(3150), timeout);
+    assert!(halfway > 0.4 && halfway < 0.6);
+    assert_eq!(banner_opacity(Duration::from_millis(3300), timeout), 0.0);
+    assert_eq!(banner_opacity(Duration::from_secs(10), timeout), 0.0);
+}
+
+#[test]
+fn kinds_have_distinct_colors() {
+    let kinds = [NotificationKind::Info, NotificationKind::Success, NotificationKind::Warning, NotificationKind::Error];
+    for (index, kind) in kinds.iter().enumerate() {
+        for other in &kinds[index + 1..] {
+            assert!(kind.background_color() != other.background_color());
+        }
+    }
+}
+
+#[test]
+fn banners_stack_below_top_edge() {
+    let mut app = app();
+    let first = app.ui().show_notification("Saved", NotificationKind::Success, None);
+    let second = app.ui().show_notification("Disk almost full", NotificationKind::Warning, None);
+    util::frame(&mut app);
+    assert_eq!(app.ui().notifications(), vec![first.clone(), second.clone()]);
+    let (first, second) = (first.bounds(), second.bounds());
+    assert_eq!(first.top(), 8.0);
+    assert!(first.height() > 0.0);
+    assert_eq!(second.top(), first.bottom() + 8.0);
+    // centered in the 400 wide window
+    assert_eq!((first.left(), first.width()), (20.0, 360.0));
+    assert_eq!(second.left(), 20.0);
+}
+
+#[test]
+fn banners_shown_over_other_widgets() {
+    let mut app = app();
+    app.ui().show_notification("First", NotificationKind::Info, None);
+    let mut root = app.ui().get_root();
+    root.add_child(WidgetBuilder::new("added_later"));
+    let banner = app.ui().show_notification("Second", NotificationKind::Info, None);
+    util::frame(&mut app);
+    let layer = banner.parent().and_then(|column| column.parent()).unwrap();
+    assert_eq!(root.children().last(), Some(&layer));
+}
+
+#[test]
+fn timed_out_banners_fade_and_are_removed() {
+    let mut app = app();
+    let banner = app.ui().show_notification("Copied", NotificationKind::Info, Some(Duration::from_millis(100)));
+    let other = app.ui().show_notification("Error", NotificationKind::Error, None);
+    util::frame(&mut app);
+    assert_eq!(opacity(&banner), 255);
+    // 16ms frames, past the timeout and into the fade
+    for _ in 0..15 {
+        util::frame(&mut app);
+    }
+    let fading = opacity(&banner);
+    assert!(fading > 0 && fading < 255, "opacity {}", fading);
+    assert_eq!(app.ui().notifications().len(), 2);
+    for _ in 0..20 {
+        util::frame(&mut app);
+    }
+    assert_eq!(app.ui().notifications(), vec![other.clone()]);
+    // the remaining banner moves up to take its place
+    util::frame(&mut app);
+    assert_eq!(other.bounds().top(), 8.0);
+}
+
+#[test]
+fn banners_without_timeout_stay_until_removed() {
+    let mut app = app();
+    let mut banner = app.ui().show_notification("Offline", NotificationKind::Warning, None);
+    for _ in 0..100 {
+        util::frame(&mut app);
+    }
+    assert_eq!(app.ui().notifications(), vec![banner.clone()]);
+    assert_eq!(opacity(&banner), 255);
+    banner.remove_widget();
+    util::frame(&mut app);
+    assert!(app.ui().notifications().is_empty());
+}
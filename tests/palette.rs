@@ -0,0 +1,85 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::widgets::palette::{PaletteBuilder, MAX_RECENT_COLORS};
+
+fn colors(count: usize) -> Vec<Color> {
+    (0..count).map(|index| Color::from_hsl(index as f32 * 360.0 / count as f32, 1.0, 0.5)).collect()
+}
+
+// a palette of `colors` 250 wide, at the top left of the window
+fn palette(colors: Vec<Color>) -> (App, WidgetRef) {
+    let mut palette = PaletteBuilder::new(colors);
+    palette.layout().add(constraints![top_left(Point::new(0.0, 0.0)), width(250.0)]);
+    let palette_ref = palette.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    root.add_child(palette);
+    let mut app = util::headless(root);
+    util::frame(&mut app);
+    (app, palette_ref)
+}
+
+fn child(widget: &WidgetRef, name: &str) -> WidgetRef {
+    widget.children().into_iter().find(|child| child.name() == name).unwrap()
+}
+
+fn grid_swatches(palette: &WidgetRef) -> Vec<WidgetRef> {
+    child(&child(palette, "palette_grid_holder"), "palette_grid").children()
+}
+
+fn swatch_colors(swatches: &[WidgetRef]) -> Vec<Color> {
+    swatches.iter().map(|swatch| {
+        let color = swatch.drawable::<RectState>().unwrap().background_color;
+        color
+    }).collect()
+}
+
+fn recent_colors(palette: &WidgetRef) -> Vec<Color> {
+    swatch_colors(&child(palette, "palette_recent").children())
+}
+
+fn pick(app: &mut App, palette: &WidgetRef, index: usize) {
+    let swatch = grid_swatches(palette)[index].clone();
+    util::click(app, util::center(&swatch));
+}
+
+#[test]
+fn columns_fit_width() {
+    let (_app, palette) = palette(colors(20));
+    let swatches = grid_swatches(&palette);
+    assert_eq!(swatch_colors(&swatches), colors(20));
+    // ten 24 wide swatches fit in each row
+    let row_top = swatches[0].bounds().top();
+    assert!(swatches[..10].iter().all(|swatch| swatch.bounds().top() == row_top));
+    assert!(swatches[10].bounds().top() > row_top);
+    assert_eq!(swatches[10].bounds().left(), swatches[0].bounds().left());
+}
+
+#[test]
+fn recent_colors_most_recent_first() {
+    let (mut app, palette) = palette(colors(20));
+    assert!(recent_colors(&palette).is_empty());
+    pick(&mut app, &palette, 3);
+    pick(&mut app, &palette, 5);
+    pick(&mut app, &palette, 3);
+    assert_eq!(recent_colors(&palette), vec![colors(20)[3], colors(20)[5]]);
+}
+
+#[test]
+fn recent_colors_drop_oldest() {
+    let (mut app, palette) = palette(colors(20));
+    for index in 0..(MAX_RECENT_COLORS + 2) {
+        pick(&mut app, &palette, index);
+    }
+    let recent = recent_colors(&palette);
+    assert_eq!(recent.len(), MAX_RECENT_COLORS);
+    assert_eq!(recent[0], colors(20)[MAX_RECENT_COLORS + 1]);
+    assert_eq!(recent[MAX_RECENT_COLORS - 1], colors(20)[2]);
+}
@@ -0,0 +1,147 @@
+extern crate limn;
+
+mod util;
+
+use std::f32::consts::PI;
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::wedge::{WedgeState, point_inside_wedge};
+use limn::widgets::pie_menu::{PieMenuBuilder, PieItemSelectedEvent, wedge_at, wedge_angles};
+
+#[test]
+fn first_wedge_is_up() {
+    assert_eq!(wedge_at(Vector::new(0.0, -50.0), 10.0, 100.0, 4), Some(0));
+    assert_eq!(wedge_at(Vector::new(50.0, 0.0), 10.0, 100.0, 4), Some(1));
+    assert_eq!(wedge_at(Vector::new(0.0, 50.0), 10.0, 100.0, 4), Some(2));
+    assert_eq!(wedge_at(Vector::new(-50.0, 0.0), 10.0, 100.0, 4), Some(3));
+}
+
+#[test]
+fn wedge_wraps_around_top() {
+    // just left of straight up is still the first wedge
+    assert_eq!(wedge_at(Vector::new(-5.0, -50.0), 10.0, 100.0, 4), Some(0));
+}
+
+#[test]
+fn outside_ring() {
+    assert_eq!(wedge_at(Vector::new(0.0, -5.0), 10.0, 100.0, 4), None);
+    assert_eq!(wedge_at(Vector::new(0.0, -105.0), 10.0, 100.0, 4), None);
+    assert_eq!(wedge_at(Vector::new(0.0, -50.0), 10.0, 100.0, 0), None);
+}
+
+#[test]
+fn angles_match_hit_testing() {
+    let count = 6;
+    for index in 0..count {
+        let (start, end) = wedge_angles(index, count);
+        let middle = (start + end) / 2.0;
+        let offset = Vector::new(middle.sin() * 50.0, -middle.cos() * 50.0);
+        assert_eq!(wedge_at(offset, 10.0, 100.0, count), Some(index));
+        assert!((end - start - 2.0 * PI / count as f32).abs() < 0.001);
+    }
+}
+
+fn spans_match_angles(start: f32, end: f32) {
+    let wedge = WedgeState { inner_radius: 0.3, ..WedgeState::new(start, end) };
+    let bounds = Rect::new(Point::new(0.0, 0.0), Size::new(200.0, 200.0));
+    let spans = wedge.spans(bounds);
+    assert!(!spans.is_empty());
+    for span in &spans {
+        let offset = span.center() - bounds.center();
+        assert!(point_inside_wedge(offset, 30.0, 100.0, start, end), "{:?} outside {} to {}", span, start, end);
+    }
+    let area: f32 = spans.iter().map(|span| span.width() * span.height()).sum();
+    let expected = (end - start) / 2.0 * (100.0f32.powi(2) - 30.0f32.powi(2));
+    assert!((area - expected).abs() < expected * 0.02, "area {}, expected {}", area, expected);
+}
+
+#[test]
+fn drawn_wedges_match_angles() {
+    for &count in &[1, 2, 3, 4, 6] {
+        for index in 0..count {
+            let (start, end) = wedge_angles(index, count);
+            spans_match_angles(start, end);
+        }
+    }
+    // wider than half the ring
+    spans_match_angles(0.5, 0.5 + 1.5 * PI);
+}
+
+fn open_menu(app: &mut App, selected: &Rc<Cell<Option<usize>>>) -> WidgetRef {
+    let mut menu = PieMenuBuilder::new();
+    menu.add_item("one").add_item("two").add_item("three").add_item("four");
+    let selected = selected.clone();
+    menu.on_item_selected(move |event: &PieItemSelectedEvent, _| {
+        selected.set(Some(event.0));
+    });
+    let menu = menu.open(app.ui(), Point::new(200.0, 150.0));
+    util::frame(app);
+    menu
+}
+
+fn is_open(app: &mut App, menu: &WidgetRef) -> bool {
+    app.ui().get_root().children().contains(menu)
+}
+
+fn root() -> WidgetBuilder {
+    let mut root = WidgetBuilder::new("root");
+    root.layout().add(size(Size::new(400.0, 300.0)));
+    root
+}
+
+#[test]
+fn clicking_wedge_selects_item() {
+    let mut app = util::headless(root());
+    let selected = Rc::new(Cell::new(None));
+    let menu = open_menu(&mut app, &selected);
+    let ring = menu.children()[0].clone();
+    assert_eq!(ring.bounds(), Rect::new(Point::new(100.0, 50.0), Size::new(200.0, 200.0)));
+    // the wedges are drawn in the ring's bounds
+    assert!(ring.children().iter().all(|wedge| wedge.bounds() == ring.bounds()));
+    // right of the center, in the ring
+    util::click(&mut app, Point::new(265.0, 150.0));
+    assert_eq!(selected.get(), Some(1));
+    assert!(!is_open(&mut app, &menu));
+}
+
+#[test]
+fn releasing_over_wedge_selects_item() {
+    let mut app = util::headless(root());
+    let selected = Rc::new(Cell::new(None));
+    // opened on a press, at the cursor
+    util::press(&mut app, Point::new(200.0, 150.0));
+    open_menu(&mut app, &selected);
+    util::move_mouse(&mut app, Point::new(200.0, 215.0));
+    assert_eq!(selected.get(), None);
+    util::release(&mut app);
+    assert_eq!(selected.get(), Some(2));
+}
+
+#[test]
+fn number_keys_select_items() {
+    let mut app = util::headless(root());
+    let selected = Rc::new(Cell::new(None));
+    let menu = open_menu(&mut app, &selected);
+    util::tap_key(&mut app, "Key4");
+    assert_eq!(selected.get(), Some(3));
+    assert!(!is_open(&mut app, &menu));
+}
+
+#[test]
+fn escape_and_outside_press_close() {
+    let mut app = util::headless(root());
+    let selected = Rc::new(Cell::new(None));
+    let menu = open_menu(&mut app, &selected);
+    assert!(is_open(&mut app, &menu));
+    util::tap_key(&mut app, "Escape");
+    assert!(!is_open(&mut app, &menu));
+
+    let menu = open_menu(&mut app, &selected);
+    // in the hole in the middle
+    util::click(&mut app, Point::new(200.0, 150.0));
+    assert!(!is_open(&mut app, &menu));
+    assert_eq!(selected.get(), None);
+}
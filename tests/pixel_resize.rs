@@ -0,0 +1,64 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::layout::WidgetResizedEvent;
+
+// a widget filling the window, responding to whole pixel resizes or to every resize,
+// returns the sizes sent with each `WidgetResizedEvent` it gets after it's first laid out
+fn canvas(whole_pixels: bool) -> (App, Rc<RefCell<Vec<(Size, Size)>>>) {
+    let resizes = Rc::new(RefCell::new(Vec::new()));
+    let mut canvas = WidgetBuilder::new("canvas");
+    if whole_pixels {
+        canvas.respond_to_pixel_resize(true);
+    } else {
+        canvas.respond_to_resize(true);
+    }
+    {
+        let resizes = resizes.clone();
+        canvas.add_handler_fn(move |event: &WidgetResizedEvent, _| resizes.borrow_mut().push((event.old, event.new)));
+    }
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(canvas);
+    let app = util::headless(root);
+    resizes.borrow_mut().clear();
+    (app, resizes)
+}
+
+fn resize(app: &mut App, width: f32) {
+    app.ui().resize_headless(Size::new(width, 300.0));
+    util::frame(app);
+}
+
+#[test]
+fn sub_pixel_changes_are_ignored() {
+    let (mut app, resizes) = canvas(true);
+    resize(&mut app, 400.3);
+    resize(&mut app, 400.45);
+    resize(&mut app, 399.6);
+    assert!(resizes.borrow().is_empty());
+    // the old size is the one last sent
+    resize(&mut app, 400.7);
+    assert_eq!(*resizes.borrow(), vec![(Size::new(400.0, 300.0), Size::new(400.7, 300.0))]);
+    resize(&mut app, 401.2);
+    resize(&mut app, 401.4);
+    assert_eq!(resizes.borrow().len(), 1);
+    resize(&mut app, 402.0);
+    assert_eq!(resizes.borrow()[1], (Size::new(400.7, 300.0), Size::new(402.0, 300.0)));
+}
+
+#[test]
+fn every_change_without_whole_pixels() {
+    let (mut app, resizes) = canvas(false);
+    resize(&mut app, 400.3);
+    resize(&mut app, 400.45);
+    assert_eq!(*resizes.borrow(), vec![
+        (Size::new(400.0, 300.0), Size::new(400.3, 300.0)),
+        (Size::new(400.3, 300.0), Size::new(400.45, 300.0)),
+    ]);
+}
@@ -0,0 +1,89 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::input::mouse::{MouseMoved, RelativeMotionEvent, PointerUnlocked};
+
+struct Locked {
+    app: App,
+    widget: WidgetRef,
+    motions: Rc<RefCell<Vec<(f32, f32)>>>,
+    unlocked: Rc<Cell<usize>>,
+    mouse_moves: Rc<Cell<usize>>,
+}
+
+// a widget the pointer is locked to, recording the motion and unlocks sent to it,
+// and how many times the mouse is seen moving
+fn locked() -> Locked {
+    let mut root = WidgetBuilder::new("root");
+    let mut widget = WidgetBuilder::new("locked");
+    let (motions, unlocked) = (Rc::new(RefCell::new(Vec::new())), Rc::new(Cell::new(0)));
+    {
+        let motions = motions.clone();
+        widget.add_handler_fn(move |event: &RelativeMotionEvent, _| motions.borrow_mut().push((event.dx, event.dy)));
+        let unlocked = unlocked.clone();
+        widget.add_handler_fn(move |_: &PointerUnlocked, _| unlocked.set(unlocked.get() + 1));
+    }
+    let widget_ref = widget.widget_ref();
+    root.add_child(widget);
+    let mut app = util::headless(root);
+    let mouse_moves = Rc::new(Cell::new(0));
+    {
+        let mouse_moves = mouse_moves.clone();
+        app.add_handler_fn(move |_: &MouseMoved, _| mouse_moves.set(mouse_moves.get() + 1));
+    }
+    util::move_mouse(&mut app, Point::new(10.0, 10.0));
+    app.ui().lock_pointer(widget_ref.id());
+    Locked { app: app, widget: widget_ref, motions: motions, unlocked: unlocked, mouse_moves: mouse_moves }
+}
+
+#[test]
+fn motion_sent_to_locked_widget() {
+    let mut locked = locked();
+    assert_eq!(locked.app.ui().pointer_lock(), Some(locked.widget.id()));
+    locked.app.ui().relative_motion(3.0, -2.0);
+    util::frame(&mut locked.app);
+    assert_eq!(*locked.motions.borrow(), vec![(3.0, -2.0)]);
+    // once unlocked, the motion isn't sent anywhere
+    locked.app.ui().unlock_pointer();
+    locked.app.ui().relative_motion(5.0, 0.0);
+    util::frame(&mut locked.app);
+    assert_eq!(locked.motions.borrow().len(), 1);
+    assert_eq!(locked.unlocked.get(), 1);
+}
+
+#[test]
+fn mouse_moves_swallowed() {
+    let mut locked = locked();
+    let moves = locked.mouse_moves.get();
+    util::move_mouse(&mut locked.app, Point::new(200.0, 100.0));
+    assert_eq!(locked.mouse_moves.get(), moves);
+    assert_eq!(locked.app.ui().cursor_position(), Point::new(10.0, 10.0));
+    locked.app.ui().unlock_pointer();
+    util::move_mouse(&mut locked.app, Point::new(200.0, 100.0));
+    assert_eq!(locked.mouse_moves.get(), moves + 1);
+    assert_eq!(locked.app.ui().cursor_position(), Point::new(200.0, 100.0));
+}
+
+#[test]
+fn escape_unlocks() {
+    let mut locked = locked();
+    util::tap_key(&mut locked.app, "Escape");
+    assert_eq!(locked.app.ui().pointer_lock(), None);
+    assert_eq!(locked.unlocked.get(), 1);
+}
+
+#[test]
+fn removing_widget_unlocks() {
+    let mut locked = locked();
+    locked.widget.remove_widget();
+    util::frame(&mut locked.app);
+    assert_eq!(locked.app.ui().pointer_lock(), None);
+    util::move_mouse(&mut locked.app, Point::new(200.0, 100.0));
+    assert_eq!(locked.app.ui().cursor_position(), Point::new(200.0, 100.0));
+}
@@ -0,0 +1,154 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::widgets::popup::{Side, PopupBuilder, PopupWidget, popup_position};
+
+fn window() -> Size {
+    Size::new(400.0, 300.0)
+}
+
+fn anchor(x: f32, y: f32) -> Rect {
+    Rect::new(Point::new(x, y), Size::new(80.0, 20.0))
+}
+
+#[test]
+fn placed_on_side() {
+    let size = Size::new(100.0, 50.0);
+    assert_eq!(popup_position(anchor(50.0, 100.0), size, Side::Below, window()), Point::new(50.0, 120.0));
+    assert_eq!(popup_position(anchor(50.0, 100.0), size, Side::Above, window()), Point::new(50.0, 50.0));
+    assert_eq!(popup_position(anchor(150.0, 100.0), size, Side::Left, window()), Point::new(50.0, 100.0));
+    assert_eq!(popup_position(anchor(150.0, 100.0), size, Side::Right, window()), Point::new(230.0, 100.0));
+}
+
+#[test]
+fn flipped_when_no_room() {
+    let size = Size::new(100.0, 50.0);
+    // too close to the bottom of the window, so it's shown above
+    assert_eq!(popup_position(anchor(50.0, 260.0), size, Side::Below, window()), Point::new(50.0, 210.0));
+    assert_eq!(popup_position(anchor(50.0, 20.0), size, Side::Above, window()), Point::new(50.0, 40.0));
+}
+
+#[test]
+fn kept_inside_window() {
+    // too tall for either side, it's moved up from the bottom of the window
+    let size = Size::new(100.0, 200.0);
+    assert_eq!(popup_position(anchor(50.0, 140.0), size, Side::Below, window()), Point::new(50.0, 100.0));
+    // past the right edge
+    let size = Size::new(100.0, 50.0);
+    assert_eq!(popup_position(anchor(350.0, 100.0), size, Side::Below, window()), Point::new(300.0, 120.0));
+}
+
+// move the anchor to `(left, top)`, 80 wide and `height` high, like a layout editor would
+fn place_anchor(anchor: &WidgetRef, left: f32, top: f32, height: f32) {
+    anchor.update_layout(|layout| {
+        layout.edit_left().set(left);
+        layout.edit_top().set(top);
+        layout.edit_width().set(80.0);
+        layout.edit_height().set(height);
+    });
+}
+
+// a 100x50 popup below an anchor at (20, 20), returns the anchor, the popup's contents,
+// the popup, and how many times it's been dismissed
+fn open_popup() -> (App, WidgetRef, WidgetRef, PopupWidget, Rc<Cell<usize>>) {
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    let mut anchor = WidgetBuilder::new("anchor");
+    let anchor_ref = anchor.widget_ref();
+    anchor.layout().edit_left().set(20.0);
+    anchor.layout().edit_top().set(20.0);
+    anchor.layout().edit_width().set(80.0);
+    anchor.layout().edit_height().set(20.0);
+    root.add_child(anchor);
+    let mut app = util::headless(root);
+
+    let dismissed = Rc::new(Cell::new(0));
+    let mut popup = PopupBuilder::new();
+    popup.widget.layout().add(size(Size::new(100.0, 50.0)));
+    let contents = popup.widget.widget_ref();
+    {
+        let dismissed = dismissed.clone();
+        popup.anchor(anchor_ref.id(), Side::Below).on_dismiss(move |_| dismissed.set(dismissed.get() + 1));
+    }
+    let popup = popup.open(app.ui());
+    util::frame(&mut app);
+    (app, anchor_ref, contents, popup, dismissed)
+}
+
+fn is_open(app: &mut App, popup: &PopupWidget) -> bool {
+    app.ui().get_widget(popup.id()).is_some()
+}
+
+#[test]
+fn dismissed_by_press_outside() {
+    let (mut app, _, contents, popup, dismissed) = open_popup();
+    assert_eq!(contents.bounds(), Rect::new(Point::new(20.0, 40.0), Size::new(100.0, 50.0)));
+    util::click(&mut app, util::center(&contents));
+    assert!(is_open(&mut app, &popup));
+    assert_eq!(dismissed.get(), 0);
+    util::click(&mut app, Point::new(300.0, 250.0));
+    assert!(!is_open(&mut app, &popup));
+    assert_eq!(dismissed.get(), 1);
+}
+
+#[test]
+fn follows_anchor() {
+    let (mut app, anchor, contents, _, _) = open_popup();
+    place_anchor(&anchor, 150.0, 100.0, 20.0);
+    util::frame(&mut app);
+    assert_eq!(contents.bounds().origin, Point::new(150.0, 120.0));
+    place_anchor(&anchor, 150.0, 100.0, 40.0);
+    util::frame(&mut app);
+    assert_eq!(contents.bounds().origin, Point::new(150.0, 140.0));
+    // no room below, so it's shown above
+    place_anchor(&anchor, 150.0, 260.0, 20.0);
+    util::frame(&mut app);
+    assert_eq!(contents.bounds().origin, Point::new(150.0, 210.0));
+}
+
+#[test]
+fn kept_over_widgets_added_after() {
+    let (mut app, _, _, popup, _) = open_popup();
+    let mut root = app.ui().get_root();
+    root.add_child(WidgetBuilder::new("added_after"));
+    util::frame(&mut app);
+    let children = root.children();
+    assert_eq!(children.last().unwrap().id(), popup.id());
+    assert_eq!(children[children.len() - 2].name(), "added_after");
+}
+
+#[test]
+fn closed_with_anchor() {
+    let (mut app, anchor, _, popup, dismissed) = open_popup();
+    app.ui().remove_widget(anchor.id());
+    util::frame(&mut app);
+    assert!(!is_open(&mut app, &popup));
+    assert_eq!(dismissed.get(), 1);
+}
+
+#[test]
+fn reopened_on_same_anchor() {
+    let (mut app, anchor, _, popup, dismissed) = open_popup();
+    popup.dismiss();
+    util::frame(&mut app);
+    let mut reopened = PopupBuilder::new();
+    reopened.widget.layout().add(size(Size::new(100.0, 50.0)));
+    let contents = reopened.widget.widget_ref();
+    reopened.anchor(anchor.id(), Side::Below);
+    let reopened = reopened.open(app.ui());
+    util::frame(&mut app);
+    place_anchor(&anchor, 150.0, 100.0, 20.0);
+    util::frame(&mut app);
+    assert_eq!(contents.bounds().origin, Point::new(150.0, 120.0));
+    app.ui().remove_widget(anchor.id());
+    util::frame(&mut app);
+    assert!(!is_open(&mut app, &reopened));
+    // the first popup isn't dismissed again
+    assert_eq!(dismissed.get(), 1);
+}
@@ -0,0 +1,140 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::ui::{WidgetAttachedEvent, WidgetDetachedEvent};
+use limn::widget::reconcile::{WidgetSpec, reconcile};
+
+// how many items have been attached and detached
+#[derive(Clone, Default)]
+struct Counts {
+    attached: Rc<Cell<usize>>,
+    detached: Rc<Cell<usize>>,
+}
+
+impl Counts {
+    fn get(&self) -> (usize, usize) {
+        (self.attached.get(), self.detached.get())
+    }
+}
+
+// a column of items with the keys and names in `items`
+fn named_list_spec(items: &[(&str, &str)], counts: &Counts) -> WidgetSpec {
+    let mut list = WidgetSpec::new("list", || WidgetBuilder::new("list"));
+    for &(key, name) in items {
+        let name = name.to_string();
+        let counts = counts.clone();
+        let mut item = WidgetSpec::new("item", move || {
+            let mut item = WidgetBuilder::with_draw_state("item", RectState::new());
+            item.layout().add(size(Size::new(100.0, 20.0)));
+            let attached = counts.attached.clone();
+            let detached = counts.detached.clone();
+            item.add_handler_fn(move |_: &WidgetAttachedEvent, _| attached.set(attached.get() + 1));
+            item.add_handler_fn(move |_: &WidgetDetachedEvent, _| detached.set(detached.get() + 1));
+            item
+        });
+        item.set_key(key).set_props(move |widget| {
+            widget.set_name(&name);
+        });
+        list.add_child(item);
+    }
+    list
+}
+
+fn list_spec(keys: &[&str], counts: &Counts) -> WidgetSpec {
+    let items: Vec<(&str, &str)> = keys.iter().map(|key| (*key, *key)).collect();
+    named_list_spec(&items, counts)
+}
+
+// a vertical list attached to a headless app, reconciled with `spec`
+fn list(spec: &WidgetSpec) -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut list = WidgetBuilder::new("list");
+    list.linear_layout(LinearLayoutSettings::new(Orientation::Vertical));
+    let mut list_ref = list.widget_ref();
+    root.add_child(list);
+    let mut app = util::headless(root);
+    reconcile(&mut list_ref, spec);
+    util::frame(&mut app);
+    (app, list_ref)
+}
+
+fn update(app: &mut App, list: &WidgetRef, spec: &WidgetSpec) {
+    reconcile(&mut list.clone(), spec);
+    util::frame(app);
+}
+
+fn names(list: &WidgetRef) -> Vec<String> {
+    list.children().iter().map(|child| child.name()).collect()
+}
+
+fn tops(list: &WidgetRef) -> Vec<f32> {
+    list.children().iter().map(|child| child.bounds().top()).collect()
+}
+
+#[test]
+fn insert_in_middle_of_keyed_list() {
+    let counts = Counts::default();
+    let (mut app, list) = list(&list_spec(&["a", "b", "c"], &counts));
+    assert_eq!(counts.get(), (3, 0));
+    let before = list.children();
+    update(&mut app, &list, &list_spec(&["a", "x", "b", "c"], &counts));
+    assert_eq!(names(&list), vec!["a", "x", "b", "c"]);
+    assert_eq!(tops(&list), vec![0.0, 20.0, 40.0, 60.0]);
+    // only the new item is attached, the others are kept
+    assert_eq!(counts.get(), (4, 0));
+    let after = list.children();
+    assert!(after[0] == before[0] && after[2] == before[1] && after[3] == before[2]);
+}
+
+#[test]
+fn prop_only_update_keeps_widgets() {
+    let counts = Counts::default();
+    let (mut app, list) = list(&named_list_spec(&[("a", "first"), ("b", "second")], &counts));
+    let before = list.children();
+    update(&mut app, &list, &named_list_spec(&[("a", "first, edited"), ("b", "second, edited")], &counts));
+    // the same widgets, in the same order, with the new props
+    assert!(list.children() == before);
+    assert_eq!(names(&list), vec!["first, edited", "second, edited"]);
+    assert_eq!(counts.get(), (2, 0));
+}
+
+#[test]
+fn remove_and_reorder_keyed_list() {
+    let counts = Counts::default();
+    let (mut app, list) = list(&list_spec(&["a", "b", "c", "d"], &counts));
+    let before = list.children();
+    update(&mut app, &list, &list_spec(&["b", "c", "a"], &counts));
+    assert_eq!(names(&list), vec!["b", "c", "a"]);
+    assert_eq!(tops(&list), vec![0.0, 20.0, 40.0]);
+    assert_eq!(counts.get(), (4, 1));
+    let after = list.children();
+    assert!(after[0] == before[1] && after[1] == before[2] && after[2] == before[0]);
+
+    update(&mut app, &list, &list_spec(&["a", "c", "b"], &counts));
+    assert_eq!(names(&list), vec!["a", "c", "b"]);
+    assert_eq!(counts.get(), (4, 1));
+}
+
+#[test]
+fn unkeyed_children_match_by_position_and_kind() {
+    let mut list_spec = WidgetSpec::new("list", || WidgetBuilder::new("list"));
+    list_spec.add_child(WidgetSpec::new("label", || WidgetBuilder::new("label")));
+    list_spec.add_child(WidgetSpec::new("label", || WidgetBuilder::new("label")));
+    let (mut app, list) = list(&list_spec);
+    let before = list.children();
+
+    let mut list_spec = WidgetSpec::new("list", || WidgetBuilder::new("list"));
+    list_spec.add_child(WidgetSpec::new("button", || WidgetBuilder::new("button")));
+    list_spec.add_child(WidgetSpec::new("label", || WidgetBuilder::new("label")));
+    update(&mut app, &list, &list_spec);
+    assert_eq!(names(&list), vec!["button", "label"]);
+    let after = list.children();
+    assert!(after[0] != before[0] && after[1] == before[1]);
+}
@@ -0,0 +1,57 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::input::keyboard::FocusGained;
+use limn::resources::lookup_widget_by_name;
+
+fn focusable(name: &str, focus_count: &Rc<Cell<usize>>) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new(name);
+    widget.make_tab_focusable();
+    let focus_count = focus_count.clone();
+    widget.add_handler_fn(move |_: &FocusGained, _| focus_count.set(focus_count.get() + 1));
+    widget
+}
+
+#[test]
+fn descendants_removed_with_widget() {
+    let kept_focus = Rc::new(Cell::new(0));
+    let field_focus = Rc::new(Cell::new(0));
+    let kept = focusable("kept_field", &kept_focus);
+    let field = focusable("nested_field", &field_focus);
+    let (kept_ref, field_ref) = (kept.widget_ref(), field.widget_ref());
+    let mut inner = WidgetBuilder::new("inner_panel");
+    inner.add_child(field);
+    let mut panel = WidgetBuilder::new("outer_panel");
+    panel.add_child(inner);
+    let panel_ref = panel.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(kept).add_child(panel);
+    let mut app = util::headless(root);
+
+    assert_eq!(lookup_widget_by_name("nested_field"), Some(field_ref.id()));
+    util::type_text(&mut app, "\t");
+    assert_eq!(field_focus.get(), 1);
+
+    app.ui().remove_widget(panel_ref.id());
+    util::frame(&mut app);
+    assert!(app.ui().get_widget(field_ref.id()).is_none());
+    assert_eq!(lookup_widget_by_name("inner_panel"), None);
+    assert_eq!(lookup_widget_by_name("nested_field"), None);
+    assert!(!field_ref.props().contains(&Property::Focused));
+
+    // the removed field isn't in the tab order anymore
+    util::type_text(&mut app, "\t\t\t");
+    assert_eq!(field_focus.get(), 1);
+    assert!(kept_ref.props().contains(&Property::Focused));
+    assert_eq!(kept_focus.get(), 2);
+
+    // the layout is still solved without the removed widgets
+    app.ui().resize_headless(Size::new(200.0, 100.0));
+    util::frame(&mut app);
+    assert_eq!(kept_ref.bounds(), Rect::new(Point::new(0.0, 0.0), Size::new(200.0, 100.0)));
+}
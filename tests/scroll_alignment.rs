@@ -0,0 +1,84 @@
+extern crate limn;
+extern crate cassowary;
+
+mod util;
+
+use cassowary::Constraint;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::RecordedInput;
+use limn::widgets::scroll::{ScrollBuilder, ContentAlignment};
+
+struct Scrolled {
+    app: App,
+    content: WidgetRef,
+    size: Vec<Constraint>,
+}
+
+// content of `content_size` in a scroll widget filling the 400x300 window
+fn scrolled(content_size: Size, horizontal: ContentAlignment, vertical: ContentAlignment) -> Scrolled {
+    let mut content = WidgetBuilder::with_draw_state("content", RectState::new());
+    let size_constraints = content.layout().create_constraint(size(content_size));
+    content.layout().add(size_constraints.clone());
+    let content_ref = content.widget_ref();
+    let mut scroll = ScrollBuilder::new();
+    scroll.set_content_alignment(horizontal, vertical).add_content(content);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    let mut app = util::headless(root);
+    util::frame(&mut app);
+    Scrolled { app: app, content: content_ref, size: size_constraints }
+}
+
+impl Scrolled {
+    fn resize(&mut self, content_size: Size) {
+        let old_size = self.size.drain(..).collect();
+        let mut new_size = Vec::new();
+        self.content.update_layout(|layout| {
+            layout.remove_constraints(old_size);
+            new_size = layout.create_constraint(size(content_size));
+            layout.add(new_size.clone());
+        });
+        self.size = new_size;
+        util::frame(&mut self.app);
+        util::frame(&mut self.app);
+    }
+}
+
+#[test]
+fn small_content_is_aligned() {
+    let scrolled = scrolled(Size::new(100.0, 50.0), ContentAlignment::Center, ContentAlignment::Center);
+    assert_eq!(scrolled.content.bounds(), Rect::new(Point::new(150.0, 125.0), Size::new(100.0, 50.0)));
+    let scrolled = scrolled(Size::new(100.0, 50.0), ContentAlignment::End, ContentAlignment::Start);
+    assert_eq!(scrolled.content.bounds().origin, Point::new(300.0, 0.0));
+}
+
+#[test]
+fn start_aligned_by_default() {
+    let mut content = WidgetBuilder::with_draw_state("content", RectState::new());
+    content.layout().add(size(Size::new(100.0, 50.0)));
+    let content_ref = content.widget_ref();
+    let mut scroll = ScrollBuilder::new();
+    scroll.add_content(content);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    util::headless(root);
+    assert_eq!(content_ref.bounds().origin, Point::new(0.0, 0.0));
+}
+
+#[test]
+fn growing_content_stops_aligning() {
+    let mut scrolled = scrolled(Size::new(100.0, 50.0), ContentAlignment::Center, ContentAlignment::Center);
+    // taller than the window, it moves to the top and scrolls, still centered horizontally
+    scrolled.resize(Size::new(100.0, 500.0));
+    assert_eq!(scrolled.content.bounds().origin, Point::new(150.0, 0.0));
+    util::move_mouse(&mut scrolled.app, Point::new(200.0, 150.0));
+    util::input(&mut scrolled.app, RecordedInput::MouseWheel { x: 0.0, y: -3.0, pixels: false });
+    util::frame(&mut scrolled.app);
+    assert_eq!(scrolled.content.bounds().origin, Point::new(150.0, -39.0));
+    // smaller again, it's centered again
+    scrolled.resize(Size::new(100.0, 50.0));
+    assert_eq!(scrolled.content.bounds().origin, Point::new(150.0, 125.0));
+}
@@ -0,0 +1,107 @@
+extern crate limn;
+extern crate cassowary;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use cassowary::Constraint;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::RecordedInput;
+use limn::widgets::scroll::ScrollBuilder;
+
+struct Scrolled {
+    app: App,
+    content: WidgetRef,
+    size: Vec<Constraint>,
+    // the remaining distance sent with each vertical `ScrollNearEndEvent`
+    near_end: Rc<RefCell<Vec<f32>>>,
+}
+
+// content `content_height` tall in a scroll widget filling the 400x300 window, sending events within 50 of the end
+fn scrolled(content_height: f32) -> Scrolled {
+    let mut content = WidgetBuilder::with_draw_state("content", RectState::new());
+    let size_constraints = content.layout().create_constraint(size(Size::new(400.0, content_height)));
+    content.layout().add(size_constraints.clone());
+    let content_ref = content.widget_ref();
+    let near_end = Rc::new(RefCell::new(Vec::new()));
+    let mut scroll = ScrollBuilder::new();
+    scroll.set_near_end_distance(50.0).add_content(content);
+    {
+        let near_end = near_end.clone();
+        scroll.on_scroll_near_end(move |event, _| {
+            if let Orientation::Vertical = event.axis {
+                near_end.borrow_mut().push(event.remaining_px);
+            }
+        });
+    }
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    let mut app = util::headless(root);
+    util::frame(&mut app);
+    Scrolled { app: app, content: content_ref, size: size_constraints, near_end: near_end }
+}
+
+impl Scrolled {
+    // scroll down by `lines` lines of 13 pixels, or up if negative
+    fn scroll(&mut self, lines: f32) {
+        util::move_mouse(&mut self.app, Point::new(200.0, 150.0));
+        util::input(&mut self.app, RecordedInput::MouseWheel { x: 0.0, y: -lines, pixels: false });
+        util::frame(&mut self.app);
+    }
+    fn set_height(&mut self, content_height: f32) {
+        let old_size = self.size.drain(..).collect();
+        let mut new_size = Vec::new();
+        self.content.update_layout(|layout| {
+            layout.remove_constraints(old_size);
+            new_size = layout.create_constraint(size(Size::new(400.0, content_height)));
+            layout.add(new_size.clone());
+        });
+        self.size = new_size;
+        util::frame(&mut self.app);
+        util::frame(&mut self.app);
+    }
+    fn near_end(&self) -> Vec<f32> {
+        self.near_end.borrow().clone()
+    }
+}
+
+#[test]
+fn sent_once_per_approach() {
+    // 500 to scroll
+    let mut scrolled = scrolled(800.0);
+    scrolled.scroll(20.0);
+    assert!(scrolled.near_end().is_empty());
+    scrolled.scroll(15.0);
+    assert_eq!(scrolled.near_end(), vec![45.0]);
+    scrolled.scroll(1.0);
+    scrolled.scroll(10.0);
+    assert_eq!(scrolled.near_end().len(), 1);
+    // scrolling away sends it again on the next approach
+    scrolled.scroll(-10.0);
+    scrolled.scroll(20.0);
+    assert_eq!(scrolled.near_end(), vec![45.0, 0.0]);
+}
+
+#[test]
+fn sent_again_when_content_grows() {
+    let mut scrolled = scrolled(800.0);
+    scrolled.scroll(50.0);
+    assert_eq!(scrolled.near_end(), vec![0.0]);
+    // rows appended, but still near the end
+    scrolled.set_height(820.0);
+    assert_eq!(scrolled.near_end(), vec![0.0, 20.0]);
+    scrolled.scroll(1.0);
+    assert_eq!(scrolled.near_end().len(), 2);
+}
+
+#[test]
+fn not_sent_without_scrolling() {
+    let mut scrolled = scrolled(100.0);
+    scrolled.scroll(10.0);
+    assert!(scrolled.near_end().is_empty());
+}
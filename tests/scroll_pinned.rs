@@ -0,0 +1,92 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::RecordedInput;
+use limn::widgets::scroll::ScrollBuilder;
+use limn::widgets::table::TableBuilder;
+
+fn row(name: &str, row_size: Size) -> WidgetBuilder {
+    let mut row = WidgetBuilder::with_draw_state(name, RectState::new());
+    row.layout().add(size(row_size));
+    row
+}
+
+// content `content_height` tall, with a pinned header and footer, in a scroll widget filling the 400x300 window
+fn pinned(content_height: f32) -> (App, WidgetRef) {
+    let mut scroll = ScrollBuilder::new();
+    scroll.add_content(row("rows", Size::new(400.0, content_height)));
+    scroll.set_header(row("titles", Size::new(400.0, 20.0)));
+    scroll.set_footer(row("totals", Size::new(400.0, 20.0)));
+    let scroll: WidgetBuilder = scroll.into();
+    let scroll_ref = scroll.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    let mut app = util::headless(root);
+    util::frame(&mut app);
+    (app, scroll_ref)
+}
+
+// whether the header's and the footer's shadows are shown
+fn shadows_shown(scroll: &WidgetRef) -> (bool, bool) {
+    let shown = |name: &str| {
+        let mut shadow = scroll.children().into_iter().find(|child| child.name() == name).unwrap();
+        let hidden = shadow.layout().hidden;
+        !hidden
+    };
+    (shown("scroll_header_shadow"), shown("scroll_footer_shadow"))
+}
+
+fn scroll_lines(app: &mut App, lines: f32) {
+    util::move_mouse(app, Point::new(200.0, 150.0));
+    util::input(app, RecordedInput::MouseWheel { x: 0.0, y: -lines, pixels: false });
+    util::frame(app);
+}
+
+#[test]
+fn shadows_follow_scroll_position() {
+    let (mut app, scroll) = pinned(1000.0);
+    // at the start only the footer has content under it
+    assert_eq!(shadows_shown(&scroll), (false, true));
+    scroll_lines(&mut app, 3.0);
+    assert_eq!(shadows_shown(&scroll), (true, true));
+    scroll_lines(&mut app, 100.0);
+    assert_eq!(shadows_shown(&scroll), (true, false));
+    scroll_lines(&mut app, -100.0);
+    assert_eq!(shadows_shown(&scroll), (false, true));
+}
+
+#[test]
+fn no_shadows_without_scrolling() {
+    let (mut app, scroll) = pinned(100.0);
+    assert_eq!(shadows_shown(&scroll), (false, false));
+    scroll_lines(&mut app, 3.0);
+    assert_eq!(shadows_shown(&scroll), (false, false));
+}
+
+#[test]
+fn pinned_rows_are_drawn_over_content() {
+    let mut scroll = ScrollBuilder::new();
+    scroll.add_content(WidgetBuilder::new("rows"));
+    scroll.set_header(WidgetBuilder::new("titles"));
+    scroll.set_footer(WidgetBuilder::new("totals"));
+    let scroll: WidgetRef = scroll.into();
+    let names: Vec<String> = scroll.children().iter().map(|child| child.name()).collect();
+    assert_eq!(names, vec!["content_holder", "scroll_header", "scroll_footer", "scroll_header_shadow", "scroll_footer_shadow"]);
+    assert_eq!(scroll.children()[1].children()[0].name(), "titles");
+}
+
+#[test]
+fn table_header_built_separately() {
+    let mut table = TableBuilder::new();
+    table.add_column("Name", 100.0).add_column("Size", 60.0);
+    let (header, body) = table.into_header_and_body();
+    let header: WidgetRef = header.into();
+    let body: WidgetRef = body.into();
+    assert_eq!(header.name(), "table_header");
+    assert_eq!(header.children().len(), 2);
+    assert!(body.children().iter().all(|child| child.name() != "table_header"));
+}
@@ -0,0 +1,34 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::widget::WidgetBuilder;
+use limn::widgets::slider::SliderBuilder;
+
+#[test]
+fn arrow_keys_step_value() {
+    let value = Rc::new(Cell::new(0.0));
+    let mut slider = SliderBuilder::new();
+    slider.set_range(0.0..100.0).set_step(1.0).set_value(50.0);
+    {
+        let value = value.clone();
+        slider.on_value_changed(move |new_value, _| value.set(new_value));
+    }
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(slider);
+    let mut app = util::headless(root);
+
+    util::tap_key(&mut app, "Right");
+    assert_eq!(value.get(), 51.0);
+    // shift steps ten times as far while it's held
+    util::key(&mut app, "LShift", true);
+    util::tap_key(&mut app, "Right");
+    util::tap_key(&mut app, "Up");
+    util::key(&mut app, "LShift", false);
+    assert_eq!(value.get(), 71.0);
+    util::tap_key(&mut app, "Left");
+    assert_eq!(value.get(), 70.0);
+}
@@ -0,0 +1,84 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::TextState;
+use limn::widgets::spin_box::SpinBoxBuilder;
+
+struct SpinBox {
+    app: App,
+    widget: WidgetRef,
+    text: WidgetRef,
+    value: Rc<Cell<f64>>,
+}
+
+// a spin box from 0 to `max` in steps of 0.1, starting at `initial`, its edit text focused
+fn spin_box(max: f64, initial: f64) -> SpinBox {
+    let mut spin_box = SpinBoxBuilder::new();
+    spin_box.range(0.0, max).step(0.1).initial(initial);
+    let value = Rc::new(Cell::new(initial));
+    {
+        let value = value.clone();
+        spin_box.on_value_changed(move |new_value, _| value.set(new_value));
+    }
+    let (widget, text) = (spin_box.widget_ref(), spin_box.edit_text.text_widget.widget_ref());
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(spin_box);
+    SpinBox { app: util::headless(root), widget: widget, text: text, value: value }
+}
+
+impl SpinBox {
+    fn click_button(&mut self, name: &str) {
+        let button = self.widget.children().into_iter().find(|child| child.name() == name).unwrap();
+        util::click(&mut self.app, util::center(&button));
+    }
+    fn text(&self) -> String {
+        let text = self.text.drawable::<TextState>().unwrap().text.clone();
+        text
+    }
+}
+
+#[test]
+fn buttons_step_within_range() {
+    let mut spin_box = spin_box(1.0, 0.95);
+    spin_box.click_button("spin_box_up");
+    assert_eq!((spin_box.value.get(), spin_box.text()), (1.0, "1".to_owned()));
+    spin_box.click_button("spin_box_up");
+    assert_eq!(spin_box.value.get(), 1.0);
+    spin_box.click_button("spin_box_down");
+    assert_eq!((spin_box.value.get(), spin_box.text()), (0.9, "0.9".to_owned()));
+}
+
+#[test]
+fn arrow_keys_step_without_adding_up_error() {
+    let mut spin_box = spin_box(10.0, 0.0);
+    for _ in 0..3 {
+        util::tap_key(&mut spin_box.app, "Up");
+    }
+    assert_eq!(spin_box.text(), "0.3");
+    // ten steps at a time with shift held
+    util::key(&mut spin_box.app, "LShift", true);
+    util::tap_key(&mut spin_box.app, "Up");
+    util::key(&mut spin_box.app, "LShift", false);
+    assert_eq!(spin_box.text(), "1.3");
+    util::tap_key(&mut spin_box.app, "Down");
+    assert_eq!(spin_box.text(), "1.2");
+    assert!((spin_box.value.get() - 1.2).abs() < 1e-9);
+}
+
+#[test]
+fn entered_text_clamped_or_ignored() {
+    let mut spin_box = spin_box(1.0, 0.0);
+    util::type_text(&mut spin_box.app, "7");
+    util::tap_key(&mut spin_box.app, "Return");
+    assert_eq!((spin_box.value.get(), spin_box.text()), (1.0, "1".to_owned()));
+    // text that isn't a number keeps the current value, and is replaced by it
+    util::type_text(&mut spin_box.app, "e");
+    util::tap_key(&mut spin_box.app, "Return");
+    assert_eq!((spin_box.value.get(), spin_box.text()), (1.0, "1".to_owned()));
+}
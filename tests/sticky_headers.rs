@@ -0,0 +1,88 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::RecordedInput;
+use limn::widgets::list::ListBuilder;
+use limn::widgets::scroll::ScrollBuilder;
+
+fn row(name: &str) -> WidgetBuilder {
+    let mut row = WidgetBuilder::with_draw_state(name, RectState::new());
+    row.layout().add(height(20.0));
+    row
+}
+
+// three sections of a 20 tall header and ten 20 tall items, 220 tall in all, in a list scrolling in the window
+fn sectioned_list() -> (App, Vec<WidgetRef>) {
+    let mut list = ListBuilder::new();
+    let mut headers = Vec::new();
+    for name in &["a_header", "b_header", "c_header"] {
+        let header = row(name);
+        headers.push(header.widget_ref());
+        list.add_section(header, 0..10, |_, _| row("item"));
+    }
+    let mut scroll = ScrollBuilder::new();
+    scroll.add_content(list);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    let mut app = util::headless(root);
+    util::frame(&mut app);
+    (app, headers)
+}
+
+// scroll down by `lines` lines of 13 pixels
+fn scroll(app: &mut App, lines: f32) {
+    util::move_mouse(app, Point::new(200.0, 150.0));
+    util::input(app, RecordedInput::MouseWheel { x: 0.0, y: -lines, pixels: false });
+    util::frame(app);
+}
+
+fn offsets(headers: &[WidgetRef]) -> Vec<f32> {
+    headers.iter().map(|header| header.draw_offset().y).collect()
+}
+
+fn hit(app: &mut App, point: Point) -> Option<String> {
+    app.ui().widget_under_cursor(point).map(|widget| widget.name())
+}
+
+#[test]
+fn header_stays_at_top_of_viewport() {
+    let (mut app, headers) = sectioned_list();
+    assert_eq!(offsets(&headers), vec![0.0, 0.0, 0.0]);
+    // the first section is scrolled 130 past the top
+    scroll(&mut app, 10.0);
+    assert_eq!(offsets(&headers), vec![130.0, 0.0, 0.0]);
+    // and is hit where it's drawn
+    assert_eq!(hit(&mut app, Point::new(10.0, 10.0)), Some("a_header".to_owned()));
+}
+
+#[test]
+fn header_pushed_out_by_next_section() {
+    let (mut app, headers) = sectioned_list();
+    // scrolled 234, the first header is kept inside its section, 200 down at most,
+    // the second section is scrolled 14 past the top
+    scroll(&mut app, 18.0);
+    assert_eq!(offsets(&headers), vec![200.0, 14.0, 0.0]);
+    assert_eq!(hit(&mut app, Point::new(10.0, 10.0)), Some("b_header".to_owned()));
+    assert_eq!(hit(&mut app, Point::new(10.0, 25.0)), Some("list_item".to_owned()));
+}
+
+#[test]
+fn sections_hold_their_items() {
+    let mut list = ListBuilder::new();
+    list.add_section(WidgetBuilder::new("a_header"), vec!["Ada", "Alan"].into_iter(), |name, _| WidgetBuilder::new(name));
+    list.add_section(WidgetBuilder::new("b_header"), vec!["Barbara"].into_iter(), |name, _| WidgetBuilder::new(name));
+    let selection = list.selection();
+    let list: WidgetRef = list.into();
+    let sections = list.children();
+    assert_eq!(sections.len(), 2);
+    let section = &sections[0];
+    let names: Vec<String> = section.children().iter().map(|child| child.name()).collect();
+    // the header is drawn last, over the items
+    assert_eq!(names, vec!["list_section_items", "a_header"]);
+    assert_eq!(section.children()[0].children().len(), 2);
+    assert!(selection.selected_indices().is_empty());
+}
@@ -0,0 +1,56 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+
+struct Ping;
+
+type Received = Rc<RefCell<Vec<String>>>;
+
+fn widget(name: &str, received: &Received) -> WidgetBuilder {
+    let mut widget = WidgetBuilder::new(name);
+    let received = received.clone();
+    widget.add_handler_fn(move |_: &Ping, args| received.borrow_mut().push(args.widget.name()));
+    widget
+}
+
+// "a" with the children "a1", which has "a1x", and "a2", then "b", and "c" which doesn't handle `Ping`
+fn tree(received: &Received) -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut a = widget("a", received);
+    let mut a1 = widget("a1", received);
+    a1.add_child(widget("a1x", received));
+    a.add_child(a1).add_child(widget("a2", received));
+    let a_ref = a.widget_ref();
+    root.add_child(a).add_child(widget("b", received)).add_child(WidgetBuilder::new("c"));
+    (util::headless(root), a_ref)
+}
+
+#[test]
+fn sent_to_subtree_in_tree_order() {
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let (mut app, a) = tree(&received);
+    a.event_subtree(Ping);
+    util::frame(&mut app);
+    assert_eq!(*received.borrow(), vec!["a", "a1", "a1x", "a2"]);
+    received.borrow_mut().clear();
+    app.ui().get_root().event_subtree(Ping);
+    util::frame(&mut app);
+    assert_eq!(*received.borrow(), vec!["a", "a1", "a1x", "a2", "b"]);
+}
+
+#[test]
+fn inserted_widgets_receive_in_tree_order() {
+    let received = Rc::new(RefCell::new(Vec::new()));
+    let (mut app, a) = tree(&received);
+    app.ui().add_widget_at(widget("a0", &received), a.id(), 0).unwrap();
+    util::frame(&mut app);
+    a.event_subtree(Ping);
+    util::frame(&mut app);
+    assert_eq!(*received.borrow(), vec!["a", "a0", "a1", "a1x", "a2"]);
+}
@@ -0,0 +1,81 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+
+fn field(name: &str, tab_index: i32) -> WidgetBuilder {
+    let mut field = WidgetBuilder::with_draw_state(name, RectState::new());
+    field.layout().add(size(Size::new(100.0, 20.0)));
+    field.make_tab_focusable().tab_index(tab_index);
+    field
+}
+
+// a form with the fields, added in tree order
+fn form(fields: Vec<WidgetBuilder>) -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut form = WidgetBuilder::new("form");
+    for field in fields {
+        form.add_child(field);
+    }
+    let form_ref = form.widget_ref();
+    root.add_child(form);
+    (util::headless(root), form_ref)
+}
+
+fn focused(form: &WidgetRef) -> String {
+    form.children().iter().find(|child| child.props().contains(&Property::Focused)).unwrap().name()
+}
+
+// press tab `count` times, the widgets focused after each
+fn traversal(app: &mut App, form: &WidgetRef, count: usize) -> Vec<String> {
+    let mut order = Vec::new();
+    for _ in 0..count {
+        util::type_text(app, "\t");
+        order.push(focused(form));
+    }
+    order
+}
+
+#[test]
+fn grid_form_tabs_down_columns() {
+    // a two column form, the fields are added row by row
+    let rows = [["first_name", "last_name"], ["email", "phone"], ["city", "country"]];
+    let mut fields = Vec::new();
+    for (row, row_fields) in rows.iter().enumerate() {
+        for (column, name) in row_fields.iter().enumerate() {
+            fields.push(field(name, (column * rows.len() + row) as i32));
+        }
+    }
+    let (mut app, form) = form(fields);
+    assert_eq!(focused(&form), "first_name");
+    assert_eq!(traversal(&mut app, &form, 6), vec!["email", "city", "last_name", "phone", "country", "first_name"]);
+}
+
+#[test]
+fn duplicate_indices_keep_tree_order() {
+    let (mut app, form) = form(vec![field("a", 0), field("b", 1), field("c", 0), field("d", 1), field("e", -1)]);
+    assert_eq!(focused(&form), "a");
+    assert_eq!(traversal(&mut app, &form, 5), vec!["c", "b", "d", "e", "a"]);
+}
+
+#[test]
+fn removed_widgets_are_skipped() {
+    let fields = vec![field("a", 0), field("b", 0), field("c", 0)];
+    let b = fields[1].widget_ref();
+    let (mut app, form) = form(fields);
+    app.ui().remove_widget(b.id());
+    util::frame(&mut app);
+    assert_eq!(traversal(&mut app, &form, 3), vec!["c", "a", "c"]);
+}
+
+#[test]
+fn initial_focus_once_attached() {
+    let mut last = field("c", 0);
+    last.set_initial_focus(true);
+    let (mut app, form) = form(vec![field("a", 0), field("b", 0), last]);
+    assert_eq!(focused(&form), "c");
+    assert_eq!(traversal(&mut app, &form, 1), vec!["a"]);
+}
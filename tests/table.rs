@@ -0,0 +1,220 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::event::RecordedInput;
+use limn::draw::text::TextState;
+use limn::widgets::scroll::ScrollBuilder;
+use limn::widgets::table::{TableBuilder, TableModel, SortOrder, compare_text, compare_numbers};
+
+fn model() -> TableModel {
+    let mut model = TableModel::new(vec![false, true, true]);
+    model.add_row(vec!["a".to_owned(), "1".to_owned(), "x".to_owned()]);
+    model.add_row(vec!["b".to_owned(), "2".to_owned()]);
+    model.add_row(vec!["c".to_owned(), "3".to_owned(), "z".to_owned()]);
+    model
+}
+
+#[test]
+fn missing_cells_are_empty() {
+    let model = model();
+    assert_eq!(model.cell(1, 2), "");
+}
+
+#[test]
+fn only_editable_cells_are_edited() {
+    let mut model = model();
+    assert!(!model.start_edit(0, 0));
+    assert!(!model.start_edit(3, 1));
+    assert!(model.start_edit(0, 1));
+    assert!(!model.start_edit(1, 1));
+    assert_eq!(model.editing(), Some((0, 1)));
+}
+
+#[test]
+fn commit_and_cancel() {
+    let mut model = model();
+    model.start_edit(2, 2);
+    assert_eq!(model.commit("y"), Some((2, 2)));
+    assert_eq!(model.cell(2, 2), "y");
+    assert_eq!(model.commit("w"), None);
+    model.start_edit(2, 1);
+    assert_eq!(model.cancel(), Some((2, 1)));
+    assert_eq!(model.cell(2, 1), "3");
+    assert_eq!(model.editing(), None);
+}
+
+#[test]
+fn next_editable_skips_read_only_columns() {
+    let model = model();
+    assert_eq!(model.next_editable(0, 1), Some((0, 2)));
+    assert_eq!(model.next_editable(0, 2), Some((1, 1)));
+    assert_eq!(model.next_editable(2, 2), None);
+}
+
+#[test]
+fn removing_edited_row_stops_editing() {
+    let mut model = model();
+    model.start_edit(1, 2);
+    model.remove_row(1);
+    assert_eq!(model.editing(), None);
+    assert_eq!(model.rows(), 2);
+    assert_eq!(model.cell(1, 0), "c");
+    // a late commit from the removed editor doesn't change any cell
+    assert_eq!(model.commit("late"), None);
+    assert_eq!(model.cell(1, 2), "z");
+}
+
+#[test]
+fn removing_earlier_row_moves_edited_cell() {
+    let mut model = model();
+    model.start_edit(2, 1);
+    model.remove_row(0);
+    assert_eq!(model.editing(), Some((1, 1)));
+}
+
+#[test]
+fn removing_later_row_keeps_edited_cell() {
+    let mut model = model();
+    model.start_edit(0, 1);
+    model.remove_row(2);
+    assert_eq!(model.editing(), Some((0, 1)));
+}
+
+fn sortable_model() -> TableModel {
+    let mut model = TableModel::new(vec![false, true]);
+    model.set_comparator(0, compare_text);
+    model.set_comparator(1, compare_numbers);
+    for &(name, count) in &[("pear", "2"), ("apple", "10"), ("fig", "2"), ("kiwi", "x")] {
+        model.add_row(vec![name.to_owned(), count.to_owned()]);
+    }
+    model
+}
+
+#[test]
+fn header_clicks_cycle_through_sorts() {
+    let mut model = sortable_model();
+    assert!(model.cycle_sort(1));
+    assert_eq!(model.sort(), Some((1, SortOrder::Ascending)));
+    assert!(model.cycle_sort(1));
+    assert_eq!(model.sort(), Some((1, SortOrder::Descending)));
+    assert!(model.cycle_sort(0));
+    assert_eq!(model.sort(), Some((0, SortOrder::Ascending)));
+    model.cycle_sort(0);
+    model.cycle_sort(0);
+    assert_eq!(model.sort(), None);
+    assert_eq!(model.view(), &[0, 1, 2, 3]);
+}
+
+#[test]
+fn columns_without_comparator_are_not_sorted() {
+    let mut model = model();
+    assert!(!model.cycle_sort(0));
+    assert_eq!(model.sort(), None);
+}
+
+#[test]
+fn sorting_is_stable() {
+    let mut model = sortable_model();
+    model.set_sort(Some((1, SortOrder::Ascending)));
+    assert_eq!(model.view(), &[0, 2, 1, 3]);
+    // equal cells keep the order they were added in, descending too
+    model.set_sort(Some((1, SortOrder::Descending)));
+    assert_eq!(model.view(), &[3, 1, 0, 2]);
+    model.set_sort(Some((0, SortOrder::Ascending)));
+    assert_eq!(model.view(), &[1, 2, 3, 0]);
+}
+
+#[test]
+fn filter_hides_rows() {
+    let mut model = sortable_model();
+    model.set_sort(Some((0, SortOrder::Ascending)));
+    model.set_filter(Some(Rc::new(|row: &[String]| row[1] == "2")));
+    assert_eq!(model.view(), &[2, 0]);
+    // hidden rows can't be edited, and tab skips them
+    assert!(!model.start_edit(1, 1));
+    assert_eq!(model.next_editable(2, 1), Some((0, 1)));
+    assert_eq!(model.next_editable(0, 1), None);
+    model.set_filter(None);
+    assert_eq!(model.view(), &[1, 2, 3, 0]);
+}
+
+#[test]
+fn view_follows_removed_rows() {
+    let mut model = sortable_model();
+    model.set_sort(Some((0, SortOrder::Descending)));
+    model.remove_row(1);
+    assert_eq!(model.view(), &[0, 2, 1]);
+}
+
+// a table with `rows` rows, in a scroll widget filling the window, with the header pinned above the rows,
+// returns its header and body
+fn scrolled_table(rows: usize) -> (App, WidgetRef, WidgetRef) {
+    let mut table = TableBuilder::new();
+    table.add_column("Name", 100.0).add_column("Size", 60.0);
+    table.set_comparator(1, compare_numbers);
+    for row in 0..rows {
+        table.add_row(vec![format!("row {}", row), row.to_string()]);
+    }
+    let (header, body) = table.into_header_and_body();
+    let (header_ref, body_ref) = (header.widget_ref(), body.widget_ref());
+    let mut scroll = ScrollBuilder::new();
+    scroll.set_header(header).add_content(body);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(scroll);
+    (util::headless(root), header_ref, body_ref)
+}
+
+fn row_widgets(body: &WidgetRef) -> Vec<WidgetRef> {
+    body.children().into_iter().filter(|child| child.name() == "table_row").collect()
+}
+
+// the text of the first cell of each row of widgets
+fn row_names(body: &WidgetRef) -> Vec<String> {
+    row_widgets(body).iter().map(|row| {
+        let label = row.children()[0].children()[0].clone();
+        let text = label.drawable::<TextState>().unwrap().text.clone();
+        text
+    }).collect()
+}
+
+fn names(rows: ::std::ops::Range<usize>) -> Vec<String> {
+    rows.map(|row| format!("row {}", row)).collect()
+}
+
+#[test]
+fn only_rows_in_view_are_built() {
+    let (_app, _, body) = scrolled_table(10000);
+    // 264 pixels under the header, 7 and a third rows
+    assert_eq!(row_names(&body), names(0..8));
+    // as tall as if every row was built
+    assert_eq!(body.bounds().height(), 10000.0 * 36.0);
+}
+
+#[test]
+fn rows_recycled_while_scrolling() {
+    let (mut app, _, body) = scrolled_table(10000);
+    let before = row_widgets(&body);
+    util::move_mouse(&mut app, Point::new(100.0, 150.0));
+    // 169 pixels down
+    util::input(&mut app, RecordedInput::MouseWheel { x: 0.0, y: -13.0, pixels: false });
+    assert_eq!(row_names(&body), names(4..13));
+    // the rows still in view keep their widgets
+    let after = row_widgets(&body);
+    assert!(after[..4] == before[4..]);
+}
+
+#[test]
+fn sorting_keeps_row_widgets() {
+    let (mut app, header, body) = scrolled_table(10000);
+    let before = row_widgets(&body);
+    let size_header = header.children()[1].clone();
+    util::click(&mut app, util::center(&size_header));
+    util::click(&mut app, util::center(&size_header));
+    assert_eq!(row_names(&body), names(9992..10000).into_iter().rev().collect::<Vec<_>>());
+    assert!(row_widgets(&body) == before);
+}
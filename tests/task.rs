@@ -0,0 +1,130 @@
+extern crate limn;
+
+mod util;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use std::thread;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+use limn::task::{TaskPool, TaskHandle, TaskCompleteEvent};
+use limn::widget::WidgetBuilder;
+
+// wait for `done`, failing the test if it takes too long, eg. if a task never finishes
+fn wait_until<F: Fn() -> bool>(done: F) {
+    let start = Instant::now();
+    while !done() {
+        assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+        thread::yield_now();
+    }
+}
+
+#[test]
+fn delivers_result() {
+    let mut pool = TaskPool::new(2);
+    let (sender, receiver) = mpsc::channel();
+    let handle = pool.spawn(|| 6 * 7, move |result| sender.send(result).unwrap());
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(42));
+    wait_until(|| handle.is_finished());
+}
+
+#[test]
+fn cancelled_before_completion() {
+    // the task is held until it's cancelled, like a widget removed while its task is running
+    let mut pool = TaskPool::new(1);
+    let (release, wait) = mpsc::channel::<()>();
+    let delivered = Arc::new(Mutex::new(false));
+    let task_delivered = delivered.clone();
+    let handle = pool.spawn(move || wait.recv().unwrap(), move |_| *task_delivered.lock().unwrap() = true);
+    handle.cancel();
+    release.send(()).unwrap();
+    wait_until(|| handle.is_finished());
+    assert!(!*delivered.lock().unwrap());
+}
+
+#[test]
+fn cancelled_before_start() {
+    let mut pool = TaskPool::new(1);
+    let (release, wait) = mpsc::channel::<()>();
+    let blocking = pool.spawn(move || wait.recv().unwrap(), |_| ());
+    let ran = Arc::new(Mutex::new(false));
+    let task_ran = ran.clone();
+    let queued = pool.spawn(move || *task_ran.lock().unwrap() = true, |_| ());
+    queued.cancel();
+    release.send(()).unwrap();
+    wait_until(|| queued.is_finished() && blocking.is_finished());
+    assert!(!*ran.lock().unwrap());
+}
+
+#[test]
+fn concurrency_limit() {
+    let mut pool = TaskPool::new(2);
+    let running = Arc::new(AtomicUsize::new(0));
+    let most_running = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..8).map(|_| {
+        let running = running.clone();
+        let most_running = most_running.clone();
+        pool.spawn(move || {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            {
+                let mut most_running = most_running.lock().unwrap();
+                *most_running = (*most_running).max(now);
+            }
+            thread::sleep(Duration::from_millis(10));
+            running.fetch_sub(1, Ordering::SeqCst);
+        }, |_| ())
+    }).collect();
+    wait_until(|| handles.iter().all(|handle| handle.is_finished()));
+    assert!(*most_running.lock().unwrap() <= 2);
+}
+
+#[test]
+fn panicking_task_keeps_thread() {
+    let mut pool = TaskPool::new(1);
+    let panicked = pool.spawn(|| panic!("task failed"), |_: ()| ());
+    wait_until(|| panicked.is_finished());
+    // the pool's only thread runs the next task
+    let (sender, receiver) = mpsc::channel();
+    pool.spawn(|| 6 * 7, move |result| sender.send(result).unwrap());
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(42));
+}
+
+struct StartTask;
+
+#[test]
+fn result_dropped_for_removed_widget() {
+    let (release, wait) = mpsc::channel::<()>();
+    let wait = Arc::new(Mutex::new(wait));
+    let handle: Rc<RefCell<Option<TaskHandle>>> = Rc::new(RefCell::new(None));
+    let delivered = Rc::new(Cell::new(false));
+
+    let mut loader = WidgetBuilder::new("loader");
+    {
+        let handle = handle.clone();
+        loader.add_handler_fn(move |_: &StartTask, mut args| {
+            let wait = wait.clone();
+            *handle.borrow_mut() = Some(args.spawn(move || wait.lock().unwrap().recv().unwrap()));
+        });
+        let delivered = delivered.clone();
+        loader.add_handler_fn(move |_: &TaskCompleteEvent<()>, _| delivered.set(true));
+    }
+    let loader_ref = loader.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(loader);
+    let mut app = util::headless(root);
+
+    loader_ref.event(StartTask);
+    util::frame(&mut app);
+    let handle = handle.borrow().clone().unwrap();
+    app.ui().remove_widget(loader_ref.id());
+    util::frame(&mut app);
+    assert!(handle.is_cancelled());
+
+    release.send(()).unwrap();
+    wait_until(|| handle.is_finished());
+    util::frame(&mut app);
+    assert!(!delivered.get());
+}
@@ -0,0 +1,79 @@
+#[macro_use]
+extern crate limn;
+extern crate text_layout;
+
+mod util;
+
+use text_layout::Align;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::{TextState, TextStyle};
+use limn::widgets::text::TextBuilder;
+
+// a text widget on its own, laid out in a headless app
+fn text(text: WidgetBuilder) -> (App, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    let text_ref = text.widget_ref();
+    root.add_child(text);
+    (util::headless(root), text_ref)
+}
+
+fn decoration_rects(text: &WidgetRef) -> Vec<(Rect, Color)> {
+    let rects = text.drawable::<TextState>().unwrap().decoration_rects(text.bounds());
+    rects
+}
+
+fn center_y(rect: Rect) -> f32 {
+    rect.top() + rect.height() / 2.0
+}
+
+#[test]
+fn no_decorations_by_default() {
+    let (mut app, mut label) = text(TextBuilder::new("plain"));
+    assert!(decoration_rects(&label).is_empty());
+    label.update(|state: &mut TextState| state.underline = true);
+    util::frame(&mut app);
+    assert_eq!(decoration_rects(&label).len(), 1);
+}
+
+#[test]
+fn lines_cross_text() {
+    let (_app, label) = text(TextBuilder::new_with_style(style!(
+        TextStyle::Text: "Decorated".to_owned(),
+        TextStyle::TextColor: RED,
+        TextStyle::Underline: true,
+        TextStyle::Strikethrough: true)));
+    let bounds = label.bounds();
+    let rects = decoration_rects(&label);
+    assert_eq!(rects.len(), 2);
+    let ((underline, underline_color), (strikethrough, strikethrough_color)) = (rects[0], rects[1]);
+    assert_eq!((underline_color, strikethrough_color), (RED, RED));
+    // both span the text inside its bounds, the strikethrough through its middle and the underline near its bottom
+    for rect in &[underline, strikethrough] {
+        assert!((rect.left() - bounds.left()).abs() < 0.5 && (rect.right() - bounds.right()).abs() < 0.5);
+        assert!(rect.top() > bounds.top() && rect.bottom() < bounds.bottom());
+    }
+    let quarter = bounds.height() / 4.0;
+    assert!(center_y(strikethrough) > bounds.top() + quarter && center_y(strikethrough) < bounds.bottom() - quarter);
+    assert!(center_y(underline) > bounds.bottom() - quarter);
+}
+
+#[test]
+fn each_line_decorated_where_aligned() {
+    let (_app, label) = text(TextBuilder::new_with_style(style!(
+        TextStyle::Text: "a\nwider line".to_owned(),
+        TextStyle::Align: Align::End,
+        TextStyle::Underline: true)));
+    let bounds = label.bounds();
+    let rects = decoration_rects(&label);
+    assert_eq!(rects.len(), 2);
+    let (short, long) = (rects[0].0, rects[1].0);
+    assert!(center_y(short) < center_y(long));
+    // the widget is as wide as the longest line, the shorter line's underline ends with it on the right
+    assert!((long.left() - bounds.left()).abs() < 0.5);
+    assert!(short.left() > bounds.left() + bounds.width() / 2.0);
+    assert!((short.right() - bounds.right()).abs() < 0.5);
+    assert!((long.right() - bounds.right()).abs() < 0.5);
+}
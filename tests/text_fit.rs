@@ -0,0 +1,71 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::text::{TextState, shrink_to_fit};
+use limn::widgets::text::TextBuilder;
+
+// ten characters, each half as wide as the font size
+fn text_width(font_size: f32) -> f32 {
+    10.0 * font_size / 2.0
+}
+
+#[test]
+fn text_that_fits_keeps_its_size() {
+    assert_eq!(shrink_to_fit(24.0, 10.0, 200.0, text_width), 24.0);
+    assert_eq!(shrink_to_fit(24.0, 10.0, 120.0, text_width), 24.0);
+}
+
+#[test]
+fn wide_text_shrinks_until_it_fits() {
+    assert_eq!(shrink_to_fit(24.0, 10.0, 100.0, text_width), 20.0);
+    assert_eq!(shrink_to_fit(24.0, 10.0, 99.0, text_width), 19.0);
+}
+
+#[test]
+fn text_shrinks_no_smaller_than_the_minimum() {
+    assert_eq!(shrink_to_fit(24.0, 12.0, 10.0, text_width), 12.0);
+    assert_eq!(shrink_to_fit(24.0, 23.5, 10.0, text_width), 23.5);
+}
+
+// text that can shrink to fit, in a box `box_width` wide, returns the text widget once it's laid out
+fn text_in_box(box_width: f32) -> (App, WidgetRef) {
+    let mut text_box = WidgetBuilder::new("text_box");
+    text_box.layout().add(constraints![top_left(Point::new(0.0, 0.0)), size(Size::new(box_width, 50.0))]);
+    let text = TextBuilder::new("a line of text too long for the box");
+    let mut text_ref = text.widget_ref();
+    text_ref.update_drawable(|state: &mut TextState| state.shrink_to_fit = Some(10.0));
+    text_box.add_child(text);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    root.add_child(text_box);
+    (util::headless(root), text_ref)
+}
+
+#[test]
+fn drawn_text_shrinks_to_its_width() {
+    let (_app, text_ref) = text_in_box(100.0);
+    let width = text_ref.bounds().width();
+    assert_eq!(width, 100.0);
+    let mut state = text_ref.drawable::<TextState>().unwrap();
+    // chosen when the text is drawn
+    assert_eq!(state.effective_font_size(), state.font_size);
+    state.fit_to_width(width);
+    let fitted = state.effective_font_size();
+    assert!(fitted < state.font_size && fitted >= 10.0);
+    // the widget is still measured at the full font size
+    assert!(state.measure().width > width);
+}
+
+#[test]
+fn drawn_text_that_fits_keeps_its_size() {
+    let (_app, text_ref) = text_in_box(1000.0);
+    let width = text_ref.bounds().width();
+    let mut state = text_ref.drawable::<TextState>().unwrap();
+    state.fit_to_width(width);
+    assert_eq!(state.effective_font_size(), state.font_size);
+}
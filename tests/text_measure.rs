@@ -0,0 +1,86 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::draw::text::TextState;
+use limn::widgets::text::TextBuilder;
+use limn::ui::MeasureEvent;
+
+// a counter label showing "9", followed by a 20x20 sibling in a row
+fn counter() -> (App, WidgetRef, WidgetRef) {
+    let mut root = WidgetBuilder::new("root");
+    let mut row = WidgetBuilder::new("row");
+    row.linear_layout(LinearLayoutSettings::new(Orientation::Horizontal));
+    let label = TextBuilder::new("9");
+    let mut sibling = WidgetBuilder::with_draw_state("sibling", RectState::new());
+    sibling.layout().add(size(Size::new(20.0, 20.0)));
+    let (label_ref, sibling_ref) = (label.widget_ref(), sibling.widget_ref());
+    row.add_child(label).add_child(sibling);
+    root.add_child(row);
+    (util::headless(root), label_ref, sibling_ref)
+}
+
+fn measured(label: &WidgetRef) -> Size {
+    let size = label.drawable::<TextState>().unwrap().measure();
+    size
+}
+
+fn set_text(app: &mut App, label: &mut WidgetRef, text: &str) {
+    label.update(|state: &mut TextState| state.text = text.to_owned());
+    util::frame(app);
+}
+
+#[test]
+fn growing_text_moves_sibling() {
+    let (mut app, mut label, sibling) = counter();
+    let small = label.bounds();
+    assert_eq!(small.size, measured(&label));
+    assert_eq!(sibling.bounds().left(), small.right());
+
+    set_text(&mut app, &mut label, "1000");
+    let large = label.bounds();
+    assert_eq!(large.size, measured(&label));
+    assert!(large.width() > small.width());
+    assert_eq!(sibling.bounds().left(), large.right());
+}
+
+#[test]
+fn shrinking_text_moves_sibling_back() {
+    let (mut app, mut label, sibling) = counter();
+    let left = sibling.bounds().left();
+    set_text(&mut app, &mut label, "1000");
+    set_text(&mut app, &mut label, "9");
+    assert_eq!(label.bounds().size, measured(&label));
+    assert_eq!(sibling.bounds().left(), left);
+}
+
+// counts the `MeasureEvent`s sent to `widget`
+fn count_measures(widget: &mut WidgetRef) -> Rc<Cell<usize>> {
+    let measures = Rc::new(Cell::new(0));
+    {
+        let measures = measures.clone();
+        widget.add_handler_fn(move |_: &MeasureEvent, _| measures.set(measures.get() + 1));
+    }
+    measures
+}
+
+#[test]
+fn only_text_changes_are_measured() {
+    let (mut app, mut label, mut sibling) = counter();
+    let (label_measures, sibling_measures) = (count_measures(&mut label), count_measures(&mut sibling));
+    sibling.update(|state: &mut RectState| state.background_color = RED);
+    label.update(|state: &mut TextState| state.text_color = RED);
+    util::frame(&mut app);
+    assert_eq!(sibling_measures.get(), 0);
+    assert_eq!(label_measures.get(), 0);
+    set_text(&mut app, &mut label, "1000");
+    assert_eq!(label_measures.get(), 1);
+}
@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::draw::text::{outline_offsets, TextOutline, TextState, TextStyle};
+use limn::widgets::text::TextBuilder;
+
+#[test]
+fn offsets_surround_glyphs() {
+    let offsets = outline_offsets(2.0);
+    assert_eq!(offsets.len(), 8);
+    for offset in &offsets {
+        assert!((offset.length() - 2.0).abs() < 0.001);
+    }
+    // every direction is balanced by the opposite one
+    for offset in &offsets {
+        assert!(offsets.iter().any(|other| (*other + *offset).length() < 0.001));
+    }
+}
+
+#[test]
+fn no_outline_by_default() {
+    assert_eq!(TextState::new("caption").outline, None);
+    assert_eq!(TextState::default().outline, None);
+}
+
+#[test]
+fn outline_from_style() {
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    let plain = TextBuilder::new("caption");
+    let plain_ref = plain.widget_ref();
+    let outlined = TextBuilder::new_with_style(style!(
+        TextStyle::Text: "caption".to_owned(),
+        TextStyle::Outline: Some(TextOutline::new(BLACK, 1.5))));
+    let outlined_ref = outlined.widget_ref();
+    root.add_child(plain).add_child(outlined);
+    let _app = util::headless(root);
+    assert_eq!(plain_ref.drawable::<TextState>().unwrap().outline, None);
+    assert_eq!(outlined_ref.drawable::<TextState>().unwrap().outline, Some(TextOutline::new(BLACK, 1.5)));
+    // the outline isn't measured, so it doesn't change the text's size
+    assert_eq!(outlined_ref.bounds().size, plain_ref.bounds().size);
+}
@@ -0,0 +1,87 @@
+extern crate limn;
+extern crate text_layout;
+
+use limn::draw::text::{TextState, StyledRun, runs_text};
+use limn::geometry::{Rect, Point, Size};
+use limn::color::*;
+use text_layout::Wrap;
+use text_layout::runs::{CharAdvance, break_lines};
+
+fn lines(text: &str, wrap: Wrap, max_width: f32) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let advances: Vec<CharAdvance> = chars.iter().map(|&char| CharAdvance::new(char, 10.0)).collect();
+    break_lines(&advances, wrap, max_width).iter().map(|line| chars[line.start..line.end].iter().collect()).collect()
+}
+
+#[test]
+fn wraps_at_whitespace() {
+    assert_eq!(lines("let x = 1;", Wrap::Whitespace, 55.0), vec!["let x", "= 1;"]);
+    // a word longer than the line is broken
+    assert_eq!(lines("abcdef", Wrap::Whitespace, 30.0), vec!["abc", "def"]);
+}
+
+#[test]
+fn breaks_at_newlines() {
+    assert_eq!(lines("ab\ncd\n", Wrap::NoWrap, 10.0), vec!["ab", "cd", ""]);
+    assert_eq!(lines("ab\r\ncd", Wrap::Whitespace, 100.0), vec!["ab", "cd"]);
+}
+
+#[test]
+fn wide_characters_are_kept() {
+    assert_eq!(lines("ab", Wrap::Character, 5.0), vec!["a", "b"]);
+}
+
+#[test]
+fn runs_keep_plain_text() {
+    let runs = vec![StyledRun::colored("fn", BLUE), StyledRun::new(" main() {}")];
+    assert_eq!(runs_text(&runs), "fn main() {}");
+    let state = TextState::with_runs(runs.clone());
+    assert_eq!(state.text, "fn main() {}");
+    assert_eq!(state.runs(), Some(&runs[..]));
+}
+
+#[test]
+fn setting_text_drops_runs() {
+    let mut state = TextState::with_runs(vec![StyledRun { font_size: Some(48.0), ..StyledRun::new("big") }]);
+    let styled_size = state.measure();
+    state.text = "big".to_owned();
+    assert!(state.runs().is_some());
+    state.text = "plain".to_owned();
+    assert_eq!(state.runs(), None);
+    assert_eq!(state.measure(), TextState::new("plain").measure());
+    assert!(state.measure().height < styled_size.height);
+}
+
+#[test]
+fn decorations_follow_runs() {
+    let big = StyledRun { font_size: Some(48.0), ..StyledRun::new("Big") };
+    let mut state = TextState::with_runs(vec![big, StyledRun::colored(" small", RED)]);
+    state.underline = true;
+    let bounds = Rect::new(Point::new(0.0, 0.0), Size::new(1000.0, 200.0));
+    let rects = state.decoration_rects(bounds);
+    assert_eq!(rects.len(), 2);
+    let ((big_rect, big_color), (small_rect, small_color)) = (rects[0], rects[1]);
+    // each run's line is as thick as its font size calls for, in its color
+    assert!(big_rect.size.height > small_rect.size.height);
+    assert_eq!(big_color, state.text_color);
+    assert_eq!(small_color, RED);
+    // side by side, the small run's line right after the big run's
+    assert!(big_rect.origin.x < small_rect.origin.x);
+    assert!((big_rect.origin.x + big_rect.size.width - small_rect.origin.x).abs() < 0.01);
+    // the runs share a baseline, the big run's underline is further below it
+    assert!(big_rect.origin.y + big_rect.size.height / 2.0 > small_rect.origin.y + small_rect.size.height / 2.0);
+}
+
+#[test]
+fn decorations_across_wrapped_runs() {
+    let mut state = TextState::with_runs(vec![StyledRun::new("one two "), StyledRun::colored("three", RED)]);
+    state.strikethrough = true;
+    let wide = Rect::new(Point::new(0.0, 0.0), Size::new(1000.0, 200.0));
+    assert_eq!(state.decoration_rects(wide).len(), 2);
+    // too narrow for more than one word per line, each word gets its own line
+    let narrow = Rect::new(Point::new(0.0, 0.0), Size::new(state.font_size * 3.0, 200.0));
+    let rects = state.decoration_rects(narrow);
+    assert_eq!(rects.len(), 3);
+    assert!(rects[0].0.origin.y < rects[1].0.origin.y && rects[1].0.origin.y < rects[2].0.origin.y);
+    assert_eq!(rects[2].1, RED);
+}
@@ -0,0 +1,92 @@
+extern crate limn;
+
+mod util;
+
+use limn::app::App;
+use limn::widget::{WidgetBuilder, WidgetRef};
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::draw::text::{TextState, previous_word_start, next_word_end, word_at};
+
+#[test]
+fn words_in_multi_byte_text() {
+    let text = "héllo wörld";
+    assert_eq!(next_word_end(text, 0), 5);
+    assert_eq!(next_word_end(text, 5), 11);
+    assert_eq!(previous_word_start(text, 11), 6);
+    assert_eq!(previous_word_start(text, 6), 0);
+    assert_eq!(word_at(text, 8), (6, 11));
+
+    let text = "привет, мир";
+    assert_eq!(next_word_end(text, 0), 6);
+    assert_eq!(previous_word_start(text, 11), 8);
+    assert_eq!(word_at(text, 2), (0, 6));
+}
+
+#[test]
+fn punctuation_runs_are_separate_words() {
+    let text = "foo...bar";
+    assert_eq!(next_word_end(text, 0), 3);
+    assert_eq!(next_word_end(text, 3), 6);
+    assert_eq!(previous_word_start(text, 9), 6);
+    assert_eq!(previous_word_start(text, 6), 3);
+    assert_eq!(word_at(text, 4), (3, 6));
+    // underscores are part of words
+    assert_eq!(word_at("a snake_case name", 4), (2, 12));
+}
+
+#[test]
+fn words_at_text_bounds() {
+    assert_eq!(previous_word_start("", 0), 0);
+    assert_eq!(next_word_end("", 0), 0);
+    assert_eq!(word_at("", 0), (0, 0));
+    // indices past the end are clamped
+    assert_eq!(next_word_end("word", 10), 4);
+    assert_eq!(previous_word_start("two words  ", 20), 4);
+    assert_eq!(word_at("two words", 20), (4, 9));
+}
+
+// a focused edit text, and the widget its text is drawn in
+fn edit_text_app() -> (App, WidgetRef) {
+    let edit_text = EditTextBuilder::new();
+    let text_ref = edit_text.text_widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(edit_text);
+    (util::headless(root), text_ref)
+}
+
+fn text(text_ref: &WidgetRef) -> String {
+    text_ref.drawable::<TextState>().unwrap().text.clone()
+}
+
+#[test]
+fn modifiers_held_while_editing() {
+    let (mut app, text_ref) = edit_text_app();
+    util::type_text(&mut app, "hello world");
+
+    // ctrl+left moves to the start of the word
+    util::key(&mut app, "LControl", true);
+    util::tap_key(&mut app, "Left");
+    util::key(&mut app, "LControl", false);
+    util::type_text(&mut app, "X");
+    assert_eq!(text(&text_ref), "hello Xworld");
+
+    // shift+left selects the character before the caret, replaced by what's typed next
+    util::key(&mut app, "RShift", true);
+    util::tap_key(&mut app, "Left");
+    util::key(&mut app, "RShift", false);
+    util::type_text(&mut app, "Y");
+    assert_eq!(text(&text_ref), "hello Yworld");
+
+    // once they're released, left moves by one character without selecting
+    util::tap_key(&mut app, "Left");
+    util::type_text(&mut app, "Z");
+    assert_eq!(text(&text_ref), "hello ZYworld");
+
+    // ctrl+backspace deletes to the start of the word
+    util::key(&mut app, "LControl", true);
+    util::key(&mut app, "Back", true);
+    util::type_text(&mut app, "\u{8}");
+    util::key(&mut app, "Back", false);
+    util::key(&mut app, "LControl", false);
+    assert_eq!(text(&text_ref), "hello Yworld");
+}
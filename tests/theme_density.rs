@@ -0,0 +1,152 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+
+mod util;
+
+use std::collections::BTreeMap;
+
+use limn::app::App;
+use limn::theme::{Theme, Density, ThemePadding};
+use limn::layout::{LimnSolver, Layout, VarType};
+use limn::layout::constraint::*;
+use limn::geometry::{Point, Size, Rect};
+use limn::widget::{WidgetBuilder, WidgetRef};
+use limn::widgets::button::ToggleButtonBuilder;
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::draw::text::{TextState, DEFAULT_FONT_SIZE};
+
+// lays out a form of two rows, each a label and an input, spaced like the built-in widgets with `theme`,
+// and describes the result one line per widget, to compare against the expected layout
+fn form_snapshot(theme: &Theme) -> String {
+    let padding = theme.padding(ThemePadding::Widget);
+    let label_size = Size::new(theme.scale(80.0), theme.scale(30.0));
+    let input_size = Size::new(theme.scale(160.0), theme.scale(30.0));
+
+    let names = ["form", "label_1", "input_1", "label_2", "input_2"];
+    let mut layouts: Vec<Layout> = names.iter().enumerate()
+        .map(|(id, name)| Layout::new(id, Some(name.to_string())))
+        .collect();
+    let vars: Vec<_> = layouts.iter().map(|layout| layout.vars.clone()).collect();
+    let (form, label_1, input_1, label_2) = (&vars[0], &vars[1], &vars[2], &vars[3]);
+    layouts[0].add(constraints![top_left(Point::new(0.0, 0.0)), shrink()]);
+    layouts[1].add(constraints![
+        align_left(form).padding(padding),
+        align_top(form).padding(padding),
+        size(label_size),
+    ]);
+    layouts[2].add(constraints![
+        align_to_right_of(label_1).padding(padding),
+        align_top(label_1),
+        bound_right(form).padding(padding),
+        size(input_size),
+    ]);
+    layouts[3].add(constraints![
+        align_left(form).padding(padding),
+        align_below(label_1).padding(padding),
+        bound_bottom(form).padding(padding),
+        size(label_size),
+    ]);
+    layouts[4].add(constraints![
+        align_left(input_1),
+        align_top(label_2),
+        bound_bottom(form).padding(padding),
+        size(input_size),
+    ]);
+
+    let mut solver = LimnSolver::new();
+    for layout in &mut layouts {
+        solver.update_layout(layout).unwrap();
+    }
+    let mut rects = BTreeMap::new();
+    for id in 0..names.len() {
+        rects.insert(id, Rect::zero());
+    }
+    for (id, var, value) in solver.fetch_changes() {
+        let rect = rects.get_mut(&id).unwrap();
+        match var {
+            VarType::Left => rect.origin.x = value as f32,
+            VarType::Top => rect.origin.y = value as f32,
+            VarType::Width => rect.size.width = value as f32,
+            VarType::Height => rect.size.height = value as f32,
+            _ => (),
+        }
+    }
+    rects.iter().map(|(id, rect)| {
+        format!("{} {},{} {}x{}\n", names[*id], rect.origin.x, rect.origin.y, rect.size.width, rect.size.height)
+    }).collect()
+}
+
+#[test]
+fn comfortable_form() {
+    let theme = Theme::default();
+    assert_eq!(form_snapshot(&theme), "\
+form 0,0 270x90
+label_1 10,10 80x30
+input_1 100,10 160x30
+label_2 10,50 80x30
+input_2 100,50 160x30
+");
+}
+
+#[test]
+fn compact_form() {
+    let theme = Theme { density: Density::Compact, ..Theme::default() };
+    assert_eq!(form_snapshot(&theme), "\
+form 0,0 204x70
+label_1 8,8 60x23
+input_1 76,8 120x23
+label_2 8,39 60x23
+input_2 76,39 120x23
+");
+}
+
+#[test]
+fn density_scales_theme_values() {
+    let theme = Theme { density: Density::Custom(2.0), ..Theme::default() };
+    assert_eq!(theme.padding(ThemePadding::Input), 10.0);
+    assert_eq!(theme.scale(24.0), 48.0);
+    assert_eq!(Density::Comfortable.factor(), 1.0);
+}
+
+// a button and an edit text built by a Ui using `density`,
+// returns the button, and the text widget of the edit text
+fn widgets(density: Density) -> (App, WidgetRef, WidgetRef) {
+    let mut app = App::headless(Size::new(400.0, 300.0));
+    app.ui().set_density(density);
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    let mut button = ToggleButtonBuilder::new();
+    button.widget.layout().add(top_left(Point::new(0.0, 0.0)));
+    let button_ref = button.widget.widget_ref();
+    let edit_text = EditTextBuilder::new();
+    let text_ref = edit_text.text_widget.widget_ref();
+    root.add_child(button).add_child(edit_text);
+    app.attach(root);
+    util::frame(&mut app);
+    (app, button_ref, text_ref)
+}
+
+fn measure(button: &WidgetRef, text: &WidgetRef) -> (Size, f32) {
+    (button.bounds().size, text.drawable::<TextState>().unwrap().font_size)
+}
+
+#[test]
+fn compact_widgets() {
+    let (_app, button, text) = widgets(Density::Comfortable);
+    assert_eq!(measure(&button, &text), (Size::new(70.0, 30.0), DEFAULT_FONT_SIZE));
+    let (_app, button, text) = widgets(Density::Compact);
+    assert_eq!(measure(&button, &text), (Size::new(53.0, 23.0), 18.0));
+}
+
+#[test]
+fn switching_density_lays_out_again() {
+    let (_compact_app, compact_button, compact_text) = widgets(Density::Compact);
+    let (mut app, button, text) = widgets(Density::Comfortable);
+    app.ui().set_density(Density::Compact);
+    util::frame(&mut app);
+    assert_eq!(measure(&button, &text), measure(&compact_button, &compact_text));
+    app.ui().set_density(Density::Comfortable);
+    util::frame(&mut app);
+    assert_eq!(measure(&button, &text), (Size::new(70.0, 30.0), DEFAULT_FONT_SIZE));
+}
@@ -0,0 +1,28 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::theme::{Theme, ThemePadding};
+
+#[test]
+fn padded_inside_parent() {
+    let mut root = WidgetBuilder::new("root");
+    let mut panel = WidgetBuilder::new("panel");
+    panel.padding_from_theme();
+    let panel_ref = panel.widget_ref();
+    let mut field = WidgetBuilder::new("field");
+    field.padding_from_theme_for(ThemePadding::Input);
+    let field_ref = field.widget_ref();
+    panel.add_child(field);
+    root.add_child(panel);
+    let mut app = util::headless(root);
+    assert_eq!(panel_ref.bounds(), Rect::new(Point::new(10.0, 10.0), Size::new(380.0, 280.0)));
+    assert_eq!(field_ref.bounds(), Rect::new(Point::new(15.0, 15.0), Size::new(370.0, 270.0)));
+
+    // the padding follows the theme
+    app.ui().set_theme(Theme { widget_padding: 20.0, ..Theme::default() });
+    util::frame(&mut app);
+    assert_eq!(panel_ref.bounds(), Rect::new(Point::new(20.0, 20.0), Size::new(360.0, 260.0)));
+    assert_eq!(field_ref.bounds(), Rect::new(Point::new(25.0, 25.0), Size::new(350.0, 250.0)));
+}
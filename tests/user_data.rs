@@ -0,0 +1,24 @@
+extern crate limn;
+
+use limn::widget::WidgetBuilder;
+
+struct RecordId(u32);
+
+#[test]
+fn user_data_is_typed() {
+    let mut widget = WidgetBuilder::new("record");
+    widget.set_user_data(RecordId(7));
+    let mut widget_ref = widget.widget_ref();
+    assert_eq!(widget_ref.user_data::<RecordId>().map(|id| id.0), Some(7));
+    assert!(widget_ref.user_data::<String>().is_none());
+
+    widget_ref.set_user_data("replaced".to_owned());
+    assert!(widget_ref.user_data::<RecordId>().is_none());
+    assert_eq!(widget_ref.user_data::<String>().map(|text| text.clone()), Some("replaced".to_owned()));
+}
+
+#[test]
+fn widgets_start_without_user_data() {
+    let widget = WidgetBuilder::new("empty");
+    assert!(widget.widget_ref().user_data::<RecordId>().is_none());
+}
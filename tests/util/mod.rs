@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use limn::app::App;
+use limn::event::{RecordedInput, RecordedButton};
+use limn::geometry::{Point, Size, RectExt};
+use limn::widget::{WidgetBuilder, WidgetRef};
+
+/// A headless app with a 400x300 window, with `root` attached and its layout settled
+#[allow(dead_code)]
+pub fn headless(root: WidgetBuilder) -> App {
+    let mut app = App::headless(Size::new(400.0, 300.0));
+    app.attach(root);
+    frame(&mut app);
+    app
+}
+
+/// Handle the events queued since the last frame, like the main loop does once per frame
+#[allow(dead_code)]
+pub fn frame(app: &mut App) {
+    app.ui().frame(Duration::from_millis(16));
+}
+
+/// Send `input` as if it came from the window, and handle the events that follow
+#[allow(dead_code)]
+pub fn input(app: &mut App, input: RecordedInput) {
+    input.dispatch(app.ui());
+    frame(app);
+}
+
+#[allow(dead_code)]
+pub fn move_mouse(app: &mut App, point: Point) {
+    input(app, RecordedInput::MouseMoved(point.x, point.y));
+}
+
+/// Move the mouse to `point` and press the left button there
+#[allow(dead_code)]
+pub fn press(app: &mut App, point: Point) {
+    move_mouse(app, point);
+    input(app, RecordedInput::MouseButton { pressed: true, button: RecordedButton::Left });
+}
+
+#[allow(dead_code)]
+pub fn release(app: &mut App) {
+    input(app, RecordedInput::MouseButton { pressed: false, button: RecordedButton::Left });
+}
+
+#[allow(dead_code)]
+pub fn click(app: &mut App, point: Point) {
+    press(app, point);
+    release(app);
+}
+
+/// Press or release the key with the virtual key code named `key`, eg. "Escape"
+#[allow(dead_code)]
+pub fn key(app: &mut App, key: &str, pressed: bool) {
+    input(app, RecordedInput::Key { pressed: pressed, scancode: 0, key: Some(key.to_owned()) });
+}
+
+/// Press and release the key named `key`
+#[allow(dead_code)]
+pub fn tap_key(app: &mut App, name: &str) {
+    key(app, name, true);
+    key(app, name, false);
+}
+
+/// Send each character of `text` as if it was typed
+#[allow(dead_code)]
+pub fn type_text(app: &mut App, text: &str) {
+    for char in text.chars() {
+        input(app, RecordedInput::Character(char));
+    }
+}
+
+#[allow(dead_code)]
+pub fn center(widget: &WidgetRef) -> Point {
+    widget.bounds().center()
+}
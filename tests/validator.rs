@@ -0,0 +1,105 @@
+extern crate limn;
+#[macro_use]
+extern crate limn_layout;
+extern crate regex;
+
+mod util;
+
+use regex::Regex;
+
+use limn::prelude::*;
+use limn::widgets::edit_text::EditTextBuilder;
+use limn::widgets::validator::{TextInputValidator, ValidationResult, NumericValidator, EmailValidator, RegexValidator};
+
+#[test]
+fn numbers() {
+    assert_eq!(NumericValidator.validate("-12.5"), ValidationResult::Valid);
+    assert_eq!(NumericValidator.validate(" 3 "), ValidationResult::Valid);
+    assert!(!NumericValidator.validate("12a").is_valid());
+    assert!(!NumericValidator.validate("inf").is_valid());
+    assert!(!NumericValidator.validate("").is_valid());
+}
+
+#[test]
+fn emails() {
+    let validator = EmailValidator { allow_empty: false };
+    assert_eq!(validator.validate("someone@example.com"), ValidationResult::Valid);
+    assert!(!validator.validate("").is_valid());
+    assert!(!validator.validate("someone").is_valid());
+    assert!(!validator.validate("some one@example.com").is_valid());
+    assert!(!validator.validate("a@b@example.com").is_valid());
+    assert!(!validator.validate("someone@example..com").is_valid());
+    // valid, but probably missing the ending
+    let result = validator.validate("someone@example");
+    assert!(result.is_valid());
+    assert!(result.message().is_some());
+    assert_eq!(EmailValidator { allow_empty: true }.validate(""), ValidationResult::Valid);
+}
+
+#[test]
+fn regex() {
+    let validator = RegexValidator(Regex::new("^[0-9]{5}$").unwrap());
+    assert!(validator.validate("12345").is_valid());
+    assert!(!validator.validate("123456").is_valid());
+}
+
+#[test]
+fn closures_are_validators() {
+    let validator = |text: &str| if text.len() < 8 {
+        ValidationResult::Warning("Short passwords are easy to guess".to_owned())
+    } else {
+        ValidationResult::Valid
+    };
+    assert_eq!(validator.validate("hunter2").message(), Some("Short passwords are easy to guess"));
+}
+
+#[test]
+fn message_shown_below_edit_text() {
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_validator(NumericValidator);
+    let widget: WidgetRef = edit_text.into();
+    let names: Vec<String> = widget.children().iter().map(|child| child.name()).collect();
+    assert_eq!(widget.name(), "validated_edit_text");
+    assert_eq!(names, vec!["edit_text", "edit_text_validation_message"]);
+}
+
+// a validated edit text placed by constraints added to its builder, returns it and its edit text and message
+fn placed_edit_text() -> (limn::app::App, WidgetRef, WidgetRef, WidgetRef) {
+    let mut edit_text = EditTextBuilder::new();
+    edit_text.set_validator(NumericValidator);
+    edit_text.layout().add(constraints![top_left(Point::new(20.0, 30.0)), width(200.0)]);
+    let widget: WidgetRef = edit_text.into();
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    root.add_child(widget.clone());
+    let app = util::headless(root);
+    let children = widget.children();
+    (app, widget.clone(), children[0].clone(), children[1].clone())
+}
+
+#[test]
+fn constraints_placed_on_wrapper() {
+    let (_app, widget, edit_text, message) = placed_edit_text();
+    assert_eq!(widget.bounds().origin, Point::new(20.0, 30.0));
+    assert_eq!(widget.bounds().width(), 200.0);
+    assert_eq!(edit_text.bounds().origin, Point::new(20.0, 30.0));
+    assert_eq!(edit_text.bounds().width(), 200.0);
+    // no message yet, so it takes no space
+    assert_eq!(message.bounds().height(), 0.0);
+    assert_eq!(widget.bounds().height(), edit_text.bounds().height());
+}
+
+#[test]
+fn message_shown_while_invalid() {
+    let (mut app, widget, edit_text, message) = placed_edit_text();
+    let edit_height = edit_text.bounds().height();
+    util::click(&mut app, util::center(&edit_text));
+    util::type_text(&mut app, "12a");
+    assert!(message.bounds().height() > 0.0);
+    assert!(message.bounds().origin.y > edit_text.bounds().origin.y + edit_height);
+    assert_eq!(widget.bounds().max_y(), message.bounds().max_y());
+
+    util::tap_key(&mut app, "Back");
+    assert_eq!(message.bounds().height(), 0.0);
+    assert_eq!(widget.bounds().height(), edit_height);
+}
@@ -0,0 +1,135 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::draw::rect::RectState;
+use limn::event::{RecordedInput, RecordedButton};
+use limn::geometry::ViewTransform;
+use limn::widgets::viewport::{ViewportBuilder, ViewChangedEvent, SetViewEvent, DEFAULT_MAX_SCALE};
+
+struct Viewport {
+    app: App,
+    viewport: WidgetRef,
+    content: WidgetRef,
+    changes: Rc<RefCell<Vec<ViewChangedEvent>>>,
+}
+
+impl Viewport {
+    // a 200x200 viewport showing content of `content_size`, set up with `setup`
+    fn new<F: FnOnce(&mut ViewportBuilder)>(content_size: Size, setup: F) -> Self {
+        let mut content = WidgetBuilder::with_draw_state("content", RectState::new());
+        content.layout().add(size(content_size));
+        let content_ref = content.widget_ref();
+        let mut viewport = ViewportBuilder::new();
+        viewport.layout().add(size(Size::new(200.0, 200.0)));
+        viewport.add_content(content);
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        {
+            let changes = changes.clone();
+            viewport.on_view_changed(move |event, _| changes.borrow_mut().push(*event));
+        }
+        setup(&mut viewport);
+        let viewport_ref = viewport.widget_ref();
+        let mut root = WidgetBuilder::new("root");
+        root.add_child(viewport);
+        Viewport {
+            app: util::headless(root),
+            viewport: viewport_ref,
+            content: content_ref,
+            changes: changes,
+        }
+    }
+    // `point` relative to the top left of the viewport, in window coordinates
+    fn at(&self, x: f32, y: f32) -> Point {
+        self.viewport.bounds().origin + Vector::new(x, y)
+    }
+    fn hit(&mut self, point: Point) -> Option<String> {
+        self.app.ui().widget_under_cursor(point).map(|widget| widget.name())
+    }
+    fn wheel(&mut self, point: Point, lines: f32) {
+        util::move_mouse(&mut self.app, point);
+        util::input(&mut self.app, RecordedInput::MouseWheel { x: 0.0, y: lines, pixels: false });
+    }
+    fn last_change(&self) -> ViewChangedEvent {
+        *self.changes.borrow().last().unwrap()
+    }
+}
+
+fn middle_button(app: &mut App, pressed: bool) {
+    util::input(app, RecordedInput::MouseButton { pressed: pressed, button: RecordedButton::Middle });
+}
+
+#[test]
+fn middle_drag_pans() {
+    let mut viewport = Viewport::new(Size::new(100.0, 100.0), |_| {});
+    let (start, end) = (viewport.at(50.0, 50.0), viewport.at(80.0, 70.0));
+    let (moved_onto, outside) = (viewport.at(120.0, 110.0), viewport.at(10.0, 10.0));
+    assert_eq!(viewport.hit(moved_onto), None);
+    util::move_mouse(&mut viewport.app, start);
+    middle_button(&mut viewport.app, true);
+    util::move_mouse(&mut viewport.app, end);
+    middle_button(&mut viewport.app, false);
+    assert_eq!(viewport.last_change(), ViewChangedEvent { offset: Vector::new(30.0, 20.0), scale: 1.0 });
+    // hit where the content is drawn, not where it's laid out
+    assert_eq!(viewport.hit(moved_onto), Some("content".to_owned()));
+    assert_eq!(viewport.hit(outside), None);
+    assert_eq!(viewport.content.bounds().origin, viewport.at(0.0, 0.0));
+}
+
+#[test]
+fn wheel_pans_without_control() {
+    let mut viewport = Viewport::new(Size::new(100.0, 100.0), |_| {});
+    let point = viewport.at(50.0, 50.0);
+    viewport.wheel(point, -2.0);
+    assert_eq!(viewport.last_change(), ViewChangedEvent { offset: Vector::new(0.0, -26.0), scale: 1.0 });
+}
+
+#[test]
+fn control_wheel_zooms_around_cursor() {
+    let mut viewport = Viewport::new(Size::new(100.0, 100.0), |_| {});
+    let cursor = viewport.at(50.0, 50.0);
+    let before = viewport.content.local_point(cursor);
+    util::key(&mut viewport.app, "LControl", true);
+    viewport.wheel(cursor, 1.0);
+    util::key(&mut viewport.app, "LControl", false);
+    assert!(viewport.last_change().scale > 1.0);
+    // the same point of the content is under the cursor
+    let after = viewport.content.local_point(cursor);
+    assert!((after - before).length() < 0.01);
+}
+
+#[test]
+fn zoom_is_limited() {
+    let mut viewport = Viewport::new(Size::new(100.0, 100.0), |viewport| { viewport.set_zoom_limits(0.5, 2.0); });
+    let cursor = viewport.at(50.0, 50.0);
+    util::key(&mut viewport.app, "LControl", true);
+    viewport.wheel(cursor, 20.0);
+    assert_eq!(viewport.last_change().scale, 2.0);
+    viewport.wheel(cursor, -40.0);
+    assert_eq!(viewport.last_change().scale, 0.5);
+    // a view that's set is limited too
+    viewport.viewport.event(SetViewEvent { offset: Vector::zero(), scale: 4.0 });
+    util::frame(&mut viewport.app);
+    assert_eq!(viewport.last_change(), ViewChangedEvent { offset: Vector::zero(), scale: 2.0 });
+}
+
+#[test]
+fn set_view_is_kept_within_default_limits() {
+    let mut viewport = Viewport::new(Size::new(100.0, 100.0), |_| {});
+    viewport.viewport.event(SetViewEvent { offset: Vector::new(10.0, 10.0), scale: 50.0 });
+    util::frame(&mut viewport.app);
+    assert_eq!(viewport.last_change(), ViewChangedEvent { offset: Vector::new(10.0, 10.0), scale: DEFAULT_MAX_SCALE });
+}
+
+#[test]
+fn fit_content_centers_content() {
+    let viewport = Viewport::new(Size::new(400.0, 100.0), |viewport| { viewport.fit_content(); });
+    // half as large to fit the width, centered vertically
+    assert_eq!(viewport.viewport.view_transform(), Some(ViewTransform::new(Vector::new(0.0, 75.0), 0.5)));
+    assert_eq!(viewport.last_change(), ViewChangedEvent { offset: Vector::new(0.0, 75.0), scale: 0.5 });
+}
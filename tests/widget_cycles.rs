@@ -0,0 +1,25 @@
+extern crate limn;
+
+use limn::widget::{WidgetBuilder, WidgetRef};
+
+#[test]
+fn tree_has_no_cycles() {
+    let mut outer = WidgetBuilder::new("outer");
+    let mut inner = WidgetBuilder::new("inner");
+    let leaf = WidgetBuilder::new("leaf");
+    let leaf_ref = leaf.widget_ref();
+    inner.add_child(leaf);
+    outer.add_child(inner);
+    assert_eq!(leaf_ref.assert_no_cycles(), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "outer -> inner -> outer")]
+fn adding_an_ancestor_as_a_child_panics() {
+    let mut outer = WidgetBuilder::new("outer");
+    let inner = WidgetBuilder::new("inner");
+    let mut inner_ref = inner.widget_ref();
+    outer.add_child(inner);
+    let outer: WidgetRef = outer.into();
+    inner_ref.add_child(outer);
+}
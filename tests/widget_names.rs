@@ -0,0 +1,55 @@
+extern crate limn;
+
+mod util;
+
+use limn::prelude::*;
+use limn::resources::lookup_widget_by_name;
+
+// names are shared by every app, so each test uses its own
+
+#[test]
+fn attached_widgets_found_by_name() {
+    let mut root = WidgetBuilder::new("root");
+    let button = WidgetBuilder::new("lookup_button");
+    let button_ref = button.widget_ref();
+    root.add_child(button);
+    assert_eq!(lookup_widget_by_name("lookup_button"), None);
+    let mut app = util::headless(root);
+    assert_eq!(lookup_widget_by_name("lookup_button"), Some(button_ref.id()));
+    assert_eq!(WidgetId::from_debug_name("lookup_button"), Some(button_ref.id()));
+    app.ui().remove_widget(button_ref.id());
+    util::frame(&mut app);
+    assert_eq!(lookup_widget_by_name("lookup_button"), None);
+}
+
+#[test]
+fn latest_widget_keeps_name() {
+    let mut root = WidgetBuilder::new("root");
+    let older = WidgetBuilder::new("shared_name");
+    let older_ref = older.widget_ref();
+    root.add_child(older);
+    let root_ref = root.widget_ref();
+    let mut app = util::headless(root);
+    let newer = WidgetBuilder::new("shared_name");
+    let newer_ref = newer.widget_ref();
+    app.ui().add_widget_at(newer, root_ref.id(), 1).unwrap();
+    util::frame(&mut app);
+    assert_eq!(lookup_widget_by_name("shared_name"), Some(newer_ref.id()));
+    // removing the older widget doesn't affect the newer one
+    app.ui().remove_widget(older_ref.id());
+    util::frame(&mut app);
+    assert_eq!(lookup_widget_by_name("shared_name"), Some(newer_ref.id()));
+}
+
+#[test]
+fn renamed_widget_found_by_new_name() {
+    let mut root = WidgetBuilder::new("root");
+    let widget = WidgetBuilder::new("before_rename");
+    let mut widget_ref = widget.widget_ref();
+    root.add_child(widget);
+    let mut app = util::headless(root);
+    widget_ref.set_name("after_rename");
+    util::frame(&mut app);
+    assert_eq!(lookup_widget_by_name("before_rename"), None);
+    assert_eq!(lookup_widget_by_name("after_rename"), Some(widget_ref.id()));
+}
@@ -0,0 +1,79 @@
+extern crate limn;
+
+mod util;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use limn::prelude::*;
+use limn::app::App;
+use limn::layout::WidgetResizedEvent;
+
+// records the sizes sent with each `WidgetResizedEvent` the widget gets
+fn record_resizes(widget: &mut WidgetBuilder) -> Rc<RefCell<Vec<(Size, Size)>>> {
+    let resizes = Rc::new(RefCell::new(Vec::new()));
+    let recorded = resizes.clone();
+    widget.add_handler_fn(move |event: &WidgetResizedEvent, _| recorded.borrow_mut().push((event.old, event.new)));
+    resizes
+}
+
+#[test]
+fn one_event_per_change() {
+    let mut canvas = WidgetBuilder::new("canvas");
+    canvas.respond_to_resize(true);
+    let resizes = record_resizes(&mut canvas);
+    let mut root = WidgetBuilder::new("root");
+    root.add_child(canvas);
+    let mut app = util::headless(root);
+    resizes.borrow_mut().clear();
+    // the width and height change together, in one event
+    app.ui().resize_headless(Size::new(300.0, 200.0));
+    util::frame(&mut app);
+    assert_eq!(*resizes.borrow(), vec![(Size::new(400.0, 300.0), Size::new(300.0, 200.0))]);
+}
+
+// size the widget `size` by `size`, at `(left, 10)`
+fn place(widget: &WidgetRef, left: f32, size: f32) {
+    widget.update_layout(|layout| {
+        layout.edit_left().set(left);
+        layout.edit_top().set(10.0);
+        layout.edit_width().set(size);
+        layout.edit_height().set(size);
+    });
+}
+
+fn placed_widget(respond_to_resize: bool) -> (App, WidgetRef, Rc<RefCell<Vec<(Size, Size)>>>) {
+    let mut widget = WidgetBuilder::new("widget");
+    widget.respond_to_resize(respond_to_resize);
+    let resizes = record_resizes(&mut widget);
+    let widget_ref = widget.widget_ref();
+    let mut root = WidgetBuilder::new("root");
+    root.layout().no_container();
+    root.add_child(widget);
+    let mut app = util::headless(root);
+    place(&widget_ref, 10.0, 50.0);
+    util::frame(&mut app);
+    resizes.borrow_mut().clear();
+    (app, widget_ref, resizes)
+}
+
+#[test]
+fn not_sent_when_moved() {
+    let (mut app, widget, resizes) = placed_widget(true);
+    place(&widget, 100.0, 50.0);
+    util::frame(&mut app);
+    assert_eq!(widget.bounds().origin.x, 100.0);
+    assert!(resizes.borrow().is_empty());
+    place(&widget, 100.0, 60.0);
+    util::frame(&mut app);
+    assert_eq!(*resizes.borrow(), vec![(Size::new(50.0, 50.0), Size::new(60.0, 60.0))]);
+}
+
+#[test]
+fn only_sent_to_widgets_that_respond() {
+    let (mut app, widget, resizes) = placed_widget(false);
+    place(&widget, 10.0, 80.0);
+    util::frame(&mut app);
+    assert_eq!(widget.bounds().size, Size::new(80.0, 80.0));
+    assert!(resizes.borrow().is_empty());
+}
@@ -7,6 +7,7 @@ pub mod types;
 pub mod cursor;
 pub mod glyph;
 pub mod line;
+pub mod runs;
 
 use std::f32;
 use rusttype::Scale;
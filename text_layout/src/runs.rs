@@ -0,0 +1,205 @@
+//! Layout of a block of text made of runs, each in its own font and size, eg. to color or embolden
+//! some of the words of a line. The runs are laid out one after another and wrapped as one text,
+//! so lines can break inside a run, and a word made of several runs is kept together.
+
+use std::f32;
+use rusttype::{self, Scale};
+use super::{Font, PositionedGlyph, Wrap};
+use types::{Align, Range, Rect, RectExt, Size};
+
+/// A part of a block of text, laid out in one font and size
+#[derive(Clone, Copy)]
+pub struct TextRun<'a> {
+    pub text: &'a str,
+    pub font: &'a Font,
+    pub font_size: f32,
+}
+
+/// A character of a block of runs, and how far it advances the line, including any kerning with the character before it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CharAdvance {
+    pub char: char,
+    pub advance: f32,
+}
+
+impl CharAdvance {
+    pub fn new(char: char, advance: f32) -> Self {
+        CharAdvance {
+            char: char,
+            advance: advance,
+        }
+    }
+}
+
+/// A line of a block of runs, as a range of indices into the characters of all the runs, end exclusive.
+/// The newline or whitespace the line was broken at isn't part of any line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RunLine {
+    pub start: usize,
+    pub end: usize,
+    /// The total advance of the characters in the line
+    pub width: f32,
+}
+
+/// Break the characters of a block of runs into lines, the same way `LineInfos` breaks text in a single font:
+/// at newlines, and for `Wrap::Character` and `Wrap::Whitespace`, before the first character past `max_width`,
+/// or at the last whitespace up to it. Every line that's wrapped keeps at least one character, so a character
+/// wider than `max_width` is still laid out. Text that ends with a newline, or is empty, ends with an empty line.
+pub fn break_lines(chars: &[CharAdvance], wrap: Wrap, max_width: f32) -> Vec<RunLine> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut width = 0.0;
+    // the index of the last whitespace in the line, and the width of the line before it
+    let mut last_whitespace: Option<(usize, f32)> = None;
+    let mut index = 0;
+    while index < chars.len() {
+        let char = chars[index].char;
+        let crlf = char == '\r' && chars.get(index + 1).map(|next| next.char) == Some('\n');
+        if char == '\n' || crlf {
+            lines.push(RunLine { start: start, end: index, width: width });
+            index += if crlf { 2 } else { 1 };
+            start = index;
+            width = 0.0;
+            last_whitespace = None;
+            continue;
+        }
+        let new_width = width + chars[index].advance;
+        if wrap != Wrap::NoWrap && new_width > max_width && index > start {
+            if wrap == Wrap::Whitespace && char.is_whitespace() {
+                // the whitespace past the end of the line is where it breaks
+                lines.push(RunLine { start: start, end: index, width: width });
+                index += 1;
+                start = index;
+                width = 0.0;
+                last_whitespace = None;
+                continue;
+            }
+            match last_whitespace {
+                Some((whitespace, width_before)) if wrap == Wrap::Whitespace => {
+                    lines.push(RunLine { start: start, end: whitespace, width: width_before });
+                    start = whitespace + 1;
+                    // the rest of the word moves to the next line, this character is checked again there
+                    width = chars[start..index].iter().fold(0.0, |width, char| width + char.advance);
+                }
+                _ => {
+                    lines.push(RunLine { start: start, end: index, width: width });
+                    start = index;
+                    width = 0.0;
+                }
+            }
+            last_whitespace = None;
+            continue;
+        }
+        if char.is_whitespace() {
+            last_whitespace = Some((index, width));
+        }
+        width = new_width;
+        index += 1;
+    }
+    lines.push(RunLine { start: start, end: chars.len(), width: width });
+    lines
+}
+
+/// A glyph of a block of runs, and the index of the run it's from
+pub struct RunGlyph {
+    pub run: usize,
+    pub glyph: PositionedGlyph,
+}
+
+/// A block of runs laid out in a `Rect`, see `layout_runs`
+pub struct RunsLayout {
+    /// The bounds of each line, as wide as its text, and as tall as the tallest run in it
+    pub lines: Vec<Rect>,
+    /// The baseline of each line, shared by all of the runs in it
+    pub baselines: Vec<f32>,
+    pub glyphs: Vec<RunGlyph>,
+}
+
+// the run, glyph and advance of each character of the runs, the advance is split into the kerning
+// with the character before, which comes before the glyph, and the glyph's own advance
+struct RunChar<'a> {
+    run: usize,
+    glyph: rusttype::ScaledGlyph<'a>,
+    kern: f32,
+    advance: f32,
+    char: char,
+}
+
+fn run_chars<'a>(runs: &[TextRun<'a>]) -> Vec<RunChar<'a>> {
+    let mut chars = Vec::new();
+    for (index, run) in runs.iter().enumerate() {
+        let scale = Scale::uniform(run.font_size);
+        let mut last_glyph = None;
+        for char in run.text.chars() {
+            let glyph = run.font.glyph(char).unwrap().scaled(scale);
+            let kern = last_glyph.map(|last| run.font.pair_kerning(scale, last, glyph.id())).unwrap_or(0.0);
+            last_glyph = Some(glyph.id());
+            let advance = glyph.h_metrics().advance_width;
+            chars.push(RunChar { run: index, glyph: glyph, kern: kern, advance: advance, char: char });
+        }
+    }
+    chars
+}
+
+fn break_run_chars(chars: &[RunChar], wrap: Wrap, max_width: f32) -> Vec<RunLine> {
+    let advances: Vec<CharAdvance> = chars.iter().map(|char| CharAdvance::new(char.char, char.kern + char.advance)).collect();
+    break_lines(&advances, wrap, max_width)
+}
+
+// the height of a line, and the distance from its top to its baseline, the largest of the runs in it,
+// measured like text in a single font, with the line gap below the glyphs
+fn line_metrics(runs: &[TextRun], chars: &[RunChar], line: &RunLine) -> (f32, f32) {
+    let mut line_runs: Vec<usize> = chars[line.start..line.end].iter().map(|char| char.run).collect();
+    if line_runs.is_empty() {
+        // an empty line is as tall as the run it's in
+        let run = chars.get(line.start).or_else(|| chars.last()).map(|char| char.run);
+        line_runs.extend(run);
+    }
+    line_runs.dedup();
+    line_runs.iter().fold((0.0, 0.0), |(height, baseline), &run| {
+        let run = &runs[run];
+        let v_metrics = run.font.v_metrics(Scale::uniform(run.font_size));
+        (f32::max(height, run.font_size + v_metrics.line_gap), f32::max(baseline, run.font_size + v_metrics.descent))
+    })
+}
+
+/// The size of a block of runs, as wide as its widest line, and as tall as its lines together
+pub fn get_runs_size(runs: &[TextRun], wrap: Wrap) -> Size {
+    let chars = run_chars(runs);
+    let lines = break_run_chars(&chars, wrap, f32::MAX);
+    lines.iter().fold(Size::zero(), |size, line| {
+        let (line_height, _) = line_metrics(runs, &chars, line);
+        Size::new(f32::max(size.width, line.width), size.height + line_height)
+    })
+}
+
+/// Lay out a block of runs from the top of `rect`, wrapped to its width, each line aligned horizontally with `align`
+pub fn layout_runs(runs: &[TextRun], rect: Rect, wrap: Wrap, align: Align) -> RunsLayout {
+    let chars = run_chars(runs);
+    let mut layout = RunsLayout {
+        lines: Vec::new(),
+        baselines: Vec::new(),
+        glyphs: Vec::new(),
+    };
+    let mut top = rect.top();
+    for line in break_run_chars(&chars, wrap, rect.width()) {
+        let (line_height, baseline) = line_metrics(runs, &chars, &line);
+        let range = Range::new(0.0, line.width);
+        let x = match align {
+            Align::Start => range.align_start_of(rect.x_range()),
+            Align::Middle => range.align_middle_of(rect.x_range()),
+            Align::End => range.align_end_of(rect.x_range()),
+        };
+        let baseline = top + baseline;
+        let mut left = x.start;
+        for char in &chars[line.start..line.end] {
+            let glyph = char.glyph.clone().positioned(rusttype::Point { x: left + char.kern, y: baseline });
+            layout.glyphs.push(RunGlyph { run: char.run, glyph: glyph.standalone() });
+            left += char.kern + char.advance;
+        }
+        layout.lines.push(Rect::from_ranges(x, Range::new(top, top + line_height)));
+        layout.baselines.push(baseline);
+        top += line_height;
+    }
+    layout
+}